@@ -3,7 +3,7 @@
 #![allow(unreachable_code)]
 
 #[allow(unused_imports)]
-use binschema_runtime::{BitStreamEncoder, BitStreamDecoder, Endianness, BitOrder, Result, EncodeContext, FieldValue};
+use binschema_runtime::{BitStreamEncoder, BitStreamDecoder, Endianness, BitOrder, Result, BinSchemaError, EncodeContext, FieldValue};
 #[allow(unused_imports)]
 use std::collections::HashMap;
 
@@ -48,25 +48,43 @@ impl UnionARdataNSRdataCNAMERdata {
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
     pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {
         // Union type - try each variant in order until one succeeds
+        let mut truncated = false;
+        let mut attempts: Vec<(&str, binschema_runtime::BinSchemaError)> = Vec::new();
         let start_pos = decoder.position();
-        if let Ok(v) = ARdataOutput::decode_with_decoder(decoder) {
-            return Ok(UnionARdataNSRdataCNAMERdata::ARdata(v));
+        match decoder.strict(|d| ARdataOutput::decode_with_decoder(d)) {
+            Ok(v) => return Ok(UnionARdataNSRdataCNAMERdata::ARdata(v)),
+            Err(e) => {
+                truncated |= matches!(e, binschema_runtime::BinSchemaError::UnexpectedEof);
+                attempts.push(("ARdata", e));
+            }
         }
         decoder.seek(start_pos)?;
-        if let Ok(v) = NSRdataOutput::decode_with_decoder(decoder) {
-            return Ok(UnionARdataNSRdataCNAMERdata::NSRdata(v));
+        match decoder.strict(|d| NSRdataOutput::decode_with_decoder(d)) {
+            Ok(v) => return Ok(UnionARdataNSRdataCNAMERdata::NSRdata(v)),
+            Err(e) => {
+                truncated |= matches!(e, binschema_runtime::BinSchemaError::UnexpectedEof);
+                attempts.push(("NSRdata", e));
+            }
         }
         decoder.seek(start_pos)?;
-        if let Ok(v) = CNAMERdataOutput::decode_with_decoder(decoder) {
-            return Ok(UnionARdataNSRdataCNAMERdata::CNAMERdata(v));
+        match decoder.strict(|d| CNAMERdataOutput::decode_with_decoder(d)) {
+            Ok(v) => return Ok(UnionARdataNSRdataCNAMERdata::CNAMERdata(v)),
+            Err(e) => {
+                truncated |= matches!(e, binschema_runtime::BinSchemaError::UnexpectedEof);
+                attempts.push(("CNAMERdata", e));
+            }
         }
-        Err(binschema_runtime::BinSchemaError::InvalidVariant(0))
+        Err(if truncated {
+            binschema_runtime::BinSchemaError::UnexpectedEof
+        } else {
+            binschema_runtime::BinSchemaError::no_variant_matched("UnionARdataNSRdataCNAMERdata", &attempts)
+        })
     }
 }
 
@@ -122,6 +140,9 @@ pub struct Label(pub std::string::String);
 impl Label {
     pub fn encode(&self) -> Result<Vec<u8>> {
         let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        if self.0.chars().count() > 63 {
+            return Err(BinSchemaError::InvalidValue(format!("string is {} bytes, which exceeds max_length 63", self.0.chars().count())));
+        }
         encoder.write_uint8(self.0.chars().count() as u8);
         let string_bytes: Vec<u8> = self.0.chars().map(|c| c as u8).collect();
         for &b in string_bytes.iter() {
@@ -131,7 +152,7 @@ impl Label {
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -175,7 +196,7 @@ impl CompressedLabel {
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -192,8 +213,11 @@ impl CompressedLabel {
     }
 }
 
+/// `.1` holds the pointer as decoded. While it is set, encode writes it back
+/// verbatim and ignores `.0`; set it to `None` to have the encoder pick a
+/// pointer, e.g. after an edit moves the data it points to.
 #[derive(Debug, Clone, PartialEq)]
-pub struct LabelPointer(pub Label);
+pub struct LabelPointer(pub Label, pub Option<u16>);
 
 impl LabelPointer {
     pub fn encode(&self) -> Result<Vec<u8>> {
@@ -201,6 +225,12 @@ impl LabelPointer {
     }
 
     pub fn encode_with_context(&self, ctx: &EncodeContext) -> Result<Vec<u8>> {
+        if let Some(reference_value) = self.1 {
+            let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+            encoder.write_uint16(reference_value, Endianness::BigEndian);
+            return Ok(encoder.finish());
+        }
+
         // Encode target value to get bytes for dict lookup
         let target_bytes = self.0.encode()?;
 
@@ -222,11 +252,12 @@ impl LabelPointer {
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
     pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {
+        let pointer_pos = decoder.position();
         // Read the reference value (uint16)
         let reference_value = decoder.read_uint16(Endianness::BigEndian)?;
         let offset = (reference_value & 0x3FFF) as usize;
@@ -234,6 +265,7 @@ impl LabelPointer {
         // Save current position and seek to the referenced offset
         let saved_pos = decoder.position();
         decoder.seek(offset)?;
+        decoder.record_pointer(pointer_pos, decoder.position());
 
         // Decode the target type at the referenced position
         let value = Label::decode_with_decoder(decoder)?;
@@ -241,7 +273,7 @@ impl LabelPointer {
         // Restore position
         decoder.seek(saved_pos)?;
 
-        Ok(Self(value))
+        Ok(Self(value, Some(reference_value)))
     }
 }
 
@@ -276,11 +308,14 @@ impl CompressedDomain {
         if !is_terminal {
             encoder.write_uint8(0);
         }
+        if encoder.byte_offset() > 255 {
+            return Err(BinSchemaError::InvalidValue(format!("'value' encodes to {} bytes, which exceeds max_encoded_length 255", encoder.byte_offset())));
+        }
         Ok(encoder.finish())
     }
 
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -347,7 +382,7 @@ impl QuestionInput {
 
 impl QuestionOutput {
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -396,7 +431,7 @@ impl ARdataInput {
 
 impl ARdataOutput {
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -451,7 +486,7 @@ impl NSRdataInput {
 
 impl NSRdataOutput {
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -506,7 +541,7 @@ impl CNAMERdataInput {
 
 impl CNAMERdataOutput {
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -579,7 +614,7 @@ impl ResourceRecordInput {
 
 impl ResourceRecordOutput {
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
@@ -694,10 +729,24 @@ impl DnsMessageInput {
 
 impl DnsMessageOutput {
     pub fn decode(bytes: &[u8]) -> Result<Self> {
-        let mut decoder = BitStreamDecoder::new(bytes.to_vec(), BitOrder::MsbFirst);
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
         Self::decode_with_decoder(&mut decoder)
     }
 
+    /// Creates a push-based decoder that buffers chunks until a whole value has arrived.
+    pub fn partial_decoder() -> binschema_runtime::PartialDecoder<Self> {
+        binschema_runtime::PartialDecoder::new(BitOrder::MsbFirst, Self::decode_with_decoder)
+    }
+
+    /// Decodes and also returns every back-reference followed, as
+    /// `(pointer_position, target_offset)` pairs in the order they were read.
+    pub fn decode_with_pointer_log(bytes: &[u8]) -> Result<(Self, Vec<(usize, usize)>)> {
+        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
+        decoder.log_pointers();
+        let value = Self::decode_with_decoder(&mut decoder)?;
+        Ok((value, decoder.take_pointer_log()))
+    }
+
     pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {
         let id = decoder.read_uint16(Endianness::BigEndian)?;
         let flags = DnsMessageFlags::decode(decoder)?;
@@ -738,6 +787,22 @@ impl DnsMessageOutput {
             additional,
         })
     }
+
+    /// Byte offset of the field called `name` in every encoded value, for patching
+    /// it in place. `None` when the field follows a variable-length field,
+    /// starts mid-byte, or doesn't exist.
+    pub fn field_offset(name: &str) -> Option<usize> {
+        match name {
+            "id" => Some(0),
+            "flags" => Some(2),
+            "qdcount" => Some(4),
+            "ancount" => Some(6),
+            "nscount" => Some(8),
+            "arcount" => Some(10),
+            "questions" => Some(12),
+            _ => None,
+        }
+    }
 }
 
 impl From<DnsMessageOutput> for DnsMessageInput {
@@ -756,3 +821,11 @@ impl From<DnsMessageOutput> for DnsMessageInput {
         }
     }
 }
+
+impl TryFrom<&DnsMessageInput> for Vec<u8> {
+    type Error = BinSchemaError;
+
+    fn try_from(value: &DnsMessageInput) -> Result<Self> {
+        value.encode()
+    }
+}
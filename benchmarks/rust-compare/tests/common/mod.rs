@@ -0,0 +1,11 @@
+// ABOUTME: Fixtures shared by the integration tests of the generated DNS code
+
+/// The benchmark's DNS response (45 bytes): "example.com" A record whose answer
+/// name is a compression pointer (0xC00C) to the question name at offset 12.
+pub const DNS_RESPONSE_PACKET: &[u8] = &[
+    0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+    0x00, 0x01, 0x00, 0x01,
+    0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x04,
+    0x5d, 0xb8, 0xd8, 0x22,
+];
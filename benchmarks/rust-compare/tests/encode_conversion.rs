@@ -0,0 +1,12 @@
+mod common;
+
+use binschema_bench::dns_message::{DnsMessage, DnsMessageInput};
+use common::DNS_RESPONSE_PACKET;
+
+#[test]
+fn try_into_vec_matches_encode() {
+    let message: DnsMessageInput = DnsMessage::decode(DNS_RESPONSE_PACKET).unwrap().into();
+    let bytes: Vec<u8> = (&message).try_into().unwrap();
+    assert_eq!(bytes, message.encode().unwrap());
+    assert_eq!(DnsMessage::decode(&bytes).unwrap(), DnsMessage::decode(DNS_RESPONSE_PACKET).unwrap());
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use binschema_bench::dns_message::DnsMessage;
+use common::DNS_RESPONSE_PACKET;
+
+#[test]
+fn header_fields_have_fixed_offsets() {
+    assert_eq!(DnsMessage::field_offset("id"), Some(0));
+    assert_eq!(DnsMessage::field_offset("flags"), Some(2));
+    assert_eq!(DnsMessage::field_offset("arcount"), Some(10));
+    // The first variable-length field still starts at a known offset
+    assert_eq!(DnsMessage::field_offset("questions"), Some(12));
+}
+
+#[test]
+fn fields_after_variable_array_have_no_offset() {
+    assert_eq!(DnsMessage::field_offset("answers"), None);
+    assert_eq!(DnsMessage::field_offset("additional"), None);
+    assert_eq!(DnsMessage::field_offset("no_such_field"), None);
+}
+
+#[test]
+fn patching_at_offset_changes_only_that_field() {
+    let mut packet = DNS_RESPONSE_PACKET.to_vec();
+    let offset = DnsMessage::field_offset("id").unwrap();
+    packet[offset..offset + 2].copy_from_slice(&0xBEEFu16.to_be_bytes());
+
+    let patched = DnsMessage::decode(&packet).unwrap();
+    let original = DnsMessage::decode(DNS_RESPONSE_PACKET).unwrap();
+    assert_eq!(patched.id, 0xBEEF);
+    assert_eq!(patched.answers, original.answers);
+}
@@ -0,0 +1,31 @@
+use binschema_bench::dns_message::{CompressedDomain, CompressedLabel, Label};
+use binschema_runtime::BinSchemaError;
+
+fn domain(label_lens: &[usize]) -> CompressedDomain {
+    CompressedDomain {
+        value: label_lens.iter().map(|&n| CompressedLabel::Label(Label("a".repeat(n)))).collect(),
+    }
+}
+
+#[test]
+fn label_of_63_bytes_encodes() {
+    let bytes = Label("a".repeat(63)).encode().unwrap();
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(bytes[0], 63);
+}
+
+#[test]
+fn label_of_64_bytes_is_rejected() {
+    let err = Label("a".repeat(64)).encode().unwrap_err();
+    assert!(matches!(err, BinSchemaError::InvalidValue(_)), "{:?}", err);
+}
+
+#[test]
+fn name_over_255_bytes_is_rejected() {
+    // Three full labels plus the terminator take 193 bytes; four take 257
+    assert_eq!(domain(&[63, 63, 63]).encode().unwrap().len(), 193);
+    // 255 bytes exactly is still a valid name
+    assert_eq!(domain(&[63, 63, 63, 61]).encode().unwrap().len(), 255);
+    let err = domain(&[63, 63, 63, 62]).encode().unwrap_err();
+    assert!(matches!(err, BinSchemaError::InvalidValue(_)), "{:?}", err);
+}
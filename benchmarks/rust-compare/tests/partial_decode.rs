@@ -0,0 +1,30 @@
+mod common;
+
+use binschema_bench::dns_message::DnsMessage;
+use binschema_runtime::Progress;
+use common::DNS_RESPONSE_PACKET;
+
+#[test]
+fn dns_response_fed_one_byte_at_a_time() {
+    let expected = DnsMessage::decode(DNS_RESPONSE_PACKET).unwrap();
+    let mut partial = DnsMessage::partial_decoder();
+
+    let (last, head) = DNS_RESPONSE_PACKET.split_last().unwrap();
+    for byte in head {
+        assert_eq!(partial.feed(std::slice::from_ref(byte)), Ok(Progress::NeedMoreData));
+    }
+    assert_eq!(partial.feed(&[*last]), Ok(Progress::Complete(expected)));
+    assert!(partial.buffered().is_empty());
+}
+
+#[test]
+fn back_to_back_messages_in_one_chunk() {
+    let mut stream = DNS_RESPONSE_PACKET.to_vec();
+    stream.extend_from_slice(&DNS_RESPONSE_PACKET[..10]);
+
+    let mut partial = DnsMessage::partial_decoder();
+    assert!(matches!(partial.feed(&stream), Ok(Progress::Complete(_))));
+    assert_eq!(partial.buffered(), &DNS_RESPONSE_PACKET[..10]);
+    assert_eq!(partial.feed(&[]), Ok(Progress::NeedMoreData));
+    assert!(matches!(partial.feed(&DNS_RESPONSE_PACKET[10..]), Ok(Progress::Complete(_))));
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use binschema_bench::dns_message::{CompressedLabel, DnsMessage};
+use common::DNS_RESPONSE_PACKET;
+
+#[test]
+fn pointer_log_records_answer_name_pointer() {
+    let (message, pointers) = DnsMessage::decode_with_pointer_log(DNS_RESPONSE_PACKET).unwrap();
+    assert_eq!(pointers, vec![(29, 12)]);
+    assert!(matches!(message.answers[0].name.value[0], CompressedLabel::LabelPointer(_)));
+    assert_eq!(message, DnsMessage::decode(DNS_RESPONSE_PACKET).unwrap());
+}
+
+#[test]
+fn pointer_log_is_empty_without_compression() {
+    let query = &DNS_RESPONSE_PACKET[..29];
+    let mut header = query.to_vec();
+    header[2] = 0x01;
+    header[3] = 0x00;
+    header[7] = 0x00; // no answers
+    let (_, pointers) = DnsMessage::decode_with_pointer_log(&header).unwrap();
+    assert!(pointers.is_empty());
+}
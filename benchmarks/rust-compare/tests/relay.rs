@@ -0,0 +1,47 @@
+mod common;
+
+use binschema_bench::dns_message::{CompressedLabel, DnsMessage, DnsMessageInput};
+use common::DNS_RESPONSE_PACKET;
+
+/// Two uncompressed copies of "example.com" (at 12 and 29); the answer
+/// points at the second one, which the encoder would never pick itself.
+const SECOND_COPY_PACKET: &[u8] = &[
+    0x12, 0x34, 0x81, 0x80, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+    0x00, 0x01, 0x00, 0x01,
+    0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+    0x00, 0x01, 0x00, 0x01,
+    0xc0, 0x1d, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x04,
+    0x5d, 0xb8, 0xd8, 0x22,
+];
+
+fn reencode(packet: &[u8]) -> Vec<u8> {
+    let message: DnsMessageInput = DnsMessage::decode(packet).unwrap().into();
+    message.encode().unwrap()
+}
+
+#[test]
+fn unmodified_response_reencodes_byte_for_byte() {
+    assert_eq!(reencode(DNS_RESPONSE_PACKET), DNS_RESPONSE_PACKET);
+    assert_eq!(reencode(SECOND_COPY_PACKET), SECOND_COPY_PACKET);
+}
+
+#[test]
+fn edited_response_keeps_its_pointers() {
+    let mut message: DnsMessageInput = DnsMessage::decode(DNS_RESPONSE_PACKET).unwrap().into();
+    message.answers[0].ttl = 60;
+    let bytes = message.encode().unwrap();
+    assert_eq!(&bytes[29..31], &[0xc0, 0x0c]);
+    assert_eq!(&bytes[35..39], &[0x00, 0x00, 0x00, 0x3c]);
+}
+
+#[test]
+fn cleared_pointer_is_chosen_by_the_encoder() {
+    let mut message: DnsMessageInput = DnsMessage::decode(SECOND_COPY_PACKET).unwrap().into();
+    match &mut message.answers[0].name.value[0] {
+        CompressedLabel::LabelPointer(pointer) => pointer.1 = None,
+        other => panic!("expected a pointer, got {:?}", other),
+    }
+    let bytes = message.encode().unwrap();
+    assert_eq!(&bytes[46..48], &[0xc0, 0x0c]);
+}
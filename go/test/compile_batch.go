@@ -68,6 +68,27 @@ func CompileAndTestBatch(suites []*TestSuite) (map[string][]TestResult, error) {
 		}
 
 		code, err := generateGoSource(suite.Schema, suite.TestType)
+		if suite.IsRejectedBy("go") {
+			// The suite uses a Rust-only option: generation must fail with the
+			// generator's "does not support" error
+			errMsg := "expected the Go generator to reject this schema, but it generated code"
+			if err != nil {
+				errMsg = ""
+				if !strings.Contains(err.Error(), "does not support") {
+					errMsg = fmt.Sprintf("code generation failed with an unexpected error: %v", err)
+				}
+			}
+			var rejectedResults []TestResult
+			for _, tc := range suite.TestCases {
+				rejectedResults = append(rejectedResults, TestResult{
+					Description: tc.Description,
+					Pass:        errMsg == "",
+					Error:       errMsg,
+				})
+			}
+			results[suite.Name] = rejectedResults
+			continue
+		}
 		if err != nil {
 			// Mark all test cases in this suite as failed due to code generation error
 			var failedResults []TestResult
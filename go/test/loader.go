@@ -22,6 +22,17 @@ type TestSuite struct {
 	Tests                 []TestCase             `json:"tests"`      // Alternative field name (both are accepted)
 	SchemaValidationError bool                   `json:"schema_validation_error,omitempty"` // True if this tests schema validation failure
 	ErrorMessage          string                 `json:"error_message,omitempty"`           // Expected error message for validation error tests
+	RejectedBy            []string               `json:"rejected_by,omitempty"`             // Generators expected to refuse the schema (Rust-only options)
+}
+
+// IsRejectedBy reports whether the suite expects the given generator to refuse its schema
+func (s *TestSuite) IsRejectedBy(generator string) bool {
+	for _, g := range s.RejectedBy {
+		if g == generator {
+			return true
+		}
+	}
+	return false
 }
 
 // GetTestCases returns the test cases, handling both "test_cases" and "tests" field names
@@ -46,6 +46,8 @@ export interface GenerateCommand {
   readonly outputDir: string;
   readonly watch: boolean;
   readonly debug: boolean;
+  readonly modules: boolean;
+  readonly roundtripTests: boolean;
 }
 
 export interface ValidateCommand {
@@ -302,6 +304,8 @@ function buildCommand(path: string[], values: Record<string, unknown>): CLIParse
       outputDir: values.outputDir as string,
       watch: values.watch as boolean,
       debug: values.debug as boolean,
+      modules: values.modules as boolean,
+      roundtripTests: values.roundtripTests as boolean,
     } satisfies GenerateCommand);
   }
 
@@ -648,7 +652,7 @@ function createRootSpec(): CommandSpec {
   const generate: CommandSpec = {
     name: "generate",
     description: "Emit code for a target language.",
-    usage: ["binschema generate --language <ts|go|rust|python> --schema <file> --out <dir> [--watch]"],
+    usage: ["binschema generate --language <ts|go|rust|python> --schema <file> --out <dir> [--watch] [--modules] [--roundtrip-tests]"],
     options: [
       {
         name: "schema",
@@ -699,6 +703,20 @@ function createRootSpec(): CommandSpec {
         description: "Include trace logging in generated code (for debugging encoding/decoding).",
         defaultValue: false,
       },
+      {
+        name: "modules",
+        key: "modules",
+        type: "boolean",
+        description: "Rust only: write one module per schema type under src/generated/ instead of a single file.",
+        defaultValue: false,
+      },
+      {
+        name: "roundtrip-tests",
+        key: "roundtripTests",
+        type: "boolean",
+        description: "Rust only: add a #[cfg(test)] module that encodes and decodes random values of each type.",
+        defaultValue: false,
+      },
     ],
   };
 
@@ -255,8 +255,10 @@ async function handleGenerate(command: GenerateCommand): Promise<void> {
         schema,
         typeName,
         outputDir: absoluteOut,
+        modules: command.modules,
+        roundtripTests: command.roundtripTests,
       });
-      console.log(`Generated Rust sources → ${join(absoluteOut, "src", "generated.rs")}`);
+      console.log(`Generated Rust sources → ${join(absoluteOut, "src", command.modules ? "generated" : "generated.rs")}`);
       break;
     }
     case "python": {
@@ -400,7 +402,7 @@ function findRustRuntimeDir(): string | null {
   return null;
 }
 
-async function runRustGenerator(opts: { schema: BinarySchema; typeName: string; outputDir: string }): Promise<void> {
+async function runRustGenerator(opts: { schema: BinarySchema; typeName: string; outputDir: string; modules?: boolean; roundtripTests?: boolean }): Promise<void> {
   mkdirSync(opts.outputDir, { recursive: true });
 
   // Copy runtime files to outputDir/binschema_runtime/ as a local crate
@@ -416,40 +418,64 @@ async function runRustGenerator(opts: { schema: BinarySchema; typeName: string;
   const runtimeCrateSrcDir = join(runtimeCrateDir, "src");
   mkdirSync(runtimeCrateSrcDir, { recursive: true });
 
-  // Copy runtime source files (exclude test_schema.rs which is test-only)
-  const runtimeFiles = ["bitstream.rs", "context.rs"];
+  // Copy runtime source files. test_schema.rs (test-only) and the modules built on
+  // it need serde, which the generated runtime crate doesn't depend on.
+  const excludedModules = ["test_schema", "schema_validation", "schema_compat", "schema_introspect", "schema_size"];
+  const runtimeFiles = readdirSync(runtimeSrcDir).filter(
+    f => f.endsWith(".rs") && f !== "lib.rs" && !excludedModules.includes(f.slice(0, -3))
+  );
   for (const file of runtimeFiles) {
     const content = readFileSync(join(runtimeSrcDir, file), "utf-8");
     writeFileSync(join(runtimeCrateSrcDir, file), content, "utf-8");
   }
 
-  // Write a lib.rs that excludes test_schema module
-  const libRsContent = readFileSync(join(runtimeSrcDir, "lib.rs"), "utf-8");
-  const strippedLibRs = libRsContent
-    .replace(/pub mod test_schema;\n?/, "")
-    .replace(/pub use test_schema[^\n]*\n?/g, "");
+  // Write a lib.rs without the excluded modules, along with the cfg attribute
+  // gating each one so it doesn't end up on the next item
+  let strippedLibRs = readFileSync(join(runtimeSrcDir, "lib.rs"), "utf-8");
+  for (const module of excludedModules) {
+    strippedLibRs = strippedLibRs
+      .replace(new RegExp(`(#\\[cfg\\([^\\n]*\\)\\]\\n)?pub mod ${module};\\n?`), "")
+      .replace(new RegExp(`(#\\[cfg\\([^\\n]*\\)\\]\\n)?pub use ${module}[^\\n]*\\n?`, "g"), "");
+  }
   writeFileSync(join(runtimeCrateSrcDir, "lib.rs"), strippedLibRs, "utf-8");
 
-  // Write Cargo.toml for the runtime crate (no external deps needed)
+  // Write Cargo.toml for the runtime crate. Without std it builds as no_std +
+  // alloc; serde is only pulled in for the optional JSON conversions (BinSchemaValue).
   const runtimeCargoToml = `[package]
 name = "binschema-runtime"
 version = "0.1.0"
 edition = "2021"
 
+[dependencies]
+serde = { version = "1.0", optional = true }
+serde_json = { version = "1.0", optional = true }
+
+[features]
+default = ["std"]
+std = []
+serde = ["std", "dep:serde", "dep:serde_json"]
+
 [lib]
 name = "binschema_runtime"
 path = "src/lib.rs"
 `;
   writeFileSync(join(runtimeCrateDir, "Cargo.toml"), runtimeCargoToml, "utf-8");
 
-  // Generate the code
-  const { generateRust } = await import("../generators/rust.js");
-  const result = generateRust(opts.schema, opts.typeName);
-
-  // Write generated code into src/
+  // Generate the code into src/, either as one file or as src/generated/<type>.rs
+  // modules; both are reached through the same `generated` module path
+  const { generateRust, generateRustModules } = await import("../generators/rust.js");
   const srcDir = join(opts.outputDir, "src");
   mkdirSync(srcDir, { recursive: true });
-  writeFileSync(join(srcDir, "generated.rs"), result.code, "utf-8");
+  if (opts.modules) {
+    const moduleDir = join(srcDir, "generated");
+    mkdirSync(moduleDir, { recursive: true });
+    for (const [file, code] of generateRustModules(opts.schema, { roundtripTests: opts.roundtripTests })) {
+      writeFileSync(join(moduleDir, file), code, "utf-8");
+    }
+  } else {
+    const result = generateRust(opts.schema, opts.typeName, { roundtripTests: opts.roundtripTests });
+    writeFileSync(join(srcDir, "generated.rs"), result.code, "utf-8");
+  }
 
   // Write a lib.rs that re-exports the generated module
   writeFileSync(join(srcDir, "lib.rs"), `pub mod generated;\n`, "utf-8");
@@ -464,6 +490,14 @@ edition = "2021"
 
 [dependencies]
 binschema-runtime = { path = "binschema_runtime" }
+serde = { version = "1.0", features = ["derive"], optional = true }
+
+[features]
+default = ["builder"]
+# Serialize/Deserialize on generated types, plus to_json/from_json
+serde = ["dep:serde", "binschema-runtime/serde"]
+# Field-by-field builders for types whose Input struct has a Default
+builder = []
 
 [lib]
 path = "src/lib.rs"
@@ -2,6 +2,7 @@
 // ABOUTME: Produces byte-for-byte compatible code with TypeScript runtime
 
 import { type BinarySchema, type Field, type Endianness, isEnumType } from "../schema/binary-schema.js";
+import { rejectRustOnlyFeatures } from "../schema/rust-only-features.js";
 
 /**
  * Get all field names for a type (only for struct types with sequence)
@@ -1545,6 +1546,7 @@ export function generateGo(
   if (!schema.types[typeName]) {
     throw new Error(`Type ${typeName} not found in schema`);
   }
+  rejectRustOnlyFeatures(schema, "Go");
 
   const lines: string[] = [];
 
@@ -3138,6 +3140,20 @@ function generateEncodeField(
   const endianness = fieldAny.endianness || defaultEndianness;
   const runtimeEndianness = mapEndianness(endianness);
 
+  // Protocol limit on an array's encoded size: measure what it wrote, prefix
+  // and terminator included, whichever encoding path the array takes
+  if (field.type === "array" && fieldAny.max_encoded_length !== undefined) {
+    const { max_encoded_length: maxEncodedLength, ...unlimited } = fieldAny;
+    const startVar = `${toGoFieldName(field.name)}_start`;
+    return [
+      `${indent}${startVar} := encoder.Position()`,
+      ...generateEncodeField(unlimited, defaultEndianness, indent, containingFields, currentFieldIndex, schema, containingTypeName),
+      `${indent}if encoder.Position()-${startVar} > ${maxEncodedLength} {`,
+      `${indent}\treturn nil, fmt.Errorf("'${field.name}' encodes to %d bytes, which exceeds max_encoded_length ${maxEncodedLength}", encoder.Position()-${startVar})`,
+      `${indent}}`,
+    ];
+  }
+
   // Handle padding fields - write zero bytes for alignment
   if (field.type === "padding") {
     const alignTo = fieldAny.align_to || 4;
@@ -3783,6 +3799,9 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
     const runtimeEnd = mapEndianness(stringEndianness);
     const unitsVar = `${cleanFieldName.replace(/\./g, "_")}_units`;
     lines.push(`${indent}${unitsVar} := utf16.Encode([]rune(${fieldName}))`);
+    if (kind !== "fixed") {
+      lines.push(...generateStringMaxLengthCheck(field, `len(${unitsVar})*2`, indent));
+    }
 
     switch (kind) {
       case "length_prefixed": {
@@ -3857,6 +3876,9 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
     // UTF-8: use Go's native string->byte conversion
     lines.push(`${indent}${bytesVar} := []byte(${fieldName})`);
   }
+  if (kind !== "fixed") {
+    lines.push(...generateStringMaxLengthCheck(field, `len(${bytesVar})`, indent));
+  }
 
   switch (kind) {
     case "length_prefixed": {
@@ -3919,6 +3941,20 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
   return lines;
 }
 
+/**
+ * Generates the encode-time max_length check for a variable-length string,
+ * so an over-long value fails instead of producing an out-of-spec message
+ */
+function generateStringMaxLengthCheck(field: any, lenExpr: string, indent: string): string[] {
+  if (field.max_length === undefined) return [];
+  const what = field.name && field.name !== "0" ? `'${field.name}'` : "string";
+  return [
+    `${indent}if ${lenExpr} > ${field.max_length} {`,
+    `${indent}\treturn nil, fmt.Errorf("${what} is %d bytes, which exceeds max_length ${field.max_length}", ${lenExpr})`,
+    `${indent}}`,
+  ];
+}
+
 /**
  * Generates encoding code for array field
  */
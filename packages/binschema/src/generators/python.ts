@@ -3,6 +3,7 @@
 // ABOUTME: Reference: src/generators/typescript.ts is the canonical implementation
 
 import { type BinarySchema, type Field, type Endianness, isEnumType } from "../schema/binary-schema.js";
+import { rejectRustOnlyFeatures } from "../schema/rust-only-features.js";
 
 export interface GeneratedPythonCode {
   code: string;
@@ -492,6 +493,14 @@ function generateStringEncode(field: any, fieldAccess: string, indent: string, e
   const isUtf16 = isUtf16Encoding(encoding);
   const kind = field.kind;
 
+  // Protocol limit on variable-length strings (e.g., 63-byte DNS labels)
+  if (field.max_length !== undefined && kind !== "fixed") {
+    const what = field.name && field.name !== "0" ? `'${field.name}'` : "string";
+    code += `${indent}_str_len = len(${fieldAccess}.encode("${pyEncoding}"))\n`;
+    code += `${indent}if _str_len > ${field.max_length}:\n`;
+    code += `${indent}    raise ValueError(f"${what} is {_str_len} bytes, which exceeds max_length ${field.max_length}")\n`;
+  }
+
   if (kind === "fixed" && field.length !== undefined) {
     code += `${indent}_str_bytes = ${fieldAccess}.encode("${pyEncoding}")\n`;
     code += `${indent}encoder.write_bytes(_str_bytes[:${field.length}])\n`;
@@ -595,6 +604,18 @@ function generateArrayIterationDone(arrName: string | undefined, indent: string)
 }
 
 function generateArrayEncode(field: any, fieldAccess: string, indent: string, endianness: string, schema: BinarySchema, bitOrder: string): string {
+  // Protocol limit on the encoded size: measure what the array wrote, prefix and terminator included
+  if (field.max_encoded_length !== undefined) {
+    const { max_encoded_length: maxEncodedLength, ...unlimited } = field;
+    const what = field.name ? `'${field.name}'` : "array";
+    const startVar = `_${field.name ?? "array"}_start`;
+    let code = `${indent}${startVar} = encoder.byte_offset\n`;
+    code += generateArrayEncode(unlimited, fieldAccess, indent, endianness, schema, bitOrder);
+    code += `${indent}if encoder.byte_offset - ${startVar} > ${maxEncodedLength}:\n`;
+    code += `${indent}    raise ValueError(f"${what} encodes to {encoder.byte_offset - ${startVar}} bytes, which exceeds max_encoded_length ${maxEncodedLength}")\n`;
+    return code;
+  }
+
   let code = '';
   const items = field.items;
   const kind = field.kind;
@@ -2359,6 +2380,7 @@ export function generatePython(
   if (!schema.types[typeName]) {
     throw new Error(`Type ${typeName} not found in schema`);
   }
+  rejectRustOnlyFeatures(schema, "Python");
 
   const defaultEndianness = schema.config?.endianness || "big_endian";
   const defaultBitOrder = schema.config?.bit_order || "msb_first";
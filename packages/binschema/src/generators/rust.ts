@@ -1,14 +1,16 @@
 // ABOUTME: Generates Rust encoder/decoder code from BinSchema definitions
 // ABOUTME: Produces byte-for-byte compatible code with TypeScript and Go runtimes
 
-import { type BinarySchema, type Field, type Endianness, isEnumType } from "../schema/binary-schema.js";
+import { type BinarySchema, type Field, type Endianness, isEnumType, isFlagsType, isTlvType } from "../schema/binary-schema.js";
 import { monomorphizeTemplates } from "../schema/monomorphize.js";
+import { expandInlineGroups } from "../schema/inline-groups.js";
 
 /**
  * Options for Rust code generation
  */
 export interface RustGeneratorOptions {
   crateName?: string; // default: "binschema_runtime"
+  roundtripTests?: boolean; // emit a #[cfg(test)] module round-tripping random values (default: false)
 }
 
 /**
@@ -19,6 +21,15 @@ export interface GeneratedRustCode {
   typeName: string;
 }
 
+/**
+ * Emitted after the derive of every generated data type. With the generated
+ * crate's `serde` feature on, the types serialize under their schema field
+ * names and pick up `to_json`/`from_json` from the runtime's `BinSchemaValue`.
+ * Fixed-length arrays over 32 elements have no serde impl and won't compile
+ * with the feature on.
+ */
+const SERDE_DERIVE = `#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]`;
+
 // ===== Inline encoders used by variant-arm inliners =====
 
 /**
@@ -82,6 +93,14 @@ function generateInlineArrayEncode(
   // primitive type is covered. The synthetic Field gives the helper just the
   // shape it needs (type + any sub-fields like array items / kind).
   const itemField: Field = { name: "", type: items.type, ...(items as any) } as Field;
+  if (field.encoding === "delta_zigzag") {
+    const prevVar = `${toRustFieldName(field.name).replace(/^r#/, "")}_prev`;
+    lines.push(`${indent}let mut ${prevVar}: Option<${mapPrimitiveToRustType(items.type)}> = None;`);
+    lines.push(`${indent}for item in &${accessor} {`);
+    lines.push(...generateDeltaItemEncode(items, prevVar, endianness, `${indent}    `, false));
+    lines.push(`${indent}}`);
+    return lines;
+  }
   lines.push(`${indent}for item in &${accessor} {`);
   const itemLines = generateEncodeArrayItem(itemField, "item", endianness, `${indent}    `, schema);
   lines.push(...itemLines);
@@ -106,6 +125,8 @@ function primitiveFieldBitWidth(field: Field | any): number | null {
     case "uint64": case "int64": case "float64": return 64;
     case "bit": return (field as any).size || 1;
     case "int": return (field as any).size || 8;
+    case "custom_float": return customFloatWidth(field);
+    case "uuid": return 128;
     case "bitfield": {
       const subFields = (field as any).fields;
       if (subFields && Array.isArray(subFields)) {
@@ -120,6 +141,123 @@ function primitiveFieldBitWidth(field: Field | any): number | null {
   }
 }
 
+/** Encoded width of a custom_float field: optional sign bit + exponent + mantissa */
+function customFloatWidth(field: any): number {
+  return (field.sign === false ? 0 : 1) + field.exponent_bits + field.mantissa_bits;
+}
+
+/** Runtime `CustomFloat` layout literal for a custom_float field */
+function customFloatLayout(field: any): string {
+  const sign = field.sign !== false;
+  const implied = field.implied_leading_bit !== false;
+  return `binschema_runtime::CustomFloat { sign: ${sign}, exponent_bits: ${field.exponent_bits}, mantissa_bits: ${field.mantissa_bits}, bias: ${field.bias}, implied_leading_bit: ${implied} }`;
+}
+
+/** Sign-extending read of an N-bit `int` field, narrowed to its Rust type */
+function signedBitsRead(bitSize: number, rustType: string): string {
+  const read = `decoder.read_bits_signed(${bitSize})?`;
+  return rustType === "i64" ? read : `${read} as ${rustType}`;
+}
+
+/** Runtime read/write method suffix for a uuid field's byte layout */
+function uuidLayoutSuffix(field: any): string {
+  return field.layout === "guid" ? "guid" : "uuid";
+}
+
+/**
+ * Lower bound on the encoded size of one value of `field`, in bits. Generated
+ * decoders use it to reject untrusted element counts that can't fit in the
+ * remaining input. Conservative: data-dependent or unknown sizes count as 0.
+ */
+function minEncodedBits(field: any, schema?: BinarySchema, seen: Set<string> = new Set()): number {
+  if (!field || isFieldConditional(field)) return 0;
+  const width = primitiveFieldBitWidth(field);
+  if (width !== null) return width;
+  const prefixBits = (lengthType?: string) => primitiveFieldBitWidth({ type: lengthType || "uint8" }) ?? 0;
+
+  switch (field.type) {
+    case "string":
+      if (field.kind === "length_prefixed") return prefixBits(field.length_type);
+      if (field.kind === "fixed") return (field.length || 0) * 8;
+      if (field.kind === "null_terminated") return 8;
+      return 0;
+    case "array":
+      if (field.kind === "length_prefixed" || field.kind === "byte_length_prefixed" || field.kind === "length_prefixed_items" || field.kind === "columnar") {
+        return prefixBits(field.length_type);
+      }
+      if (field.kind === "fixed") {
+        // Items after the first may be one-byte deltas
+        if (field.encoding === "delta_zigzag") return field.length ? minEncodedBits(field.items, schema, seen) + (field.length - 1) * 8 : 0;
+        return (field.length || 0) * minEncodedBits(field.items, schema, seen);
+      }
+      if (field.kind === "null_terminated") return 8;
+      if (field.kind === "bit_length_prefixed") return field.length_bits || 0;
+      return 0;
+  }
+
+  const typeDef: any = schema?.types?.[field.type];
+  if (!typeDef || seen.has(field.type)) return 0;
+  if (isEnumType(typeDef)) return prefixBits(typeDef.repr);
+  if (isFlagsType(typeDef)) return prefixBits(typeDef.storage);
+  if (isTlvType(typeDef)) return prefixBits(typeDef.tag_type) + prefixBits(typeDef.length_type);
+  if ("sequence" in typeDef) {
+    const path = new Set(seen).add(field.type);
+    const groupBits = (typeDef.sequence as any[]).reduce((sum, f) => sum + minEncodedBits(f, schema, path), 0);
+    return groupBits * (field.repeat ?? 1);
+  }
+  return 0;
+}
+
+/**
+ * Exact encoded size of every value of `field`, in bits, or null when it
+ * depends on the data (strings, non-fixed arrays, conditionals, unions).
+ */
+function fixedEncodedBits(field: any, schema?: BinarySchema, seen: Set<string> = new Set()): number | null {
+  if (!field || isFieldConditional(field) || field.type === "optional") return null;
+  const width = primitiveFieldBitWidth(field);
+  if (width !== null) return width;
+
+  switch (field.type) {
+    case "string":
+      return field.kind === "fixed" ? (field.length || 0) * 8 : null;
+    case "array": {
+      // Record padding depends on where the array starts
+      if (field.kind !== "fixed" || field.record_alignment || field.encoding === "delta_zigzag") return null;
+      const itemBits = fixedEncodedBits(field.items, schema, seen);
+      return itemBits === null ? null : (field.length || 0) * itemBits;
+    }
+  }
+
+  const typeDef: any = schema?.types?.[field.type];
+  if (!typeDef || seen.has(field.type)) return null;
+  if (isEnumType(typeDef)) return primitiveFieldBitWidth({ type: typeDef.repr });
+  if (isFlagsType(typeDef)) return primitiveFieldBitWidth({ type: typeDef.storage });
+  if ("sequence" in typeDef) {
+    const path = new Set(seen).add(field.type);
+    let total = 0;
+    for (const f of typeDef.sequence as any[]) {
+      // Padding depends on where the nested struct starts
+      if (f.type === "padding") return null;
+      const bits = fixedEncodedBits(f, schema, path);
+      if (bits === null) return null;
+      total += bits;
+    }
+    return total * (field.repeat ?? 1);
+  }
+  return null;
+}
+
+/**
+ * Emits the guard that precedes allocating for an untrusted element count:
+ * fail with InvalidValue when `count` items can't fit in the remaining input
+ * or `count` is above the decoder's `max_alloc`. Items with
+ * no known minimum size (`minBits` 0) only get the `max_alloc` check, which is
+ * all that stops a forged count from looping or allocating without bound.
+ */
+function emitCountFitsCheck(countExpr: string, minBits: number, indent: string): string[] {
+  return [`${indent}decoder.ensure_count_fits(${countExpr}, ${Math.max(minBits, 0)})?;`];
+}
+
 /**
  * Compute per-field byte-alignment for a sequence of fields.
  * Returns a boolean[] where each entry indicates whether the corresponding
@@ -138,6 +276,12 @@ function computeFieldAlignments(fields: Field[]): boolean[] {
     // Record whether this field starts at a byte-aligned position
     result.push(bitOffset >= 0 && bitOffset % 8 === 0);
 
+    // Padding finishes any partial byte, whatever came before
+    if (field.type === "padding") {
+      bitOffset = 0;
+      continue;
+    }
+
     if (bitOffset < 0 || !field.type) continue;
 
     // Conditional fields: if inner type is not byte-aligned, alignment becomes unknown
@@ -155,6 +299,12 @@ function computeFieldAlignments(fields: Field[]): boolean[] {
       continue;
     }
 
+    // A bit-count prefix and bit-wide items can end anywhere within a byte
+    if (fieldAny.type === "array" && fieldAny.kind === "bit_length_prefixed") {
+      bitOffset = -1;
+      continue;
+    }
+
     const width = primitiveFieldBitWidth(field);
     if (width !== null) {
       bitOffset += width;
@@ -239,6 +389,68 @@ function emitDecoderRead(type: string, rustEndianness: string, byteAligned: bool
   throw new Error(`Unsupported type for emitDecoderRead: ${type}`);
 }
 
+/**
+ * True when a 32-bit integer field declares `byte_order: "pdp"` (PDP-11
+ * middle-endian, bytes 2-3-0-1), which overrides the field's endianness.
+ */
+function fieldUsesPdpByteOrder(field: Field | any): boolean {
+  return (field.type === "uint32" || field.type === "int32") && field.byte_order === "pdp";
+}
+
+/** Bits in the storage type of an integer field with `signed_repr: "offset_binary"`, or 0 */
+function offsetBinaryBits(field: Field | any): number {
+  if (field.signed_repr !== "offset_binary") return 0;
+  return field.type === "uint8" ? 8 : field.type === "uint16" ? 16 : 32;
+}
+
+/**
+ * Narrowest signed Rust type holding every value an offset binary field can
+ * decode to, `-bias ..= 2^bits - 1 - bias`
+ */
+function offsetBinaryRustType(field: any): string {
+  const bits = offsetBinaryBits(field);
+  const min = -field.bias;
+  const max = 2 ** bits - 1 - field.bias;
+  for (const width of [8, 16, 32]) {
+    if (min >= -(2 ** (width - 1)) && max < 2 ** (width - 1)) return `i${width}`;
+  }
+  return "i64";
+}
+
+/**
+ * Field-aware wrapper around `emitEncoderWrite` that honors per-field byte
+ * order overrides before falling back to the plain endianness path.
+ */
+function emitFieldEncoderWrite(field: Field, value: string, rustEndianness: string, byteAligned: boolean): string {
+  if (offsetBinaryBits(field)) {
+    const storage = mapPrimitiveToRustType(field.type);
+    const stored = `${storage}::try_from(i64::from(${value}) + ${(field as any).bias}).map_err(|_| BinSchemaError::InvalidValue(format!("${field.name} is {}, outside its offset binary range", ${value})))?`;
+    return emitEncoderWrite(field.type, stored, rustEndianness, byteAligned);
+  }
+  if (fieldUsesPdpByteOrder(field)) {
+    return field.type === "int32"
+      ? `encoder.write_uint32_pdp(${value} as u32);`
+      : `encoder.write_uint32_pdp(${value});`;
+  }
+  return emitEncoderWrite(field.type, value, rustEndianness, byteAligned);
+}
+
+/**
+ * Field-aware wrapper around `emitDecoderRead` (see `emitFieldEncoderWrite`).
+ */
+function emitFieldDecoderRead(field: Field, rustEndianness: string, byteAligned: boolean): string {
+  if (offsetBinaryBits(field)) {
+    // Every stored value fits offsetBinaryRustType, so the cast never truncates
+    const rustType = offsetBinaryRustType(field);
+    const value = `i64::from(${emitDecoderRead(field.type, rustEndianness, byteAligned)}) - ${(field as any).bias}`;
+    return rustType === "i64" ? value : `(${value}) as ${rustType}`;
+  }
+  if (fieldUsesPdpByteOrder(field)) {
+    return field.type === "int32" ? `decoder.read_uint32_pdp()? as i32` : `decoder.read_uint32_pdp()?`;
+  }
+  return emitDecoderRead(field.type, rustEndianness, byteAligned);
+}
+
 /**
  * Generates Rust encoder/decoder code from a binary schema.
  *
@@ -259,20 +471,142 @@ export function generateRust(
 ): GeneratedRustCode {
   const crateName = options?.crateName || "binschema_runtime";
 
-  // Pre-pass: monomorphize parameterized templates (e.g. Optional<T>) into
-  // concrete types so the rest of the generator only deals with plain names.
-  const schema = monomorphizeTemplates(schemaInput);
+  // Pre-pass: monomorphize parameterized templates (e.g. Optional<T>) and
+  // lift inline groups into concrete types so the rest of the generator only
+  // deals with plain names.
+  const schema = expandInlineGroups(monomorphizeTemplates(schemaInput));
 
   // Verify the requested type exists
   if (!schema.types[typeName]) {
     throw new Error(`Type ${typeName} not found in schema`);
   }
 
+  rustNameOverrides = collectRustNameOverrides(schema);
+
+  const lines: string[] = generateRustPrelude(crateName);
+
+  // Determine default endianness and bit order
+  const defaultEndianness = schema.config?.endianness || "big_endian";
+  const defaultBitOrder = schema.config?.bit_order || "msb_first";
+
+  lines.push(...generateSharedItems(schema, defaultEndianness, defaultBitOrder));
+
+  // Generate all types in the schema
+  for (const [name, typeDef] of Object.entries(schema.types)) {
+    lines.push(...generateTypeItems(name, typeDef, schema, defaultEndianness, defaultBitOrder));
+  }
+
+  if (options?.roundtripTests) {
+    const testItems = generateRoundtripTestItems(schema);
+    if (testItems.length > 0) {
+      lines.push(`#[cfg(test)]`);
+      lines.push(`mod roundtrip_tests {`);
+      lines.push(...testItems.map(line => line ? `    ${line}` : line));
+      lines.push(`}`);
+      lines.push(``);
+    }
+  }
+
+  return {
+    code: lines.join("\n"),
+    typeName,
+  };
+}
+
+/**
+ * Generates one Rust module per top-level schema type, keyed by file name.
+ *
+ * Enums synthesized for inline unions and bitfield sub-field structs belong to
+ * no single type, so they go to `shared.rs`. Each module imports exactly the
+ * items it references from its siblings, and `mod.rs` declares every module
+ * and re-exports its items, so callers see the same names as `generateRust`.
+ */
+export function generateRustModules(
+  schemaInput: BinarySchema,
+  options?: RustGeneratorOptions
+): Map<string, string> {
+  const crateName = options?.crateName || "binschema_runtime";
+  const schema = expandInlineGroups(monomorphizeTemplates(schemaInput));
+  rustNameOverrides = collectRustNameOverrides(schema);
+
+  const defaultEndianness = schema.config?.endianness || "big_endian";
+  const defaultBitOrder = schema.config?.bit_order || "msb_first";
+
+  // Module name -> generated items (without prelude or cross-module imports)
+  const bodies = new Map<string, string[]>();
+  const sharedItems = generateSharedItems(schema, defaultEndianness, defaultBitOrder);
+  if (sharedItems.length > 0) {
+    bodies.set("shared", sharedItems);
+  }
+  for (const [name, typeDef] of Object.entries(schema.types)) {
+    const moduleName = toRustModuleName(name);
+    if (moduleName === "mod" || bodies.has(moduleName)) {
+      throw new Error(`Type '${name}' maps to Rust module '${moduleName}', which is already taken`);
+    }
+    bodies.set(moduleName, generateTypeItems(name, typeDef, schema, defaultEndianness, defaultBitOrder));
+  }
+
+  // Items each module defines, for resolving references between modules
+  const definedIn = new Map<string, string>();
+  for (const [moduleName, body] of bodies) {
+    for (const line of body) {
+      const match = line.match(/^pub (?:struct|enum|type|trait) ([A-Za-z_][A-Za-z0-9_]*)/);
+      if (match) definedIn.set(match[1], moduleName);
+    }
+  }
+
+  const files = new Map<string, string>();
+  for (const [moduleName, body] of bodies) {
+    const code = body.join("\n");
+    const imports = new Map<string, string[]>();
+    for (const [item, owner] of definedIn) {
+      if (owner === moduleName || !new RegExp(`\\b${item}\\b`).test(code)) continue;
+      if (!imports.has(owner)) imports.set(owner, []);
+      imports.get(owner)!.push(item);
+    }
+
+    const lines = generateRustPrelude(crateName);
+    for (const owner of [...imports.keys()].sort()) {
+      lines.push(`#[allow(unused_imports)]`);
+      const items = imports.get(owner)!.sort();
+      lines.push(`use super::${owner}::${items.length === 1 ? items[0] : `{${items.join(", ")}}`};`);
+    }
+    if (imports.size > 0) lines.push(``);
+    lines.push(code);
+    files.set(`${moduleName}.rs`, lines.join("\n"));
+  }
+
+  const modLines: string[] = [];
+  for (const moduleName of bodies.keys()) {
+    modLines.push(`pub mod ${moduleName};`);
+  }
+  modLines.push(``);
+  for (const moduleName of bodies.keys()) {
+    modLines.push(`pub use ${moduleName}::*;`);
+  }
+
+  const testItems = options?.roundtripTests ? generateRoundtripTestItems(schema) : [];
+  if (testItems.length > 0) {
+    if (bodies.has("roundtrip_tests")) {
+      throw new Error(`Type module 'roundtrip_tests' collides with the generated roundtrip test module`);
+    }
+    modLines.push(``);
+    modLines.push(`#[cfg(test)]`);
+    modLines.push(`mod roundtrip_tests;`);
+    files.set("roundtrip_tests.rs", [`#![allow(dead_code)]`, ``, ...testItems].join("\n"));
+  }
+  files.set("mod.rs", modLines.join("\n") + "\n");
+
+  return files;
+}
+
+/** Crate-level attributes and runtime imports shared by every generated file */
+function generateRustPrelude(crateName: string): string[] {
   const lines: string[] = [];
 
   // Suppress common warnings for generated code. We blanket-allow clippy
-  // because the generator emits a fixed set of patterns (parent_fields
-  // mut HashMaps that may not get reassigned, redundant primitive casts in
+  // because the generator emits a fixed set of patterns (mut parent_fields
+  // maps that may not get reassigned, redundant primitive casts in
   // write_uint8 paths, etc.) that aren't worth contorting the generator
   // to dodge. Consumers can still run -D warnings against their own crate.
   lines.push(`#![allow(non_camel_case_types)]`);
@@ -280,17 +614,24 @@ export function generateRust(
   lines.push(`#![allow(unreachable_code)]`);
   lines.push(``);
 
-  // Determine default endianness and bit order
-  const defaultEndianness = schema.config?.endianness || "big_endian";
-  const defaultBitOrder = schema.config?.bit_order || "msb_first";
-
   // Use statement - allow unused since different schemas need different imports
   lines.push(`#[allow(unused_imports)]`);
   lines.push(`use ${crateName}::{BitStreamEncoder, BitStreamDecoder, Endianness, BitOrder, Result, BinSchemaError, EncodeContext, FieldValue};`);
   lines.push(`#[allow(unused_imports)]`);
   lines.push(`use std::collections::HashMap;`);
+  // Maps handed to EncodeContext / FieldValue::Items must be the runtime's
+  // own map type; aliased so it can't clash with a schema type named Map
+  lines.push(`#[allow(unused_imports)]`);
+  lines.push(`use ${crateName}::context::Map as FieldMap;`);
   lines.push(``);
 
+  return lines;
+}
+
+/** Enums for inline unions and structs for bitfield sub-fields, which no single type owns */
+function generateSharedItems(schema: BinarySchema, defaultEndianness: string, defaultBitOrder: string): string[] {
+  const lines: string[] = [];
+
   // Collect inline union types (choice and discriminated_union) and generate enums for them
   const unionEnums = collectInlineUnionTypes(schema);
   for (const [enumName, variantTypes] of Object.entries(unionEnums)) {
@@ -303,37 +644,258 @@ export function generateRust(
     lines.push(...generateBitfieldStruct(structName, bitfieldDef, defaultBitOrder));
   }
 
-  // Generate all types in the schema
-  for (const [name, typeDef] of Object.entries(schema.types)) {
-    // Convert type name to Rust PascalCase convention
-    const rustTypeName = toRustTypeName(name);
+  for (const [newtypeName, primitive] of collectNewtypes(schema)) {
+    lines.push(...generateNewtype(newtypeName, primitive));
+  }
 
-    // Check if this is a composite type (has sequence) or type alias
-    // IMPORTANT: Check for "variants" before "type" because discriminated unions have both
-    if ("sequence" in typeDef) {
-      // Composite type with fields - generate struct(s) and impl
-      const instances = (typeDef as any).instances || [];
-      lines.push(...generateStructs(rustTypeName, name, typeDef.sequence, schema, instances));
-      lines.push(...generateImpl(rustTypeName, name, typeDef.sequence, defaultEndianness, defaultBitOrder, schema, instances));
-    } else if (isEnumType(typeDef)) {
-      // Enum type - must check before "variants" since enum also has variants
-      lines.push(...generateRustEnumType(rustTypeName, typeDef as any, defaultEndianness, defaultBitOrder));
-    } else if ("variants" in typeDef) {
-      // Discriminated union type - must check before "type" since it has both
-      lines.push(...generateDiscriminatedUnion(rustTypeName, typeDef as any, defaultEndianness, defaultBitOrder, schema));
-    } else if ("type" in typeDef) {
-      // Type alias - generate wrapper struct
-      lines.push(...generateTypeAlias(rustTypeName, name, typeDef as any, defaultEndianness, defaultBitOrder, schema));
-    } else {
-      // Unknown type definition
-      throw new Error(`Unknown type definition for ${name}: ${JSON.stringify(typeDef)}`);
+  return lines;
+}
+
+/** Newtype name -> wrapped primitive type, from the `newtype` annotations on sequence fields */
+function collectNewtypes(schema: BinarySchema): Map<string, string> {
+  const newtypes = new Map<string, string>();
+  for (const typeDef of Object.values(schema.types)) {
+    for (const field of ((typeDef as any).sequence ?? []) as any[]) {
+      if (field.newtype) newtypes.set(field.newtype, field.type);
     }
   }
+  return newtypes;
+}
 
-  return {
-    code: lines.join("\n"),
-    typeName,
-  };
+/**
+ * A `#[repr(transparent)]` wrapper for fields annotated `newtype`. Fields of
+ * the newtype encode and decode as the wrapped primitive.
+ */
+function generateNewtype(name: string, primitive: string): string[] {
+  const inner = mapPrimitiveToRustType(primitive);
+  return [
+    `#[repr(transparent)]`,
+    `#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]`,
+    SERDE_DERIVE,
+    `pub struct ${name}(pub ${inner});`,
+    ``,
+    `impl From<${inner}> for ${name} {`,
+    `    fn from(value: ${inner}) -> Self {`,
+    `        ${name}(value)`,
+    `    }`,
+    `}`,
+    ``,
+    `impl From<${name}> for ${inner} {`,
+    `    fn from(value: ${name}) -> Self {`,
+    `        value.0`,
+    `    }`,
+    `}`,
+    ``,
+  ];
+}
+
+/** Generates the items (structs, enums, impls) for one top-level schema type */
+function generateTypeItems(name: string, typeDef: any, schema: BinarySchema, defaultEndianness: string, defaultBitOrder: string): string[] {
+  return [
+    ...generateTypeDefinitionItems(name, typeDef, schema, defaultEndianness, defaultBitOrder),
+    ...generateDecodableImpl(toRustTypeName(name), defaultBitOrder),
+  ];
+}
+
+/**
+ * Implements the runtime's `Decodable` for a top-level type by forwarding to
+ * its inherent `decode_with_decoder`, so generic callers such as fuzz targets
+ * can use `try_decode::<T>`. For split types `name` is the alias of the Output
+ * struct, which is what decoding produces.
+ */
+function generateDecodableImpl(name: string, defaultBitOrder: string): string[] {
+  return [
+    `impl binschema_runtime::Decodable for ${name} {`,
+    `    const BIT_ORDER: BitOrder = BitOrder::${mapBitOrder(defaultBitOrder)};`,
+    ``,
+    `    fn decode_with_decoder(decoder: &mut BitStreamDecoder<'_>) -> Result<Self> {`,
+    `        ${name}::decode_with_decoder(decoder)`,
+    `    }`,
+    `}`,
+    ``,
+  ];
+}
+
+/** The type's own structs, enums and inherent impls */
+function generateTypeDefinitionItems(name: string, typeDef: any, schema: BinarySchema, defaultEndianness: string, defaultBitOrder: string): string[] {
+  // Convert type name to Rust PascalCase convention
+  const rustTypeName = toRustTypeName(name);
+
+  // Check if this is a composite type (has sequence) or type alias
+  // IMPORTANT: Check for "variants" before "type" because discriminated unions have both
+  if ("sequence" in typeDef) {
+    // Composite type with fields - generate struct(s) and impl
+    const instances = (typeDef as any).instances || [];
+    // Struct keeps declaration order; encode/decode follow wire_order when given
+    return [
+      ...generateStructs(rustTypeName, name, typeDef.sequence, schema, instances),
+      ...generateImpl(rustTypeName, name, wireOrderedSequence(name, typeDef), defaultEndianness, defaultBitOrder, schema, instances),
+      ...(typeDef.builder
+        ? generateBuilder(rustTypeName, name, typeDef.sequence, schema)
+        : typeHasDefault(name, schema) ? generateDefaultingBuilder(rustTypeName, name, typeDef.sequence, schema) : []),
+    ];
+  } else if (isEnumType(typeDef)) {
+    // Enum type - must check before "variants" since enum also has variants
+    return generateRustEnumType(rustTypeName, typeDef as any, defaultEndianness, defaultBitOrder);
+  } else if (isFlagsType(typeDef)) {
+    // Flags type - bitflags-style newtype over the storage integer
+    return generateRustFlagsType(rustTypeName, typeDef as any, defaultEndianness, defaultBitOrder);
+  } else if (isTlvType(typeDef)) {
+    // TLV option type - enum over the known tags plus an Unknown catch-all
+    return generateRustTlvType(rustTypeName, typeDef as any, defaultEndianness, defaultBitOrder, schema);
+  } else if ("variants" in typeDef) {
+    // Discriminated union type - must check before "type" since it has both
+    return generateDiscriminatedUnion(rustTypeName, typeDef as any, defaultEndianness, defaultBitOrder, schema);
+  } else if ("type" in typeDef) {
+    // Type alias - generate wrapper struct
+    return generateTypeAlias(rustTypeName, name, typeDef as any, defaultEndianness, defaultBitOrder, schema);
+  }
+  // Unknown type definition
+  throw new Error(`Unknown type definition for ${name}: ${JSON.stringify(typeDef)}`);
+}
+
+// ===== Roundtrip Property Tests =====
+
+/**
+ * Rust expression building a random value for `field`, or null when the
+ * roundtrip tests can't construct one. Only shapes whose decode is the exact
+ * inverse of encode are covered: plain numbers, bools, variable-length strings,
+ * counted or fixed arrays, enums and nested types that are themselves covered.
+ */
+function roundtripValueExpr(field: any, schema: BinarySchema, covered: Set<string>): string | null {
+  if (field.conditional || field.repeat !== undefined) return null;
+  if (field.newtype) {
+    const inner = roundtripValueExpr({ ...field, newtype: undefined }, schema, covered);
+    return inner === null ? null : `${field.newtype}(${inner})`;
+  }
+  if (offsetBinaryBits(field)) {
+    const value = `rng.below(${2 ** offsetBinaryBits(field)}) as i64 - ${field.bias}`;
+    const rustType = offsetBinaryRustType(field);
+    return rustType === "i64" ? value : `(${value}) as ${rustType}`;
+  }
+  switch (field.type) {
+    case "uint8": return "rng.next() as u8";
+    case "uint16": return "rng.next() as u16";
+    case "uint32": return "rng.next() as u32";
+    case "uint64": return "rng.next()";
+    case "int8": return "rng.next() as i8";
+    case "int16": return "rng.next() as i16";
+    case "int32": return "rng.next() as i32";
+    case "int64": return "rng.next() as i64";
+    // Multiples of 1/8 survive the float round trip exactly
+    case "float32": return "rng.below(1_000_000) as f32 / 8.0";
+    case "float64": return "rng.below(1_000_000) as f64 / 8.0";
+    case "bool": return "rng.below(2) == 1";
+    case "string": {
+      if (field.kind !== "length_prefixed" && field.kind !== "null_terminated") return null;
+      const bytesPerChar = field.encoding === "utf16" ? 2 : 1;
+      const maxChars = Math.min(16, Math.floor((field.max_length ?? Infinity) / bytesPerChar));
+      return `rng.text(${maxChars})`;
+    }
+    case "array": {
+      const item = roundtripValueExpr(field.items ?? {}, schema, covered);
+      if (item === null) return null;
+      if (field.kind === "fixed") return `(0..${field.length}).map(|_| ${item}).collect()`;
+      if (field.kind !== "length_prefixed" || !["uint8", "uint16", "uint32", "uint64"].includes(field.length_type)) return null;
+      return `(0..rng.below(9)).map(|_| ${item}).collect()`;
+    }
+    default: {
+      const typeDef = schema.types[field.type];
+      if (!typeDef) return null;
+      if (isEnumType(typeDef)) {
+        const variants = Object.keys((typeDef as any).variants).map(v => `${toRustTypeName(field.type)}::${toRustTypeName(v)}`);
+        return `[${variants.join(", ")}][rng.below(${variants.length}) as usize]`;
+      }
+      return covered.has(field.type) ? `${toRustTypeName(field.type)}::arbitrary(rng)` : null;
+    }
+  }
+}
+
+/**
+ * Generates the body of the `roundtrip_tests` module: a dependency-free
+ * xorshift generator, an `Arbitrary` impl per covered sequence type, and one
+ * test per type asserting `decode(encode(v)) == v` over a batch of random values.
+ * Types the generator can't build values for are skipped. Returns an empty
+ * list when no type is covered.
+ */
+function generateRoundtripTestItems(schema: BinarySchema): string[] {
+  // Fixpoint: a type is covered once all of its fields are
+  const covered = new Set<string>();
+  const candidates = Object.entries(schema.types).filter(([name, typeDef]: [string, any]) =>
+    "sequence" in typeDef && !typeDef.instances?.length && !typeDef.endianness_marker && !typeNeedsInputOutputSplit(name, schema)
+  );
+  let changed = true;
+  while (changed) {
+    changed = false;
+    for (const [name, typeDef] of candidates) {
+      if (covered.has(name)) continue;
+      const fields = ((typeDef as any).sequence as any[]).filter(f => f.type !== "padding");
+      if (fields.every(f => f.name && roundtripValueExpr(f, schema, covered) !== null)) {
+        covered.add(name);
+        changed = true;
+      }
+    }
+  }
+  if (covered.size === 0) return [];
+
+  const lines: string[] = [];
+  lines.push(`use super::*;`);
+  lines.push(``);
+  lines.push(`/// xorshift64* generator, so the tests need no extra dependencies`);
+  lines.push(`struct Rng(u64);`);
+  lines.push(``);
+  lines.push(`impl Rng {`);
+  lines.push(`    fn next(&mut self) -> u64 {`);
+  lines.push(`        self.0 ^= self.0 >> 12;`);
+  lines.push(`        self.0 ^= self.0 << 25;`);
+  lines.push(`        self.0 ^= self.0 >> 27;`);
+  lines.push(`        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    fn below(&mut self, n: u64) -> u64 {`);
+  lines.push(`        self.next() % n`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    fn text(&mut self, max_len: u64) -> String {`);
+  lines.push(`        let len = self.below(max_len + 1);`);
+  lines.push(`        (0..len).map(|_| (b'a' + self.below(26) as u8) as char).collect()`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+  lines.push(`trait Arbitrary: Sized {`);
+  lines.push(`    fn arbitrary(rng: &mut Rng) -> Self;`);
+  lines.push(`}`);
+  lines.push(``);
+  lines.push(`const CASES: usize = 256;`);
+
+  for (const [name, typeDef] of candidates) {
+    if (!covered.has(name)) continue;
+    const rustName = toRustTypeName(name);
+    lines.push(``);
+    lines.push(`impl Arbitrary for ${rustName} {`);
+    lines.push(`    fn arbitrary(rng: &mut Rng) -> Self {`);
+    lines.push(`        Self {`);
+    for (const field of (typeDef as any).sequence as any[]) {
+      if (field.type === "padding") continue;
+      lines.push(`            ${toRustFieldName(field.name)}: ${roundtripValueExpr(field, schema, covered)},`);
+    }
+    lines.push(`        }`);
+    lines.push(`    }`);
+    lines.push(`}`);
+    lines.push(``);
+    lines.push(`#[test]`);
+    lines.push(`fn ${toRustModuleName(name)}_roundtrips() {`);
+    lines.push(`    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);`);
+    lines.push(`    for _ in 0..CASES {`);
+    lines.push(`        let value = ${rustName}::arbitrary(&mut rng);`);
+    lines.push(`        let bytes = value.encode().unwrap();`);
+    lines.push(`        assert_eq!(${rustName}::decode(&bytes).unwrap(), value, "bytes: {:02x?}", bytes);`);
+    lines.push(`    }`);
+    lines.push(`}`);
+  }
+  lines.push(``);
+
+  return lines;
 }
 
 // ===== Selector Parsing Helpers =====
@@ -837,6 +1399,7 @@ function variantTypeNeedsEncodeContext(typeName: string, schema: BinarySchema):
  * Get the Rust type for a FieldValue conversion based on field type
  */
 function getFieldValueConversion(field: Field): string {
+  if (offsetBinaryBits(field)) return `FieldValue::${offsetBinaryRustType(field).toUpperCase()}`;
   const fieldType = field.type;
   switch (fieldType) {
     case "uint8": return "FieldValue::U8";
@@ -850,6 +1413,7 @@ function getFieldValueConversion(field: Field): string {
     case "int64": return "FieldValue::I64";
     case "float32": return "FieldValue::F32";
     case "float64": return "FieldValue::F64";
+    case "custom_float": return "FieldValue::F64";
     case "string": return "FieldValue::String";
     case "array": return "FieldValue::Bytes";
     case "bytes": return "FieldValue::Bytes";
@@ -1138,7 +1702,8 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
   // Just generate encode/decode that handles the string with its specific options
   if (typeDef.type === "string") {
     // Generate a newtype wrapper for string
-    lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+    lines.push(`#[derive(Debug, Clone, PartialEq, Default)]`);
+    lines.push(SERDE_DERIVE);
     lines.push(`pub struct ${name}(pub std::string::String);`);
     lines.push(``);
     lines.push(`impl ${name} {`);
@@ -1202,28 +1767,43 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
     const targetNeedsSplit = targetIsComposite && typeNeedsInputOutputSplit(targetType, schema);
     const targetDecodeName = targetNeedsSplit ? `${targetRustTypeName}Output` : targetRustTypeName;
 
-    // Determine pointer marker bits based on storage type
-    let markerBits: string;
+    // Pointer marker bits: schema-configured pointer_flag, else the top two bits outside the mask.
+    // Offsets that don't fit the mask are an error rather than a truncated pointer.
+    const markerBits = backReferencePointerFlag(typeDef);
+    const isDnsPointer = storage === "uint16" && parseInt(offsetMask, 16) === 0x3FFF && parseInt(markerBits, 16) === 0xC000;
+    const pointerExpr = isDnsPointer
+      ? `binschema_runtime::encode_compression_pointer(offset)?`
+      : `binschema_runtime::encode_pointer(offset, ${offsetMask}, ${markerBits})?`;
     let writeMethod: string;
     switch (storage) {
       case "uint8":
-        markerBits = "0xC0";
-        writeMethod = `encoder.write_uint8(${markerBits}u8 | (offset as u8 & ${offsetMask}u8));`;
+        writeMethod = `encoder.write_uint8(${pointerExpr} as u8);`;
         break;
       case "uint32":
-        markerBits = "0xC0000000";
-        writeMethod = `encoder.write_uint32(${markerBits}u32 | (offset as u32 & ${offsetMask}u32), Endianness::${rustEndianness});`;
+        writeMethod = `encoder.write_uint32(${pointerExpr} as u32, Endianness::${rustEndianness});`;
         break;
       case "uint16":
       default:
-        markerBits = "0xC000";
-        writeMethod = `encoder.write_uint16(${markerBits}u16 | (offset as u16 & ${offsetMask}u16), Endianness::${rustEndianness});`;
+        writeMethod = isDnsPointer
+          ? `encoder.write_uint16(${pointerExpr}, Endianness::${rustEndianness});`
+          : `encoder.write_uint16(${pointerExpr} as u16, Endianness::${rustEndianness});`;
         break;
     }
 
-    // Generate a newtype wrapper for back_reference
+    // Generate a newtype wrapper for back_reference. With preserve_pointer
+    // the storage integer as read rides along and is re-emitted as is.
+    const preservePointer = typeDef.preserve_pointer === true;
+    const storageRustType = storage === "uint8" ? "u8" : storage === "uint32" ? "u32" : "u16";
+    if (preservePointer) {
+      lines.push(`/// \`.1\` holds the pointer as decoded. While it is set, encode writes it back`);
+      lines.push(`/// verbatim and ignores \`.0\`; set it to \`None\` to have the encoder pick a`);
+      lines.push(`/// pointer, e.g. after an edit moves the data it points to.`);
+    }
     lines.push(`#[derive(Debug, Clone, PartialEq)]`);
-    lines.push(`pub struct ${name}(pub ${targetDecodeName});`);
+    lines.push(SERDE_DERIVE);
+    lines.push(preservePointer
+      ? `pub struct ${name}(pub ${targetDecodeName}, pub Option<${storageRustType}>);`
+      : `pub struct ${name}(pub ${targetDecodeName});`);
     lines.push(``);
     lines.push(`impl ${name} {`);
 
@@ -1247,6 +1827,13 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
     lines.push(``);
 
     lines.push(`    pub fn encode_into_with_context(&self, encoder: &mut BitStreamEncoder, ctx: &EncodeContext) -> Result<()> {`);
+    if (preservePointer) {
+      lines.push(`        if let Some(reference_value) = self.1 {`);
+      lines.push(`            ${storage === "uint8" ? "encoder.write_uint8(reference_value);" : `encoder.write_${storage}(reference_value, Endianness::${rustEndianness});`}`);
+      lines.push(`            return Ok(());`);
+      lines.push(`        }`);
+      lines.push(``);
+    }
     lines.push(`        // Encode target value to get bytes for dict lookup`);
     lines.push(`        let target_bytes = self.0.encode()?;`);
     lines.push(``);
@@ -1277,6 +1864,7 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
 
     // Generate decode_with_decoder method
     lines.push(`    pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {`);
+    lines.push(`        let pointer_pos = decoder.position();`);
     lines.push(`        // Read the reference value (${storage})`);
 
     // Read the storage value
@@ -1294,6 +1882,7 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
         lines.push(`        let reference_value = decoder.read_uint16(Endianness::${rustEndianness})?;`);
     }
 
+    lines.push(...generatePointerFlagCheck(typeDef, "        "));
     lines.push(`        let offset = (reference_value & ${offsetMask}) as usize;`);
     lines.push(``);
     lines.push(`        // Save current position and seek to the referenced offset`);
@@ -1306,6 +1895,7 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
       lines.push(`        decoder.seek(offset)?;`);
     }
 
+    lines.push(`        decoder.record_pointer(pointer_pos, decoder.position());`);
     lines.push(``);
     lines.push(`        // Decode the target type at the referenced position`);
     lines.push(`        let value = ${targetDecodeName}::decode_with_decoder(decoder)?;`);
@@ -1313,7 +1903,7 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
     lines.push(`        // Restore position`);
     lines.push(`        decoder.seek(saved_pos)?;`);
     lines.push(``);
-    lines.push(`        Ok(Self(value))`);
+    lines.push(preservePointer ? `        Ok(Self(value, Some(reference_value)))` : `        Ok(Self(value))`);
     lines.push(`    }`);
     lines.push(`}`);
     lines.push(``);
@@ -1338,7 +1928,7 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
     lines.push(...generateImpl(name, name, [field], defaultEndianness, defaultBitOrder, schema));
   } else {
     // Simple wrappers (primitives, other type aliases) don't need Input/Output
-    lines.push(...generateSimpleStruct(name, [field], schemaTypeName));
+    lines.push(...generateSimpleStruct(name, [field], schema, schemaTypeName));
     lines.push(...generateSimpleImpl(name, schemaTypeName, [field], defaultEndianness, defaultBitOrder, schema));
 
     // Ergonomic impls for bytes-kind aliases — see comment on the string
@@ -1352,14 +1942,16 @@ function generateTypeAlias(name: string, schemaTypeName: string, typeDef: any, d
   return lines;
 }
 
-/**
- * Check if a type needs Input/Output suffix (i.e., is composite or alias to composite)
- */
 /**
  * Determines if a sequence type needs separate Input/Output structs.
  * Returns true only when the type's sequence contains fields with computed or const values,
  * meaning Input (for encoding, excludes computed/const) differs from Output (includes all).
  * Types without computed/const fields get a single unified struct.
+ *
+ * The split is what keeps callers from supplying values the encoder derives
+ * itself: a length or checksum set by hand could disagree with the data it
+ * describes. `From<FooOutput> for FooInput` drops those fields again, so a
+ * decoded value can be edited and re-encoded, and `Foo` aliases `FooOutput`.
  */
 function typeNeedsInputOutputSplit(typeName: string, schema: BinarySchema): boolean {
   const typeDef = schema.types[typeName];
@@ -1385,6 +1977,9 @@ function typeNeedsInputOutputSplit(typeName: string, schema: BinarySchema): bool
   return false;
 }
 
+/**
+ * Check if a type needs Input/Output suffix (i.e., is composite or alias to composite)
+ */
 function typeNeedsInputOutputSuffix(typeName: string, schema: BinarySchema): boolean {
   const typeDef = schema.types[typeName];
   if (!typeDef) return false;
@@ -1410,7 +2005,11 @@ function typeNeedsInputOutputSuffix(typeName: string, schema: BinarySchema): boo
 }
 
 /**
- * Generates a Rust enum type with repr and encode/decode support
+ * Generates a Rust enum type with repr and encode/decode support.
+ *
+ * With `on_unknown_enum: "keep_raw"` the enum gains a `Raw(u64)` variant that
+ * holds discriminants outside the declared set, so decoding never fails on
+ * them and re-encoding writes the original value back.
  */
 function generateRustEnumType(name: string, typeDef: any, defaultEndianness: string, defaultBitOrder: string): string[] {
   const lines: string[] = [];
@@ -1418,29 +2017,39 @@ function generateRustEnumType(name: string, typeDef: any, defaultEndianness: str
   const repr = typeDef.repr as string;
   const bitOrder = mapBitOrder(defaultBitOrder);
   const rustRepr = repr === "uint8" ? "u8" : repr === "uint16" ? "u16" : "u32";
+  const keepRaw = typeDef.on_unknown_enum === "keep_raw";
+  const valueExpr = keepRaw ? "val" : `*self as ${rustRepr}`;
 
   // Determine read/write methods based on repr
   let writeExpr: string;
   let readExpr: string;
   if (repr === "uint8") {
-    writeExpr = `encoder.write_uint8(*self as u8);`;
+    writeExpr = `encoder.write_uint8(${valueExpr});`;
     readExpr = `decoder.read_uint8()?`;
   } else if (repr === "uint16") {
     const endianness = mapEndianness(defaultEndianness);
-    writeExpr = `encoder.write_uint16(*self as u16, Endianness::${endianness});`;
+    writeExpr = `encoder.write_uint16(${valueExpr}, Endianness::${endianness});`;
     readExpr = `decoder.read_uint16(Endianness::${endianness})?`;
   } else {
     const endianness = mapEndianness(defaultEndianness);
-    writeExpr = `encoder.write_uint32(*self as u32, Endianness::${endianness});`;
+    writeExpr = `encoder.write_uint32(${valueExpr}, Endianness::${endianness});`;
     readExpr = `decoder.read_uint32(Endianness::${endianness})?`;
   }
 
-  // Enum definition
+  // Enum definition. The data-carrying Raw variant rules out explicit
+  // discriminants, so keep_raw enums map to their values through value().
   lines.push(`#[derive(Debug, Clone, Copy, PartialEq, Eq)]`);
-  lines.push(`#[repr(${rustRepr})]`);
+  lines.push(SERDE_DERIVE);
+  if (!keepRaw) {
+    lines.push(`#[repr(${rustRepr})]`);
+  }
   lines.push(`pub enum ${name} {`);
   for (const [variantName, value] of Object.entries(variants)) {
-    lines.push(`    ${toRustTypeName(variantName)} = ${value},`);
+    lines.push(keepRaw ? `    ${toRustTypeName(variantName)},` : `    ${toRustTypeName(variantName)} = ${value},`);
+  }
+  if (keepRaw) {
+    lines.push(`    /// A discriminant not declared in the schema, kept as read`);
+    lines.push(`    Raw(u64),`);
   }
   lines.push(`}`);
   lines.push(``);
@@ -1454,11 +2063,28 @@ function generateRustEnumType(name: string, typeDef: any, defaultEndianness: str
   for (const [variantName, value] of Object.entries(variants)) {
     lines.push(`            ${value} => Ok(${name}::${toRustTypeName(variantName)}),`);
   }
-  lines.push(`            _ => Err(BinSchemaError::InvalidValue(format!("invalid ${name} value: {}", val))),`);
+  if (keepRaw) {
+    lines.push(`            _ => Ok(${name}::Raw(val as u64)),`);
+  } else {
+    lines.push(`            _ => Err(BinSchemaError::InvalidVariant(val as u64)),`);
+  }
   lines.push(`        }`);
   lines.push(`    }`);
   lines.push(``);
 
+  // value: the discriminant as written on the wire, including a raw one
+  if (keepRaw) {
+    lines.push(`    pub fn value(&self) -> u64 {`);
+    lines.push(`        match self {`);
+    for (const [variantName, value] of Object.entries(variants)) {
+      lines.push(`            ${name}::${toRustTypeName(variantName)} => ${value},`);
+    }
+    lines.push(`            ${name}::Raw(val) => *val,`);
+    lines.push(`        }`);
+    lines.push(`    }`);
+    lines.push(``);
+  }
+
   // encode
   lines.push(`    pub fn encode(&self) -> Result<Vec<u8>> {`);
   lines.push(`        let mut encoder = BitStreamEncoder::new(BitOrder::${bitOrder});`);
@@ -1469,6 +2095,11 @@ function generateRustEnumType(name: string, typeDef: any, defaultEndianness: str
 
   // encode_into
   lines.push(`    pub fn encode_into(&self, encoder: &mut BitStreamEncoder) -> Result<()> {`);
+  if (keepRaw) {
+    // A hand-built Raw value may not fit the repr
+    lines.push(`        let val = ${rustRepr}::try_from(self.value())`);
+    lines.push(`            .map_err(|_| BinSchemaError::InvalidValue(format!("${name} value {} does not fit in ${rustRepr}", self.value())))?;`);
+  }
   lines.push(`        ${writeExpr}`);
   lines.push(`        Ok(())`);
   lines.push(`    }`);
@@ -1494,21 +2125,246 @@ function generateRustEnumType(name: string, typeDef: any, defaultEndianness: str
 }
 
 /**
- * Generates a discriminated union as a Rust enum
- * Uses Output types for variants that have Input/Output separation
- */
-/**
- * Post-emission helper: if the body of a match arm (or any block) doesn't
- * reference its bound variable name, rewrite the binding to `_` so rustc
- * doesn't warn about an unused variable.
- *
- * `headerIdx` is the index of the line containing `Variant(v) => {` (or
- * similar `fn from(v: T)` etc.). The body is `lines[headerIdx+1 ..]` —
- * we scan up to the current end of the buffer.
- *
- * `varName` defaults to `"v"`. Pattern: matches `\bvarName\b` in the body.
+ * Generates a flags type as a bitflags-style newtype over its storage integer.
+ * Each named flag becomes an associated constant; bits without a name are kept
+ * so that re-encoding a decoded value is lossless.
  */
-function rewriteBindingIfUnused(
+function generateRustFlagsType(name: string, typeDef: any, defaultEndianness: string, defaultBitOrder: string): string[] {
+  const lines: string[] = [];
+  const flags = typeDef.flags as Record<string, number>;
+  const storage = typeDef.storage as string;
+  const bitOrder = mapBitOrder(defaultBitOrder);
+  const rustRepr = storage === "uint8" ? "u8" : storage === "uint16" ? "u16" : "u32";
+
+  let writeExpr: string;
+  let readExpr: string;
+  if (storage === "uint8") {
+    writeExpr = `encoder.write_uint8(self.0);`;
+    readExpr = `decoder.read_uint8()?`;
+  } else {
+    const endianness = mapEndianness(defaultEndianness);
+    const width = storage === "uint16" ? "16" : "32";
+    writeExpr = `encoder.write_uint${width}(self.0, Endianness::${endianness});`;
+    readExpr = `decoder.read_uint${width}(Endianness::${endianness})?`;
+  }
+
+  lines.push(`#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]`);
+  lines.push(SERDE_DERIVE);
+  lines.push(`pub struct ${name}(${rustRepr});`);
+  lines.push(``);
+
+  lines.push(`impl ${name} {`);
+  for (const [flagName, bit] of Object.entries(flags)) {
+    lines.push(`    pub const ${flagName.toUpperCase()}: Self = Self(1 << ${bit});`);
+  }
+  lines.push(``);
+  lines.push(`    pub const fn empty() -> Self {`);
+  lines.push(`        Self(0)`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub const fn from_bits(bits: ${rustRepr}) -> Self {`);
+  lines.push(`        Self(bits)`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub const fn bits(&self) -> ${rustRepr} {`);
+  lines.push(`        self.0`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub const fn contains(&self, other: Self) -> bool {`);
+  lines.push(`        (self.0 & other.0) == other.0`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn insert(&mut self, other: Self) {`);
+  lines.push(`        self.0 |= other.0;`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn remove(&mut self, other: Self) {`);
+  lines.push(`        self.0 &= !other.0;`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn set(&mut self, other: Self, value: bool) {`);
+  lines.push(`        if value {`);
+  lines.push(`            self.insert(other);`);
+  lines.push(`        } else {`);
+  lines.push(`            self.remove(other);`);
+  lines.push(`        }`);
+  lines.push(`    }`);
+  lines.push(``);
+
+  lines.push(`    pub fn encode(&self) -> Result<Vec<u8>> {`);
+  lines.push(`        let mut encoder = BitStreamEncoder::new(BitOrder::${bitOrder});`);
+  lines.push(`        self.encode_into(&mut encoder)?;`);
+  lines.push(`        Ok(encoder.finish())`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn encode_into(&self, encoder: &mut BitStreamEncoder) -> Result<()> {`);
+  lines.push(`        ${writeExpr}`);
+  lines.push(`        Ok(())`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn decode(bytes: &[u8]) -> Result<Self> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  lines.push(`        Self::decode_with_decoder(&mut decoder)`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {`);
+  lines.push(`        Ok(Self(${readExpr}))`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+
+  lines.push(`impl std::ops::BitOr for ${name} {`);
+  lines.push(`    type Output = Self;`);
+  lines.push(``);
+  lines.push(`    fn bitor(self, rhs: Self) -> Self {`);
+  lines.push(`        Self(self.0 | rhs.0)`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+
+  return lines;
+}
+
+/**
+ * Generates a TLV option type as a Rust enum: one variant per known tag plus
+ * an `Unknown { tag, value }` catch-all that keeps the raw bytes so unknown
+ * options round-trip. The length bounds each value; a known option whose
+ * value doesn't consume exactly `length` bytes is rejected.
+ */
+function generateRustTlvType(name: string, typeDef: any, defaultEndianness: string, defaultBitOrder: string, schema: BinarySchema): string[] {
+  const lines: string[] = [];
+  const options = typeDef.options as Array<{ name: string; tag: number; type: string }>;
+  const bitOrder = mapBitOrder(defaultBitOrder);
+  const endianness = mapEndianness(defaultEndianness);
+  const integerTypes: Record<string, string> = {
+    uint8: "u8", uint16: "u16", uint32: "u32", uint64: "u64",
+    int8: "i8", int16: "i16", int32: "i32", int64: "i64",
+  };
+  const tagRust = integerTypes[typeDef.tag_type];
+  const lengthRust = integerTypes[typeDef.length_type];
+
+  // Rust type carried by each known option
+  const valueRust = (valueType: string): string => {
+    if (integerTypes[valueType]) return integerTypes[valueType];
+    if (valueType === "bytes") return "Vec<u8>";
+    if (!schema.types[valueType]) {
+      throw new Error(`TLV option type '${valueType}' in '${name}' is not defined`);
+    }
+    const rustName = toRustTypeName(valueType);
+    return typeNeedsInputOutputSuffix(valueType, schema) ? `${rustName}Output` : rustName;
+  };
+
+  lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  lines.push(SERDE_DERIVE);
+  lines.push(`pub enum ${name} {`);
+  for (const option of options) {
+    lines.push(`    ${toRustTypeName(option.name)}(${valueRust(option.type)}),`);
+  }
+  lines.push(`    Unknown { tag: ${tagRust}, value: Vec<u8> },`);
+  lines.push(`}`);
+  lines.push(``);
+
+  lines.push(`impl ${name} {`);
+  lines.push(`    pub fn tag(&self) -> ${tagRust} {`);
+  lines.push(`        match self {`);
+  for (const option of options) {
+    lines.push(`            ${name}::${toRustTypeName(option.name)}(_) => ${option.tag},`);
+  }
+  lines.push(`            ${name}::Unknown { tag, .. } => *tag,`);
+  lines.push(`        }`);
+  lines.push(`    }`);
+  lines.push(``);
+
+  lines.push(`    pub fn encode(&self) -> Result<Vec<u8>> {`);
+  lines.push(`        let mut encoder = BitStreamEncoder::new(BitOrder::${bitOrder});`);
+  lines.push(`        self.encode_into(&mut encoder)?;`);
+  lines.push(`        Ok(encoder.finish())`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn encode_into(&self, encoder: &mut BitStreamEncoder) -> Result<()> {`);
+  lines.push(`        let value = match self {`);
+  for (const option of options) {
+    const variant = `${name}::${toRustTypeName(option.name)}(v)`;
+    if (integerTypes[option.type]) {
+      const write = emitEncoderWrite(option.type, "*v", endianness, false).replace(/^encoder\./, "inner.");
+      lines.push(`            ${variant} => {`);
+      lines.push(`                let mut inner = BitStreamEncoder::new(BitOrder::${bitOrder});`);
+      lines.push(`                ${write}`);
+      lines.push(`                inner.finish()`);
+      lines.push(`            }`);
+    } else if (option.type === "bytes") {
+      lines.push(`            ${variant} => v.clone(),`);
+    } else {
+      lines.push(`            ${variant} => v.encode()?,`);
+    }
+  }
+  lines.push(`            ${name}::Unknown { value, .. } => value.clone(),`);
+  lines.push(`        };`);
+  lines.push(`        let length = ${lengthRust}::try_from(value.len()).map_err(|_| {`);
+  lines.push(`            BinSchemaError::InvalidValue(format!("${name} value of {} bytes does not fit a ${typeDef.length_type} length", value.len()))`);
+  lines.push(`        })?;`);
+  lines.push(`        ${emitEncoderWrite(typeDef.tag_type, "self.tag()", endianness, false)}`);
+  lines.push(`        ${emitEncoderWrite(typeDef.length_type, "length", endianness, false)}`);
+  lines.push(`        for byte in &value {`);
+  lines.push(`            encoder.write_uint8(*byte);`);
+  lines.push(`        }`);
+  lines.push(`        Ok(())`);
+  lines.push(`    }`);
+  lines.push(``);
+
+  lines.push(`    pub fn decode(bytes: &[u8]) -> Result<Self> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  lines.push(`        Self::decode_with_decoder(&mut decoder)`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {`);
+  lines.push(`        let tag = ${emitDecoderRead(typeDef.tag_type, endianness, false)};`);
+  lines.push(`        let length = ${emitDecoderRead(typeDef.length_type, endianness, false)} as usize;`);
+  lines.push(`        let value = decoder.read_bytes_vec(length)?;`);
+  lines.push(`        let mut inner = BitStreamDecoder::new(&value, BitOrder::${bitOrder});`);
+  lines.push(`        let option = match tag {`);
+  for (const option of options) {
+    const variant = `${name}::${toRustTypeName(option.name)}`;
+    if (integerTypes[option.type]) {
+      const read = emitDecoderRead(option.type, endianness, false).replace(/^decoder\./, "inner.");
+      lines.push(`            ${option.tag} => ${variant}(${read}),`);
+    } else if (option.type === "bytes") {
+      lines.push(`            ${option.tag} => return Ok(${variant}(value)),`);
+    } else {
+      lines.push(`            ${option.tag} => ${variant}(${valueRust(option.type)}::decode_with_decoder(&mut inner)?),`);
+    }
+  }
+  lines.push(`            _ => return Ok(${name}::Unknown { tag, value }),`);
+  lines.push(`        };`);
+  lines.push(`        if inner.position() != value.len() {`);
+  lines.push(`            return Err(BinSchemaError::InvalidValue(format!(`);
+  lines.push(`                "${name} tag {} has {} value bytes but decoded {}", tag, value.len(), inner.position()`);
+  lines.push(`            )));`);
+  lines.push(`        }`);
+  lines.push(`        Ok(option)`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+
+  return lines;
+}
+
+/**
+ * Generates a discriminated union as a Rust enum
+ * Uses Output types for variants that have Input/Output separation
+ */
+/**
+ * Post-emission helper: if the body of a match arm (or any block) doesn't
+ * reference its bound variable name, rewrite the binding to `_` so rustc
+ * doesn't warn about an unused variable.
+ *
+ * `headerIdx` is the index of the line containing `Variant(v) => {` (or
+ * similar `fn from(v: T)` etc.). The body is `lines[headerIdx+1 ..]` —
+ * we scan up to the current end of the buffer.
+ *
+ * `varName` defaults to `"v"`. Pattern: matches `\bvarName\b` in the body.
+ */
+function rewriteBindingIfUnused(
   lines: string[],
   headerIdx: number,
   varName: string = "v",
@@ -1599,6 +2455,7 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
 
   // Generate enum definition - variants wrap Output types for composite types, plain types otherwise
   lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  lines.push(SERDE_DERIVE);
   lines.push(`pub enum ${name} {`);
   for (const variant of variants) {
     const variantTypeName = toRustTypeName(variant.type);
@@ -1675,7 +2532,7 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
       if (variantHasNestedStructNeedingContext) {
         lines.push(`                // Build per-variant context for nested struct sub-fields`);
         variantFieldsLineIdx = lines.length;
-        lines.push(`                let mut variant_fields: HashMap<std::string::String, FieldValue> = HashMap::new();`);
+        lines.push(`                let mut variant_fields: FieldMap<std::string::String, FieldValue> = FieldMap::new();`);
         for (const vf of sequence) {
           const vfAny = vf as any;
           if (!vf.name || vf.type === "padding" || vfAny.computed || vfAny.const != null) continue;
@@ -1754,6 +2611,11 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
               lines.push(`                let ${fieldName}_computed = binschema_runtime::crc32(&${crcInputVar});`);
               lines.push(`                encoder.write_uint32(${fieldName}_computed, Endianness::${fieldEndianness});`);
               continue;
+            } else if (computed.type === "crc_of") {
+              const targetRust = toRustFieldName(target);
+              lines.push(`                let ${fieldName}_computed = ${crcOfExpr(computed, `&v.${targetRust}`)};`);
+              lines.push(...generateComputedFieldWrite(field, `${fieldName}_computed`, fieldEndianness, "                "));
+              continue;
             }
           }
           // Fall through for complex targets or other computed types - encode the field value as-is
@@ -1846,7 +2708,7 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
       // variant whose sole field is a const), rust would warn
       // `unused variable: v`. Rewrite the binding to `_`.
       rewriteBindingIfUnused(lines, variantArmHeaderIdx);
-      // The `variant_fields` HashMap may have nothing to .insert() if the
+      // The `variant_fields` map may have nothing to .insert() if the
       // variant's sequence is all const/computed/nested-struct fields, and
       // `variant_ctx` may go unused if no nested struct consumes it.
       if (variantFieldsLineIdx >= 0) {
@@ -1873,7 +2735,7 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
       lines.push(`                if let Some(dict) = ctx.compression_dict() {`);
       lines.push(`                    dict.borrow_mut().entry(bytes.clone()).or_insert(encoder.byte_offset());`);
       lines.push(`                }`);
-      lines.push(`                for b in bytes { encoder.write_uint8(b); }`);
+      lines.push(`                encoder.write_bytes(&bytes);`);
       lines.push(`            }`);
     } else {
       // Simple type - encode directly into encoder
@@ -1991,7 +2853,7 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
         lines.push(`        Err(binschema_runtime::BinSchemaError::InvalidVariant(value as u64))`);
       }
     }
-  } else if (discriminator.field) {
+  } else if (discriminator.field || discriminator.fields) {
     // Field-based discriminator - would need context from parent
     lines.push(`        // Field-based discriminator not yet fully supported`);
     lines.push(`        Err(binschema_runtime::BinSchemaError::NotImplemented("field-based discriminator".to_string()))`);
@@ -2004,6 +2866,42 @@ function generateDiscriminatedUnion(name: string, unionDef: any, defaultEndianne
   return lines;
 }
 
+/**
+ * Decode for a union selected by a combination of earlier fields
+ * (`discriminator: { fields: [...] }`). Each variant's `values` tuple becomes a
+ * match arm; a variant without `values` is the catch-all, otherwise an
+ * unmapped combination is an error. `decodeVariant` returns the lines that
+ * decode one variant, ending in the enum value expression.
+ */
+function generateMultiFieldDiscriminatorDecode(
+  discriminatorFields: string[],
+  variants: any[],
+  varName: string,
+  indent: string,
+  decodeVariant: (variant: any, indent: string) => string[]
+): string[] {
+  const lines: string[] = [];
+  const scrutinee = `(${discriminatorFields.map(f => toRustFieldName(f)).join(", ")})`;
+  lines.push(`${indent}let ${varName} = match ${scrutinee} {`);
+  for (const variant of variants.filter(v => Array.isArray(v.values))) {
+    lines.push(`${indent}    (${variant.values.join(", ")}) => {`);
+    lines.push(...decodeVariant(variant, `${indent}        `));
+    lines.push(`${indent}    }`);
+  }
+  const fallback = variants.find(v => !Array.isArray(v.values));
+  if (fallback) {
+    lines.push(`${indent}    _ => {`);
+    lines.push(...decodeVariant(fallback, `${indent}        `));
+    lines.push(`${indent}    }`);
+  } else {
+    lines.push(`${indent}    unmapped => return Err(binschema_runtime::BinSchemaError::InvalidValue(format!(`);
+    lines.push(`${indent}        "no variant for (${discriminatorFields.join(", ")}) = {:?}", unmapped`);
+    lines.push(`${indent}    ))),`);
+  }
+  lines.push(`${indent}};`);
+  return lines;
+}
+
 /**
  * Translates a condition expression from schema format to Rust
  * e.g., "value == 0x01" -> "value == 0x01"
@@ -2018,6 +2916,119 @@ function translateConditionToRust(condition: string): string {
   return rustCondition;
 }
 
+/**
+ * Resolve a (possibly dotted) discriminator path to the field it names.
+ * "header.rtype" walks into the sequence type of the sibling `header` field,
+ * which is how a shared common header selects the variant of a union body.
+ */
+function resolveDiscriminatorField(path: string, fields: Field[], schema: BinarySchema): Field | undefined {
+  const [head, ...rest] = path.split('.');
+  const field = fields.find((f: any) => f.name === head);
+  if (!field || rest.length === 0) return field;
+  const typeDef = typeof field.type === "string" ? schema.types?.[field.type] as any : undefined;
+  if (!typeDef || !Array.isArray(typeDef.sequence)) return undefined;
+  return resolveDiscriminatorField(rest.join('.'), typeDef.sequence, schema);
+}
+
+/**
+ * When the discriminator field is an enum, rewrite integer literals in a
+ * translated `when` condition into enum variant paths, since Rust enums
+ * can't be compared to raw integers.
+ */
+function rewriteEnumDiscriminatorLiterals(condition: string, path: string, fields: Field[], schema: BinarySchema): string {
+  const discField = resolveDiscriminatorField(path, fields, schema);
+  if (!discField || typeof discField.type !== 'string') return condition;
+  const discTypeDef = schema.types?.[discField.type];
+  if (!discTypeDef || !isEnumType(discTypeDef)) return condition;
+
+  const enumVariants = (discTypeDef as any).variants as Record<string, number>;
+  const reverseMap = new Map<number, string>();
+  for (const [name, value] of Object.entries(enumVariants)) {
+    reverseMap.set(value, name);
+  }
+  const rustEnumName = toRustTypeName(discField.type);
+  return condition.replace(/(==|!=)\s*(0x[0-9a-fA-F]+|\d+)/g, (match: string, op: string, numStr: string) => {
+    const variantName = reverseMap.get(Number(numStr));
+    return variantName !== undefined ? `${op} ${rustEnumName}::${toRustTypeName(variantName)}` : match;
+  });
+}
+
+/**
+ * Returns the sequence in wire order. Composite types may declare `wire_order`
+ * to encode/decode fields in a different order than the struct declares them;
+ * unnamed fields (padding) must not appear in a type that uses it.
+ */
+function wireOrderedSequence(typeName: string, typeDef: any): Field[] {
+  const sequence = typeDef.sequence as Field[];
+  const wireOrder: string[] | undefined = typeDef.wire_order;
+  if (!wireOrder) return sequence;
+
+  const byName = new Map<string, Field>();
+  for (const field of sequence) {
+    if (!field.name) {
+      throw new Error(`Type '${typeName}' uses wire_order but has an unnamed field`);
+    }
+    byName.set(field.name, field);
+  }
+  const unique = new Set(wireOrder);
+  if (unique.size !== wireOrder.length || wireOrder.length !== sequence.length || !wireOrder.every(n => byName.has(n))) {
+    throw new Error(`wire_order of type '${typeName}' must list each sequence field exactly once`);
+  }
+  return wireOrder.map(n => byName.get(n)!);
+}
+
+/**
+ * Names of sequence fields whose start offset is needed as an anchor, i.e.
+ * referenced by `relative_to` on a computed position_of field or an instance.
+ */
+function collectAnchorFieldNames(fields: Field[], instances?: any[]): Set<string> {
+  const anchors = new Set<string>();
+  for (const field of fields) {
+    const relativeTo = (field as any).computed?.relative_to;
+    if (relativeTo) anchors.add(relativeTo);
+  }
+  for (const instance of instances ?? []) {
+    if (instance.relative_to) anchors.add(instance.relative_to);
+  }
+  return anchors;
+}
+
+/**
+ * Decode-side checks for length_of fields: maps a target field name to the
+ * length field that must equal the target's decoded byte length. Only targets
+ * whose length_of value equals their wire span qualify (composites and
+ * eof_terminated byte data); targets already sized by that same field are
+ * consistent by construction and are skipped.
+ */
+function collectDecodedLengthChecks(fields: Field[], schema: BinarySchema): Map<string, { lengthField: string; offset: number }> {
+  const checks = new Map<string, { lengthField: string; offset: number }>();
+  fields.forEach((field, index) => {
+    const computed = (field as any).computed;
+    if (computed?.type !== "length_of" || !computed.target || computed.from_after_field) return;
+    const target = computed.target as string;
+    if (target.includes(".") || target.startsWith("../")) return;
+
+    const targetIndex = fields.findIndex((f) => f.name === target);
+    if (targetIndex <= index) return;
+    const targetField = fields[targetIndex] as any;
+    if (isFieldConditional(targetField) || targetField.length_field === field.name) return;
+
+    // Strings and byte arrays carrying their own prefix or terminator occupy
+    // more wire bytes than their length_of value, so only eof_terminated ones
+    // compare cleanly against the decoded span.
+    const targetType = targetField.type as string;
+    const isByteLike = targetType === "string" || targetType === "bytes" ||
+      (targetType === "array" && targetField.items?.type === "uint8");
+    const isByteLength =
+      (isByteLike && targetField.kind === "eof_terminated") ||
+      !!(schema.types?.[targetType] && "sequence" in schema.types[targetType]);
+    if (!isByteLength) return;
+
+    checks.set(target, { lengthField: field.name!, offset: computed.offset ?? 0 });
+  });
+  return checks;
+}
+
 /**
  * Check if field is conditional
  * Note: padding fields are never considered conditional (they don't have values)
@@ -2219,6 +3230,7 @@ function generateUnionEnum(enumName: string, variantTypes: string[], defaultEndi
 
   // Generate enum definition - variants wrap Output types for composite types, plain types otherwise
   lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  lines.push(SERDE_DERIVE);
   lines.push(`pub enum ${enumName} {`);
   for (const typeName of variantTypes) {
     const rustTypeName = toRustTypeName(typeName);
@@ -2305,7 +3317,7 @@ function generateUnionEnum(enumName: string, variantTypes: string[], defaultEndi
       if (variantHasNestedStructNeedingContext) {
         lines.push(`                // Build per-variant context for nested struct sub-fields`);
         inlineVariantFieldsLineIdx = lines.length;
-        lines.push(`                let mut variant_fields: HashMap<std::string::String, FieldValue> = HashMap::new();`);
+        lines.push(`                let mut variant_fields: FieldMap<std::string::String, FieldValue> = FieldMap::new();`);
         for (const vf of sequence) {
           const vfAny = vf as any;
           if (!vf.name || vf.type === "padding" || vfAny.computed || vfAny.const != null) continue;
@@ -2392,6 +3404,11 @@ function generateUnionEnum(enumName: string, variantTypes: string[], defaultEndi
               lines.push(`                let ${fieldName}_computed = binschema_runtime::crc32(&${crcInputVar});`);
               lines.push(`                encoder.write_uint32(${fieldName}_computed, Endianness::${fieldEndianness});`);
               continue;
+            } else if (computed.type === "crc_of") {
+              const targetRust = toRustFieldName(target);
+              lines.push(`                let ${fieldName}_computed = ${crcOfExpr(computed, `&v.${targetRust}`)};`);
+              lines.push(...generateComputedFieldWrite(field, `${fieldName}_computed`, fieldEndianness, "                "));
+              continue;
             }
           } else if (anyVariantNeedsEncodeContext && target) {
             // Complex target with parent refs or selectors - use context to compute
@@ -2453,12 +3470,13 @@ function generateUnionEnum(enumName: string, variantTypes: string[], defaultEndi
                 lines.push(`                    Some(array_val) => {`);
                 lines.push(`                        match array_val.get_nth_item_of_type("${filterType}", ${computedVarName}_corr_idx) {`);
                 if (fieldAccess) {
+                  const sourceEndianness = referencedFieldEndianness(fieldAccess, [filterType], schema, defaultEndianness);
                   lines.push(`                            Some(item_fields) => {`);
-                  lines.push(`                                item_fields.get("${fieldAccess}").map(|v| binschema_runtime::crc32(&v.to_bytes())).unwrap_or(0)`);
+                  lines.push(`                                item_fields.get("${fieldAccess}").map(|v| binschema_runtime::crc32(&${fieldValueBytes("v", sourceEndianness)})).unwrap_or(0)`);
                   lines.push(`                            },`);
                 } else {
                   lines.push(`                            Some(item_fields) => {`);
-                  lines.push(`                                item_fields.get("_encoded_size").map(|v| binschema_runtime::crc32(&v.to_bytes())).unwrap_or(0)`);
+                  lines.push(`                                item_fields.get("_encoded_size").map(|v| binschema_runtime::crc32(&${fieldValueBytes("v", defaultEndianness)})).unwrap_or(0)`);
                   lines.push(`                            },`);
                 }
                 lines.push(`                            None => 0,`);
@@ -2671,6 +3689,9 @@ function generateUnionEnum(enumName: string, variantTypes: string[], defaultEndi
     lines.push(`    pub fn decode_with_decoder_and_context(decoder: &mut BitStreamDecoder, ctx: Option<&HashMap<std::string::String, u64>>) -> Result<Self> {`);
   }
   lines.push(`        // Union type - try each variant in order until one succeeds`);
+  // If any variant ran out of input, more bytes could still make it match
+  lines.push(`        let mut truncated = false;`);
+  lines.push(`        let mut attempts: Vec<(&str, binschema_runtime::BinSchemaError)> = Vec::new();`);
 
   // Generate try-each-variant pattern
   for (let i = 0; i < variantTypes.length; i++) {
@@ -2685,17 +3706,25 @@ function generateUnionEnum(enumName: string, variantTypes: string[], defaultEndi
     const variantNeedsSplit = typeNeedsInputOutputSplit(typeName, schema);
     const decodeType = variantNeedsSplit ? `${rustTypeName}Output` : rustTypeName;
     if (variantNeedsContext && anyVariantNeedsContext) {
-      lines.push(`        if let Ok(v) = ${decodeType}::decode_with_decoder_and_context(decoder, ctx) {`);
+      lines.push(`        match decoder.strict(|d| ${decodeType}::decode_with_decoder_and_context(d, ctx)) {`);
     } else {
-      lines.push(`        if let Ok(v) = ${decodeType}::decode_with_decoder(decoder) {`);
+      lines.push(`        match decoder.strict(|d| ${decodeType}::decode_with_decoder(d)) {`);
     }
-    lines.push(`            return Ok(${enumName}::${rustTypeName}(v));`);
+    lines.push(`            Ok(v) => return Ok(${enumName}::${rustTypeName}(v)),`);
+    lines.push(`            Err(e) => {`);
+    lines.push(`                truncated |= matches!(e, binschema_runtime::BinSchemaError::UnexpectedEof);`);
+    lines.push(`                attempts.push(("${rustTypeName}", e));`);
+    lines.push(`            }`);
     lines.push(`        }`);
     if (i < variantTypes.length - 1) {
-      lines.push(`        decoder.seek(start_pos)?;`);
+      lines.push(`        decoder.rewind_to(start_pos);`);
     }
   }
-  lines.push(`        Err(binschema_runtime::BinSchemaError::InvalidVariant(0))`);
+  lines.push(`        Err(if truncated {`);
+  lines.push(`            binschema_runtime::BinSchemaError::UnexpectedEof`);
+  lines.push(`        } else {`);
+  lines.push(`            binschema_runtime::BinSchemaError::no_variant_matched("${enumName}", &attempts)`);
+  lines.push(`        })`);
   lines.push(`    }`);
   lines.push(`}`);
   lines.push(``);
@@ -2758,7 +3787,8 @@ function generateBitfieldStruct(structName: string, bitfieldDef: BitfieldDef, de
   const bitOrder = mapBitOrder(defaultBitOrder);
 
   // Generate struct definition
-  lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  lines.push(`#[derive(Debug, Clone, PartialEq, Default)]`);
+  lines.push(SERDE_DERIVE);
   lines.push(`pub struct ${structName} {`);
   for (const subField of bitfieldDef.fields) {
     const rustType = getBitfieldSubFieldType(subField.size);
@@ -2820,7 +3850,8 @@ function getBitfieldSubFieldType(size: number): string {
 function generateInputStruct(name: string, schemaTypeName: string, fields: Field[], schema: BinarySchema): string[] {
   const lines: string[] = [];
 
-  lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  lines.push(structDerive(sequenceHasDefault(fields, schema, new Set([schemaTypeName]))));
+  lines.push(SERDE_DERIVE);
   lines.push(`pub struct ${name}Input {`);
 
   for (const field of fields) {
@@ -2868,6 +3899,7 @@ function generateOutputStruct(name: string, schemaTypeName: string, fields: Fiel
   const lines: string[] = [];
 
   lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  lines.push(SERDE_DERIVE);
   lines.push(`pub struct ${name}Output {`);
 
   for (const field of fields) {
@@ -2936,7 +3968,10 @@ function generateBackwardCompatAlias(name: string): string[] {
 function generateUnifiedStruct(name: string, schemaTypeName: string, fields: Field[], schema: BinarySchema, instances?: any[]): string[] {
   const lines: string[] = [];
 
-  lines.push(`#[derive(Debug, Clone, PartialEq)]`);
+  // Instance fields are decode-only, so a type with them has no sensible default
+  const hasDefault = !instances?.length && sequenceHasDefault(fields, schema, new Set([schemaTypeName]));
+  lines.push(structDerive(hasDefault));
+  lines.push(SERDE_DERIVE);
   lines.push(`pub struct ${name} {`);
 
   for (const field of fields) {
@@ -2996,70 +4031,536 @@ function generateStructs(name: string, schemaTypeName: string, fields: Field[],
 }
 
 /**
- * Generates a simple Rust struct definition (for type aliases/wrappers)
- * This is used for wrapper structs that don't need Input/Output separation
+ * Generates `{Name}Builder` for a sequence type with `builder: true`. Each
+ * `with_<field>` setter checks the value against the field's schema
+ * constraints (bit width, length prefix, max_length, fixed length) and fails
+ * right there, so a bad value is reported where it was set rather than at
+ * encode time. `build()` fails if a required field was never set.
+ */
+function generateBuilder(name: string, schemaTypeName: string, fields: Field[], schema: BinarySchema): string[] {
+  const split = typeNeedsInputOutputSplit(schemaTypeName, schema);
+  const target = split ? `${name}Input` : name;
+  const builderFields = fields.filter(f => f.name && f.type && f.type !== "padding" && isInputField(f));
+  const lines: string[] = [];
+
+  lines.push(`/// Builds a [\`${target}\`] one field at a time, checking each value as it is set`);
+  lines.push(`#[derive(Debug, Clone, Default)]`);
+  lines.push(`pub struct ${name}Builder {`);
+  for (const field of builderFields) {
+    lines.push(`    ${toRustFieldName(field.name)}: Option<${builderValueType(name, schemaTypeName, field, schema, split)}>,`);
+  }
+  lines.push(`}`);
+  lines.push(``);
+
+  lines.push(`impl ${name}Builder {`);
+  lines.push(`    pub fn new() -> Self {`);
+  lines.push(`        Self::default()`);
+  lines.push(`    }`);
+  for (const field of builderFields) {
+    const rustName = toRustFieldName(field.name);
+    lines.push(``);
+    lines.push(`    pub fn with_${rustName.replace(/^r#/, "")}(mut self, value: ${builderValueType(name, schemaTypeName, field, schema, split)}) -> Result<Self> {`);
+    lines.push(...generateBuilderChecks(`${name}.${field.name}`, field, "        "));
+    lines.push(`        self.${rustName} = Some(value);`);
+    lines.push(`        Ok(self)`);
+    lines.push(`    }`);
+  }
+  lines.push(``);
+  lines.push(`    /// Fails if a field that isn't conditional was never set`);
+  lines.push(`    pub fn build(self) -> Result<${target}> {`);
+  lines.push(`        Ok(${target} {`);
+  for (const field of builderFields) {
+    const rustName = toRustFieldName(field.name);
+    if (isFieldConditional(field)) {
+      lines.push(`            ${rustName}: self.${rustName},`);
+    } else {
+      lines.push(`            ${rustName}: self.${rustName}.ok_or_else(|| BinSchemaError::InvalidValue("${name}.${field.name} is not set".to_string()))?,`);
+    }
+  }
+  lines.push(`        })`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+
+  lines.push(`impl ${target} {`);
+  lines.push(`    pub fn builder() -> ${name}Builder {`);
+  lines.push(`        ${name}Builder::new()`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+  return lines;
+}
+
+/**
+ * Generates `{Name}Builder` for a sequence type whose Input struct derives
+ * `Default`, behind the generated crate's `builder` feature. Setters are named
+ * after the fields and take values as they are; anything not set keeps its
+ * default (zero, empty, `None`), so `build()` can't fail. Types with
+ * `builder: true` get the checking builder from `generateBuilder` instead.
+ */
+function generateDefaultingBuilder(name: string, schemaTypeName: string, fields: Field[], schema: BinarySchema): string[] {
+  const split = typeNeedsInputOutputSplit(schemaTypeName, schema);
+  const target = split ? `${name}Input` : name;
+  const builderFields = fields.filter(f => f.name && f.type && f.type !== "padding" && isInputField(f));
+  // A field named `build` would clash with the method that finishes the builder
+  if (builderFields.some(f => toRustFieldName(f.name) === "build")) return [];
+  const lines: string[] = [];
+
+  lines.push(`/// Builds a [\`${target}\`], leaving every field that isn't set at its default`);
+  lines.push(`#[cfg(feature = "builder")]`);
+  lines.push(`#[derive(Debug, Clone, Default)]`);
+  lines.push(`pub struct ${name}Builder {`);
+  lines.push(`    value: ${target},`);
+  lines.push(`}`);
+  lines.push(``);
+
+  lines.push(`#[cfg(feature = "builder")]`);
+  lines.push(`impl ${name}Builder {`);
+  for (const field of builderFields) {
+    const rustName = toRustFieldName(field.name);
+    lines.push(`    pub fn ${rustName}(mut self, value: ${builderValueType(name, schemaTypeName, field, schema, split)}) -> Self {`);
+    lines.push(`        self.value.${rustName} = ${isFieldConditional(field) ? "Some(value)" : "value"};`);
+    lines.push(`        self`);
+    lines.push(`    }`);
+    lines.push(``);
+  }
+  lines.push(`    pub fn build(self) -> ${target} {`);
+  lines.push(`        self.value`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+
+  lines.push(`#[cfg(feature = "builder")]`);
+  lines.push(`impl ${target} {`);
+  lines.push(`    pub fn builder() -> ${name}Builder {`);
+  lines.push(`        ${name}Builder::default()`);
+  lines.push(`    }`);
+  lines.push(`}`);
+  lines.push(``);
+  return lines;
+}
+
+/** Derive line for a generated data struct, adding `Default` when every field has one */
+function structDerive(hasDefault: boolean): string {
+  return hasDefault ? `#[derive(Debug, Clone, PartialEq, Default)]` : `#[derive(Debug, Clone, PartialEq)]`;
+}
+
+/** Field types whose Rust mapping is a primitive, `String`, `Vec` or `Option`, all of which have a `Default` */
+const FIELD_TYPES_WITH_DEFAULT = new Set([
+  "uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64",
+  "float32", "float64", "custom_float", "uuid", "bool", "bytes", "message_bytes",
+  "varlength", "bitfield", "string", "bit", "int", "array", "optional",
+]);
+
+/**
+ * Whether the struct generated for a type (its Input struct when split) can
+ * derive `Default`. Enums and unions have no natural default, so a type
+ * holding one directly has none either; `visiting` catches a type embedding
+ * itself through a Box, whose default would never finish building.
+ */
+function typeHasDefault(typeName: string, schema: BinarySchema, visiting: Set<string> = new Set()): boolean {
+  const typeDef = schema.types[typeName] as any;
+  if (!typeDef || visiting.has(typeName)) return false;
+  if ("sequence" in typeDef) {
+    // Instance fields live on the unified struct, or only on Output when split
+    if (typeDef.instances?.length && !typeNeedsInputOutputSplit(typeName, schema)) return false;
+    return sequenceHasDefault(typeDef.sequence, schema, new Set([...visiting, typeName]));
+  }
+  if (isFlagsType(typeDef)) return true;
+  if (isEnumType(typeDef) || isTlvType(typeDef) || "variants" in typeDef) return false;
+  if (typeDef.type === "string") return true;
+  return fieldHasDefault({ name: "value", ...typeDef }, schema, new Set([...visiting, typeName]));
+}
+
+/** Whether every field a struct for `fields` holds has a `Default` (see typeHasDefault) */
+function sequenceHasDefault(fields: Field[], schema: BinarySchema, visiting: Set<string>): boolean {
+  return fields
+    .filter(f => f.name && f.type && f.type !== "padding" && isInputField(f))
+    .every(f => fieldHasDefault(f, schema, visiting));
+}
+
+function fieldHasDefault(field: Field, schema: BinarySchema, visiting: Set<string>): boolean {
+  const fieldAny = field as any;
+  // Conditional fields are Options; fixed repeats are arrays, which only have a Default up to 32 items
+  if (isFieldConditional(field)) return true;
+  if (fieldAny.repeat != null) return false;
+  if (fieldAny.newtype || FIELD_TYPES_WITH_DEFAULT.has(field.type)) return true;
+  if (["choice", "discriminated_union", "back_reference"].includes(field.type)) return false;
+  return typeHasDefault(field.type, schema, visiting);
+}
+
+/** Type a builder setter takes: the struct field's type without a conditional's Option */
+function builderValueType(name: string, schemaTypeName: string, field: Field, schema: BinarySchema, split: boolean): string {
+  let rustType: string;
+  if (field.type === "bitfield" && Array.isArray((field as any).fields) && (field as any).fields.length > 0) {
+    rustType = `${name}${toRustTypeName(field.name)}`;
+  } else {
+    rustType = split ? mapFieldToRustTypeForInput(field, schema, schemaTypeName) : mapFieldToRustType(field, schema, schemaTypeName);
+  }
+  return fieldIsRecursive(schemaTypeName, field, schema) ? `Box<${rustType}>` : rustType;
+}
+
+/** Checks of `value` against `field`'s width and length constraints, returning InvalidValue */
+function generateBuilderChecks(what: string, field: Field, indent: string): string[] {
+  const fieldAny = field as any;
+  const fail = (message: string, arg: string) => [
+    `${indent}    return Err(BinSchemaError::InvalidValue(format!("${what} ${message}", ${arg})));`,
+    `${indent}}`,
+  ];
+  const prefixMax: Record<string, string> = { uint8: "u8::MAX", uint16: "u16::MAX", uint32: "u32::MAX" };
+
+  switch (field.type) {
+    case "bit": {
+      // Widths matching the Rust type can't overflow
+      const size = fieldAny.size || 1;
+      if ([8, 16, 32, 64].includes(size)) return [];
+      return [`${indent}if u64::from(value) >= 1u64 << ${size} {`, ...fail(`is {}, which does not fit in ${size} bits`, "value")];
+    }
+    case "int": {
+      const size = fieldAny.size || 8;
+      if ([8, 16, 32, 64].includes(size)) return [];
+      return [
+        `${indent}if !(-(1i64 << ${size - 1})..1i64 << ${size - 1}).contains(&i64::from(value)) {`,
+        ...fail(`is {}, which does not fit in ${size} signed bits`, "value"),
+      ];
+    }
+    case "string": {
+      const lengthType = fieldAny.kind === "length_prefixed" ? fieldAny.length_type || "uint8" : undefined;
+      const named = { ...fieldAny, name: what };
+      return generateStringLengthCheck(named, generateStringLen("value", fieldAny.encoding || "utf8"), lengthType, indent);
+    }
+    case "bytes":
+    case "array": {
+      if (fieldAny.kind === "fixed" && fieldAny.type === "array") {
+        return [`${indent}if value.len() != ${fieldAny.length || 0} {`, ...fail(`has {} items, but must have ${fieldAny.length || 0}`, "value.len()")];
+      }
+      const limit = fieldAny.kind === "length_prefixed" ? prefixMax[fieldAny.length_type || "uint8"] : undefined;
+      if (limit === undefined) return [];
+      return [
+        `${indent}if value.len() > ${limit} as usize {`,
+        ...fail(`has {} ${field.type === "bytes" ? "bytes" : "items"}, which does not fit a ${fieldAny.length_type || "uint8"} length prefix`, "value.len()"),
+      ];
+    }
+  }
+  return [];
+}
+
+/**
+ * Generates a simple Rust struct definition (for type aliases/wrappers)
+ * This is used for wrapper structs that don't need Input/Output separation
+ */
+function generateSimpleStruct(name: string, fields: Field[], schema: BinarySchema, schemaTypeName?: string): string[] {
+  const lines: string[] = [];
+
+  lines.push(structDerive(sequenceHasDefault(fields, schema, new Set(schemaTypeName ? [schemaTypeName] : []))));
+  lines.push(SERDE_DERIVE);
+  lines.push(`pub struct ${name} {`);
+
+  for (const field of fields) {
+    if (!field.name || !field.type || field.type === "padding") {
+      continue;
+    }
+    let rustType: string;
+    if (field.type === "bitfield" && (field as any).fields && Array.isArray((field as any).fields) && (field as any).fields.length > 0) {
+      rustType = `${name}${toRustTypeName(field.name)}`;
+    } else {
+      rustType = mapFieldToRustType(field, undefined, schemaTypeName);
+    }
+    const fieldName = toRustFieldName(field.name);
+    lines.push(`    pub ${fieldName}: ${rustType},`);
+  }
+
+  lines.push(`}`);
+  lines.push(``);
+
+  return lines;
+}
+
+/**
+ * Generates impl blocks for a sequence type.
+ * If the type needs Input/Output split, generates separate impls for Input (encode) and Output (decode)
+ * plus a From conversion. Otherwise generates a single impl with both encode and decode.
+ */
+function generateImpl(name: string, schemaTypeName: string, fields: Field[], defaultEndianness: string, defaultBitOrder: string, schema: BinarySchema, instances?: any[]): string[] {
+  const lines: string[] = [];
+
+  if (typeNeedsInputOutputSplit(schemaTypeName, schema)) {
+    // Split mode: encode on Input, decode on Output, From conversion
+    lines.push(`impl ${name}Input {`);
+    lines.push(...generateEncodeMethod(fields, defaultEndianness, defaultBitOrder, schema, schemaTypeName));
+    lines.push(...generateSignedBytesMethod(schemaTypeName, fields, schema));
+    lines.push(`}`);
+    lines.push(``);
+
+    lines.push(`impl ${name}Output {`);
+    lines.push(...generateDecodeMethod(name, fields, defaultEndianness, defaultBitOrder, schema, instances, schemaTypeName));
+    // Also add encode delegation methods on Output so callers can call .encode()
+    // on Output types without manually converting to Input first
+    lines.push(...generateOutputEncodeDelegation(name, fields, schema));
+    if (signatureFieldOf(schemaTypeName, schema)) {
+      lines.push(`    pub fn signed_bytes(&self) -> Result<Vec<u8>> {`);
+      lines.push(`        ${name}Input::from(self.clone()).signed_bytes()`);
+      lines.push(`    }`);
+    }
+    lines.push(...generateApplyPatchMethod(fields));
+    lines.push(...generateFieldOffsetMethod(fields, schema));
+    lines.push(`}`);
+    lines.push(``);
+
+    // Generate From<Output> for Input conversion (drops computed/const fields)
+    // This enables encoding from Output structs (e.g., in choice variant encoding)
+    lines.push(...generateFromOutputToInput(name, fields, schema));
+    lines.push(...generateEncodeConversion(`${name}Input`));
+  } else {
+    // Unified mode: single impl with both encode and decode
+    lines.push(`impl ${name} {`);
+    lines.push(...generateEncodeMethod(fields, defaultEndianness, defaultBitOrder, schema, schemaTypeName));
+    lines.push(...generateSignedBytesMethod(schemaTypeName, fields, schema));
+    lines.push(...generateDecodeMethod(name, fields, defaultEndianness, defaultBitOrder, schema, instances, schemaTypeName));
+    lines.push(...generateApplyPatchMethod(fields));
+    lines.push(...generateFieldOffsetMethod(fields, schema));
+    lines.push(`}`);
+    lines.push(``);
+    lines.push(...generateEncodeConversion(name));
+  }
+
+  return lines;
+}
+
+/**
+ * Generates `TryFrom<&T> for Vec<u8>` delegating to `encode`, so callers can
+ * write `let bytes: Vec<u8> = (&msg).try_into()?`.
+ */
+function generateEncodeConversion(encodableName: string): string[] {
+  return [
+    `impl TryFrom<&${encodableName}> for Vec<u8> {`,
+    `    type Error = BinSchemaError;`,
+    ``,
+    `    fn try_from(value: &${encodableName}) -> Result<Self> {`,
+    `        value.encode()`,
+    `    }`,
+    `}`,
+    ``,
+  ];
+}
+
+/**
+ * Returns the `signature_field` declared on a composite type, if any.
+ */
+function signatureFieldOf(schemaTypeName: string, schema: BinarySchema): string | undefined {
+  return (schema.types[schemaTypeName] as any)?.signature_field;
+}
+
+/**
+ * Generates `signed_bytes(&self)` for types that declare a `signature_field`.
+ * The message is encoded with the signature zeroed (keeping its length), which
+ * is the canonical input both when signing and when verifying a received value.
+ * Returns no lines when the type has no signature field.
+ */
+function generateSignedBytesMethod(schemaTypeName: string, fields: Field[], schema: BinarySchema): string[] {
+  const signatureField = signatureFieldOf(schemaTypeName, schema);
+  if (!signatureField) return [];
+
+  const field = fields.find(f => f.name === signatureField);
+  if (!field) {
+    throw new Error(`signature_field '${signatureField}' of type '${schemaTypeName}' is not a field of that type`);
+  }
+  if (isFieldConditional(field) || (field as any).computed || (field as any).const != null) {
+    throw new Error(`signature_field '${signatureField}' of type '${schemaTypeName}' must be a plain, unconditional field`);
+  }
+
+  const fieldName = toRustFieldName(signatureField);
+  let zeroStatement: string;
+  switch (field.type) {
+    case "uint8": case "uint16": case "uint32": case "uint64":
+    case "int8": case "int16": case "int32": case "int64":
+      zeroStatement = `unsigned.${fieldName} = 0;`;
+      break;
+    case "bytes":
+    case "array":
+      if (field.type === "array" && !["uint8", "uint16", "uint32", "uint64"].includes((field as any).items?.type)) {
+        throw new Error(`signature_field '${signatureField}' of type '${schemaTypeName}' must be an array of unsigned integers`);
+      }
+      // Zero in place so fixed-size signatures keep their wire length
+      zeroStatement = `unsigned.${fieldName}.fill(0);`;
+      break;
+    default:
+      throw new Error(`signature_field '${signatureField}' of type '${schemaTypeName}' has unsupported type '${field.type}'`);
+  }
+
+  const lines: string[] = [];
+  lines.push(``);
+  lines.push(`    /// Encodes with \`${fieldName}\` zeroed, giving the canonical byte range covered by the signature.`);
+  lines.push(`    pub fn signed_bytes(&self) -> Result<Vec<u8>> {`);
+  lines.push(`        let mut unsigned = self.clone();`);
+  lines.push(`        ${zeroStatement}`);
+  lines.push(`        unsigned.encode()`);
+  lines.push(`    }`);
+  return lines;
+}
+
+/**
+ * Generates `apply_patch(&mut self, patch: &Self)` for types with Option-valued
+ * fields (`optional` or `conditional`). Every field that is `Some` in the patch
+ * overwrites the base; absent and non-optional fields are left untouched.
+ * Intended for config/delta-style formats where a partial record overrides a base.
+ * Returns no lines when the type has nothing patchable.
+ */
+function generateApplyPatchMethod(fields: Field[]): string[] {
+  const patchable = fields.filter(f =>
+    f.name && f.type && f.type !== "padding" && (f.type === "optional" || isFieldConditional(f))
+  );
+  if (patchable.length === 0) return [];
+
+  const lines: string[] = [];
+  lines.push(``);
+  lines.push(`    /// Overwrites every field that is present (\`Some\`) in \`patch\`; absent fields keep their current value.`);
+  lines.push(`    pub fn apply_patch(&mut self, patch: &Self) {`);
+  for (const field of patchable) {
+    const fieldName = toRustFieldName(field.name);
+    lines.push(`        if patch.${fieldName}.is_some() {`);
+    lines.push(`            self.${fieldName} = patch.${fieldName}.clone();`);
+    lines.push(`        }`);
+  }
+  lines.push(`    }`);
+  return lines;
+}
+
+/**
+ * Generates `decode_autodetect_endianness(bytes)`: reads the marker constant
+ * at its fixed offset and, if it only matches byte-swapped, decodes with a
+ * decoder that swaps every multi-byte read. The validator guarantees the
+ * marker is a multi-byte const preceded only by fixed-size fields.
+ */
+function generateAutodetectEndiannessMethod(markerName: string, fields: Field[], defaultEndianness: string, bitOrder: string, needsContext: boolean): string[] {
+  const index = fields.findIndex(f => f.name === markerName);
+  const marker = fields[index] as any;
+  const offset = fields.slice(0, index).reduce((sum, f) => sum + (primitiveFieldBitWidth(f) ?? 0) / 8, 0);
+  const rustType = marker.type === "uint16" ? "u16" : marker.type === "uint32" ? "u32" : "u64";
+  const expected = `0x${BigInt(marker.const).toString(16).toUpperCase()}${rustType}`;
+  const endianness = mapEndianness(marker.endianness || defaultEndianness);
+
+  const lines: string[] = [];
+  lines.push(`    /// Decodes a value written in either byte order. When \`${markerName}\` only matches`);
+  lines.push(`    /// byte-swapped, every multi-byte field is read in the opposite endianness.`);
+  lines.push(`    pub fn decode_autodetect_endianness(bytes: &[u8]) -> Result<Self> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  if (offset > 0) {
+    lines.push(`        decoder.seek(${offset})?;`);
+  }
+  lines.push(`        let marker = decoder.read_${marker.type}(Endianness::${endianness})?;`);
+  lines.push(`        decoder.seek(0)?;`);
+  lines.push(`        if marker != ${expected} && marker.swap_bytes() == ${expected} {`);
+  lines.push(`            decoder.swap_byte_order();`);
+  lines.push(`        }`);
+  if (needsContext) {
+    lines.push(`        Self::decode_with_decoder_and_context(&mut decoder, None)`);
+  } else {
+    lines.push(`        Self::decode_with_decoder(&mut decoder)`);
+  }
+  lines.push(`    }`);
+  lines.push(``);
+  return lines;
+}
+
+/**
+ * Types that get `decode_into`: every type flagged with `decode_into` plus the
+ * sequence types nested in them, whose own `decode_into` the parent calls.
+ */
+function decodeIntoTypes(schema: BinarySchema): Set<string> {
+  const result = new Set<string>();
+  const visit = (typeName: string) => {
+    const typeDef = schema.types[typeName] as any;
+    if (result.has(typeName) || !typeDef?.sequence) return;
+    result.add(typeName);
+    for (const field of typeDef.sequence) visit(field.type);
+  };
+  for (const [typeName, typeDef] of Object.entries(schema.types)) {
+    if ((typeDef as any).decode_into) visit(typeName);
+  }
+  return result;
+}
+
+/**
+ * Generates `decode_into`, which overwrites an existing value field by field
+ * instead of building a new one. Nested structs decode into their slot through
+ * their own `decode_into_with_decoder`; the schema validator guarantees every
+ * field is fixed-size, so nothing allocates.
  */
-function generateSimpleStruct(name: string, fields: Field[], schemaTypeName?: string): string[] {
+function generateDecodeIntoMethod(name: string, fields: Field[], defaultEndianness: string, bitOrder: string, schema: BinarySchema): string[] {
   const lines: string[] = [];
-
-  lines.push(`#[derive(Debug, Clone, PartialEq)]`);
-  lines.push(`pub struct ${name} {`);
-
-  for (const field of fields) {
-    if (!field.name || !field.type || field.type === "padding") {
-      continue;
-    }
-    let rustType: string;
-    if (field.type === "bitfield" && (field as any).fields && Array.isArray((field as any).fields) && (field as any).fields.length > 0) {
-      rustType = `${name}${toRustTypeName(field.name)}`;
-    } else {
-      rustType = mapFieldToRustType(field, undefined, schemaTypeName);
-    }
+  lines.push(`    /// Decodes into \`out\` in place, without allocating. On error \`out\` may be`);
+  lines.push(`    /// partly overwritten.`);
+  lines.push(`    pub fn decode_into(bytes: &[u8], out: &mut Self) -> Result<()> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  lines.push(`        Self::decode_into_with_decoder(&mut decoder, out)`);
+  lines.push(`    }`);
+  lines.push(``);
+  lines.push(`    pub fn decode_into_with_decoder(decoder: &mut BitStreamDecoder, out: &mut Self) -> Result<()> {`);
+  const alignments = computeFieldAlignments(fields);
+  fields.forEach((field, i) => {
     const fieldName = toRustFieldName(field.name);
-    lines.push(`    pub ${fieldName}: ${rustType},`);
-  }
-
-  lines.push(`}`);
+    const nested = schema.types[field.type as string];
+    if (nested && "sequence" in nested) {
+      const nestedName = toRustTypeName(field.type);
+      const decodeName = typeNeedsInputOutputSplit(field.type, schema) ? `${nestedName}Output` : nestedName;
+      lines.push(`        ${decodeName}::decode_into_with_decoder(decoder, &mut out.${fieldName})?;`);
+      return;
+    }
+    lines.push(...generateDecodeField(field, defaultEndianness, "        ", name, schema, fields, false, alignments[i]));
+    if (field.name && field.type !== "padding") {
+      const value = (field as any).newtype ? `${(field as any).newtype}(${fieldName})` : fieldName;
+      lines.push(`        out.${fieldName} = ${value};`);
+    }
+  });
+  lines.push(...generateTransformChecks(fields, name));
+  lines.push(`        Ok(())`);
+  lines.push(`    }`);
   lines.push(``);
-
   return lines;
 }
 
 /**
- * Generates impl blocks for a sequence type.
- * If the type needs Input/Output split, generates separate impls for Input (encode) and Output (decode)
- * plus a From conversion. Otherwise generates a single impl with both encode and decode.
+ * Generates `field_offset(name)`: the byte offset of each named field that
+ * starts at the same byte in every encoding. Offsets stop being known after
+ * the first field whose size depends on the data, and a field that starts
+ * mid-byte has no byte offset.
  */
-function generateImpl(name: string, schemaTypeName: string, fields: Field[], defaultEndianness: string, defaultBitOrder: string, schema: BinarySchema, instances?: any[]): string[] {
-  const lines: string[] = [];
-
-  if (typeNeedsInputOutputSplit(schemaTypeName, schema)) {
-    // Split mode: encode on Input, decode on Output, From conversion
-    lines.push(`impl ${name}Input {`);
-    lines.push(...generateEncodeMethod(fields, defaultEndianness, defaultBitOrder, schema, schemaTypeName));
-    lines.push(`}`);
-    lines.push(``);
-
-    lines.push(`impl ${name}Output {`);
-    lines.push(...generateDecodeMethod(name, fields, defaultEndianness, defaultBitOrder, schema, instances, schemaTypeName));
-    // Also add encode delegation methods on Output so callers can call .encode()
-    // on Output types without manually converting to Input first
-    lines.push(...generateOutputEncodeDelegation(name, fields, schema));
-    lines.push(`}`);
-    lines.push(``);
+function generateFieldOffsetMethod(fields: Field[], schema: BinarySchema): string[] {
+  const arms: string[] = [];
+  let bitOffset: number | null = 0;
+  for (const field of fields) {
+    if (bitOffset === null) break;
+    const fieldAny = field as any;
+    if (field.type === "padding") {
+      if (bitOffset % 8 !== 0) { bitOffset = null; break; }
+      const align = fieldAny.align_to || 1;
+      bitOffset += ((align - (bitOffset / 8) % align) % align) * 8;
+      continue;
+    }
+    if (field.name && bitOffset % 8 === 0) {
+      arms.push(`            "${field.name}" => Some(${bitOffset / 8}),`);
+    }
+    const bits = fixedEncodedBits(field, schema);
+    bitOffset = bits === null ? null : bitOffset + bits;
+  }
 
-    // Generate From<Output> for Input conversion (drops computed/const fields)
-    // This enables encoding from Output structs (e.g., in choice variant encoding)
-    lines.push(...generateFromOutputToInput(name, fields, schema));
+  const lines: string[] = [];
+  lines.push(``);
+  lines.push(`    /// Byte offset of the field called \`name\` in every encoded value, for patching`);
+  lines.push(`    /// it in place. \`None\` when the field follows a variable-length field,`);
+  lines.push(`    /// starts mid-byte, or doesn't exist.`);
+  lines.push(`    pub fn field_offset(name: &str) -> Option<usize> {`);
+  if (arms.length === 0) {
+    lines.push(`        let _ = name;`);
+    lines.push(`        None`);
   } else {
-    // Unified mode: single impl with both encode and decode
-    lines.push(`impl ${name} {`);
-    lines.push(...generateEncodeMethod(fields, defaultEndianness, defaultBitOrder, schema, schemaTypeName));
-    lines.push(...generateDecodeMethod(name, fields, defaultEndianness, defaultBitOrder, schema, instances, schemaTypeName));
-    lines.push(`}`);
-    lines.push(``);
+    lines.push(`        match name {`);
+    lines.push(...arms);
+    lines.push(`            _ => None,`);
+    lines.push(`        }`);
   }
-
+  lines.push(`    }`);
   return lines;
 }
 
@@ -3221,7 +4722,7 @@ function generateFromFieldConversion(fieldName: string, field: Field, schema: Bi
       default: {
         // For composite type references, use .into()
         if (baseFieldNeedsConversion()) {
-          return `${accessor}.into()`;
+          return (field as any).repeat != null ? `${accessor}.map(|x| x.into())` : `${accessor}.into()`;
         }
         return accessor;
       }
@@ -3410,7 +4911,7 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
     lines.push(``);
     lines.push(`        // Build parent context for nested struct encoding`);
     parentFieldsLineIdx = lines.length;
-    lines.push(`        let mut parent_fields: HashMap<std::string::String, FieldValue> = HashMap::new();`);
+    lines.push(`        let mut parent_fields: FieldMap<std::string::String, FieldValue> = FieldMap::new();`);
     for (const field of fields) {
       if (!field.name) continue;
       // Skip padding, const, and computed fields - they don't have input values
@@ -3440,10 +4941,10 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
 
           lines.push(`        // Collect items with sub-field values for choice array '${field.name}'`);
           lines.push(`        {`);
-          lines.push(`            let mut items_data: Vec<(std::string::String, HashMap<std::string::String, FieldValue>)> = Vec::new();`);
+          lines.push(`            let mut items_data: Vec<(std::string::String, FieldMap<std::string::String, FieldValue>)> = Vec::new();`);
           lines.push(`            for item in &self.${rustFieldName} {`);
           lines.push(`                let item_bytes = item.encode().unwrap_or_default();`);
-          lines.push(`                let mut item_fields: HashMap<std::string::String, FieldValue> = HashMap::new();`);
+          lines.push(`                let mut item_fields: FieldMap<std::string::String, FieldValue> = FieldMap::new();`);
           lines.push(`                item_fields.insert("_encoded_size".to_string(), FieldValue::U64(item_bytes.len() as u64));`);
           // Match on the enum to extract sub-field values from each variant's Output struct
           lines.push(`                match item {`);
@@ -3529,7 +5030,7 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
 
           lines.push(`        // Collect items with sub-field values for typed array '${field.name}'`);
           lines.push(`        {`);
-          lines.push(`            let mut items_data: Vec<(std::string::String, HashMap<std::string::String, FieldValue>)> = Vec::new();`);
+          lines.push(`            let mut items_data: Vec<(std::string::String, FieldMap<std::string::String, FieldValue>)> = Vec::new();`);
           // When items_need_ctx, we don't actually use `item` inside the loop
           // (we just push an empty placeholder), so bind to `_` to avoid the
           // `unused variable: item` lint.
@@ -3540,7 +5041,7 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
           } else {
             lines.push(`                let item_bytes = Vec::<u8>::new(); // Items need context, skip encoding for now`);
           }
-          lines.push(`                let mut item_fields: HashMap<std::string::String, FieldValue> = HashMap::new();`);
+          lines.push(`                let mut item_fields: FieldMap<std::string::String, FieldValue> = FieldMap::new();`);
           lines.push(`                item_fields.insert("_encoded_size".to_string(), FieldValue::U64(item_bytes.len() as u64));`);
           lines.push(`                items_data.push(("${itemTypeName}".to_string(), item_fields));`);
           lines.push(`            }`);
@@ -3689,16 +5190,33 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
 
   // Compute per-field byte-alignment for optimized encode calls
   const fieldAlignments = computeFieldAlignments(fields);
+  const encodeAnchors = collectAnchorFieldNames(fields);
+  const backPatches = collectBackPatchedLengths(fields, fieldAlignments, schema)
+    .filter(patch => !fieldsConsumedByFromAfter.has(fields[patch.targetIndex].name!));
 
   // Generate encoding logic for each field
   for (let fieldIdx = 0; fieldIdx < fields.length; fieldIdx++) {
     const field = fields[fieldIdx];
     const fieldByteAligned = fieldAlignments[fieldIdx];
+
+    // The previous field was the target of a back-patched length
+    for (const patch of backPatches.filter(p => p.targetIndex === fieldIdx - 1)) {
+      lines.push(...generateLengthBackPatch(patch, fields, defaultEndianness, "        "));
+    }
+
     // Skip fields without names
     if (!field.name) {
       continue;
     }
 
+    // Record where an anchor field starts so relative offsets can subtract it
+    if (encodeAnchors.has(field.name)) {
+      lines.push(`        let ${toRustFieldName(field.name)}_anchor = encoder.byte_offset();`);
+    }
+    if (backPatches.some(p => p.targetIndex === fieldIdx)) {
+      lines.push(`        let ${toRustFieldName(field.name)}_start = encoder.byte_offset();`);
+    }
+
     // Skip fields that are already encoded by from_after_field content-first encoding
     if (fieldsConsumedByFromAfter.has(field.name)) {
       continue;
@@ -3715,11 +5233,17 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
     }
 
     // Handle computed fields - compute value and encode it
+    const backPatch = backPatches.find(p => p.field === field);
+    if (backPatch) {
+      lines.push(`        // Computed field '${field.name}': length_of '${fields[backPatch.targetIndex].name}', patched once it is written`);
+      lines.push(`        let ${toRustFieldName(field.name)}_slot = encoder.reserve_${field.type === "uint16" ? "u16" : "u32"}()?;`);
+      continue;
+    }
     if (fieldAny.computed != null) {
       // Use child_ctx if we have nested structs (it has parent fields + position tracking),
       // otherwise use ctx (the raw parameter)
       const ctxVarForComputed = hasNestedStructs ? "child_ctx" : (needsContext ? "ctx" : undefined);
-      lines.push(...generateEncodeComputedField(field, fields, defaultEndianness, "        ", ctxVarForComputed, schema));
+      lines.push(...generateEncodeComputedField(field, fields, defaultEndianness, "        ", ctxVarForComputed, schema, containingTypeName));
       continue;
     }
 
@@ -3729,6 +5253,12 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
       continue;
     }
 
+    // Repeated inline group - one encode call per element
+    if (fieldAny.repeat != null) {
+      lines.push(...generateEncodeRepeatedField(field, "        ", schema));
+      continue;
+    }
+
     // Regular input field - encode from self
     // For nested structs, pass context if needed
     const fieldType = field.type as string;
@@ -3760,6 +5290,9 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
       lines.push(...generateEncodeField(field, defaultEndianness, "        ", schema, hasNestedStructs, choiceCtxVarForField, fieldByteAligned));
     }
   }
+  for (const patch of backPatches.filter(p => p.targetIndex === fields.length - 1)) {
+    lines.push(...generateLengthBackPatch(patch, fields, defaultEndianness, "        "));
+  }
 
   lines.push(`        Ok(())`);
   lines.push(`    }`);
@@ -3774,6 +5307,28 @@ function generateEncodeMethod(fields: Field[], defaultEndianness: string, defaul
   return lines;
 }
 
+/**
+ * Generates unrolled encoding code for a group repeated a fixed number of times
+ * (`repeat: N`), stored as `[T; N]`. The group type must encode standalone:
+ * elements get no parent context or compression dictionary.
+ */
+function generateEncodeRepeatedField(field: Field, indent: string, schema: BinarySchema): string[] {
+  const fieldType = field.type as string;
+  const typeDef = schema.types[fieldType];
+  if (typeDef && "sequence" in typeDef &&
+      (typeHasParentReferences(typeDef.sequence) || hasNestedStructFields(typeDef.sequence, schema) ||
+       typeTransitivelyContainsBackReference(fieldType, schema))) {
+    throw new Error(`Field '${field.name}' repeats '${fieldType}', which needs encode context; repeated groups must encode standalone`);
+  }
+
+  const rustFieldName = toRustFieldName(field.name);
+  const lines: string[] = [`${indent}// Encode ${field.name} (${fieldType} x ${(field as any).repeat})`];
+  for (let i = 0; i < (field as any).repeat; i++) {
+    lines.push(`${indent}self.${rustFieldName}[${i}].encode_into(encoder)?;`);
+  }
+  return lines;
+}
+
 /**
  * Generates encoding code for a nested struct field with context passing
  */
@@ -3842,6 +5397,108 @@ function generateEncodeConditionalField(field: Field, allFields: Field[], defaul
   return lines;
 }
 
+/**
+ * Bytes of a context `FieldValue` for checksumming, with scalars in the given
+ * byte order rather than `to_bytes`' fixed little-endian
+ */
+function fieldValueBytes(value: string, endianness: string): string {
+  return `${value}.to_bytes_with_endianness(Endianness::${mapEndianness(endianness)})`;
+}
+
+/**
+ * Byte order of the field a checksum reads from the context: its own
+ * `endianness`, else the schema default. `path` may be dotted into nested
+ * structs. `holders` are the types that may declare it; those that do must
+ * agree, since one generated expression serves them all.
+ */
+function referencedFieldEndianness(path: string, holders: string[], schema: BinarySchema | undefined, defaultEndianness: string): string {
+  const orders = new Map<string, string>();
+  for (const holder of holders) {
+    let field: any;
+    let typeName: string | undefined = holder;
+    for (const segment of path.split(".")) {
+      field = ((typeName && schema?.types[typeName]) as any)?.sequence?.find((f: any) => f.name === segment);
+      typeName = field?.type;
+    }
+    if (field) orders.set(holder, field.endianness || defaultEndianness);
+  }
+  const distinct = new Set(orders.values());
+  if (distinct.size > 1) {
+    throw new Error(`Checksum source '${path}' has different byte orders in ${[...orders.keys()].join(", ")}`);
+  }
+  return distinct.values().next().value ?? defaultEndianness;
+}
+
+/**
+ * Types that contain `typeName` at any depth, through fields, array items or
+ * union variants
+ */
+function enclosingTypes(typeName: string, schema: BinarySchema): string[] {
+  const mentions = (node: unknown, target: string): boolean => {
+    if (Array.isArray(node)) return node.some(item => mentions(item, target));
+    if (node === null || typeof node !== "object") return false;
+    return Object.entries(node).some(([key, value]) => (key === "type" && value === target) || mentions(value, target));
+  };
+  const found = new Set<string>();
+  const pending = [typeName];
+  while (pending.length > 0) {
+    const inner = pending.pop()!;
+    for (const [name, def] of Object.entries(schema.types)) {
+      if (!found.has(name) && mentions(def, inner)) {
+        found.add(name);
+        pending.push(name);
+      }
+    }
+  }
+  return [...found];
+}
+
+/** A length_of field written as a placeholder and filled in after its target */
+interface BackPatchedLength {
+  field: Field;
+  targetIndex: number;
+}
+
+/**
+ * Finds the length_of fields that `generateEncodeMethod` back-patches: 16- and
+ * 32-bit byte-aligned fields whose target is a later composite or union field.
+ * Measuring such a target up front means encoding it twice, and encoding it
+ * standalone, without the compression dictionary the real encode uses, so the
+ * measured size can disagree with the bytes written. Instead the encoder
+ * reserves the slot and patches in the size of what was actually written.
+ */
+function collectBackPatchedLengths(fields: Field[], alignments: boolean[], schema: BinarySchema): BackPatchedLength[] {
+  const result: BackPatchedLength[] = [];
+  fields.forEach((field, index) => {
+    const computed = (field as any).computed;
+    if (computed?.type !== "length_of" || computed.from_after_field || typeof computed.target !== "string") return;
+    if ((field.type !== "uint16" && field.type !== "uint32") || alignments[index] !== true) return;
+    const targetIndex = fields.findIndex(f => f.name === computed.target);
+    const target = fields[targetIndex];
+    if (targetIndex <= index || isFieldConditional(target) || (target as any).optional) return;
+    if ((target.type as string) === "discriminated_union" || schema.types[target.type as string]) {
+      result.push({ field, targetIndex });
+    }
+  });
+  return result;
+}
+
+/** Fills the slot reserved for a back-patched length with its target's encoded size */
+function generateLengthBackPatch(patch: BackPatchedLength, fields: Field[], defaultEndianness: string, indent: string): string[] {
+  const field = patch.field as any;
+  const target = fields[patch.targetIndex].name!;
+  const slot = `${toRustFieldName(field.name)}_slot`;
+  const computedVarName = `${toRustFieldName(field.name)}_computed`;
+  const rustType = field.type === "uint16" ? "u16" : "u32";
+  const offset = field.computed.offset ? ` + ${field.computed.offset}` : "";
+  return [
+    `${indent}// Back-patch '${field.name}' with the encoded size of '${target}'`,
+    `${indent}let ${computedVarName} = encoder.byte_offset() - ${toRustFieldName(target)}_start${offset};`,
+    `${indent}let ${computedVarName} = ${rustType}::try_from(${computedVarName}).map_err(|_| BinSchemaError::InvalidValue(format!("'${target}' is {} bytes, too long for ${rustType} length field '${field.name}'", ${computedVarName})))?;`,
+    `${indent}encoder.patch_${rustType}(${slot}, ${computedVarName}, Endianness::${mapEndianness(field.endianness || defaultEndianness)})?;`,
+  ];
+}
+
 /**
  * Generates encoding code for a computed field
  * Computes the value from the target field and encodes it
@@ -3859,7 +5516,8 @@ function generateEncodeComputedField(
   defaultEndianness: string,
   indent: string,
   ctxVar?: string,
-  schema?: BinarySchema
+  schema?: BinarySchema,
+  containingTypeName?: string
 ): string[] {
   const lines: string[] = [];
   const fieldAny = field as any;
@@ -3867,6 +5525,10 @@ function generateEncodeComputedField(
   const fieldName = field.name;
   const endianness = fieldAny.endianness || defaultEndianness;
   const rustEndianness = mapEndianness(endianness);
+  // A parent reference resolves to a field of any type this one is nested in
+  const parentFieldEndianness = (name: string): string => referencedFieldEndianness(
+    name, containingTypeName && schema ? enclosingTypes(containingTypeName, schema) : [], schema, defaultEndianness
+  );
 
   // Helper to convert computed variable name (avoid Rust reserved keywords)
   const computedVarName = toRustFieldName(fieldName) + "_computed";
@@ -4274,35 +5936,9 @@ function generateEncodeComputedField(
       const fieldAccess = remainingPath ? remainingPath.slice(1) : ""; // Remove leading "."
 
       lines.push(`${indent}// Computed field '${fieldName}': length_of '${target}' (corresponding<${filterType}>${remainingPath || ""})`);
-      // Determine correlation index: same-array (type index) vs cross-array (array index)
-      lines.push(`${indent}let ${computedVarName}_corr_idx = {`);
-      lines.push(`${indent}    if ${ctxRef}.is_current_array("${arrayPath}") {`);
-      lines.push(`${indent}        // Same-array: use type occurrence index (counter incremented before encoding)`);
-      lines.push(`${indent}        let ti = ${ctxRef}.get_type_index("${arrayPath}_${filterType}");`);
-      lines.push(`${indent}        if ti > 0 { ti - 1 } else { 0 }`);
-      lines.push(`${indent}    } else {`);
-      lines.push(`${indent}        // Cross-array: use current array index from any containing array`);
-      lines.push(`${indent}        ${ctxRef}.get_any_array_iteration().map(|(_, idx)| idx).unwrap_or(0)`);
-      lines.push(`${indent}    }`);
-      lines.push(`${indent}};`);
-      // Find the corresponding item and access its sub-field
-      lines.push(`${indent}let ${computedVarName} = match ${ctxRef}.find_parent_field("${arrayPath}") {`);
-      lines.push(`${indent}    Some(array_val) => {`);
-      lines.push(`${indent}        match array_val.get_nth_item_of_type("${filterType}", ${computedVarName}_corr_idx) {`);
-      if (fieldAccess) {
-        lines.push(`${indent}            Some(item_fields) => {`);
-        lines.push(`${indent}                item_fields.get("${fieldAccess}").map(|v| v.length_of_value()).unwrap_or(0)`);
-        lines.push(`${indent}            },`);
-      } else {
-        lines.push(`${indent}            Some(item_fields) => {`);
-        lines.push(`${indent}                item_fields.get("_encoded_size").map(|v| v.length_of_value()).unwrap_or(0)`);
-        lines.push(`${indent}            },`);
-      }
-      lines.push(`${indent}            None => 0,`);
-      lines.push(`${indent}        }`);
-      lines.push(`${indent}    },`);
-      lines.push(`${indent}    None => 0,`);
-      lines.push(`${indent}};`);
+      // Same-array vs cross-array correlation is decided by the context
+      lines.push(`${indent}let ${computedVarName} = ${ctxRef}.resolve_corresponding("${arrayPath}", "${filterType}", "${fieldAccess}")`);
+      lines.push(`${indent}    .map(|v| v.length_of_value()).unwrap_or(0);`);
       lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
       return lines;
     }
@@ -4415,34 +6051,11 @@ function generateEncodeComputedField(
       const ctxRef = ctxVar || "child_ctx";
       const fieldAccess = remainingPath ? remainingPath.slice(1) : ""; // Remove leading "."
 
+      const sourceEndianness = referencedFieldEndianness(fieldAccess, [filterType], schema, defaultEndianness);
+
       lines.push(`${indent}// Computed field '${fieldName}': crc32_of '${target}' (corresponding<${filterType}>${remainingPath || ""})`);
-      // Determine correlation index: same-array vs cross-array
-      lines.push(`${indent}let ${computedVarName}_corr_idx = {`);
-      lines.push(`${indent}    if ${ctxRef}.is_current_array("${arrayPath}") {`);
-      lines.push(`${indent}        let ti = ${ctxRef}.get_type_index("${arrayPath}_${filterType}");`);
-      lines.push(`${indent}        if ti > 0 { ti - 1 } else { 0 }`);
-      lines.push(`${indent}    } else {`);
-      lines.push(`${indent}        ${ctxRef}.get_any_array_iteration().map(|(_, idx)| idx).unwrap_or(0)`);
-      lines.push(`${indent}    }`);
-      lines.push(`${indent}};`);
-      // Find corresponding item and compute CRC32 of its sub-field
-      lines.push(`${indent}let ${computedVarName} = match ${ctxRef}.find_parent_field("${arrayPath}") {`);
-      lines.push(`${indent}    Some(array_val) => {`);
-      lines.push(`${indent}        match array_val.get_nth_item_of_type("${filterType}", ${computedVarName}_corr_idx) {`);
-      if (fieldAccess) {
-        lines.push(`${indent}            Some(item_fields) => {`);
-        lines.push(`${indent}                item_fields.get("${fieldAccess}").map(|v| binschema_runtime::crc32(&v.to_bytes())).unwrap_or(0)`);
-        lines.push(`${indent}            },`);
-      } else {
-        lines.push(`${indent}            Some(item_fields) => {`);
-        lines.push(`${indent}                item_fields.get("_encoded_size").map(|v| binschema_runtime::crc32(&v.to_bytes())).unwrap_or(0)`);
-        lines.push(`${indent}            },`);
-      }
-      lines.push(`${indent}            None => 0,`);
-      lines.push(`${indent}        }`);
-      lines.push(`${indent}    },`);
-      lines.push(`${indent}    None => 0,`);
-      lines.push(`${indent}};`);
+      lines.push(`${indent}let ${computedVarName} = ${ctxRef}.resolve_corresponding("${arrayPath}", "${filterType}", "${fieldAccess}")`);
+      lines.push(`${indent}    .map(|v| binschema_runtime::crc32(&${fieldValueBytes("v", sourceEndianness)})).unwrap_or(0);`);
       lines.push(`${indent}encoder.write_uint32(${computedVarName}, Endianness::${rustEndianness});`);
       return lines;
     }
@@ -4461,9 +6074,10 @@ function generateEncodeComputedField(
       lines.push(`${indent}    Some(array_val) => {`);
       lines.push(`${indent}        match array_val.${accessor}("${filterType}") {`);
       if (fieldAccess) {
-        lines.push(`${indent}            Some(item_fields) => item_fields.get("${fieldAccess}").map(|v| binschema_runtime::crc32(&v.to_bytes())).unwrap_or(0),`);
+        const sourceEndianness = referencedFieldEndianness(fieldAccess, [filterType], schema, defaultEndianness);
+        lines.push(`${indent}            Some(item_fields) => item_fields.get("${fieldAccess}").map(|v| binschema_runtime::crc32(&${fieldValueBytes("v", sourceEndianness)})).unwrap_or(0),`);
       } else {
-        lines.push(`${indent}            Some(item_fields) => item_fields.get("_encoded_size").map(|v| binschema_runtime::crc32(&v.to_bytes())).unwrap_or(0),`);
+        lines.push(`${indent}            Some(item_fields) => item_fields.get("_encoded_size").map(|v| binschema_runtime::crc32(&${fieldValueBytes("v", defaultEndianness)})).unwrap_or(0),`);
       }
       lines.push(`${indent}            None => 0,`);
       lines.push(`${indent}        }`);
@@ -4482,10 +6096,11 @@ function generateEncodeComputedField(
       }
 
       const { levelsUp, fieldName: targetFieldName } = parentRef;
+      const sourceEndianness = parentFieldEndianness(targetFieldName);
 
       lines.push(`${indent}// Computed field '${fieldName}': crc32_of '${target}' (parent reference)`);
       lines.push(`${indent}let ${computedVarName} = match ${ctxVar}.get_parent_field(${levelsUp}, "${targetFieldName}") {`);
-      lines.push(`${indent}    Some(field_value) => binschema_runtime::crc32(&field_value.to_bytes()),`);
+      lines.push(`${indent}    Some(field_value) => binschema_runtime::crc32(&${fieldValueBytes("field_value", sourceEndianness)}),`);
       lines.push(`${indent}    None => return Err(binschema_runtime::BinSchemaError::InvalidValue(`);
       lines.push(`${indent}        format!("Parent field '${targetFieldName}' not found at level ${levelsUp}")`);
       lines.push(`${indent}    )),`);
@@ -4509,6 +6124,78 @@ function generateEncodeComputedField(
     lines.push(`${indent}let ${crcInputVar}: Vec<u8> = ${targetPath}.iter().cloned().collect();`);
     lines.push(`${indent}let ${computedVarName} = binschema_runtime::crc32(&${crcInputVar});`);
     lines.push(`${indent}encoder.write_uint32(${computedVarName}, Endianness::${rustEndianness});`);
+  } else if (computed.type === "discriminator_of") {
+    // Tag derived from which variant the union field holds, so the two can't diverge
+    const target = computed.target as string;
+    const unionField: any = allFields.find(f => f.name === target);
+    if (!unionField) {
+      throw new Error(`Computed field '${fieldName}': discriminator_of target '${target}' is not a sibling field`);
+    }
+    let enumName: string;
+    let variants: any[];
+    if (unionField.type === "discriminated_union") {
+      if (!containingTypeName) {
+        throw new Error(`Computed field '${fieldName}': discriminator_of needs the containing type name`);
+      }
+      enumName = inlineEnumName(containingTypeName, target);
+      variants = unionField.variants || [];
+    } else {
+      const unionDef: any = schema?.types[unionField.type];
+      if (!unionDef || !Array.isArray(unionDef.variants)) {
+        throw new Error(`Computed field '${fieldName}': discriminator_of target '${target}' is not a discriminated union`);
+      }
+      enumName = toRustTypeName(unionField.type);
+      variants = unionDef.variants;
+    }
+
+    lines.push(`${indent}// Computed field '${fieldName}': discriminator_of '${target}'`);
+    lines.push(`${indent}let ${computedVarName} = match &self.${toRustFieldName(target)} {`);
+    for (const variant of variants) {
+      const arm = `${indent}    ${enumName}::${toRustTypeName(variant.type)}(_) =>`;
+      const tag = variantTagValue(variant);
+      if (tag !== undefined) {
+        lines.push(`${arm} ${tag}u64,`);
+      } else {
+        lines.push(`${arm} return Err(binschema_runtime::BinSchemaError::InvalidValue(`);
+        lines.push(`${indent}        "variant ${variant.type} of '${target}' has no single tag value; set '${fieldName}' explicitly".to_string()`);
+        lines.push(`${indent}    )),`);
+      }
+    }
+    lines.push(`${indent}};`);
+    lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
+  } else if (computed.type === "xor_of" || computed.type === "complement_of") {
+    lines.push(`${indent}// Computed field '${fieldName}': ${computed.type} ${transformSources(computed).map(t => `'${t}'`).join(", ")}`);
+    lines.push(`${indent}let ${computedVarName} = ${transformExpr(computed, "self.")};`);
+    lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
+  } else if (computed.type === "crc_of") {
+    // CRC with a schema-declared variant (polynomial, init, reflection, xorout)
+    const target = computed.target as string;
+
+    const parentRef = parseParentPath(target);
+    if (parentRef) {
+      if (!ctxVar) {
+        throw new Error(`Computed field '${fieldName}' has parent reference but no context available: ${target}`);
+      }
+      const { levelsUp, fieldName: targetFieldName } = parentRef;
+      const sourceEndianness = parentFieldEndianness(targetFieldName);
+      lines.push(`${indent}// Computed field '${fieldName}': crc_of '${target}' (parent reference)`);
+      lines.push(`${indent}let ${computedVarName} = match ${ctxVar}.get_parent_field(${levelsUp}, "${targetFieldName}") {`);
+      lines.push(`${indent}    Some(field_value) => ${crcOfExpr(computed, `&${fieldValueBytes("field_value", sourceEndianness)}`)},`);
+      lines.push(`${indent}    None => return Err(binschema_runtime::BinSchemaError::InvalidValue(`);
+      lines.push(`${indent}        format!("Parent field '${targetFieldName}' not found at level ${levelsUp}")`);
+      lines.push(`${indent}    )),`);
+      lines.push(`${indent}};`);
+      lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
+      return lines;
+    }
+
+    if (target.includes("[") || target.includes("<")) {
+      throw new Error(`Rust generator does not yet support crc_of with selector: ${target}`);
+    }
+
+    lines.push(`${indent}// Computed field '${fieldName}': crc_of '${target}'`);
+    lines.push(`${indent}let ${computedVarName} = ${crcOfExpr(computed, `&self.${toRustFieldName(target)}`)};`);
+    lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
   } else if (computed.type === "position_of") {
     // Compute position/offset of target field
     const target = computed.target as string;
@@ -4583,6 +6270,15 @@ function generateEncodeComputedField(
       lines.push(`${indent}let ${computedVarName} = ${computedVarName} + ${fieldSize};`);
     }
 
+    if (computed.relative_to) {
+      const anchorIndex = allFields.findIndex((f) => f.name === computed.relative_to);
+      const selfIndex = allFields.findIndex((f) => f.name === field.name);
+      if (anchorIndex === -1 || anchorIndex >= selfIndex) {
+        throw new Error(`Computed field '${fieldName}': relative_to '${computed.relative_to}' must name an earlier sibling field`);
+      }
+      lines.push(`${indent}let ${computedVarName} = ${computedVarName} - ${toRustFieldName(computed.relative_to)}_anchor;`);
+    }
+
     lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
   } else if (computed.type === "sum_of_type_sizes") {
     // Sum encoded sizes of array elements matching a specific type
@@ -4593,10 +6289,24 @@ function generateEncodeComputedField(
       throw new Error(`Computed field '${fieldName}' (sum_of_type_sizes) has no target specified`);
     }
 
-    // Must be a parent reference
+    // Sibling array: measure each element and sum those of the requested type
     const parentRef = parseParentPath(target);
     if (!parentRef) {
-      throw new Error(`sum_of_type_sizes target '${target}' must be a parent reference (../)`);
+      const targetField = allFields.find(f => f.name === target) as any;
+      if (!targetField || targetField.type !== "array" || !targetField.items) {
+        throw new Error(`sum_of_type_sizes target '${target}' must be a parent reference (../) or a sibling array`);
+      }
+      const items = targetField.items;
+      const itemTypeName = items.type === "choice" || items.type === "discriminated_union"
+        ? `item.type_name().to_string()`
+        : `"${items.type}".to_string()`;
+      lines.push(`${indent}// Computed field '${fieldName}': sum_of_type_sizes for '${elementType}' in '${target}'`);
+      lines.push(`${indent}let ${computedVarName}_sizes = self.${toRustFieldName(target)}.iter()`);
+      lines.push(`${indent}    .map(|item| -> Result<(std::string::String, usize)> { Ok((${itemTypeName}, item.encode()?.len())) })`);
+      lines.push(`${indent}    .collect::<Result<Vec<_>>>()?;`);
+      lines.push(`${indent}let ${computedVarName} = FieldValue::TypeSizes(${computedVarName}_sizes).sum_type_sizes("${elementType}");`);
+      lines.push(...generateComputedFieldWrite(field, computedVarName, rustEndianness, indent));
+      return lines;
     }
 
     if (!ctxVar) {
@@ -4656,8 +6366,77 @@ function generateEncodeComputedField(
 }
 
 /**
- * Generates code to write a computed field value to the encoder
+ * Decode-side checks for xor_of / complement_of fields against their decoded
+ * sources. A transform is fully determined by its sources, so a mismatch
+ * means a corrupt or tampered message.
+ */
+function generateTransformChecks(fields: Field[], typePath: string): string[] {
+  const lines: string[] = [];
+  for (const field of fields) {
+    const computed = (field as any).computed;
+    if (!field.name || (computed?.type !== "xor_of" && computed?.type !== "complement_of")) continue;
+    const varName = toRustFieldName(field.name);
+    lines.push(`        let ${varName}_expected = (${transformExpr(computed, "")}) as ${mapPrimitiveToRustType(field.type)};`);
+    lines.push(`        if ${varName} != ${varName}_expected {`);
+    lines.push(`            decoder.recover("${typePath}.${field.name}", binschema_runtime::BinSchemaError::InvalidValue(format!("${computed.type} field '${field.name}' is {} but its sources give {}", ${varName}, ${varName}_expected)))?;`);
+    lines.push(`        }`);
+  }
+  return lines;
+}
+
+/** Sibling fields a xor_of / complement_of transform is computed from */
+function transformSources(computed: any): string[] {
+  return computed.type === "xor_of" ? (computed.targets || []) : [computed.target];
+}
+
+/**
+ * Rust u64 expression for a xor_of / complement_of transform, reading the
+ * sources as `<prefix><field>` (`self.` on encode, decoded locals on decode).
+ * Callers truncate it to the computed field's width.
+ */
+function transformExpr(computed: any, prefix: string): string {
+  const operands = transformSources(computed).map(t => `(${prefix}${toRustFieldName(t)} as u64)`);
+  return computed.type === "xor_of" ? operands.join(" ^ ") : `!${operands[0]}`;
+}
+
+/**
+ * The single discriminator value that selects `variant` ("value == 0x05" -> "0x05"),
+ * or undefined for range conditions and the fallback variant.
  */
+function variantTagValue(variant: any): string | undefined {
+  const match = typeof variant.when === "string" ? variant.when.match(/^\s*value\s*===?\s*(0x[0-9A-Fa-f]+|\d+)\s*$/) : null;
+  return match ? match[1] : undefined;
+}
+
+const CRC_PRESETS: Record<string, string> = {
+  crc8: "CRC_8",
+  crc16_ccitt_false: "CRC_16_CCITT_FALSE",
+  crc16_xmodem: "CRC_16_XMODEM",
+  crc16_kermit: "CRC_16_KERMIT",
+  crc16_modbus: "CRC_16_MODBUS",
+  crc16_arc: "CRC_16_ARC",
+  crc32: "CRC_32",
+  crc32c: "CRC_32C",
+  crc32_bzip2: "CRC_32_BZIP2",
+  crc64_xz: "CRC_64_XZ",
+};
+
+/** Rust expression computing the crc_of variant declared by `computed.crc` over `inputExpr` (a byte slice), propagating an invalid width with `?` */
+function crcOfExpr(computed: any, inputExpr: string): string {
+  const crc = computed.crc;
+  let params: string;
+  if (typeof crc === "string") {
+    const preset = CRC_PRESETS[crc];
+    if (!preset) throw new Error(`Unknown crc_of preset '${crc}'`);
+    params = `binschema_runtime::CrcParams::${preset}`;
+  } else if (crc && typeof crc === "object") {
+    params = `binschema_runtime::CrcParams { width: ${crc.width}, poly: ${crc.poly}, init: ${crc.init}, refin: ${crc.refin}, refout: ${crc.refout}, xorout: ${crc.xorout} }`;
+  } else {
+    throw new Error(`crc_of requires a 'crc' preset name or parameter object`);
+  }
+  return `binschema_runtime::crc(${inputExpr}, &${params})?`;
+}
+
 function generateComputedFieldWrite(field: Field, varName: string, rustEndianness: string, indent: string): string[] {
   const lines: string[] = [];
 
@@ -4731,7 +6510,7 @@ function generateEncodeFieldWithValue(field: Field, valueVar: string, defaultEnd
     case "int64":
     case "float32":
     case "float64":
-      lines.push(`${indent}${emitEncoderWrite(field.type, `*${valueVar}`, rustEndianness, aligned)}`);
+      lines.push(`${indent}${emitFieldEncoderWrite(field, `*${valueVar}`, rustEndianness, aligned)}`);
       break;
     case "string":
       // For strings, call the encoding function
@@ -4773,7 +6552,7 @@ function generateEncodeConstField(field: Field, constValue: any, defaultEndianne
     case "int16":
     case "int32":
     case "int64":
-      lines.push(`${indent}${emitEncoderWrite(field.type, `${value}`, rustEndianness, aligned)}`);
+      lines.push(`${indent}${emitFieldEncoderWrite(field, `${value}`, rustEndianness, aligned)}`);
       break;
     case "string": {
       // String const - write the const string value as fixed-length bytes
@@ -4806,23 +6585,124 @@ function generateEncodeConstField(field: Field, constValue: any, defaultEndianne
       throw new Error(`Unsupported const field type: ${field.type} (field: ${field.name})`);
   }
 
-  return lines;
-}
+  return lines;
+}
+
+/**
+ * Generates the decode methods
+ * If the type needs decode context (has field_referenced arrays with external fields),
+ * generates decode_with_decoder_and_context that accepts a HashMap<std::string::String, u64> context.
+ */
+function generateDecodeMethod(name: string, fields: Field[], defaultEndianness: string, defaultBitOrder: string, schema: BinarySchema, instances?: any[], schemaTypeName?: string): string[] {
+  const lines: string[] = [];
+  const bitOrder = mapBitOrder(defaultBitOrder);
+  const needsContext = typeNeedsDecodeContext(name, schema);
+  const hasInstances = instances && instances.length > 0;
+
+  // Public decode function
+  lines.push(`    pub fn decode(bytes: &[u8]) -> Result<Self> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  if (needsContext) {
+    lines.push(`        Self::decode_with_decoder_and_context(&mut decoder, None)`);
+  } else {
+    lines.push(`        Self::decode_with_decoder(&mut decoder)`);
+  }
+  lines.push(`    }`);
+  lines.push(``);
+
+  // Lenient decode: recoverable field errors are collected rather than fatal
+  lines.push(`    /// Decodes as far as possible, collecting recoverable field errors (const`);
+  lines.push(`    /// mismatches, length fields that disagree with the decoded data) instead of`);
+  lines.push(`    /// stopping at the first. A fatal error such as truncated input ends the`);
+  lines.push(`    /// decode: it's reported last and the value is \`None\`.`);
+  lines.push(`    pub fn decode_lenient(bytes: &[u8]) -> (Option<Self>, Vec<binschema_runtime::FieldError>) {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  lines.push(`        decoder.set_lenient();`);
+  if (needsContext) {
+    lines.push(`        let result = Self::decode_with_decoder_and_context(&mut decoder, None);`);
+  } else {
+    lines.push(`        let result = Self::decode_with_decoder(&mut decoder);`);
+  }
+  lines.push(`        let mut errors = decoder.take_field_errors();`);
+  lines.push(`        match result {`);
+  lines.push(`            Ok(value) => (Some(value), errors),`);
+  lines.push(`            Err(error) => {`);
+  lines.push(`                errors.push(binschema_runtime::FieldError { field: "${schemaTypeName ?? name}".to_string(), error });`);
+  lines.push(`                (None, errors)`);
+  lines.push(`            }`);
+  lines.push(`        }`);
+  lines.push(`    }`);
+  lines.push(``);
+
+  // Push-based decoding for values that arrive in chunks
+  lines.push(`    /// Creates a push-based decoder that buffers chunks until a whole value has arrived.`);
+  lines.push(`    pub fn partial_decoder() -> binschema_runtime::PartialDecoder<Self> {`);
+  if (needsContext) {
+    lines.push(`        binschema_runtime::PartialDecoder::new(BitOrder::${bitOrder}, |decoder| Self::decode_with_decoder_and_context(decoder, None))`);
+  } else {
+    lines.push(`        binschema_runtime::PartialDecoder::new(BitOrder::${bitOrder}, Self::decode_with_decoder)`);
+  }
+  lines.push(`    }`);
+  lines.push(``);
+
+  // Compression-pointer log, only for types that can contain back-references
+  if (typeTransitivelyContainsBackReference(schemaTypeName ?? name, schema)) {
+    lines.push(`    /// Decodes and also returns every back-reference followed, as`);
+    lines.push(`    /// \`(pointer_position, target_offset)\` pairs in the order they were read.`);
+    lines.push(`    pub fn decode_with_pointer_log(bytes: &[u8]) -> Result<(Self, Vec<(usize, usize)>)> {`);
+    lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+    lines.push(`        decoder.log_pointers();`);
+    if (needsContext) {
+      lines.push(`        let value = Self::decode_with_decoder_and_context(&mut decoder, None)?;`);
+    } else {
+      lines.push(`        let value = Self::decode_with_decoder(&mut decoder)?;`);
+    }
+    lines.push(`        Ok((value, decoder.take_pointer_log()))`);
+    lines.push(`    }`);
+    lines.push(``);
+  }
+
+  // Byte-order autodetection from a designated multi-byte constant
+  const endiannessMarker: string | undefined = (schema.types[schemaTypeName ?? name] as any)?.endianness_marker;
+  if (endiannessMarker) {
+    lines.push(...generateAutodetectEndiannessMethod(endiannessMarker, fields, defaultEndianness, bitOrder, needsContext));
+  }
+
+  // In-place decode for fixed-size types (and the types they nest)
+  if (decodeIntoTypes(schema).has(schemaTypeName ?? name)) {
+    lines.push(...generateDecodeIntoMethod(name, fields, defaultEndianness, bitOrder, schema));
+  }
+
+  // Decode a message whose length is known from the transport. Unlike slicing,
+  // the rest of the buffer stays reachable for back-references.
+  // nom/winnow-style parser: decoded value plus the unconsumed tail
+  lines.push(`    /// Decodes one value from the front of \`input\` and returns it with the unconsumed tail.`);
+  lines.push(`    pub fn parse(input: &[u8]) -> Result<(Self, &[u8])> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new(input, BitOrder::${bitOrder});`);
+  if (needsContext) {
+    lines.push(`        let value = Self::decode_with_decoder_and_context(&mut decoder, None)?;`);
+  } else {
+    lines.push(`        let value = Self::decode_with_decoder(&mut decoder)?;`);
+  }
+  lines.push(`        Ok((value, decoder.remaining_slice()))`);
+  lines.push(`    }`);
+  lines.push(``);
 
-/**
- * Generates the decode methods
- * If the type needs decode context (has field_referenced arrays with external fields),
- * generates decode_with_decoder_and_context that accepts a HashMap<std::string::String, u64> context.
- */
-function generateDecodeMethod(name: string, fields: Field[], defaultEndianness: string, defaultBitOrder: string, schema: BinarySchema, instances?: any[], schemaTypeName?: string): string[] {
-  const lines: string[] = [];
-  const bitOrder = mapBitOrder(defaultBitOrder);
-  const needsContext = typeNeedsDecodeContext(name, schema);
-  const hasInstances = instances && instances.length > 0;
+  lines.push(`    /// Decodes a message occupying the first \`len\` bytes of \`bytes\`.`);
+  lines.push(`    pub fn decode_bounded(bytes: &[u8], len: usize) -> Result<Self> {`);
+  lines.push(`        let mut decoder = BitStreamDecoder::new_bounded(bytes, len, BitOrder::${bitOrder})?;`);
+  if (needsContext) {
+    lines.push(`        Self::decode_with_decoder_and_context(&mut decoder, None)`);
+  } else {
+    lines.push(`        Self::decode_with_decoder(&mut decoder)`);
+  }
+  lines.push(`    }`);
+  lines.push(``);
 
-  // Public decode function
-  lines.push(`    pub fn decode(bytes: &[u8]) -> Result<Self> {`);
+  lines.push(`    /// Decodes untrusted input, failing with \`LimitExceeded\` where it breaks \`limits\`.`);
+  lines.push(`    pub fn decode_with_limits(bytes: &[u8], limits: &binschema_runtime::DecodeLimits) -> Result<Self> {`);
   lines.push(`        let mut decoder = BitStreamDecoder::new(bytes, BitOrder::${bitOrder});`);
+  lines.push(`        decoder.set_limits(*limits)?;`);
   if (needsContext) {
     lines.push(`        Self::decode_with_decoder_and_context(&mut decoder, None)`);
   } else {
@@ -4841,6 +6721,8 @@ function generateDecodeMethod(name: string, fields: Field[], defaultEndianness:
     // Context-aware version
     lines.push(`    pub fn decode_with_decoder_and_context(decoder: &mut BitStreamDecoder, ctx: Option<&HashMap<std::string::String, u64>>) -> Result<Self> {`);
   }
+  // The body runs one nesting level down, so recursive types honour max_depth
+  const bodyStart = lines.length;
 
   // Compute per-field byte-alignment for optimized decode calls
   const fieldAlignments = computeFieldAlignments(fields);
@@ -4848,11 +6730,34 @@ function generateDecodeMethod(name: string, fields: Field[], defaultEndianness:
   // Generate decoding logic for each field
   // Note: We decode ALL fields (including unnamed) because they may be referenced
   // by other fields (e.g., as length_field for arrays)
+  const decodeAnchors = collectAnchorFieldNames([], hasInstances ? instances : undefined);
+  const lengthChecks = collectDecodedLengthChecks(fields, schema);
   for (let fieldIdx = 0; fieldIdx < fields.length; fieldIdx++) {
     const field = fields[fieldIdx];
+    if (field.name && decodeAnchors.has(field.name)) {
+      lines.push(`        let ${toRustFieldName(field.name)}_anchor = decoder.position();`);
+    }
+    const lengthCheck = field.name ? lengthChecks.get(field.name) : undefined;
+    const targetRust = toRustFieldName(field.name);
+    if (lengthCheck) {
+      lines.push(`        let ${targetRust}_decode_start = decoder.position();`);
+    }
     lines.push(...generateDecodeField(field, defaultEndianness, "        ", name, schema, fields, needsContext, fieldAlignments[fieldIdx]));
+    if (lengthCheck) {
+      // Inverse of length_of: the stored length must match what was actually decoded
+      const lengthRust = toRustFieldName(lengthCheck.lengthField);
+      const expected = lengthCheck.offset !== 0
+        ? `${targetRust}_decoded_len as i64 + (${lengthCheck.offset}i64)`
+        : `${targetRust}_decoded_len as i64`;
+      lines.push(`        let ${targetRust}_decoded_len = decoder.position() - ${targetRust}_decode_start;`);
+      lines.push(`        if ${lengthRust} as i64 != ${expected} {`);
+      lines.push(`            decoder.recover("${schemaTypeName ?? name}.${lengthCheck.lengthField}", binschema_runtime::BinSchemaError::InvalidValue(format!("length field '${lengthCheck.lengthField}' is {} but '${field.name}' decoded to {} bytes", ${lengthRust}, ${targetRust}_decoded_len)))?;`);
+      lines.push(`        }`);
+    }
   }
 
+  lines.push(...generateTransformChecks(fields, schemaTypeName ?? name));
+
   // Generate instance field decoding (position-based)
   // Only generate if there are non-inline-union instances
   if (hasInstances) {
@@ -4874,6 +6779,9 @@ function generateDecodeMethod(name: string, fields: Field[], defaultEndianness:
     // Box-wrap recursive fields so the struct has a fixed size (E0072).
     if (fieldIsRecursive(schemaTypeName, field, schema)) {
       lines.push(`            ${fieldName}: Box::new(${fieldName}),`);
+    } else if ((field as any).newtype) {
+      // Decoded as the primitive so later fields can reference it; wrapped last
+      lines.push(`            ${fieldName}: ${(field as any).newtype}(${fieldName}),`);
     } else {
       lines.push(`            ${fieldName},`);
     }
@@ -4888,6 +6796,8 @@ function generateDecodeMethod(name: string, fields: Field[], defaultEndianness:
     }
   }
   lines.push(`        })`);
+  const body = lines.splice(bodyStart).map(line => line && `    ${line}`);
+  lines.push(`        decoder.nested(|decoder| {`, ...body, `        })`);
   lines.push(`    }`);
 
   return lines;
@@ -4965,6 +6875,12 @@ function generateInstanceFieldDecoding(
 
     // Resolve position
     lines.push(...generatePositionResolution(instance.position, indent));
+    if (instance.relative_to) {
+      if (!sequenceFields.some((f) => f.name === instance.relative_to)) {
+        throw new Error(`Instance '${instance.name}': relative_to '${instance.relative_to}' is not a sequence field`);
+      }
+      lines.push(`${indent}let position = ${toRustFieldName(instance.relative_to)}_anchor.saturating_add(position);`);
+    }
 
     // Validate alignment if specified
     if (instance.alignment && instance.alignment > 1) {
@@ -5113,17 +7029,27 @@ function generateEncodeField(field: Field, defaultEndianness: string, indent: st
     case "int64":
     case "float32":
     case "float64":
-      lines.push(`${indent}${emitEncoderWrite(field.type, fieldName, rustEndianness, aligned)}`);
+      lines.push(`${indent}${emitFieldEncoderWrite(field, (field as any).newtype ? `${fieldName}.0` : fieldName, rustEndianness, aligned)}`);
       break;
 
     case "bit":
     case "int": {
       // Bitfield - write individual bits
       const bitSize = (field as any).size || 1;
-      lines.push(`${indent}encoder.write_bits(${fieldName} as u64, ${bitSize});`);
+      lines.push(field.type === "int"
+        ? `${indent}encoder.write_bits_signed(i64::from(${fieldName}), ${bitSize});`
+        : `${indent}encoder.try_write_bits(${fieldName} as u64, ${bitSize})?;`);
       break;
     }
 
+    case "custom_float":
+      lines.push(`${indent}encoder.write_bits(${customFloatLayout(field)}.from_f64(${fieldName})?, ${customFloatWidth(field)});`);
+      break;
+
+    case "uuid":
+      lines.push(`${indent}encoder.write_${uuidLayoutSuffix(field)}(&${fieldName});`);
+      break;
+
     case "varlength": {
       // Variable-length integer encoding (VLQ, LEB128, DER, etc.)
       const encoding = (field as any).encoding || "vlq";
@@ -5140,7 +7066,7 @@ function generateEncodeField(field: Field, defaultEndianness: string, indent: st
       } else {
         // Bitfield without sub-fields - write as packed integer
         const bitSize = (field as any).size || 8;
-        lines.push(`${indent}encoder.write_bits(${fieldName} as u64, ${bitSize});`);
+        lines.push(`${indent}encoder.try_write_bits(${fieldName} as u64, ${bitSize})?;`);
       }
       break;
     }
@@ -5149,6 +7075,11 @@ function generateEncodeField(field: Field, defaultEndianness: string, indent: st
       lines.push(`${indent}${aligned ? "encoder.write_byte" : "encoder.write_uint8"}(if ${fieldName} { 1 } else { 0 });`);
       break;
 
+    case "message_bytes":
+      // Opaque payload; its length prefix is the length_of field before it
+      lines.push(`${indent}encoder.write_bytes(&${fieldName});`);
+      break;
+
     case "bytes":
       // Delegate to array encoding with synthetic array<uint8> field
       lines.push(...generateEncodeArray({ ...field, type: "array", items: { type: "uint8" } } as any, fieldName, endianness, rustEndianness, indent, schema, choiceEncodeCtxVar, aligned));
@@ -5158,9 +7089,30 @@ function generateEncodeField(field: Field, defaultEndianness: string, indent: st
       lines.push(...generateEncodeString(field as any, fieldName, endianness, indent, aligned));
       break;
 
-    case "array":
+    case "array": {
+      if ((field as any).kind === "countdown" && schema) {
+        // The elements must count down the seed field exactly, or decode would stop elsewhere
+        const seed = (field as any).length_field;
+        const totalVar = `${toRustFieldName(field.name).replace(/^r#/, "")}_total`;
+        lines.push(`${indent}let ${totalVar}: u64 = ${fieldName}.iter().map(|item| ${countdownConsumedExpr(field, "item", schema)}).sum();`);
+        lines.push(`${indent}if ${totalVar} != self.${toRustFieldName(seed)} as u64 {`);
+        lines.push(`${indent}    return Err(BinSchemaError::InvalidValue(format!("'${field.name}' elements count for {} but '${seed}' is {}", ${totalVar}, self.${toRustFieldName(seed)})));`);
+        lines.push(`${indent}}`);
+      }
+      const maxEncodedLength: number | undefined = (field as any).max_encoded_length;
+      if (maxEncodedLength === undefined) {
+        lines.push(...generateEncodeArray(field as any, fieldName, endianness, rustEndianness, indent, schema, choiceEncodeCtxVar, aligned));
+        break;
+      }
+      // Measure what the array wrote, prefix and terminator included
+      const startVar = `${toRustFieldName(field.name).replace(/^r#/, "")}_start`;
+      lines.push(`${indent}let ${startVar} = encoder.byte_offset();`);
       lines.push(...generateEncodeArray(field as any, fieldName, endianness, rustEndianness, indent, schema, choiceEncodeCtxVar, aligned));
+      lines.push(`${indent}if encoder.byte_offset() - ${startVar} > ${maxEncodedLength} {`);
+      lines.push(`${indent}    return Err(BinSchemaError::InvalidValue(format!("'${field.name}' encodes to {} bytes, which exceeds max_encoded_length ${maxEncodedLength}", encoder.byte_offset() - ${startVar})));`);
+      lines.push(`${indent}}`);
       break;
+    }
 
     case "optional":
       lines.push(...generateEncodeOptional(field as any, fieldName, endianness, indent));
@@ -5170,13 +7122,7 @@ function generateEncodeField(field: Field, defaultEndianness: string, indent: st
       // Alignment padding: write zero bytes to align to the specified boundary
       const alignTo = (field as any).align_to || 4;
       lines.push(`${indent}// Alignment padding to ${alignTo}-byte boundary`);
-      lines.push(`${indent}{`);
-      lines.push(`${indent}    let current_pos = encoder.byte_offset();`);
-      lines.push(`${indent}    let padding_bytes = (${alignTo} - (current_pos % ${alignTo})) % ${alignTo};`);
-      lines.push(`${indent}    for _ in 0..padding_bytes {`);
-      lines.push(`${indent}        encoder.write_byte(0);`);
-      lines.push(`${indent}    }`);
-      lines.push(`${indent}}`);
+      lines.push(`${indent}encoder.align_to(${alignTo});`);
       break;
     }
 
@@ -5254,6 +7200,37 @@ function generateStringLen(fieldName: string, encoding: string): string {
   return `${fieldName}.len()`;
 }
 
+/**
+ * Generates the encode-time length check for a variable-length string. The
+ * limit is the smaller of the field's max_length and what its length prefix
+ * can hold, so an over-long value fails with InvalidValue rather than having
+ * its prefix silently truncated.
+ */
+function generateStringLengthCheck(field: any, lenExpr: string, lengthType: string | undefined, indent: string): string[] {
+  const maxLength: number | undefined = field.max_length;
+  const prefixMax: Record<string, number> = { uint8: 0xff, uint16: 0xffff, uint32: 0xffffffff };
+  const prefixLimit = lengthType !== undefined ? prefixMax[lengthType] : undefined;
+  const what = field.name && field.name !== "0" ? `'${field.name}'` : "string";
+
+  let limitExpr: string;
+  let reason: string;
+  if (maxLength !== undefined && (prefixLimit === undefined || maxLength <= prefixLimit)) {
+    limitExpr = `${maxLength}`;
+    reason = `exceeds max_length ${maxLength}`;
+  } else if (prefixLimit !== undefined) {
+    limitExpr = `${lengthType!.replace("uint", "u")}::MAX as usize`;
+    reason = `does not fit a ${lengthType} length prefix`;
+  } else {
+    return [];
+  }
+
+  return [
+    `${indent}if ${lenExpr} > ${limitExpr} {`,
+    `${indent}    return Err(BinSchemaError::InvalidValue(format!("${what} is {} bytes, which ${reason}", ${lenExpr})));`,
+    `${indent}}`,
+  ];
+}
+
 /**
  * Generates encoding code for string field
  */
@@ -5276,6 +7253,7 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
         const lengthType = field.length_type || "uint8";
         // Length is in bytes (code_units * 2)
         const lenExpr = `(code_units.len() * 2)`;
+        lines.push(...generateStringLengthCheck(field, lenExpr, lengthType, indent));
         const castType = lengthType === "uint8" ? "u8" : lengthType === "uint16" ? "u16" : lengthType === "uint32" ? "u32" : "u64";
         lines.push(`${indent}${emitEncoderWrite(lengthType, `${lenExpr} as ${castType}`, rustEndianness, aligned)}`);
         lines.push(`${indent}for &cu in code_units.iter() {`);
@@ -5284,6 +7262,7 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
         break;
       }
       case "null_terminated":
+        lines.push(...generateStringLengthCheck(field, `(code_units.len() * 2)`, undefined, indent));
         lines.push(`${indent}for &cu in code_units.iter() {`);
         lines.push(`${indent}    ${emitEncoderWrite("uint16", "cu", stringRustEndianness, aligned)}`);
         lines.push(`${indent}}`);
@@ -5302,6 +7281,7 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
         break;
       }
       case "field_referenced":
+        lines.push(...generateStringLengthCheck(field, `(code_units.len() * 2)`, undefined, indent));
         lines.push(`${indent}for &cu in code_units.iter() {`);
         lines.push(`${indent}    ${emitEncoderWrite("uint16", "cu", stringRustEndianness, aligned)}`);
         lines.push(`${indent}}`);
@@ -5316,21 +7296,19 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
     case "length_prefixed": {
       const lengthType = field.length_type || "uint8";
       const lenExpr = generateStringLen(fieldName, encoding);
+      lines.push(...generateStringLengthCheck(field, lenExpr, lengthType, indent));
       // Write length prefix
       lines.push(`${indent}${emitEncoderWrite(lengthType, `${lenExpr} as ${lengthType === "uint8" ? "u8" : lengthType === "uint16" ? "u16" : lengthType === "uint32" ? "u32" : "u64"}`, rustEndianness, aligned)}`);
       // Write bytes
       lines.push(...generateStringToBytes(fieldName, encoding, indent));
-      lines.push(`${indent}for &b in string_bytes.iter() {`);
-      lines.push(`${indent}    ${writeByteCall}(b);`);
-      lines.push(`${indent}}`);
+      lines.push(`${indent}encoder.write_bytes(&string_bytes);`);
       break;
     }
 
     case "null_terminated":
       lines.push(...generateStringToBytes(fieldName, encoding, indent));
-      lines.push(`${indent}for &b in string_bytes.iter() {`);
-      lines.push(`${indent}    ${writeByteCall}(b);`);
-      lines.push(`${indent}}`);
+      lines.push(...generateStringLengthCheck(field, "string_bytes.len()", undefined, indent));
+      lines.push(`${indent}encoder.write_bytes(&string_bytes);`);
       lines.push(`${indent}${writeByteCall}(0);`);
       break;
 
@@ -5350,9 +7328,8 @@ function generateEncodeString(field: any, fieldName: string, endianness: string,
     case "field_referenced":
       // Length is determined by another field, just write the bytes
       lines.push(...generateStringToBytes(fieldName, encoding, indent));
-      lines.push(`${indent}for &b in string_bytes.iter() {`);
-      lines.push(`${indent}    ${writeByteCall}(b);`);
-      lines.push(`${indent}}`);
+      lines.push(...generateStringLengthCheck(field, "string_bytes.len()", undefined, indent));
+      lines.push(`${indent}encoder.write_bytes(&string_bytes);`);
       break;
 
     default:
@@ -5477,6 +7454,10 @@ function generateEncodeArrayWithRef(field: any, valueVar: string, endianness: st
     }
   }
 
+  if (kind === "bit_length_prefixed") {
+    lines.push(...generateBitLengthPrefix(field, `${valueVar}.len()`, indent));
+  }
+
   // Generate loop for encoding items - use .iter() since value is already a reference
   lines.push(`${indent}for item in ${valueVar}.iter() {`);
 
@@ -5519,9 +7500,7 @@ function generateEncodeArrayWithRef(field: any, valueVar: string, endianness: st
           lines.push(`${indent}    encoder.write_uint64(item_bytes.len() as u64, Endianness::${rustEndianness});`);
           break;
       }
-      lines.push(`${indent}    for b in item_bytes {`);
-      lines.push(`${indent}        encoder.write_uint8(b);`);
-      lines.push(`${indent}    }`);
+      lines.push(`${indent}    encoder.write_bytes(&item_bytes);`);
       lines.push(`${indent}}`);
 
       // Write null terminator if needed
@@ -5550,6 +7529,69 @@ function generateEncodeArrayWithRef(field: any, valueVar: string, endianness: st
   return lines;
 }
 
+/**
+ * Columns of a columnar array: the item struct's fields, each stored as one
+ * contiguous run of `count` values. Only fixed-size numeric fields qualify, so
+ * every column has a known width and records can be rebuilt by index.
+ */
+function columnarColumns(field: any, schema?: BinarySchema): Field[] {
+  const itemDef: any = schema?.types[field.items?.type];
+  if (!itemDef || !Array.isArray(itemDef.sequence)) {
+    throw new Error(`Columnar array '${field.name}' items must reference a sequence type`);
+  }
+  const numeric = ["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64", "float32", "float64"];
+  for (const column of itemDef.sequence as any[]) {
+    if (!column.name || !numeric.includes(column.type) || column.computed || column.const != null || isFieldConditional(column)) {
+      throw new Error(`Columnar array '${field.name}': column '${column.name}' of '${field.items.type}' must be a plain numeric field`);
+    }
+  }
+  return itemDef.sequence;
+}
+
+/** Columnar array encode: item count, then each column across all items */
+function generateEncodeColumnarArray(field: any, fieldName: string, endianness: string, indent: string, schema: BinarySchema | undefined, aligned: boolean): string[] {
+  const lines: string[] = [];
+  const rustEndianness = mapEndianness(endianness);
+  const columns = columnarColumns(field, schema);
+  const lengthType = field.length_type || "uint8";
+  lines.push(`${indent}${emitEncoderWrite(lengthType, `${fieldName}.len() as ${mapPrimitiveToRustType(lengthType)}`, rustEndianness, aligned)}`);
+  for (const column of columns) {
+    const columnEndianness = mapEndianness((column as any).endianness || endianness);
+    lines.push(`${indent}for item in ${fieldName}.iter() {`);
+    lines.push(`${indent}    ${emitEncoderWrite(column.type as string, `item.${toRustFieldName(column.name)}`, columnEndianness, aligned)}`);
+    lines.push(`${indent}}`);
+  }
+  return lines;
+}
+
+/** Columnar array decode: read the count once, each column fully, then rebuild the records */
+function generateDecodeColumnarArray(field: any, varName: string, endianness: string, indent: string, schema: BinarySchema, aligned: boolean): string[] {
+  const lines: string[] = [];
+  const rustEndianness = mapEndianness(endianness);
+  const columns = columnarColumns(field, schema);
+  const lengthType = field.length_type || "uint8";
+  const base = varName.replace(/^r#/, "");
+  const recordBits = columns.reduce((sum, column) => sum + (primitiveFieldBitWidth(column) ?? 0), 0);
+
+  lines.push(`${indent}let length = ${emitDecoderRead(lengthType, rustEndianness, aligned)} as usize;`);
+  lines.push(...emitCountFitsCheck("length", recordBits, indent));
+  for (const column of columns) {
+    const columnVar = `${base}_${toRustFieldName(column.name).replace(/^r#/, "")}`;
+    const columnEndianness = mapEndianness((column as any).endianness || endianness);
+    lines.push(`${indent}let mut ${columnVar} = Vec::with_capacity(decoder.capacity_hint(length));`);
+    lines.push(`${indent}for _ in 0..length {`);
+    lines.push(`${indent}    ${columnVar}.push(${emitDecoderRead(column.type as string, columnEndianness, aligned)});`);
+    lines.push(`${indent}}`);
+  }
+  const itemType = toRustTypeName(field.items.type);
+  const inits = columns.map(column => {
+    const rustName = toRustFieldName(column.name);
+    return `${rustName}: ${base}_${rustName.replace(/^r#/, "")}[i]`;
+  });
+  lines.push(`${indent}let ${varName}: Vec<${itemType}> = (0..length).map(|i| ${itemType} { ${inits.join(", ")} }).collect();`);
+  return lines;
+}
+
 /**
  * Generates encoding code for array field
  */
@@ -5557,7 +7599,16 @@ function generateEncodeArray(field: any, fieldName: string, endianness: string,
   const lines: string[] = [];
   const kind = field.kind;
   const items = field.items;
-  const aligned = byteAligned === true;
+  // Items after a bit-count prefix start mid-byte
+  const aligned = byteAligned === true && kind !== "bit_length_prefixed";
+
+  if (kind === "columnar") {
+    return generateEncodeColumnarArray(field, fieldName, endianness, indent, schema, aligned);
+  }
+
+  if (kind === "bit_length_prefixed") {
+    lines.push(...generateBitLengthPrefix(field, `${fieldName}.len()`, indent));
+  }
 
   // Write length prefix for length_prefixed and length_prefixed_items arrays
   if (kind === "length_prefixed" || kind === "length_prefixed_items") {
@@ -5615,6 +7666,12 @@ function generateEncodeArray(field: any, fieldName: string, endianness: string,
     }
   }
 
+  // Delta-encoded items are written relative to the one before
+  const deltaPrevVar = `${(field.name ? toRustFieldName(field.name) : "item").replace(/^r#/, "")}_prev`;
+  if (field.encoding === "delta_zigzag") {
+    lines.push(`${indent}let mut ${deltaPrevVar}: Option<${mapPrimitiveToRustType(items.type)}> = None;`);
+  }
+
   // Generate loop for encoding items
   // If we have a context variable, use enumerate for iteration index tracking
   if (choiceEncodeCtxVar) {
@@ -5641,9 +7698,7 @@ function generateEncodeArray(field: any, fieldName: string, endianness: string,
       lines.push(`${indent}    let item_bytes = item.encode()?;`);
       const castType = itemLengthType === "uint8" ? "u8" : itemLengthType === "uint16" ? "u16" : itemLengthType === "uint32" ? "u32" : "u64";
       lines.push(`${indent}    ${emitEncoderWrite(itemLengthType, `item_bytes.len() as ${castType}`, rustEndianness, aligned)}`);
-      lines.push(`${indent}    for b in item_bytes {`);
-      lines.push(`${indent}        ${aligned ? "encoder.write_byte(b);" : "encoder.write_uint8(b);"}`);
-      lines.push(`${indent}    }`);
+      lines.push(`${indent}    encoder.write_bytes(&item_bytes);`);
       lines.push(`${indent}}`);
 
       // Write null terminator for null_terminated arrays
@@ -5680,8 +7735,18 @@ function generateEncodeArray(field: any, fieldName: string, endianness: string,
     lines.push(`${indent}    let item_ctx = ctx.with_base_offset(encoder.byte_offset());`);
   }
 
-  const innerLines = generateEncodeArrayItem(itemField, "item", endianness, `${indent}    `, schema, itemsNeedContext, choiceEncodeCtxVar, itemsContainBackRef, aligned);
-  lines.push(...innerLines);
+  if (field.encoding === "delta_zigzag") {
+    lines.push(...generateDeltaItemEncode(items, deltaPrevVar, endianness, `${indent}    `, aligned));
+  } else {
+    const innerLines = generateEncodeArrayItem(itemField, "item", endianness, `${indent}    `, schema, itemsNeedContext, choiceEncodeCtxVar, itemsContainBackRef, aligned);
+    lines.push(...innerLines);
+  }
+
+  if (field.record_alignment) {
+    const alignTo = field.record_alignment;
+    lines.push(`${indent}    // Pad the record to a ${alignTo}-byte boundary`);
+    lines.push(`${indent}    encoder.align_to(${alignTo});`);
+  }
 
   lines.push(`${indent}}`);
 
@@ -5718,6 +7783,92 @@ function generateEncodeArray(field: any, fieldName: string, endianness: string,
   return lines;
 }
 
+/** `value` of integer type `type` widened to i64 for delta arithmetic */
+function deltaWideExpr(type: string, value: string): string {
+  if (type === "int64") return value;
+  if (type === "uint64") return `(${value} as i64)`;
+  return `i64::from(${value})`;
+}
+
+/**
+ * Encode loop body of a delta_zigzag array: the first item as usual, every
+ * later one as the zigzag varint of its difference from the item before.
+ * `prevVar` is an `Option` of the item type declared ahead of the loop.
+ */
+function generateDeltaItemEncode(items: any, prevVar: string, endianness: string, indent: string, aligned: boolean): string[] {
+  const itemEndianness = mapEndianness(items.endianness || endianness);
+  return [
+    `${indent}if let Some(prev) = ${prevVar} {`,
+    `${indent}    encoder.write_varint_i64(${deltaWideExpr(items.type, "*item")}.wrapping_sub(${deltaWideExpr(items.type, "prev")}));`,
+    `${indent}} else {`,
+    `${indent}    ${emitEncoderWrite(items.type, "*item", itemEndianness, aligned)}`,
+    `${indent}}`,
+    `${indent}${prevVar} = Some(*item);`,
+  ];
+}
+
+/**
+ * Decode loop body of a delta_zigzag array, accumulating each delta onto the
+ * last decoded item. 64-bit items wrap like the encoder's subtraction did;
+ * narrower ones reject a sum outside their range.
+ */
+function generateDeltaItemDecode(field: any, varName: string, endianness: string, indent: string, aligned: boolean): string[] {
+  const items = field.items;
+  const itemEndianness = mapEndianness(items.endianness || endianness);
+  const sum = `${deltaWideExpr(items.type, "prev")}.wrapping_add(decoder.read_varint_i64()?)`;
+  const lines = [`${indent}let item = if let Some(&prev) = ${varName}.last() {`];
+  if (items.type === "int64") {
+    lines.push(`${indent}    ${sum}`);
+  } else if (items.type === "uint64") {
+    lines.push(`${indent}    ${sum} as u64`);
+  } else {
+    const rustType = mapPrimitiveToRustType(items.type);
+    lines.push(`${indent}    let value = ${sum};`);
+    lines.push(`${indent}    ${rustType}::try_from(value).map_err(|_| BinSchemaError::InvalidValue(format!("'${field.name}' delta gives {}, outside ${items.type}", value)))?`);
+  }
+  lines.push(`${indent}} else {`);
+  lines.push(`${indent}    ${emitDecoderRead(items.type, itemEndianness, aligned)}`);
+  lines.push(`${indent}};`);
+  return lines;
+}
+
+/**
+ * Rust u64 expression for how much the countdown array element `itemVar`
+ * counts down: its `countdown_by` field, or 1. For union items each variant
+ * without the field counts as 1.
+ */
+function countdownConsumedExpr(field: any, itemVar: string, schema: BinarySchema): string {
+  const by: string | undefined = field.countdown_by;
+  if (!by) return "1";
+  const itemDef: any = schema.types[field.items.type];
+  const hasField = (typeName: string) => ((schema.types[typeName] as any)?.sequence ?? []).some((f: any) => f.name === by);
+  if (itemDef?.sequence) return `${itemVar}.${toRustFieldName(by)} as u64`;
+  const enumName = toRustTypeName(field.items.type);
+  const arms = (itemDef?.variants ?? []).map((v: any) => hasField(v.type)
+    ? `${enumName}::${toRustTypeName(v.type)}(v) => v.${toRustFieldName(by)} as u64`
+    : `${enumName}::${toRustTypeName(v.type)}(_) => 1`);
+  return `match &${itemVar} { ${arms.join(", ")} }`;
+}
+
+/**
+ * Writes the total bit count of a bit_length_prefixed array's `lenExpr` items
+ * in the array's `length_bits`-wide prefix, rejecting counts that don't fit.
+ */
+function generateBitLengthPrefix(field: any, lenExpr: string, indent: string): string[] {
+  const itemBits = primitiveFieldBitWidth(field.items) as number;
+  const prefixBits: number = field.length_bits;
+  const bitsVar = `${toRustFieldName(field.name).replace(/^r#/, "")}_bits`;
+  const lines: string[] = [];
+  lines.push(`${indent}let ${bitsVar} = (${lenExpr} as u64) * ${itemBits};`);
+  if (prefixBits < 64) {
+    lines.push(`${indent}if ${bitsVar} >= 1u64 << ${prefixBits} {`);
+    lines.push(`${indent}    return Err(BinSchemaError::InvalidValue(format!("'${field.name}' holds {} bits, which does not fit a ${prefixBits}-bit length prefix", ${bitsVar})));`);
+    lines.push(`${indent}}`);
+  }
+  lines.push(`${indent}encoder.write_bits(${bitsVar}, ${prefixBits});`);
+  return lines;
+}
+
 /**
  * Get the fixed size of an item type, or null if variable-size
  */
@@ -5772,7 +7923,9 @@ function generateEncodeArrayItem(field: Field, itemVar: string, endianness: stri
     case "bit":
     case "int": {
       const bitSize = (field as any).size || 1;
-      lines.push(`${indent}encoder.write_bits(*${itemVar} as u64, ${bitSize});`);
+      lines.push(field.type === "int"
+        ? `${indent}encoder.write_bits_signed(i64::from(*${itemVar}), ${bitSize});`
+        : `${indent}encoder.try_write_bits(*${itemVar} as u64, ${bitSize})?;`);
       break;
     }
     case "string": {
@@ -5784,15 +7937,11 @@ function generateEncodeArrayItem(field: Field, itemVar: string, endianness: stri
           const lengthType = (field as any).length_type || "uint8";
           const castType = lengthType === "uint8" ? "u8" : lengthType === "uint16" ? "u16" : lengthType === "uint32" ? "u32" : "u64";
           lines.push(`${indent}${emitEncoderWrite(lengthType, `${itemVar}.len() as ${castType}`, rustEndianness, aligned)}`);
-          lines.push(`${indent}for b in ${itemVar}.as_bytes() {`);
-          lines.push(`${indent}    ${writeByteCall}(*b);`);
-          lines.push(`${indent}}`);
+          lines.push(`${indent}encoder.write_bytes(${itemVar}.as_bytes());`);
           break;
         }
         case "null_terminated":
-          lines.push(`${indent}for b in ${itemVar}.as_bytes() {`);
-          lines.push(`${indent}    ${writeByteCall}(*b);`);
-          lines.push(`${indent}}`);
+          lines.push(`${indent}encoder.write_bytes(${itemVar}.as_bytes());`);
           lines.push(`${indent}${writeByteCall}(0);`);
           break;
         case "fixed": {
@@ -5809,9 +7958,7 @@ function generateEncodeArrayItem(field: Field, itemVar: string, endianness: stri
         }
         default:
           // Default to null-terminated for unknown string kinds
-          lines.push(`${indent}for b in ${itemVar}.as_bytes() {`);
-          lines.push(`${indent}    ${writeByteCall}(*b);`);
-          lines.push(`${indent}}`);
+          lines.push(`${indent}encoder.write_bytes(${itemVar}.as_bytes());`);
           lines.push(`${indent}${writeByteCall}(0);`);
           break;
       }
@@ -5939,9 +8086,7 @@ function generateEncodeOptional(field: any, fieldName: string, endianness: strin
     case "string":
       // For strings in optional, we need to know the string kind
       // Default to null-terminated for simplicity
-      lines.push(`${indent}    for b in v.as_bytes() {`);
-      lines.push(`${indent}        encoder.write_uint8(*b);`);
-      lines.push(`${indent}    }`);
+      lines.push(`${indent}    encoder.write_bytes(v.as_bytes());`);
       lines.push(`${indent}    encoder.write_uint8(0);`);
       break;
     default:
@@ -5961,6 +8106,16 @@ function generateEncodeOptional(field: any, fieldName: string, endianness: strin
   return lines;
 }
 
+/**
+ * Bytes that must remain for a `when_truncated` conditional field to count as
+ * present: its exact size when fixed, else its minimum size, and at least one.
+ */
+function truncationThresholdBytes(field: Field, schema: BinarySchema): number {
+  const unconditional = { ...field, conditional: undefined };
+  const bits = fixedEncodedBits(unconditional, schema) ?? minEncodedBits(unconditional, schema);
+  return Math.max(1, Math.ceil(bits / 8));
+}
+
 /**
  * Generates decoding code for a single field
  */
@@ -5985,8 +8140,20 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
     // Pass allFields so we can detect conditional parent fields for Option-safe access
     const rustCondition = convertConditionalToRust(condition, "", allFields);
 
-    // Generate: let field = if condition { Some(decode_value) } else { None };
-    lines.push(`${indent}let ${varName} = if ${rustCondition} {`);
+    // A message cut short after the flag was set: treat the field as absent,
+    // or fail before decoding any of it
+    const whenTruncated: string | undefined = fieldAny.when_truncated;
+    const needBytes = whenTruncated ? truncationThresholdBytes(field, schema) : 0;
+    if (whenTruncated === "absent") {
+      lines.push(`${indent}let ${varName} = if (${rustCondition}) && decoder.remaining_slice().len() >= ${needBytes} {`);
+    } else {
+      lines.push(`${indent}let ${varName} = if ${rustCondition} {`);
+    }
+    if (whenTruncated === "error") {
+      lines.push(`${indent}    if decoder.remaining_slice().len() < ${needBytes} {`);
+      lines.push(`${indent}        return Err(BinSchemaError::UnexpectedEof);`);
+      lines.push(`${indent}    }`);
+    }
 
     // Generate the decode for the inner value
     const innerLines = generateDecodeFieldInner(field, defaultEndianness, `${indent}    `, containingTypeName, schema, aligned);
@@ -6010,7 +8177,7 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
     case "int64":
     case "float32":
     case "float64":
-      lines.push(`${indent}let ${varName} = ${emitDecoderRead(field.type, rustEndianness, aligned)};`);
+      lines.push(`${indent}let ${varName} = ${emitFieldDecoderRead(field, rustEndianness, aligned)};`);
       break;
 
     case "bit": {
@@ -6020,10 +8187,18 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
       break;
     }
 
+    case "custom_float":
+      lines.push(`${indent}let ${varName} = ${customFloatLayout(field)}.to_f64(decoder.read_bits(${customFloatWidth(field)})?);`);
+      break;
+
+    case "uuid":
+      lines.push(`${indent}let ${varName} = decoder.read_${uuidLayoutSuffix(field)}()?;`);
+      break;
+
     case "int": {
       const bitSize = (field as any).size || 8;
       const rustType = mapFieldToRustType(field);
-      lines.push(`${indent}let ${varName} = decoder.read_bits(${bitSize})? as ${rustType};`);
+      lines.push(`${indent}let ${varName} = ${signedBitsRead(bitSize, rustType)};`);
       break;
     }
 
@@ -6055,6 +8230,10 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
       lines.push(`${indent}let ${varName} = ${emitDecoderRead("uint8", rustEndianness, aligned)} != 0;`);
       break;
 
+    case "message_bytes":
+      lines.push(`${indent}let ${varName} = decoder.read_bytes_vec(${toRustFieldName((field as any).length_field)} as usize)?;`);
+      break;
+
     case "bytes":
       // Delegate to array decoding with synthetic array<uint8> field
       lines.push(...generateDecodeArray({ ...field, type: "array", items: { type: "uint8" } } as any, varName, endianness, rustEndianness, indent, schema, containingTypeName, hasContext, aligned));
@@ -6076,11 +8255,7 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
       // Alignment padding: skip bytes to align to the specified boundary
       const alignTo = (field as any).align_to || 4;
       lines.push(`${indent}// Skip alignment padding to ${alignTo}-byte boundary`);
-      lines.push(`${indent}{`);
-      lines.push(`${indent}    let current_pos = decoder.position();`);
-      lines.push(`${indent}    let padding_bytes = (${alignTo} - (current_pos % ${alignTo})) % ${alignTo};`);
-      lines.push(`${indent}    decoder.seek(current_pos + padding_bytes)?;`);
-      lines.push(`${indent}}`);
+      lines.push(`${indent}decoder.align_to(${alignTo})?;`);
       break;
     }
 
@@ -6120,42 +8295,45 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
         lines.push(`${indent}let mut sub_decoder = BitStreamDecoder::new(&budget_slice, BitOrder::${mapBitOrder(fieldAny.bit_order || schema.config?.bit_order || "msb_first")});`);
       }
 
-      if (discriminator.field) {
-        // Field-based discriminator - generate inline if-else chain
-        // Generates: let varName = if cond { Enum::Variant(decode...) } else { ... };
-        const discriminatorFieldName = toRustFieldName(discriminator.field);
-        const fallbackVariant = variants.find((v: any) => !v.when);
-        const conditionalVariants = variants.filter((v: any) => v.when);
+      // Helper to generate variant decode expression
+      function rustVariantDecodeExpr(variant: any, vi: string): string[] {
+        const vTypeName = toRustTypeName(variant.type);
+        const vNeedsSuffix = typeNeedsInputOutputSuffix(variant.type, schema);
+        const vDecodeTypeName = vNeedsSuffix ? `${vTypeName}Output` : vTypeName;
+        const vNeedsCtx = typesNeedingContext.has(variant.type);
 
-        // Helper to generate variant decode expression
-        function rustVariantDecodeExpr(variant: any, vi: string): string[] {
-          const vTypeName = toRustTypeName(variant.type);
-          const vNeedsSuffix = typeNeedsInputOutputSuffix(variant.type, schema);
-          const vDecodeTypeName = vNeedsSuffix ? `${vTypeName}Output` : vTypeName;
-          const vNeedsCtx = typesNeedingContext.has(variant.type);
-
-          if (needsCtx && vNeedsCtx) {
-            // Need context for this variant
-            const ctxLines: string[] = [];
-            ctxLines.push(`${vi}let mut union_ctx: HashMap<std::string::String, u64> = HashMap::new();`);
-            if (allFields) {
-              for (const prevField of allFields) {
-                if (prevField.name === field.name) break;
-                if (!prevField.name) continue;
-                const rustPrevFieldName = toRustFieldName(prevField.name);
-                if (["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64"].includes(prevField.type as string)) {
-                  ctxLines.push(`${vi}union_ctx.insert("${prevField.name}".to_string(), ${rustPrevFieldName} as u64);`);
-                }
+        if (needsCtx && vNeedsCtx) {
+          // Need context for this variant
+          const ctxLines: string[] = [];
+          ctxLines.push(`${vi}let mut union_ctx: HashMap<std::string::String, u64> = HashMap::new();`);
+          if (allFields) {
+            for (const prevField of allFields) {
+              if (prevField.name === field.name) break;
+              if (!prevField.name) continue;
+              const rustPrevFieldName = toRustFieldName(prevField.name);
+              if (["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64"].includes(prevField.type as string)) {
+                ctxLines.push(`${vi}union_ctx.insert("${prevField.name}".to_string(), ${rustPrevFieldName} as u64);`);
               }
             }
-            const decoderArg = byteBudget ? `&mut ${decoderVarForVariants}` : decoderVarForVariants;
-            ctxLines.push(`${vi}${enumName}::${vTypeName}(${vDecodeTypeName}::decode_with_decoder_and_context(${decoderArg}, Some(&union_ctx))?)`);
-            return ctxLines;
-          } else {
-            const decoderArg = byteBudget ? `&mut ${decoderVarForVariants}` : decoderVarForVariants;
-            return [`${vi}${enumName}::${vTypeName}(${vDecodeTypeName}::decode_with_decoder(${decoderArg})?)`];
           }
+          const decoderArg = byteBudget ? `&mut ${decoderVarForVariants}` : decoderVarForVariants;
+          ctxLines.push(`${vi}${enumName}::${vTypeName}(${vDecodeTypeName}::decode_with_decoder_and_context(${decoderArg}, Some(&union_ctx))?)`);
+          return ctxLines;
+        } else {
+          const decoderArg = byteBudget ? `&mut ${decoderVarForVariants}` : decoderVarForVariants;
+          return [`${vi}${enumName}::${vTypeName}(${vDecodeTypeName}::decode_with_decoder(${decoderArg})?)`];
         }
+      }
+
+      if (discriminator.fields) {
+        // Variant selected by a combination of earlier fields
+        lines.push(...generateMultiFieldDiscriminatorDecode(discriminator.fields, variants, varName, indent, rustVariantDecodeExpr));
+      } else if (discriminator.field) {
+        // Field-based discriminator - generate inline if-else chain
+        // Generates: let varName = if cond { Enum::Variant(decode...) } else { ... };
+        const discriminatorFieldName = toRustFieldName(discriminator.field);
+        const fallbackVariant = variants.find((v: any) => !v.when);
+        const conditionalVariants = variants.filter((v: any) => v.when);
 
         // Generate the if-else expression
         for (let i = 0; i < conditionalVariants.length; i++) {
@@ -6170,27 +8348,7 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
           // If discriminator field type is an enum, replace integer literals with enum variant paths
           // (Rust enums can't be compared to raw integers)
           if (allFields) {
-            const rootFieldName = discriminator.field.split('.')[0];
-            const discField = allFields.find((f: any) => f.name === rootFieldName);
-            if (discField && !discriminator.field.includes('.') && typeof discField.type === 'string') {
-              const discTypeDef = schema.types?.[discField.type];
-              if (discTypeDef && isEnumType(discTypeDef)) {
-                const enumVariants = (discTypeDef as any).variants as Record<string, number>;
-                const reverseMap = new Map<number, string>();
-                for (const [name, value] of Object.entries(enumVariants)) {
-                  reverseMap.set(value, name);
-                }
-                const rustEnumName = toRustTypeName(discField.type);
-                condition = condition.replace(/(==|!=)\s*(0x[0-9a-fA-F]+|\d+)/g, (_match: string, op: string, numStr: string) => {
-                  const num = Number(numStr);
-                  const variantName = reverseMap.get(num);
-                  if (variantName !== undefined) {
-                    return `${op} ${rustEnumName}::${toRustTypeName(variantName)}`;
-                  }
-                  return _match;
-                });
-              }
-            }
+            condition = rewriteEnumDiscriminatorLiterals(condition, discriminator.field, allFields, schema);
           }
 
           if (i === 0) {
@@ -6251,6 +8409,25 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
       // sibling discriminator field against each variant's `when` and emit
       // the correct variant's decode directly, wrapping in the DU enum.
       const refTypeDef = schema.types[field.type as string];
+      if (
+        refTypeDef &&
+        (refTypeDef as any).type === "discriminated_union" &&
+        (refTypeDef as any).discriminator?.fields
+      ) {
+        const duName = toRustTypeName(field.type);
+        lines.push(...generateMultiFieldDiscriminatorDecode(
+          (refTypeDef as any).discriminator.fields,
+          (refTypeDef as any).variants ?? [],
+          varName,
+          indent,
+          (variant, vi) => {
+            const vt = toRustTypeName(variant.type);
+            const vd = typeNeedsInputOutputSuffix(variant.type, schema) ? `${vt}Output` : vt;
+            return [`${vi}${duName}::${vt}(${vd}::decode_with_decoder(decoder)?)`];
+          },
+        ));
+        break;
+      }
       if (
         refTypeDef &&
         (refTypeDef as any).type === "discriminated_union" &&
@@ -6273,7 +8450,10 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
         } else {
           for (let i = 0; i < conditional.length; i++) {
             const variant = conditional[i];
-            const cond = translateConditionToRust(variant.when).replace(/\bvalue\b/g, discriminatorRustField);
+            let cond = translateConditionToRust(variant.when).replace(/\bvalue\b/g, discriminatorRustField);
+            if (allFields) {
+              cond = rewriteEnumDiscriminatorLiterals(cond, (refTypeDef as any).discriminator.field, allFields, schema);
+            }
             const head = i === 0
               ? `${indent}let ${varName} = if ${cond} {`
               : `${indent}} else if ${cond} {`;
@@ -6298,18 +8478,24 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
   }
 
   // Add const field validation - if the decoded value doesn't match the expected const,
-  // return an error. This is critical for choice decoder try-each-variant to work.
+  // return an error. This is critical for choice decoder try-each-variant to work
+  // (choice trials run under decoder.strict). In lenient mode the mismatch is
+  // recorded and the const value substituted.
   const fieldAny2 = field as any;
   if (fieldAny2.const != null) {
     const constVal = fieldAny2.const;
     const expectedType = field.type as string;
+    const fieldPath = containingTypeName ? `${containingTypeName}.${field.name}` : `${field.name}`;
 
     if (expectedType === "string") {
       // String const validation - compare decoded string to expected value
       const rustStrLiteral = JSON.stringify(constVal);
-      lines.push(`${indent}if ${varName} != ${rustStrLiteral} {`);
-      lines.push(`${indent}    return Err(binschema_runtime::BinSchemaError::NotImplemented(format!("const string mismatch: expected ${constVal}, got {}", ${varName})));`);
-      lines.push(`${indent}}`);
+      lines.push(`${indent}let ${varName} = if ${varName} != ${rustStrLiteral} {`);
+      lines.push(`${indent}    decoder.recover("${fieldPath}", binschema_runtime::BinSchemaError::InvalidValue(format!("${fieldPath} is {:?}, expected {:?}", ${varName}, ${rustStrLiteral})))?;`);
+      lines.push(`${indent}    ${rustStrLiteral}.to_string()`);
+      lines.push(`${indent}} else {`);
+      lines.push(`${indent}    ${varName}`);
+      lines.push(`${indent}};`);
     } else {
       // Generate appropriate literal suffix for the comparison
       let rustConstExpr: string;
@@ -6324,9 +8510,12 @@ function generateDecodeField(field: Field, defaultEndianness: string, indent: st
         case "int64": rustConstExpr = `${constVal}i64`; break;
         default: rustConstExpr = `${constVal}`; break;
       }
-      lines.push(`${indent}if ${varName} != ${rustConstExpr} {`);
-      lines.push(`${indent}    return Err(binschema_runtime::BinSchemaError::InvalidVariant(${varName} as u64));`);
-      lines.push(`${indent}}`);
+      lines.push(`${indent}let ${varName} = if ${varName} != ${rustConstExpr} {`);
+      lines.push(`${indent}    decoder.recover("${fieldPath}", binschema_runtime::BinSchemaError::InvalidValue(format!("${fieldPath} is {}, expected {}", ${varName}, ${rustConstExpr})))?;`);
+      lines.push(`${indent}    ${rustConstExpr}`);
+      lines.push(`${indent}} else {`);
+      lines.push(`${indent}    ${varName}`);
+      lines.push(`${indent}};`);
     }
   }
 
@@ -6355,7 +8544,7 @@ function generateDecodeFieldInner(field: Field, defaultEndianness: string, inden
     case "int64":
     case "float32":
     case "float64":
-      lines.push(`${indent}let ${varName} = ${emitDecoderRead(field.type, rustEndianness, aligned)};`);
+      lines.push(`${indent}let ${varName} = ${emitFieldDecoderRead(field, rustEndianness, aligned)};`);
       break;
     case "bit": {
       const bitSize = (field as any).size || 1;
@@ -6363,10 +8552,18 @@ function generateDecodeFieldInner(field: Field, defaultEndianness: string, inden
       lines.push(`${indent}let ${varName} = decoder.read_bits(${bitSize})? as ${rustType};`);
       break;
     }
+
+    case "custom_float":
+      lines.push(`${indent}let ${varName} = ${customFloatLayout(field)}.to_f64(decoder.read_bits(${customFloatWidth(field)})?);`);
+      break;
+
+    case "uuid":
+      lines.push(`${indent}let ${varName} = decoder.read_${uuidLayoutSuffix(field)}()?;`);
+      break;
     case "int": {
       const bitSize = (field as any).size || 8;
       const rustType = mapFieldToRustType(field);
-      lines.push(`${indent}let ${varName} = decoder.read_bits(${bitSize})? as ${rustType};`);
+      lines.push(`${indent}let ${varName} = ${signedBitsRead(bitSize, rustType)};`);
       break;
     }
     case "varlength": {
@@ -6389,6 +8586,9 @@ function generateDecodeFieldInner(field: Field, defaultEndianness: string, inden
     case "bool":
       lines.push(`${indent}let ${varName} = ${emitDecoderRead("uint8", rustEndianness, aligned)} != 0;`);
       break;
+    case "message_bytes":
+      lines.push(`${indent}let ${varName} = decoder.read_bytes_vec(${toRustFieldName((field as any).length_field)} as usize)?;`);
+      break;
     case "bytes":
       lines.push(...generateDecodeArray({ ...field, type: "array", items: { type: "uint8" } } as any, varName, endianness, rustEndianness, indent, schema, containingTypeName, false, aligned));
       break;
@@ -6405,11 +8605,7 @@ function generateDecodeFieldInner(field: Field, defaultEndianness: string, inden
     case "padding": {
       const alignTo2 = (field as any).align_to || 4;
       lines.push(`${indent}// Skip alignment padding to ${alignTo2}-byte boundary`);
-      lines.push(`${indent}{`);
-      lines.push(`${indent}    let current_pos = decoder.position();`);
-      lines.push(`${indent}    let padding_bytes = (${alignTo2} - (current_pos % ${alignTo2})) % ${alignTo2};`);
-      lines.push(`${indent}    decoder.seek(current_pos + padding_bytes)?;`);
-      lines.push(`${indent}}`);
+      lines.push(`${indent}decoder.align_to(${alignTo2})?;`);
       break;
     }
     case "back_reference":
@@ -6514,6 +8710,33 @@ function generateEncodeBackReference(field: any, fieldName: string, indent: stri
   return lines;
 }
 
+/**
+ * Marker bits OR-ed into a back_reference storage integer, as a hex literal.
+ * Uses the schema's `pointer_flag` when set; otherwise the two most significant
+ * storage bits not covered by `offset_mask` (0xC000 for the DNS 0x3FFF mask).
+ */
+function backReferencePointerFlag(def: any): string {
+  if (def.pointer_flag) return def.pointer_flag;
+  const topBits: Record<string, number> = { uint8: 0xC0, uint16: 0xC000, uint32: 0xC0000000 };
+  const mask = parseInt(def.offset_mask || "0x3FFF", 16);
+  const flag = (topBits[def.storage || "uint16"] & ~mask) >>> 0;
+  return `0x${flag.toString(16).toUpperCase()}`;
+}
+
+/**
+ * When a back_reference declares an explicit `pointer_flag`, the decoder checks
+ * that the non-offset bits of `reference_value` carry exactly that flag.
+ */
+function generatePointerFlagCheck(def: any, indent: string): string[] {
+  if (!def.pointer_flag) return [];
+  const offsetMask = def.offset_mask || "0x3FFF";
+  return [
+    `${indent}if reference_value & !${offsetMask} != ${def.pointer_flag} {`,
+    `${indent}    return Err(BinSchemaError::InvalidValue(format!("back_reference flag bits {:#x} do not match pointer_flag ${def.pointer_flag}", reference_value & !${offsetMask})));`,
+    `${indent}}`,
+  ];
+}
+
 /**
  * Generates decoding code for back_reference field
  * Back references point to previously decoded data and require seekable streams
@@ -6535,6 +8758,7 @@ function generateDecodeBackReference(field: any, varName: string, endianness: st
   const targetDecodeName = targetNeedsSplit ? `${targetRustTypeName}Output` : targetRustTypeName;
 
   // Read the reference value
+  lines.push(`${indent}let pointer_pos = decoder.position();`);
   lines.push(`${indent}// Read back_reference storage value (${storage})`);
   switch (storage) {
     case "uint8":
@@ -6550,6 +8774,7 @@ function generateDecodeBackReference(field: any, varName: string, endianness: st
       lines.push(`${indent}let reference_value = decoder.read_uint16(Endianness::${rustEndianness})?;`);
   }
 
+  lines.push(...generatePointerFlagCheck(field, indent));
   lines.push(`${indent}let offset = (reference_value & ${offsetMask}) as usize;`);
   lines.push(``);
   lines.push(`${indent}// Save current position and seek to the referenced offset`);
@@ -6562,6 +8787,7 @@ function generateDecodeBackReference(field: any, varName: string, endianness: st
     lines.push(`${indent}decoder.seek(offset)?;`);
   }
 
+  lines.push(`${indent}decoder.record_pointer(pointer_pos, decoder.position());`);
   lines.push(``);
   lines.push(`${indent}// Decode the target type at the referenced position`);
   lines.push(`${indent}let ${varName} = ${targetDecodeName}::decode_with_decoder(decoder)?;`);
@@ -6668,7 +8894,7 @@ function generateBytesToString(varName: string, bytesExpr: string, encoding: str
   }
   if (encoding === "latin1" || encoding === "ascii") {
     // Latin-1/ASCII: each byte maps directly to a Unicode code point (0x00-0xFF -> U+0000-U+00FF)
-    // Use fully qualified std::string::String to avoid prefix_type_names rewriting
+    // Fully qualified so a schema type named String cannot shadow it
     return `${indent}let ${varName}: std::string::String = ${bytesExpr}.iter().map(|&b| b as char).collect();`;
   }
   // UTF-8: use standard conversion with validation
@@ -6696,7 +8922,8 @@ function generateDecodeString(field: any, varName: string, endianness: string, i
         // Read byte count, then read code_units = byte_count / 2
         lines.push(`${indent}let byte_length = ${emitDecoderRead(lengthType, rustEndianness, aligned)} as usize;`);
         lines.push(`${indent}let num_units = byte_length / 2;`);
-        lines.push(`${indent}let mut code_units = Vec::with_capacity(num_units);`);
+        lines.push(...emitCountFitsCheck("num_units", 16, indent));
+        lines.push(`${indent}let mut code_units = Vec::with_capacity(decoder.capacity_hint(num_units));`);
         lines.push(`${indent}for _ in 0..num_units {`);
         lines.push(`${indent}    code_units.push(${emitDecoderRead("uint16", stringRustEndianness, aligned)});`);
         lines.push(`${indent}}`);
@@ -6737,7 +8964,8 @@ function generateDecodeString(field: any, varName: string, endianness: string, i
         const lengthField = field.length_field;
         const lengthFieldRust = toRustFieldName(lengthField);
         lines.push(`${indent}let num_units = ${lengthFieldRust} as usize / 2;`);
-        lines.push(`${indent}let mut code_units = Vec::with_capacity(num_units);`);
+        lines.push(...emitCountFitsCheck("num_units", 16, indent));
+        lines.push(`${indent}let mut code_units = Vec::with_capacity(decoder.capacity_hint(num_units));`);
         lines.push(`${indent}for _ in 0..num_units {`);
         lines.push(`${indent}    code_units.push(${emitDecoderRead("uint16", stringRustEndianness, aligned)});`);
         lines.push(`${indent}}`);
@@ -6779,7 +9007,13 @@ function generateDecodeString(field: any, varName: string, endianness: string, i
       const length = field.length || 0;
       // Bulk read, then filter null bytes
       lines.push(`${indent}let raw_bytes = decoder.read_bytes_vec(${length})?;`);
-      lines.push(`${indent}let bytes: Vec<u8> = raw_bytes.into_iter().filter(|&b| b != 0).collect();`);
+      if (encoding === "utf8" && field.strict_utf8_boundaries === false) {
+        // Drop a multi-byte character cut off by the field's end rather than failing
+        lines.push(`${indent}let mut bytes: Vec<u8> = raw_bytes.into_iter().filter(|&b| b != 0).collect();`);
+        lines.push(`${indent}bytes.truncate(binschema_runtime::complete_utf8_len(&bytes));`);
+      } else {
+        lines.push(`${indent}let bytes: Vec<u8> = raw_bytes.into_iter().filter(|&b| b != 0).collect();`);
+      }
       lines.push(generateBytesToString(varName, "bytes", encoding, indent));
       break;
     }
@@ -6818,12 +9052,24 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
   const itemsWithName = { ...items, name: field.name };
   const itemType = mapFieldToRustType(itemsWithName, schema, containingTypeName);
 
-  const aligned = byteAligned === true;
+  // Items after a bit-count prefix start mid-byte
+  const aligned = byteAligned === true && kind !== "bit_length_prefixed";
+  // Delta-encoded items after the first may be a single byte
+  const itemMinBits = field.encoding === "delta_zigzag" ? 8 : minEncodedBits(items, schema);
+
+  if (kind === "columnar") {
+    return generateDecodeColumnarArray(field, varName, endianness, indent, schema, aligned);
+  }
+
+  if (kind === "null_terminated" && field.follow_pointers) {
+    return generateDecodePointerFollowingArray(field, varName, itemType, endianness, rustEndianness, indent, schema, containingTypeName);
+  }
 
   if (kind === "length_prefixed") {
     const lengthType = field.length_type || "uint8";
     lines.push(`${indent}let length = ${emitDecoderRead(lengthType, rustEndianness, aligned)} as usize;`);
-    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(length);`);
+    lines.push(...emitCountFitsCheck("length", itemMinBits, indent));
+    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(decoder.capacity_hint(length));`);
     lines.push(`${indent}for _ in 0..length {`);
   } else if (kind === "field_referenced") {
     const lengthField = field.length_field;
@@ -6842,7 +9088,8 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
       // Field is local - access directly. Safe to compute the Rust identifier
       // here because _root references take the parent-context branch below.
       const lengthFieldRust = toRustFieldName(lengthField);
-      lines.push(`${indent}let mut ${varName} = Vec::with_capacity(${lengthFieldRust} as usize);`);
+      lines.push(...emitCountFitsCheck(`${lengthFieldRust} as usize`, itemMinBits, indent));
+      lines.push(`${indent}let mut ${varName} = Vec::with_capacity(decoder.capacity_hint(${lengthFieldRust} as usize));`);
       lines.push(`${indent}for _ in 0..${lengthFieldRust} {`);
     } else {
       // Field is in parent context - look it up from ctx. Strip the `_root.`
@@ -6855,7 +9102,8 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
       lines.push(`${indent}    .and_then(|c| c.get("${ctxKey}"))`);
       lines.push(`${indent}    .copied()`);
       lines.push(`${indent}    .ok_or_else(|| binschema_runtime::BinSchemaError::ContextMissing("${ctxKey}".to_string()))? as usize;`);
-      lines.push(`${indent}let mut ${varName} = Vec::with_capacity(${varName}_length);`);
+      lines.push(...emitCountFitsCheck(`${varName}_length`, itemMinBits, indent));
+      lines.push(`${indent}let mut ${varName} = Vec::with_capacity(decoder.capacity_hint(${varName}_length));`);
       lines.push(`${indent}for _ in 0..${varName}_length {`);
     }
   } else if (kind === "fixed") {
@@ -6866,7 +9114,7 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
     // For null-terminated arrays, we need to check for null terminator BEFORE pushing
     const itemType2 = items.type;
 
-    if (itemType2 === "uint8") {
+    if (itemType2 === "uint8" && !field.record_alignment) {
       // Optimized path for byte arrays (like c_string)
       // Read byte, check if 0, break if so, push if not
       const readByteExpr = aligned ? "decoder.read_byte()?" : "decoder.read_uint8()?";
@@ -6899,12 +9147,26 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
     }
     lines.push(`${indent}let start_pos = decoder.position();`);
     lines.push(`${indent}let mut ${varName}: Vec<${itemType}> = Vec::new();`);
-    lines.push(`${indent}while decoder.position() < start_pos + byte_length {`);
+    lines.push(`${indent}while decoder.position() < start_pos.saturating_add(byte_length) {`);
+  } else if (kind === "bit_length_prefixed") {
+    // Total bit count of the items, which must be a whole number of items
+    const itemBits = primitiveFieldBitWidth(items) as number;
+    lines.push(`${indent}let bit_length = decoder.read_bits(${field.length_bits})?;`);
+    lines.push(`${indent}if bit_length % ${itemBits} != 0 {`);
+    lines.push(`${indent}    return Err(BinSchemaError::InvalidValue(format!("'${field.name}' has {} bits, which is not a whole number of ${itemBits}-bit items", bit_length)));`);
+    lines.push(`${indent}}`);
+    lines.push(`${indent}let count = (bit_length / ${itemBits}) as usize;`);
+    lines.push(...emitCountFitsCheck("count", itemBits, indent));
+    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(decoder.capacity_hint(count));`);
+    lines.push(`${indent}for _ in 0..count {`);
   } else if (kind === "length_prefixed_items") {
     // Each item has a length prefix
     const lengthType = field.length_type || "uint8";
     lines.push(`${indent}let count = ${emitDecoderRead(lengthType, rustEndianness, aligned)} as usize;`);
-    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(count);`);
+    // Every item may carry its own length prefix on top of its payload
+    const itemPrefixBits = field.item_length_type ? primitiveFieldBitWidth({ type: field.item_length_type }) ?? 0 : 0;
+    lines.push(...emitCountFitsCheck("count", itemPrefixBits + itemMinBits, indent));
+    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(decoder.capacity_hint(count));`);
     lines.push(`${indent}for _ in 0..count {`);
   } else if (kind === "computed_count") {
     // Count is computed from another expression
@@ -6912,7 +9174,8 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
     // Wrap the whole expression in parentheses before casting to usize,
     // otherwise `as usize` only applies to the last operand
     lines.push(`${indent}let count = (${countExpr}) as usize;`);
-    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(count);`);
+    lines.push(...emitCountFitsCheck("count", itemMinBits, indent));
+    lines.push(`${indent}let mut ${varName} = Vec::with_capacity(decoder.capacity_hint(count));`);
     lines.push(`${indent}for _ in 0..count {`);
   } else if (kind === "signature_terminated") {
     // Read until a specific signature value is encountered by peeking ahead
@@ -6950,7 +9213,16 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
     // Read until a specific variant is encountered
     lines.push(`${indent}let mut ${varName}: Vec<${itemType}> = Vec::new();`);
     lines.push(`${indent}loop {`);
-    // The termination check will happen after decoding the item
+    // The termination check happens after decoding the item; running out of
+    // input first means the terminal variant is missing
+    lines.push(`${indent}    if !decoder.has_remaining() {`);
+    lines.push(`${indent}        return Err(BinSchemaError::UnexpectedEof);`);
+    lines.push(`${indent}    }`);
+  } else if (kind === "countdown") {
+    // Seeded by an earlier field; each element counts down by what it holds
+    lines.push(`${indent}let mut ${varName.replace(/^r#/, "")}_remaining = ${toRustFieldName(field.length_field)} as u64;`);
+    lines.push(`${indent}let mut ${varName}: Vec<${itemType}> = Vec::new();`);
+    lines.push(`${indent}while ${varName.replace(/^r#/, "")}_remaining > 0 {`);
   } else if (kind === "eof_terminated") {
     // Read items until end of stream
     lines.push(`${indent}let mut ${varName}: Vec<${itemType}> = Vec::new();`);
@@ -6966,10 +9238,26 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
   }
 
   // Decode item
-  const itemLines = generateDecodeArrayItem(items, endianness, rustEndianness, `${indent}    `, schema, containingTypeName, field.name, byteAligned);
+  const itemLines = field.encoding === "delta_zigzag"
+    ? generateDeltaItemDecode(field, varName, endianness, `${indent}    `, aligned)
+    : generateDecodeArrayItem(items, endianness, rustEndianness, `${indent}    `, schema, containingTypeName, field.name, byteAligned);
   lines.push(...itemLines);
+  if (kind === "countdown") {
+    const remaining = `${varName.replace(/^r#/, "")}_remaining`;
+    lines.push(`${indent}    let consumed = ${countdownConsumedExpr(field, "item", schema)};`);
+    lines.push(`${indent}    if consumed == 0 || consumed > ${remaining} {`);
+    lines.push(`${indent}        return Err(BinSchemaError::InvalidValue(format!("'${field.name}' element counts for {} with {} of '${field.length_field}' remaining", consumed, ${remaining})));`);
+    lines.push(`${indent}    }`);
+    lines.push(`${indent}    ${remaining} -= consumed;`);
+  }
   lines.push(`${indent}    ${varName}.push(item);`);
 
+  if (field.record_alignment) {
+    const alignTo = field.record_alignment;
+    lines.push(`${indent}    // Skip the record's padding to a ${alignTo}-byte boundary`);
+    lines.push(`${indent}    decoder.align_to(${alignTo})?;`);
+  }
+
   // For variant_terminated arrays, check if the decoded item is a terminal variant and break
   if (kind === "variant_terminated" && field.terminal_variants && Array.isArray(field.terminal_variants)) {
     const terminalVariants = field.terminal_variants as string[];
@@ -7009,6 +9297,79 @@ function generateDecodeArray(field: any, varName: string, endianness: string, ru
   return lines;
 }
 
+/**
+ * Generates decoding for a null-terminated array with `follow_pointers`
+ * (DNS names: labels, then either a zero byte or a pointer to the rest).
+ * The terminal back_reference variant is never stored: decode jumps to its
+ * target and keeps appending items until a zero byte, so the array ends up
+ * with the complete sequence. Each target offset may be visited once, which
+ * rejects pointer loops. Afterwards decoding resumes behind the first pointer.
+ * The decoder's limits cap the pointers followed, each item's encoded size
+ * and the expanded sequence including its terminator.
+ */
+function generateDecodePointerFollowingArray(field: any, varName: string, itemType: string, endianness: string, rustEndianness: string, indent: string, schema: BinarySchema, containingTypeName?: string): string[] {
+  const items = field.items;
+  const unionDef = schema.types[items.type] as any;
+  const pointerType = (field.terminal_variants ?? []).find((v: string) => (schema.types[v] as any)?.type === "back_reference");
+  const pointerVariant = unionDef?.variants?.find((v: any) => v.type === pointerType);
+  if (!unionDef?.discriminator?.peek || !pointerVariant?.when) {
+    throw new Error(`follow_pointers array '${field.name}' needs items that are a peek-discriminated union with a back_reference terminal variant`);
+  }
+
+  const pointerDef = schema.types[pointerType] as any;
+  const storage = pointerDef.storage || "uint16";
+  const offsetMask = pointerDef.offset_mask || "0x3FFF";
+  const pointerEndianness = mapEndianness(pointerDef.endianness || endianness);
+  const peekType = unionDef.discriminator.peek;
+  const peekEndianness = mapEndianness(unionDef.discriminator.endianness || endianness);
+  const peekExpr = peekType === "uint8" ? "decoder.peek_uint8()?" : `decoder.peek_${peekType}(Endianness::${peekEndianness})?`;
+  const readExpr = storage === "uint8" ? "decoder.read_uint8()?" : `decoder.read_${storage}(Endianness::${pointerEndianness})?`;
+  const isPointer = translateConditionToRust(pointerVariant.when).replace(/\bvalue\b/g, "tag");
+  const target = pointerDef.offset_from === "current_position" ? "decoder.position() + offset" : "offset";
+
+  const lines: string[] = [];
+  lines.push(`${indent}let mut ${varName}: Vec<${itemType}> = Vec::new();`);
+  lines.push(`${indent}// Pointers are followed and the items they point to appended`);
+  lines.push(`${indent}let mut ${varName}_resume: Option<usize> = None;`);
+  lines.push(`${indent}let mut ${varName}_visited: Vec<usize> = Vec::new();`);
+  lines.push(`${indent}let mut ${varName}_name_len = 0usize;`);
+  lines.push(`${indent}loop {`);
+  lines.push(`${indent}    if decoder.peek_uint8()? == 0 {`);
+  lines.push(`${indent}        decoder.read_uint8()?; // Consume the null byte`);
+  lines.push(`${indent}        break;`);
+  lines.push(`${indent}    }`);
+  lines.push(`${indent}    let tag = ${peekExpr};`);
+  lines.push(`${indent}    if ${isPointer} {`);
+  lines.push(`${indent}        let pointer_pos = decoder.position();`);
+  lines.push(`${indent}        let reference_value = ${readExpr};`);
+  lines.push(...generatePointerFlagCheck(pointerDef, `${indent}        `));
+  lines.push(`${indent}        let offset = (reference_value & ${offsetMask}) as usize;`);
+  lines.push(`${indent}        let target = ${target};`);
+  lines.push(`${indent}        if ${varName}_visited.contains(&target) {`);
+  lines.push(`${indent}            return Err(BinSchemaError::InvalidValue(format!("pointer loop: offset {} is already part of this name", target)));`);
+  lines.push(`${indent}        }`);
+  lines.push(`${indent}        ${varName}_visited.push(target);`);
+  lines.push(`${indent}        decoder.limits().check_pointer_jumps(${varName}_visited.len())?;`);
+  lines.push(`${indent}        ${varName}_resume.get_or_insert(decoder.position());`);
+  lines.push(`${indent}        decoder.seek(target)?;`);
+  lines.push(`${indent}        decoder.record_pointer(pointer_pos, target);`);
+  lines.push(`${indent}        continue;`);
+  lines.push(`${indent}    }`);
+  lines.push(`${indent}    let label_start = decoder.position();`);
+  lines.push(...generateDecodeArrayItem(items, endianness, rustEndianness, `${indent}    `, schema, containingTypeName, field.name));
+  lines.push(`${indent}    let label_len = decoder.position().saturating_sub(label_start);`);
+  lines.push(`${indent}    decoder.limits().check_label_len(label_len)?;`);
+  lines.push(`${indent}    ${varName}_name_len += label_len;`);
+  lines.push(`${indent}    // Counting the terminator still to come`);
+  lines.push(`${indent}    decoder.limits().check_name_len(${varName}_name_len + 1)?;`);
+  lines.push(`${indent}    ${varName}.push(item);`);
+  lines.push(`${indent}}`);
+  lines.push(`${indent}if let Some(pos) = ${varName}_resume {`);
+  lines.push(`${indent}    decoder.seek(pos)?;`);
+  lines.push(`${indent}}`);
+  return lines;
+}
+
 /**
  * Generates decoding code for a single array item
  */
@@ -7037,7 +9398,7 @@ function generateDecodeArrayItem(items: any, endianness: string, rustEndianness:
     case "int": {
       const bitSize = items.size || 1;
       const rustType = mapFieldToRustType(items);
-      lines.push(`${indent}let item = decoder.read_bits(${bitSize})? as ${rustType};`);
+      lines.push(`${indent}let item = ${items.type === "int" ? signedBitsRead(bitSize, rustType) : `decoder.read_bits(${bitSize})? as ${rustType}`};`);
       break;
     }
     case "choice": {
@@ -7109,7 +9470,8 @@ function generateDecodeArrayItem(items: any, endianness: string, rustEndianness:
       }
 
       // Decode inner items
-      lines.push(`${indent}let mut item = Vec::with_capacity(inner_len);`);
+      lines.push(...emitCountFitsCheck("inner_len", minEncodedBits(innerItems, schema), indent));
+      lines.push(`${indent}let mut item = Vec::with_capacity(decoder.capacity_hint(inner_len));`);
       lines.push(`${indent}for _ in 0..inner_len {`);
       const innerLines = generateDecodeArrayItem(innerItems, endianness, rustEndianness, `${indent}    `, schema, containingTypeName, arrayFieldName, byteAligned);
       // Rename 'item' to 'inner_item' in the inner lines to avoid shadowing
@@ -7151,6 +9513,16 @@ function generateDecodeNestedStruct(field: Field, varName: string, indent: strin
   // Use Output suffix only for composite types that need the split
   const needsSplit = isComposite && typeNeedsInputOutputSplit(field.type, schema);
   const decodeName = needsSplit ? `${typeName}Output` : typeName;
+  const repeat = (field as any).repeat;
+  if (repeat != null) {
+    // Repeated inline group - unrolled into a fixed-size array literal
+    lines.push(`${indent}let ${varName} = [`);
+    for (let i = 0; i < repeat; i++) {
+      lines.push(`${indent}    ${decodeName}::decode_with_decoder(decoder)?,`);
+    }
+    lines.push(`${indent}];`);
+    return lines;
+  }
   lines.push(`${indent}let ${varName} = ${decodeName}::decode_with_decoder(decoder)?;`);
 
   return lines;
@@ -7161,6 +9533,14 @@ function generateDecodeNestedStruct(field: Field, varName: string, indent: strin
  * Composite types get Input suffix, type aliases stay as-is
  */
 function mapFieldToRustTypeForInput(field: Field, schema: BinarySchema, containingTypeName?: string): string {
+  // Repeated inline group: fixed-size array of the lifted group type
+  const repeat = (field as any).repeat;
+  if (repeat != null) {
+    return `[${mapFieldToRustTypeForInput({ ...field, repeat: undefined } as Field, schema, containingTypeName)}; ${repeat}]`;
+  }
+  if ((field as any).newtype) return (field as any).newtype;
+  if (offsetBinaryBits(field)) return offsetBinaryRustType(field);
+
   // Handle primitive types first
   switch (field.type) {
     case "uint8": return "u8";
@@ -7173,8 +9553,11 @@ function mapFieldToRustTypeForInput(field: Field, schema: BinarySchema, containi
     case "int64": return "i64";
     case "float32": return "f32";
     case "float64": return "f64";
+    case "custom_float": return "f64";
+    case "uuid": return "[u8; 16]";
     case "bool": return "bool";
-    case "bytes": return "Vec<u8>";
+    case "bytes":
+    case "message_bytes": return "Vec<u8>";
     case "varlength": return "u64";
     case "string": return "std::string::String";
     case "bit": {
@@ -7339,6 +9722,14 @@ function getInstanceFieldRustType(instance: any, schema: BinarySchema): string {
  * The schema parameter is optional - when provided, composite types get Output suffix
  */
 function mapFieldToRustType(field: Field, schema?: BinarySchema, containingTypeName?: string): string {
+  // Repeated inline group: fixed-size array of the lifted group type
+  const repeat = (field as any).repeat;
+  if (repeat != null) {
+    return `[${mapFieldToRustType({ ...field, repeat: undefined } as Field, schema, containingTypeName)}; ${repeat}]`;
+  }
+  if ((field as any).newtype) return (field as any).newtype;
+  if (offsetBinaryBits(field)) return offsetBinaryRustType(field);
+
   switch (field.type) {
     case "uint8":
       return "u8";
@@ -7359,10 +9750,14 @@ function mapFieldToRustType(field: Field, schema?: BinarySchema, containingTypeN
     case "float32":
       return "f32";
     case "float64":
+    case "custom_float":
       return "f64";
+    case "uuid":
+      return "[u8; 16]";
     case "bool":
       return "bool";
     case "bytes":
+    case "message_bytes":
       return "Vec<u8>";
     case "varlength":
       return "u64";  // Variable-length integers decode to u64
@@ -7520,11 +9915,73 @@ const RUST_KEYWORDS = new Set([
 /**
  * Converts a field name to Rust field name (snake_case)
  */
+/**
+ * Wire field name -> `rust_name` override for the schema currently being
+ * generated. Set by generateRust before any code is emitted so that every
+ * toRustFieldName call (declarations, encode/decode, length references)
+ * agrees on the identifier.
+ */
+let rustNameOverrides = new Map<string, string>();
+
+/**
+ * Gather `rust_name` overrides from every sequence and instance field.
+ * Overrides are keyed by wire name, so all fields sharing a wire name must
+ * agree on the override; a conflicting pair, or an override that collides
+ * with another field of the same struct, is rejected here rather than
+ * surfacing as a rustc error in the generated code.
+ */
+function collectRustNameOverrides(schema: BinarySchema): Map<string, string> {
+  const overrides = new Map<string, string>();
+  const fieldsOf = (typeDef: any): any[] => [...(typeDef?.sequence ?? []), ...(typeDef?.instances ?? [])];
+
+  for (const [typeName, typeDef] of Object.entries(schema.types)) {
+    for (const field of fieldsOf(typeDef)) {
+      if (!field.name || !field.rust_name) continue;
+      const existing = overrides.get(field.name);
+      if (existing !== undefined && existing !== field.rust_name) {
+        throw new Error(
+          `Type '${typeName}': field '${field.name}' has rust_name '${field.rust_name}' but another field with that wire name uses '${existing}'`
+        );
+      }
+      overrides.set(field.name, field.rust_name);
+    }
+  }
+
+  if (overrides.size === 0) return overrides;
+
+  const previous = rustNameOverrides;
+  rustNameOverrides = overrides;
+  try {
+    for (const [typeName, typeDef] of Object.entries(schema.types)) {
+      const seen = new Map<string, string>();
+      for (const field of fieldsOf(typeDef)) {
+        if (!field.name) continue;
+        const rustName = toRustFieldName(field.name);
+        const other = seen.get(rustName);
+        if (other !== undefined && other !== field.name) {
+          throw new Error(
+            `Type '${typeName}': fields '${other}' and '${field.name}' both map to Rust field '${rustName}'`
+          );
+        }
+        seen.set(rustName, field.name);
+      }
+    }
+  } finally {
+    rustNameOverrides = previous;
+  }
+  return overrides;
+}
+
 function toRustFieldName(name: string | undefined): string {
   if (!name) {
     return "_unnamed";
   }
 
+  const override = rustNameOverrides.get(name);
+  if (override !== undefined) {
+    return override;
+  }
+
   // Handle _root references (e.g., "_root.end_of_central_dir.total_entries")
   if (name.startsWith('_root.')) {
     // For now, Rust generator doesn't support context/root references
@@ -7540,6 +9997,10 @@ function toRustFieldName(name: string | undefined): string {
   if (name.includes('.')) {
     const parts = name.split('.');
     const rustParts = parts.map(part => {
+      const partOverride = rustNameOverrides.get(part);
+      if (partOverride !== undefined) {
+        return partOverride;
+      }
       let result = part
         .replace(/([A-Z])/g, '_$1')
         .toLowerCase()
@@ -7566,6 +10027,17 @@ function toRustFieldName(name: string | undefined): string {
   return result;
 }
 
+/**
+ * Converts a type name to a Rust module name (snake_case), e.g. DNSMessage -> dns_message
+ */
+function toRustModuleName(name: string): string {
+  const result = toRustTypeName(name)
+    .replace(/([a-z0-9])([A-Z])/g, '$1_$2')
+    .replace(/([A-Z]+)([A-Z][a-z])/g, '$1_$2')
+    .toLowerCase();
+  return RUST_KEYWORDS.has(result) ? `${result}_` : result;
+}
+
 /**
  * Converts a type name to Rust type name (PascalCase)
  */
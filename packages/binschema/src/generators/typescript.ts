@@ -1,4 +1,5 @@
 import { BinarySchema, TypeDef, Field, Endianness, isEnumType } from "../schema/binary-schema.js";
+import { rejectRustOnlyFeatures } from "../schema/rust-only-features.js";
 import type { GeneratedCode, DocInput, DocBlock } from "./typescript/shared.js";
 import { ARRAY_ITER_SUFFIX } from "./typescript/shared.js";
 import { isTypeAlias, getTypeFields, isBackReferenceTypeDef, isBackReferenceType, sanitizeTypeName, sanitizeVarName, sanitizeEnumMemberName } from "./typescript/type-utils.js";
@@ -58,6 +59,7 @@ export interface GenerateTypeScriptOptions {
 }
 
 export function generateTypeScript(schema: BinarySchema, options?: GenerateTypeScriptOptions): string {
+  rejectRustOnlyFeatures(schema, "TypeScript");
   const globalEndianness = schema.config?.endianness || "big_endian";
   const globalBitOrder = schema.config?.bit_order || "msb_first";
   const addTraceLogs = options?.debug || options?.addTraceLogs || false;
@@ -1362,8 +1364,20 @@ function generateEncodeFieldCoreImpl(
     case "float64":
       return `${indent}this.writeFloat64(${valuePath}, "${endianness}");\n`;
 
-    case "array":
-      return generateEncodeArray(field, schema, globalEndianness, valuePath, indent, generateEncodeFieldCoreImpl, baseContextVar || 'context');
+    case "array": {
+      const arrayCode = generateEncodeArray(field, schema, globalEndianness, valuePath, indent, generateEncodeFieldCoreImpl, baseContextVar || 'context');
+      const maxEncodedLength: number | undefined = (field as any).max_encoded_length;
+      if (maxEncodedLength === undefined) return arrayCode;
+      // Measure what the array wrote, prefix and terminator included
+      const startVar = `${valuePath.replace(/[^A-Za-z0-9_]/g, "_")}_start`;
+      const what = field.name ? `'${field.name}'` : "array";
+      let code = `${indent}const ${startVar} = this.byteOffset;\n`;
+      code += arrayCode;
+      code += `${indent}if (this.byteOffset - ${startVar} > ${maxEncodedLength}) {\n`;
+      code += `${indent}  throw new Error(\`${what} encodes to \${this.byteOffset - ${startVar}} bytes, which exceeds max_encoded_length ${maxEncodedLength}\`);\n`;
+      code += `${indent}}\n`;
+      return code;
+    }
 
     case "bytes":
       // Delegate to array encoding with implicit uint8 items
@@ -66,6 +66,14 @@ export function generateEncodeString(
     code += `${indent}const ${bytesVarName} = Array.from(${valuePath}, c => c.charCodeAt(0));\n`;
   }
 
+  // Protocol limit on variable-length strings (e.g., 63-byte DNS labels)
+  if (field.max_length !== undefined && kind !== "fixed") {
+    const what = field.name && field.name !== "0" ? `'${field.name}'` : "string";
+    code += `${indent}if (${bytesVarName}.length > ${field.max_length}) {\n`;
+    code += `${indent}  throw new Error(\`${what} is \${${bytesVarName}.length} bytes, which exceeds max_length ${field.max_length}\`);\n`;
+    code += `${indent}}\n`;
+  }
+
   if (kind === "length_prefixed") {
     const lengthType = field.length_type || "uint8";
     // Write length prefix (always byte count, even for UTF-16)
@@ -83,6 +83,35 @@ const StringEncodingSchema = z.enum([
 ]);
 export type StringEncoding = z.infer<typeof StringEncodingSchema>;
 
+/**
+ * Explicit identifier for a field in generated Rust code
+ *
+ * Bypasses the automatic snake_case conversion and keyword escaping (e.g. a
+ * wire field named "type" becomes `r#type` unless overridden).
+ */
+const RustNameSchema = z.string().regex(/^(r#)?[A-Za-z_][A-Za-z0-9_]*$/, "Must be a valid Rust identifier").optional().meta({
+  description: "Exact Rust identifier for this field, used instead of the automatic snake_case/keyword-escaped name. Schema references (length_field, conditions, computed targets) still use the wire name. The override applies to every field with the same wire name, so those fields must agree. Currently honored by the Rust generator only."
+});
+
+/**
+ * Distinct Rust type for a semantic integer field (a TTL, a port)
+ */
+const NewtypeSchema = z.string().regex(/^[A-Z][A-Za-z0-9]*$/, "Must be a PascalCase Rust type name").optional().meta({
+  description: "Wraps this integer field in a generated #[repr(transparent)] newtype with this name (e.g. 'Ttl' gives `pub struct Ttl(pub u32)`), so values with different meanings can't be mixed up. Encoding and decoding are unchanged. Fields sharing a newtype must have the same integer type. Currently generated for Rust only."
+});
+
+const SignedReprSchema = z.literal("offset_binary").optional().meta({
+  description: "Stores a signed value as offset binary (excess-K): the wire holds value + bias, so the most negative value is all zero bits. The field gets the narrowest signed Rust type holding -bias through max - bias; encoding a value outside that range fails with InvalidValue. Requires bias. Currently generated for Rust only."
+});
+
+const OffsetBinaryBiasSchema = z.number().int().min(0).optional().meta({
+  description: "Bias K added to the value for signed_repr 'offset_binary' (e.g. 128 for 8-bit audio samples). Must fit the field's storage type."
+});
+
+const StringMaxLengthSchema = z.number().int().min(1).optional().meta({
+  description: "Protocol maximum for the encoded text in bytes (e.g., 63 for DNS labels). Encoding a longer value fails (InvalidValue in Rust) instead of producing an out-of-spec message."
+});
+
 /**
  * Computed field specification
  *
@@ -92,19 +121,38 @@ export type StringEncoding = z.infer<typeof StringEncodingSchema>;
  * Phase 3: position_of - compute byte position of target type
  * Phase 4: sum_of_sizes - sum the encoded sizes of multiple fields
  * Phase 5: sum_of_type_sizes - sum the encoded sizes of array elements of a specific type
+ * crc_of - CRC of target field with a declared variant (polynomial, init, reflection, xorout)
+ * discriminator_of - tag value of the variant held by the target discriminated union field
+ * xor_of / complement_of - bitwise transform of sibling integer fields (masks, inverted copies)
  */
+const CrcHexSchema = z.string().regex(/^0x[0-9A-Fa-f]{1,16}$/, "Must be a hex value of at most 64 bits (e.g., '0x8005')");
+
+const CrcParamsSchema = z.object({
+  width: z.number().int().min(1).max(64).meta({ description: "CRC register width in bits" }),
+  poly: CrcHexSchema.meta({ description: "Generator polynomial in normal form, without the implicit top bit" }),
+  init: CrcHexSchema.meta({ description: "Initial register value" }),
+  refin: z.boolean().meta({ description: "Whether input bytes are processed least significant bit first" }),
+  refout: z.boolean().meta({ description: "Whether the final register is bit-reversed before xorout" }),
+  xorout: CrcHexSchema.meta({ description: "Value XOR-ed into the final CRC" }),
+});
+
+const CrcVariantSchema = z.union([
+  z.enum(["crc8", "crc16_ccitt_false", "crc16_xmodem", "crc16_kermit", "crc16_modbus", "crc16_arc", "crc32", "crc32c", "crc32_bzip2", "crc64_xz"]),
+  CrcParamsSchema,
+]);
+
 const ComputedFieldSchema = z.object({
-  type: z.enum(["length_of", "crc32_of", "position_of", "sum_of_sizes", "sum_of_type_sizes"]).meta({
+  type: z.enum(["length_of", "crc32_of", "crc_of", "discriminator_of", "position_of", "sum_of_sizes", "sum_of_type_sizes", "xor_of", "complement_of"]).meta({
     description: "Type of computation to perform"
   }),
   target: z.string().optional().meta({
-    description: "Name of the field or type to compute from (supports dot notation like 'header.data'). Used by length_of, crc32_of, position_of, sum_of_type_sizes"
+    description: "Name of the field or type to compute from (supports dot notation like 'header.data'). Used by length_of, crc32_of, crc_of, discriminator_of, position_of, sum_of_type_sizes, complement_of"
   }),
   from_after_field: z.string().optional().meta({
     description: "For length_of: compute byte length of all fields after the specified field. Used in ASN.1/DER for SEQUENCE/APPLICATION tag lengths. Mutually exclusive with 'target'."
   }),
   targets: z.array(z.string()).optional().meta({
-    description: "Array of field paths to sum sizes of. Used by sum_of_sizes"
+    description: "Array of field paths to sum sizes of (sum_of_sizes), or the sibling integer fields XOR-ed together (xor_of, at least two). xor_of and complement_of are checked against their sources on decode; currently generated for Rust only."
   }),
   element_type: z.string().optional().meta({
     description: "Type name of array elements to sum sizes of. Used by sum_of_type_sizes"
@@ -115,6 +163,12 @@ const ComputedFieldSchema = z.object({
   offset: z.number().optional().meta({
     description: "For length_of: add this value to the computed length. Used for ASN.1 BIT STRING where length includes unused_bits byte (offset: 1)"
   }),
+  crc: CrcVariantSchema.optional().meta({
+    description: "For crc_of: the CRC variant, either a preset name (crc16_modbus, crc32c, ...) or explicit { width, poly, init, refin, refout, xorout } parameters. The field must be an unsigned integer at least `width` bits wide. Currently generated for Rust only."
+  }),
+  relative_to: z.string().optional().meta({
+    description: "For position_of: name of an earlier sibling field acting as an anchor. The stored value is the target position minus the byte offset where the anchor starts (section-relative offsets in font and resource files). Currently generated for Rust only."
+  }),
 });
 export type ComputedField = z.infer<typeof ComputedFieldSchema>;
 
@@ -129,6 +183,7 @@ const BitFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("bit").meta({
     description: "Field type (always 'bit')"  
   }),
@@ -145,6 +200,7 @@ const SignedIntFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("int").meta({
     description: "Field type (always 'int')"  
   }),
@@ -162,6 +218,10 @@ const Uint8FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
+  signed_repr: SignedReprSchema,
+  bias: OffsetBinaryBiasSchema,
   type: z.literal("uint8").meta({
     description: "Field type (always 'uint8')"
   }),
@@ -225,6 +285,7 @@ const BoolFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("bool").meta({
     description: "Field type (always 'bool')"
   }),
@@ -275,6 +336,10 @@ const Uint16FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
+  signed_repr: SignedReprSchema,
+  bias: OffsetBinaryBiasSchema,
   type: z.literal("uint16").meta({
     description: "Field type (always 'uint16')"
   }),
@@ -326,6 +391,10 @@ const Uint32FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
+  signed_repr: SignedReprSchema,
+  bias: OffsetBinaryBiasSchema,
   type: z.literal("uint32").meta({
     description: "Field type (always 'uint32')"
   }),
@@ -338,6 +407,9 @@ const Uint32FieldSchema = z.object({
   endianness: EndiannessSchema.optional().meta({
     description: "Byte order for multi-byte values (big_endian or little_endian). Overrides global config if specified."
   }),
+  byte_order: z.literal("pdp").optional().meta({
+    description: "Mirrored byte order not expressible as endianness. 'pdp' stores PDP-11 middle-endian values (high 16-bit word first, each word little-endian: bytes 2-3-0-1). Overrides endianness. Currently generated for Rust only; the other generators reject schemas that use it."
+  }),
   description: z.string().optional().meta({
     description: "Human-readable description of this field"
   }),
@@ -393,6 +465,8 @@ const Uint64FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
   type: z.literal("uint64").meta({
     description: "Field type (always 'uint64')"
   }),
@@ -463,6 +537,8 @@ const Int8FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
   type: z.literal("int8").meta({
     description: "Field type (always 'int8')"
   }),
@@ -501,6 +577,8 @@ const Int16FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
   type: z.literal("int16").meta({
     description: "Field type (always 'int16')"
   }),
@@ -542,12 +620,17 @@ const Int32FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
   type: z.literal("int32").meta({
     description: "Field type (always 'int32')"
   }),
   endianness: EndiannessSchema.optional().meta({
     description: "Byte order for multi-byte values (big_endian or little_endian). Overrides global config if specified."
   }),
+  byte_order: z.literal("pdp").optional().meta({
+    description: "Mirrored byte order not expressible as endianness. 'pdp' stores PDP-11 middle-endian values (high 16-bit word first, each word little-endian: bytes 2-3-0-1). Overrides endianness. Currently generated for Rust only; the other generators reject schemas that use it."
+  }),
   computed: ComputedFieldSchema.optional().meta({
     description: "Marks this field as automatically computed (e.g., length_of, crc32_of)"
   }),
@@ -583,6 +666,8 @@ const Int64FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
+  newtype: NewtypeSchema,
   type: z.literal("int64").meta({
     description: "Field type (always 'int64')"
   }),
@@ -646,6 +731,7 @@ const VarlengthFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("varlength").meta({
     description: "Field type (always 'varlength')"
   }),
@@ -732,6 +818,7 @@ const Float32FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("float32").meta({
     description: "Field type (always 'float32')"  
   }),
@@ -778,6 +865,7 @@ const Float64FieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("float64").meta({
     description: "Field type (always 'float64')"  
   }),
@@ -819,20 +907,119 @@ const Float64FieldSchema = z.object({
   ]
 });
 
+/**
+ * Custom float (non-IEEE layout)
+ */
+const CustomFloatFieldSchema = z.object({
+  name: z.string().meta({
+    description: "Field name"
+  }),
+  rust_name: RustNameSchema,
+  type: z.literal("custom_float").meta({
+    description: "Field type (always 'custom_float')"
+  }),
+  sign: z.boolean().optional().meta({
+    description: "Whether a sign bit (sign-magnitude) precedes the exponent (default: true)"
+  }),
+  exponent_bits: z.number().int().min(1).max(11).meta({
+    description: "Width of the biased exponent in bits"
+  }),
+  mantissa_bits: z.number().int().min(1).max(52).meta({
+    description: "Width of the mantissa in bits"
+  }),
+  bias: z.number().int().meta({
+    description: "Exponent bias K (stored exponent = true exponent + K)"
+  }),
+  implied_leading_bit: z.boolean().optional().meta({
+    description: "Whether normalized values have an implicit leading 1 and exponent 0 holds denormals (default: true). When false the mantissa's top bit is the explicit integer bit."
+  }),
+  description: z.string().optional().meta({
+    description: "Human-readable description of this field"
+  }),
+}).meta({
+  title: "Custom Floating Point",
+  description: "Floating point value with a custom layout: optional sign bit, excess-K exponent and mantissa, packed MSB first at bit level. Decodes to and encodes from a 64-bit float. Currently generated for Rust only.",
+  use_for: "Legacy DSP formats, instrument protocols with non-IEEE floats",
+  wire_format: "sign (0 or 1 bit) + exponent_bits + mantissa_bits, written with the bit-level writer",
+  code_generation: {
+    rust: {
+      type: "f64",
+      notes: ["Packed and unpacked with binschema_runtime::CustomFloat", "Encoding rounds to nearest; NaN, infinities and out-of-range values are errors"]
+    }
+  },
+  notes: [
+    "No exponent is reserved for infinity or NaN: every bit pattern is a finite value"
+  ],
+  examples: [
+    { name: "sample", type: "custom_float", sign: true, exponent_bits: 5, mantissa_bits: 10, bias: 15 }
+  ]
+});
+
+/**
+ * UUID / GUID (16 bytes)
+ */
+const UuidFieldSchema = z.object({
+  name: z.string().meta({
+    description: "Field name"
+  }),
+  rust_name: RustNameSchema,
+  type: z.literal("uuid").meta({
+    description: "Field type (always 'uuid')"
+  }),
+  layout: z.enum(["rfc4122", "guid"]).meta({
+    description: "Byte arrangement on the wire: 'rfc4122' writes all 16 bytes big-endian in text order; 'guid' writes the first three groups (4, 2 and 2 bytes) little-endian and the last eight bytes as is"
+  }),
+  description: z.string().optional().meta({
+    description: "Human-readable description of this field"
+  }),
+}).meta({
+  title: "UUID",
+  description: "16-byte universally unique identifier in RFC 4122 or Microsoft GUID byte order. The value is always held in RFC 4122 order, so the same UUID compares equal whichever layout it was read from. Currently generated for Rust only.",
+  use_for: "Object and interface IDs in COM, SMB, GPT partition tables, distributed-system records",
+  wire_format: "16 bytes; in 'guid' layout bytes 0-3, 4-5 and 6-7 are each reversed",
+  code_generation: {
+    rust: {
+      type: "[u8; 16]",
+      notes: ["Written with write_uuid / write_guid and read with read_uuid / read_guid", "Bytes are in RFC 4122 order, as taken by uuid::Uuid::from_bytes"]
+    }
+  },
+  examples: [
+    { name: "partition_type", type: "uuid", layout: "guid" },
+    { name: "object_id", type: "uuid", layout: "rfc4122" }
+  ]
+});
+
 /**
  * Array kinds
  */
+/**
+ * Width of a bit_length_prefixed array's prefix
+ */
+const LengthBitsSchema = z.number().int().min(1).max(64).optional().meta({
+  description: "For bit_length_prefixed arrays: width in bits of the prefix, which holds the total bit count of the items rather than an item count. Items must have a fixed bit width (e.g. { type: 'bit', size: 5 }); decode rejects a count that isn't a whole number of items. Currently generated for Rust only."
+});
+
+/**
+ * Field of a countdown array's elements giving how far each one counts down
+ */
+const CountdownBySchema = z.string().optional().meta({
+  description: "For countdown arrays: name of a field in the item type (or in some variants of a union item type) holding how much each element counts down the seed field, e.g. a run length. Elements without it count as 1. Decode stops when the counter reaches zero and rejects elements that would overshoot it; encode checks the elements add up to the seed. Currently generated for Rust only."
+});
+
 const ArrayKindSchema = z.enum([
   "fixed",           // Fixed size array
   "length_prefixed", // Length prefix, then elements
   "length_prefixed_items", // Length prefix, then per-item length prefix + elements
   "byte_length_prefixed", // Byte-length prefix, then elements (read until N bytes consumed)
+  "bit_length_prefixed", // Bit-count prefix of length_bits bits, then fixed-width elements
   "null_terminated", // Elements until null/zero terminator
   "signature_terminated", // Elements until specific multi-byte signature value
   "eof_terminated",  // Elements until end of stream
   "field_referenced", // Length comes from a field decoded earlier
   "variant_terminated", // Elements until a specific variant type is encountered (for choice/discriminated union items)
   "computed_count",  // Length is computed from an expression referencing earlier fields
+  "countdown",       // Elements until a counter seeded by an earlier field (length_field) reaches zero
+  "columnar",        // Count prefix, then each field of the item struct stored as a full column (structure-of-arrays)
 ]);
 export type ArrayKind = z.infer<typeof ArrayKindSchema>;
 
@@ -1012,6 +1199,7 @@ const OptionalFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("optional").meta({
     description: "Field type (always 'optional')"
   }),
@@ -1081,6 +1269,9 @@ const TypeRefElementSchema = z.object({
  */
 const DiscriminatedUnionVariantSchema = z.object({
   when: z.string().optional(), // Condition expression (e.g., "value >= 0xC0"), optional for fallback
+  values: z.array(z.number().int()).optional().meta({
+    description: "For multi-field discriminators: the value of each discriminator field, in order, that selects this variant. Omit for the fallback variant."
+  }),
   type: z.string(), // Type name to parse if condition matches
   description: z.string().optional().meta({
     description: "Human-readable description of this field"
@@ -1106,6 +1297,12 @@ const DiscriminatorSchema = z.union([
       description: "Name of earlier field to use as discriminator (supports dot notation like 'flags.type')"
     }),
   }),
+  // Multi-field: the combination of several earlier fields selects the variant
+  z.object({
+    fields: z.array(z.string()).min(2).meta({
+      description: "Names of earlier integer fields whose combined values select the variant (e.g., ['version', 'msg_type']). Variants list their matching values in 'values'. Currently generated for Rust only."
+    }),
+  }),
 ]);
 
 /**
@@ -1155,6 +1352,7 @@ const ChoiceFieldSchema = ChoiceElementSchema.extend({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
 }).meta({
   title: "Choice",
   description: "Flat discriminated union where the first field of each variant type serves as the discriminator. Each variant must have a const value on its first field for auto-detection during decoding.",
@@ -1192,6 +1390,9 @@ const BackReferenceElementSchema = z.object({
   offset_mask: z.string().regex(/^0x[0-9A-Fa-f]+$/, "Must be a valid hex mask (e.g., '0x3FFF')").meta({
     description: "Hex bitmask to extract offset bits from the storage integer (e.g., '0x3FFF' extracts lower 14 bits). Allows packing flags or type tags in unused bits."
   }),
+  pointer_flag: z.string().regex(/^0x[0-9A-Fa-f]+$/, "Must be a valid hex value (e.g., '0x8000')").optional().meta({
+    description: "Hex value of the flag bits that mark a pointer, OR-ed into the storage integer on encode and checked on decode. Must not overlap offset_mask. Defaults to the two most significant storage bits outside offset_mask (0xC000 for DNS). Currently honored by the Rust generator only."
+  }),
   offset_from: z.enum(["message_start", "current_position"]).meta({
     description: "Reference point for offset calculation. 'message_start' = offset from beginning of message (byte 0), 'current_position' = relative offset from current read position"
   }),
@@ -1201,6 +1402,9 @@ const BackReferenceElementSchema = z.object({
   endianness: EndiannessSchema.optional().meta({
     description: "Byte order for multi-byte storage types (required for uint16/uint32, meaningless for uint8)"
   }),
+  preserve_pointer: z.boolean().optional().meta({
+    description: "Keep the storage integer read by decode next to the resolved value and write it back verbatim on encode, instead of picking a pointer from the compression dictionary. Lets relays re-encode unmodified messages byte for byte. Type definitions only. Currently generated for Rust only."
+  }),
   description: z.string().optional().meta({
     description: "Human-readable description of this back reference field"
   }),
@@ -1214,6 +1418,7 @@ const BackReferenceFieldSchema = BackReferenceElementSchema.extend({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
 }).meta({
   title: "Back Reference",
   description: "Backward reference to data at an earlier position in the message. Used for compression via backwards references (like DNS name compression). Offset is always from message start.",
@@ -1261,6 +1466,7 @@ const PaddingFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("padding").meta({
     description: "Field type (always 'padding')"
   }),
@@ -1310,6 +1516,56 @@ const PaddingFieldSchema = z.object({
   ]
 });
 
+/**
+ * Inline group field
+ * An anonymous sequence of fields, optionally repeated a fixed number of times
+ */
+const GroupFieldSchema = z.object({
+  name: z.string().meta({
+    description: "Field name"
+  }),
+  rust_name: RustNameSchema,
+  type: z.literal("group").meta({
+    description: "Field type (always 'group')"
+  }),
+  repeat: z.number().int().min(1).optional().meta({
+    description: "Number of back-to-back copies of the group. When set, the field holds a fixed-size array of groups; when omitted it holds a single group."
+  }),
+  get sequence() {
+    return z.array(FieldTypeRefSchema); // Recursive reference
+  },
+  description: z.string().optional().meta({
+    description: "Human-readable description of this field"
+  }),
+}).meta({
+  title: "Inline Group",
+  description: "Anonymous sequence of fields declared in place, optionally repeated a fixed number of times. The generator names the group type after its parent type and field (Mesh.coords becomes MeshCoords). Currently generated for Rust only.",
+  use_for: "Small fixed sets of records sharing a layout (e.g., exactly three coordinate triplets)",
+  wire_format: "The group's fields, written `repeat` times back to back with no count prefix",
+
+  code_generation: {
+    rust: {
+      type: "[MeshCoords; 3] (or MeshCoords without repeat)",
+      notes: ["Encode and decode are unrolled, one call per element"]
+    }
+  },
+  notes: [
+    "Fields inside a group can only reference siblings within the same group"
+  ],
+  examples: [
+    {
+      name: "coords",
+      type: "group",
+      repeat: 3,
+      sequence: [
+        { name: "x", type: "int16" },
+        { name: "y", type: "int16" },
+        { name: "z", type: "int16" }
+      ]
+    }
+  ]
+});
+
 /**
  * Array element schema (array without name - for nested arrays)
  */
@@ -1324,7 +1580,7 @@ const ArrayElementSchema = z.object({
   length: z.number().int().min(1).optional(),
   length_type: z.enum(["uint8", "uint16", "uint32", "uint64"]).optional(),
   item_length_type: z.enum(["uint8", "uint16", "uint32", "uint64"]).optional(), // For length_prefixed_items: per-item length prefix type
-  length_field: z.string().optional(), // For field_referenced: field name to read length from (supports dot notation)
+  length_field: z.string().optional(), // For field_referenced: field name to read length from (supports dot notation); for countdown: the sibling field seeding the counter
   count_expr: z.string().optional(), // For computed_count: expression to compute array length
   terminator_value: z.number().optional(), // For signature_terminated: signature value to stop on
   terminator_type: z.enum(["uint8", "uint16", "uint32", "uint64"]).optional(), // For signature_terminated: type to peek for terminator
@@ -1332,6 +1588,17 @@ const ArrayElementSchema = z.object({
   variants: z.array(z.string()).optional(), // Optional: possible type names this could contain
   notes: z.array(z.string()).optional(), // Optional: notes about variants or usage
   terminal_variants: z.array(z.string()).optional(), // Optional: variant types that terminate the array (no null terminator after)
+  length_bits: LengthBitsSchema,
+  countdown_by: CountdownBySchema,
+  max_encoded_length: z.number().int().min(1).optional().meta({
+    description: "Protocol maximum for the array's encoded size in bytes, including any length prefix or terminator (e.g., 255 for DNS names). Encoding a larger array fails (InvalidValue in Rust)."
+  }),
+  record_alignment: z.number().int().min(2).optional().meta({
+    description: "Pads every element to the next multiple of this many bytes from the start of the message: encode writes zero bytes after each element and decode skips them. Unlike a padding field, this applies between array elements. Currently generated for Rust only."
+  }),
+  follow_pointers: z.boolean().optional().meta({
+    description: "For null_terminated arrays whose terminal variant is a back_reference (DNS names): decode follows the pointer and appends the items it points to, so the array holds the complete sequence. Pointer loops are rejected. Currently generated for Rust only."
+  }),
   description: z.string().optional().meta({
     description: "Human-readable description of this field"
   }),
@@ -1344,10 +1611,13 @@ const ArrayElementSchema = z.object({
     if (data.kind === "signature_terminated") return data.terminator_value !== undefined && data.terminator_type !== undefined;
     if (data.kind === "variant_terminated") return data.terminal_variants !== undefined && data.terminal_variants.length > 0;
     if (data.kind === "computed_count") return data.count_expr !== undefined;
+    if (data.kind === "columnar") return data.length_type !== undefined && data.length_type !== "varlength";
+    if (data.kind === "bit_length_prefixed") return data.length_bits !== undefined;
+    if (data.kind === "countdown") return data.length_field !== undefined;
     return true;
   },
   {
-    message: "Fixed arrays require 'length', length_prefixed arrays require 'length_type', length_prefixed_items arrays require 'length_type' and 'item_length_type', field_referenced arrays require 'length_field', signature_terminated arrays require 'terminator_value' and 'terminator_type', variant_terminated arrays require 'terminal_variants', computed_count arrays require 'count_expr'",
+    message: "Fixed arrays require 'length', length_prefixed arrays require 'length_type', length_prefixed_items arrays require 'length_type' and 'item_length_type', field_referenced arrays require 'length_field', signature_terminated arrays require 'terminator_value' and 'terminator_type', variant_terminated arrays require 'terminal_variants', computed_count arrays require 'count_expr', columnar arrays require a fixed-width 'length_type', bit_length_prefixed arrays require 'length_bits', countdown arrays require 'length_field'",
   }
 );
 
@@ -1365,6 +1635,7 @@ const StringElementSchema = z.object({
   }),
   length: z.number().int().min(1).optional(), // For fixed length
   length_type: z.enum(["uint8", "uint16", "uint32", "uint64"]).optional(), // For length_prefixed
+  max_length: StringMaxLengthSchema,
   description: z.string().optional().meta({
     description: "Human-readable description of this field"
   }),
@@ -1455,6 +1726,7 @@ const ArrayFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("array").meta({
     description: "Field type (always 'array')"
   }),
@@ -1463,10 +1735,10 @@ const ArrayFieldSchema = z.object({
     return ElementTypeSchema; // Recursive: array of element types (no name required)
   },
   length: z.number().int().min(0).optional(), // For fixed arrays
-  length_type: z.enum(["uint8", "uint16", "uint32", "uint64", "varlength"]).optional(), // For length_prefixed and byte_length_prefixed
+  length_type: z.enum(["uint8", "uint16", "uint32", "uint64", "varlength"]).optional(), // For length_prefixed, byte_length_prefixed and columnar (columnar: fixed-width only)
   length_encoding: z.enum(["der", "leb128", "ebml"]).optional(), // For varlength length_type: encoding format
   item_length_type: z.enum(["uint8", "uint16", "uint32", "uint64"]).optional(), // For length_prefixed_items: per-item length prefix type
-  length_field: z.string().optional(), // For field_referenced: field name to read item count from (supports dot notation like "flags.opcode")
+  length_field: z.string().optional(), // For field_referenced: field name to read item count from (supports dot notation like "flags.opcode"); for countdown: the sibling field seeding the counter
   count_expr: z.string().optional(), // For computed_count: expression to compute array length (e.g., "(max - min + 1) * count")
   terminator_value: z.number().optional(), // For signature_terminated: signature value to stop on
   terminator_type: z.enum(["uint8", "uint16", "uint32", "uint64"]).optional(), // For signature_terminated: type to peek for terminator
@@ -1474,6 +1746,20 @@ const ArrayFieldSchema = z.object({
   variants: z.array(z.string()).optional(), // Optional: possible type names this could contain
   notes: z.array(z.string()).optional(), // Optional: notes about variants or usage
   terminal_variants: z.array(z.string()).optional(), // Optional: variant types that terminate the array (no null terminator after)
+  length_bits: LengthBitsSchema,
+  countdown_by: CountdownBySchema,
+  encoding: z.literal("delta_zigzag").optional().meta({
+    description: "Store integer items as differences: the first item as usual, then each following item as the zigzag LEB128 varint of its difference from the previous one, so slowly changing or monotonic sequences take about a byte per item. Each varint starts on a byte boundary. Items must be plain integers. Currently generated for Rust only."
+  }),
+  max_encoded_length: z.number().int().min(1).optional().meta({
+    description: "Protocol maximum for the array's encoded size in bytes, including any length prefix or terminator (e.g., 255 for DNS names). Encoding a larger array fails (InvalidValue in Rust)."
+  }),
+  record_alignment: z.number().int().min(2).optional().meta({
+    description: "Pads every element to the next multiple of this many bytes from the start of the message: encode writes zero bytes after each element and decode skips them. Unlike a padding field, this applies between array elements. Currently generated for Rust only."
+  }),
+  follow_pointers: z.boolean().optional().meta({
+    description: "For null_terminated arrays whose terminal variant is a back_reference (DNS names): decode follows the pointer and appends the items it points to, so the array holds the complete sequence. Pointer loops are rejected. Currently generated for Rust only."
+  }),
   description: z.string().optional().meta({
     description: "Human-readable description of this field"
   }),
@@ -1487,10 +1773,13 @@ const ArrayFieldSchema = z.object({
     if (data.kind === "signature_terminated") return data.terminator_value !== undefined && data.terminator_type !== undefined;
     if (data.kind === "variant_terminated") return data.terminal_variants !== undefined && data.terminal_variants.length > 0;
     if (data.kind === "computed_count") return data.count_expr !== undefined;
+    if (data.kind === "columnar") return data.length_type !== undefined && data.length_type !== "varlength";
+    if (data.kind === "bit_length_prefixed") return data.length_bits !== undefined;
+    if (data.kind === "countdown") return data.length_field !== undefined;
     return true;
   },
   {
-    message: "Fixed arrays require 'length', length_prefixed arrays require 'length_type', length_prefixed_items arrays require 'length_type' and 'item_length_type', field_referenced arrays require 'length_field', byte_length_prefixed arrays require 'length_type', signature_terminated arrays require 'terminator_value' and 'terminator_type', variant_terminated arrays require 'terminal_variants', computed_count arrays require 'count_expr'",
+    message: "Fixed arrays require 'length', length_prefixed arrays require 'length_type', length_prefixed_items arrays require 'length_type' and 'item_length_type', field_referenced arrays require 'length_field', byte_length_prefixed arrays require 'length_type', signature_terminated arrays require 'terminator_value' and 'terminator_type', variant_terminated arrays require 'terminal_variants', computed_count arrays require 'count_expr', columnar arrays require a fixed-width 'length_type', bit_length_prefixed arrays require 'length_bits', countdown arrays require 'length_field'",
   }
 ).meta({
   title: "Array",
@@ -1516,7 +1805,8 @@ const ArrayFieldSchema = z.object({
     "length_prefixed is most common for variable-length arrays",
     "field_referenced allows dynamic sizing based on earlier fields",
     "null_terminated useful for variable-length lists with terminator value",
-    "length_prefixed_items used when each item has individual length prefix (e.g., array of strings)"
+    "length_prefixed_items used when each item has individual length prefix (e.g., array of strings)",
+    "columnar stores a struct's fields as parallel columns (all x values, then all y values); items must reference a sequence of plain numeric fields. Currently generated for Rust only."
   ],
   examples: [
     { name: "values", type: "array", kind: "fixed", items: { type: "uint32" }, length: 4 },
@@ -1533,6 +1823,7 @@ const BytesFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("bytes").meta({
     description: "Field type (always 'bytes')"
   }),
@@ -1589,6 +1880,39 @@ const BytesFieldSchema = z.object({
   ]
 });
 
+/**
+ * Opaque nested message (raw bytes sized by an earlier field)
+ */
+const MessageBytesFieldSchema = z.object({
+  name: z.string().meta({
+    description: "Field name"
+  }),
+  rust_name: RustNameSchema,
+  type: z.literal("message_bytes").meta({
+    description: "Field type (always 'message_bytes')"
+  }),
+  length_field: z.string().meta({
+    description: "Earlier field holding the byte length. It must be computed as length_of this field, so encoding always writes a matching prefix."
+  }),
+  description: z.string().optional().meta({
+    description: "Human-readable description of this field"
+  }),
+}).meta({
+  title: "Message Bytes",
+  description: "An embedded message kept as its raw bytes instead of being decoded. Lets a wrapper carry a payload (a tunneled packet, an encrypted blob) that is forwarded as is or parsed later with another schema. Currently generated for Rust only.",
+  use_for: "Tunneled or encapsulated packets, payloads whose schema depends on context, pass-through relays",
+  wire_format: "length_field bytes, copied verbatim",
+  code_generation: {
+    rust: {
+      type: "Vec<u8>",
+      notes: ["Read in one read_bytes_vec call", "Pass the bytes to another type's decode to parse the payload"]
+    }
+  },
+  examples: [
+    { name: "payload", type: "message_bytes", length_field: "inner_len" }
+  ]
+});
+
 /**
  * String field (variable or fixed length)
  */
@@ -1597,6 +1921,7 @@ const StringFieldBaseSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("string").meta({
     description: "Field type (always 'string')"
   }),
@@ -1616,23 +1941,29 @@ const StringFieldSchema = z.discriminatedUnion("kind", [
     kind: z.literal("fixed"),
     length: z.number().int().min(1),
     const: z.string().optional(),
+    strict_utf8_boundaries: z.boolean().optional().meta({
+      description: "When false, a UTF-8 sequence cut off by the end of the field is dropped on decode instead of failing with InvalidUtf8 (default: true). The partial character is lost, so a decode-then-encode round trip can yield shorter text. Invalid bytes elsewhere still fail. Currently honored by the Rust generator only."
+    }),
   }).strict(),
 
   // Length-prefixed string (encoder writes length automatically)
   StringFieldBaseSchema.extend({
     kind: z.literal("length_prefixed"),
     length_type: z.enum(["uint8", "uint16", "uint32", "uint64"]),
+    max_length: StringMaxLengthSchema,
   }).strict(),
 
   // Field-referenced string (length comes from another field)
   StringFieldBaseSchema.extend({
     kind: z.literal("field_referenced"),
     length_field: z.string(),
+    max_length: StringMaxLengthSchema,
   }).strict(),
 
   // Null-terminated string
   StringFieldBaseSchema.extend({
     kind: z.literal("null_terminated"),
+    max_length: StringMaxLengthSchema,
   }).strict(),
 ]).meta({
   title: "String",
@@ -1657,8 +1988,10 @@ const StringFieldSchema = z.discriminatedUnion("kind", [
   notes: [
     "Length-prefixed is most common for variable-length strings",
     "Fixed-length strings are padded/truncated to exact size",
+    "Fixed-length UTF-8 strings truncated mid-character can be decoded leniently with **strict_utf8_boundaries: false**, which drops the incomplete tail",
     "Fixed-length strings support **const** for constant identifiers (e.g., RIFF chunk IDs). Const value byte length must not exceed the fixed length.",
-    "Null-terminated strings read until 0x00 byte"
+    "Null-terminated strings read until 0x00 byte",
+    "Variable-length strings accept **max_length** to reject over-long values on encode (e.g., DNS labels are limited to 63 bytes)"
   ],
   examples: [
     { name: "nickname", type: "string", kind: "length_prefixed", length_type: "uint8" },
@@ -1676,6 +2009,7 @@ const DiscriminatedUnionFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("discriminated_union").meta({
     description: "Field type (always 'discriminated_union')"
   }),
@@ -1715,6 +2049,7 @@ const DiscriminatedUnionFieldSchema = z.object({
     "Each variant has a **when** condition (e.g., `value == 0x01`) that determines if it matches",
     "Conditions support **string literals** (e.g., `value == 'SIZE'`) for matching ASCII chunk IDs",
     "The last variant may omit **when** to act as a **fallback/default** for unrecognized discriminator values",
+    "Multi-field: `discriminator: { fields: [...] }` selects by a combination of earlier integer fields; each variant gives one value per field in **values**, and an unmapped combination is a decode error unless a fallback variant is present (Rust only)",
     "**byte_budget**: Limits variant decoding to N bytes from a referenced numeric field. Creates a sub-slice for the variant decoder; main decoder advances by the full budget. Essential for RIFF/IFF-style chunk formats.",
     "byte_budget pairs naturally with **eof_terminated** arrays in fallback variants — the sub-slice EOF boundary becomes the byte budget boundary"
   ],
@@ -1748,6 +2083,7 @@ const BitfieldFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.literal("bitfield").meta({
     description: "Field type (always 'bitfield')"  
   }),
@@ -1809,6 +2145,7 @@ const TypeRefFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.string().refine(
     (t) => {
       // Extract base type name (before any generic brackets)
@@ -1832,8 +2169,12 @@ const ConditionalFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.string(),
   conditional: z.string(), // Expression like "flags.present == 1"
+  when_truncated: z.enum(["absent", "error"]).optional().meta({
+    description: "What decoding does when the condition holds but fewer bytes remain than the field needs (its size, or minimum size if variable), as when an older sender sets a flag but truncates the message. 'absent' decodes the field as absent; 'error' fails with UnexpectedEof before reading it. Encoding writes a field only when it is present. Currently generated for Rust only."
+  }),
   description: z.string().optional().meta({
     description: "Human-readable description of this field"
   }),
@@ -1860,7 +2201,8 @@ const ConditionalFieldSchema = z.object({
   notes: [
     "Condition is evaluated during encoding/decoding",
     "Supports dot notation for nested field access (e.g., 'header.flags.extended')",
-    "Unlike optional type, no presence indicator is stored on wire"
+    "Unlike optional type, no presence indicator is stored on wire",
+    "when_truncated also checks the remaining bytes, for senders that set the flag but cut the field off"
   ],
   examples: [
     {
@@ -1904,15 +2246,19 @@ const FieldTypeRefSchema: z.ZodType<any> = z.union([
     VarlengthFieldSchema,
     Float32FieldSchema,
     Float64FieldSchema,
+    CustomFloatFieldSchema,
+    UuidFieldSchema,
     OptionalFieldSchema,
     ArrayFieldSchema,
     BytesFieldSchema,
+    MessageBytesFieldSchema,
     StringFieldSchema,
     BitfieldFieldSchema,
     DiscriminatedUnionFieldSchema,
     ChoiceFieldSchema,
     BackReferenceFieldSchema,
     PaddingFieldSchema,
+    GroupFieldSchema,
   ]),
 
   // Third: Fallback to type reference for user-defined types
@@ -1956,6 +2302,7 @@ const PositionFieldSchema = z.object({
   name: z.string().meta({
     description: "Field name"
   }),
+  rust_name: RustNameSchema,
   type: z.union([
     z.string(), // Simple type reference
     InlineDiscriminatedUnionSchema // Inline discriminated union (type determined by discriminator)
@@ -1968,6 +2315,9 @@ const PositionFieldSchema = z.object({
   ]).meta({
     description: "Position to seek to before decoding. Number (positive=absolute offset, negative=from EOF), or field reference (e.g., 'header.data_offset')"
   }),
+  relative_to: z.string().optional().meta({
+    description: "Name of a sequence field acting as an anchor. The position is an offset from the byte where that field starts rather than from the message start. Currently generated for Rust only."
+  }),
   size: z.union([
     z.number(),  // Fixed size
     z.string()   // Field reference
@@ -2019,6 +2369,7 @@ const PositionFieldSchema = z.object({
   notes: [
     "Position can be negative (from EOF): -22 means last 22 bytes",
     "Position can reference earlier field: 'header.offset'",
+    "relative_to makes the position an offset from the start of a named sequence field (anchor)",
     "Alignment is validated at runtime: position % alignment == 0",
     "Size is optional hint for memory allocation"
   ],
@@ -2050,6 +2401,21 @@ const CompositeTypeSchema = z.object({
   instances: z.array(PositionFieldSchema).optional().meta({
     description: "Position-based fields (lazy-evaluated when accessed). Requires seekable input."
   }),
+  wire_order: z.array(z.string()).optional().meta({
+    description: "Order in which sequence fields are encoded and decoded, when it differs from the declared (struct) order. Must list every field exactly once. Currently generated for Rust only."
+  }),
+  signature_field: z.string().optional().meta({
+    description: "Name of the field holding a signature over the rest of the message. Generates signed_bytes(), which encodes the value with this field zeroed. Currently generated for Rust only."
+  }),
+  endianness_marker: z.string().optional().meta({
+    description: "Name of a multi-byte const field (e.g. a magic number) that identifies the byte order. Generates decode_autodetect_endianness(), which also accepts messages written in the opposite endianness by reading every multi-byte field byte-swapped when the marker only matches that way. Currently generated for Rust only."
+  }),
+  decode_into: z.boolean().optional().meta({
+    description: "Generate decode_into(), which decodes into an existing value in place without allocating. Every field, including fields of nested types, must be fixed-size: numbers, bits, bitfields, padding, enums, flags or nested types of the same kind. Currently generated for Rust only."
+  }),
+  builder: z.boolean().optional().meta({
+    description: "Generate a {Type}Builder with a with_<field>() setter per field. Each setter checks the value against the field's bit width, length prefix, max_length or fixed length and fails immediately with InvalidValue; build() fails if a non-conditional field was never set. Not supported on types with instances. Currently generated for Rust only."
+  }),
   description: z.string().optional(),
 });
 
@@ -2067,6 +2433,9 @@ const EnumTypeSchema = z.object({
   type: z.literal("enum"),
   repr: z.enum(["uint8", "uint16", "uint32"]),
   variants: z.record(z.string(), z.number().int().min(0)),
+  on_unknown_enum: z.enum(["error", "keep_raw"]).optional().meta({
+    description: "What decoding does with a value that matches no variant. 'error' (default) fails the decode; 'keep_raw' adds a Raw variant holding the value, which encodes back unchanged. Currently generated for Rust only."
+  }),
   description: z.string().optional(),
 });
 
@@ -2078,7 +2447,61 @@ export function isEnumType(typeDef: any): boolean {
 }
 
 /**
- * Type definition - composite, enum, or type alias
+ * Flags type - a set of independent named boolean bits
+ *
+ * Each flag maps a name to its bit position (0 = least significant bit) within
+ * the storage integer. On the wire the flag set is just its storage integer;
+ * bits that aren't named are preserved as-is. In generated Rust code it becomes
+ * a bitflags-style newtype with named constants and contains/insert/remove.
+ */
+const FlagsTypeSchema = z.object({
+  type: z.literal("flags"),
+  storage: z.enum(["uint8", "uint16", "uint32"]),
+  flags: z.record(z.string(), z.number().int().min(0).max(31)),
+  description: z.string().optional(),
+});
+
+/**
+ * Check if a type definition is a flags type
+ */
+export function isFlagsType(typeDef: any): boolean {
+  return typeDef && typeof typeDef === 'object' && typeDef.type === 'flags' && 'flags' in typeDef;
+}
+
+/**
+ * TLV option type - one tag/length/value element whose tag selects the value type
+ *
+ * Models option lists such as DHCP options or TLS extensions: use it as the
+ * `items` type of an array. Each option maps a tag to a value type (an integer
+ * primitive, "bytes" for the raw value, or a named type). The length bounds the
+ * value; tags without an option decode to an unknown-option variant that keeps
+ * the raw value bytes, so they re-encode unchanged.
+ * Currently generated for Rust only.
+ */
+const TlvOptionSchema = z.object({
+  name: z.string(),
+  tag: z.number().int().min(0),
+  type: z.string(),
+  description: z.string().optional(),
+});
+
+const TlvTypeSchema = z.object({
+  type: z.literal("tlv"),
+  tag_type: z.enum(["uint8", "uint16"]),
+  length_type: z.enum(["uint8", "uint16", "uint32"]),
+  options: z.array(TlvOptionSchema),
+  description: z.string().optional(),
+});
+
+/**
+ * Check if a type definition is a TLV option type
+ */
+export function isTlvType(typeDef: any): boolean {
+  return typeDef && typeof typeDef === 'object' && typeDef.type === 'tlv' && 'options' in typeDef;
+}
+
+/**
+ * Type definition - composite, enum, flags, TLV, or type alias
  *
  * A type can be:
  * 1. Composite type: Has a 'sequence' of named types that appear in order on the wire
@@ -2087,7 +2510,13 @@ export function isEnumType(typeDef: any): boolean {
  * 2. Enum type: Has 'type: "enum"' with named integer variants
  *    Example: Direction has variants North=0, East=1, South=2, West=3
  *
- * 3. Type alias: Directly references a type/primitive without wrapping
+ * 3. Flags type: Has 'type: "flags"' with named bit positions in a storage integer
+ *    Example: DnsFlags has QR=15, AA=10, TC=9, RD=8, RA=7 in a uint16
+ *
+ * 4. TLV type: Has 'type: "tlv"' mapping tags to per-option value types
+ *    Example: DhcpOption has SubnetMask=1 (uint32), HostName=12 (bytes)
+ *
+ * 5. Type alias: Directly references a type/primitive without wrapping
  *    Example: String IS a length-prefixed array of uint8, not a struct containing one
  *
  * This distinction clarifies that binary schemas represent wire format (ordered byte sequences),
@@ -2096,6 +2525,8 @@ export function isEnumType(typeDef: any): boolean {
 export const TypeDefSchema = z.union([
   CompositeTypeSchema,
   EnumTypeSchema,
+  FlagsTypeSchema,
+  TlvTypeSchema,
   // Type alias - any element type (primitive, array, etc) with optional description
   ElementTypeSchema.and(z.object({
     description: z.string().optional()
@@ -2218,7 +2649,30 @@ function validateTerminalVariants(schema: any): { valid: boolean; error?: string
   for (const [typeName, typeDef] of Object.entries(schema.types)) {
     // Check if this is an array type (either top-level or nested in sequence)
     const checkArray = (arrayDef: any, path: string) => {
-      if (!arrayDef || arrayDef.type !== "array" || !arrayDef.terminal_variants) {
+      if (!arrayDef || arrayDef.type !== "array") {
+        return { valid: true };
+      }
+
+      // follow_pointers chases the array's one terminal back_reference variant
+      if (arrayDef.follow_pointers) {
+        const pointerVariants = (arrayDef.terminal_variants ?? []).filter(
+          (v: string) => schema.types[v]?.type === "back_reference"
+        );
+        if (arrayDef.kind !== "null_terminated" || pointerVariants.length !== 1) {
+          return {
+            valid: false,
+            error: `${path}: follow_pointers requires a null_terminated array with exactly one back_reference in terminal_variants`
+          };
+        }
+        if (schema.types[pointerVariants[0]].preserve_pointer) {
+          return {
+            valid: false,
+            error: `${path}: follow_pointers discards the pointers it follows, so '${pointerVariants[0]}' cannot set preserve_pointer`
+          };
+        }
+      }
+
+      if (!arrayDef.terminal_variants) {
         return { valid: true };
       }
 
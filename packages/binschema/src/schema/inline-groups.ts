@@ -0,0 +1,81 @@
+// ABOUTME: Pre-pass that lifts inline `group` fields into named sequence types
+// ABOUTME: (e.g. Mesh.coords → MeshCoords), keeping `repeat` on the field.
+
+import type { BinarySchema } from "./binary-schema.js";
+
+/**
+ * Name of the type synthesized for an inline group: the parent type name
+ * followed by the PascalCase field name, e.g. ("Mesh", "normal_vectors") →
+ * "MeshNormalVectors".
+ */
+export function inlineGroupTypeName(parentType: string, fieldName: string): string {
+  const suffix = fieldName
+    .split("_")
+    .filter(part => part.length > 0)
+    .map(part => part.charAt(0).toUpperCase() + part.slice(1))
+    .join("");
+  return parentType + suffix;
+}
+
+/** True if any sequence in the schema declares a `group` field. */
+function hasInlineGroups(schema: BinarySchema): boolean {
+  return Object.values(schema.types ?? {}).some(typeDef =>
+    Array.isArray((typeDef as any).sequence) &&
+    (typeDef as any).sequence.some((f: any) => f?.type === "group")
+  );
+}
+
+/**
+ * Pre-pass: lift inline `group` fields into ordinary named types.
+ *
+ * A group is an anonymous sequence declared in place. Generators only know
+ * how to emit named sequence types, so before code generation each group
+ * becomes a synthesized type and the field becomes a reference to it:
+ *
+ *   { name: "coords", type: "group", repeat: 3, sequence: [...] }
+ *     → types.MeshCoords = { sequence: [...] }
+ *     → { name: "coords", type: "MeshCoords", repeat: 3 }
+ *
+ * Groups nested inside groups are lifted in turn, named after the lifted
+ * parent (MeshCoordsInner). The `repeat` property survives on the field so
+ * the generator can emit a fixed-size array.
+ *
+ * @param schemaInput  The user-authored schema (NOT mutated).
+ * @returns A deep-cloned schema with every group lifted to a named type.
+ */
+export function expandInlineGroups(schemaInput: BinarySchema): BinarySchema {
+  if (!hasInlineGroups(schemaInput)) return schemaInput;
+
+  const result: BinarySchema = JSON.parse(JSON.stringify(schemaInput));
+
+  // Worklist of types whose sequences may still contain groups; synthesized
+  // types are pushed back so nested groups get lifted too.
+  const pending = Object.keys(result.types);
+  while (pending.length > 0) {
+    const typeName = pending.shift()!;
+    const sequence = (result.types[typeName] as any)?.sequence;
+    if (!Array.isArray(sequence)) continue;
+
+    for (let i = 0; i < sequence.length; i++) {
+      const field = sequence[i];
+      if (field?.type !== "group") continue;
+
+      const liftedName = inlineGroupTypeName(typeName, field.name);
+      if (result.types[liftedName]) {
+        throw new Error(
+          `Inline group '${typeName}.${field.name}' would be named '${liftedName}', which already exists in schema.types`
+        );
+      }
+      (result.types as any)[liftedName] = { sequence: field.sequence };
+      pending.push(liftedName);
+
+      const lifted: any = { name: field.name, type: liftedName };
+      if (field.repeat !== undefined) lifted.repeat = field.repeat;
+      if (field.rust_name !== undefined) lifted.rust_name = field.rust_name;
+      if (field.description !== undefined) lifted.description = field.description;
+      sequence[i] = lifted;
+    }
+  }
+
+  return result;
+}
@@ -0,0 +1,94 @@
+// ABOUTME: Guard for generators that don't implement the Rust-only schema options
+// ABOUTME: Rejects such schemas instead of silently encoding them some other way
+
+import type { BinarySchema } from "./binary-schema.js";
+
+/**
+ * A schema option only the Rust generator implements. `usedBy` is called on
+ * every object in the schema (type definitions, fields, nested items) and
+ * returns true when that object relies on the option.
+ */
+interface RustOnlyFeature {
+  option: string;
+  usedBy(node: Record<string, unknown>): boolean;
+}
+
+/** `computed.type` of a field, if it is computed */
+const computedType = (node: Record<string, unknown>): unknown =>
+  (node.computed as { type?: unknown } | undefined)?.type;
+
+/**
+ * Options other generators would otherwise ignore without any error, producing
+ * bytes that differ from the Rust output or leaving out the API they add.
+ */
+export const RUST_ONLY_FEATURES: RustOnlyFeature[] = [
+  // Falls back to `endianness`
+  { option: "byte_order", usedBy: node => node.byte_order !== undefined },
+  // Encodes in declaration order
+  { option: "wire_order", usedBy: node => node.wire_order !== undefined },
+  // Adds signed_bytes()
+  { option: "signature_field", usedBy: node => node.signature_field !== undefined },
+  // Writes no checksum bytes
+  { option: "crc_of", usedBy: node => computedType(node) === "crc_of" },
+  // Adds decode_autodetect_endianness()
+  { option: "endianness_marker", usedBy: node => node.endianness_marker !== undefined },
+  // Writes no padding between elements
+  { option: "record_alignment", usedBy: node => node.record_alignment !== undefined },
+  // Write no bytes for the transformed field
+  { option: "xor_of", usedBy: node => computedType(node) === "xor_of" },
+  { option: "complement_of", usedBy: node => computedType(node) === "complement_of" },
+  // Fails on unknown values instead of keeping them
+  { option: "on_unknown_enum: keep_raw", usedBy: node => node.on_unknown_enum === "keep_raw" },
+  // Writes every item in full
+  { option: "delta_zigzag", usedBy: node => node.encoding === "delta_zigzag" },
+  // Reads the field regardless of the bytes left
+  { option: "when_truncated", usedBy: node => node.when_truncated !== undefined },
+];
+
+export interface RustOnlyFeatureUse {
+  option: string;
+  /** "Type.field", nested fields joined with dots; just "Type" for type-level options */
+  path: string;
+}
+
+/**
+ * Every use of a Rust-only option in the schema, in declaration order.
+ */
+export function findRustOnlyFeatures(schema: BinarySchema): RustOnlyFeatureUse[] {
+  const uses: RustOnlyFeatureUse[] = [];
+  const walk = (node: unknown, path: string): void => {
+    if (Array.isArray(node)) {
+      node.forEach(item => walk(item, path));
+      return;
+    }
+    if (node === null || typeof node !== "object") return;
+    const obj = node as Record<string, unknown>;
+    const here = typeof obj.name === "string" ? `${path}.${obj.name}` : path;
+    for (const feature of RUST_ONLY_FEATURES) {
+      if (feature.usedBy(obj)) uses.push({ option: feature.option, path: here });
+    }
+    for (const value of Object.values(obj)) walk(value, here);
+  };
+  for (const [typeName, typeDef] of Object.entries(schema.types ?? {})) {
+    walk(typeDef, typeName);
+  }
+  return uses;
+}
+
+/**
+ * Throws if the schema uses any Rust-only option, naming each option and where
+ * it is used.
+ */
+export function rejectRustOnlyFeatures(schema: BinarySchema, generator: string): void {
+  const uses = findRustOnlyFeatures(schema);
+  if (uses.length === 0) return;
+  const pathsByOption = new Map<string, string[]>();
+  for (const { option, path } of uses) {
+    pathsByOption.set(option, [...(pathsByOption.get(option) ?? []), path]);
+  }
+  const options = [...pathsByOption].map(([option, paths]) => `${option} (used by ${paths.join(", ")})`);
+  throw new Error(
+    `The ${generator} generator does not support ${options.join(", ")}; ` +
+    `only the Rust generator implements ${pathsByOption.size === 1 ? "it" : "them"}`
+  );
+}
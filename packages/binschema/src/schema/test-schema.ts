@@ -98,6 +98,11 @@ export const TestSuiteSchema = z.object({
 
   // Optional: expected schema validation error message (partial match)
   error_message: z.string().optional(),
+
+  // Optional: generators that refuse this schema because it uses a Rust-only
+  // option. Their harnesses pass when code generation fails with the
+  // "does not support" error; the other generators run the test cases.
+  rejected_by: z.array(z.enum(["typescript", "go", "python"])).optional(),
 }).transform((data) => {
   // Normalize: if "tests" is provided but not "test_cases", copy it over
   if (data.tests && !data.test_cases) {
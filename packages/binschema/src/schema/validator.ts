@@ -9,8 +9,9 @@
  * - Circular type dependencies
  */
 
-import { BinarySchema, Field, TypeDef, isEnumType } from "./binary-schema.js";
+import { BinarySchema, Field, TypeDef, isEnumType, isFlagsType, isTlvType } from "./binary-schema.js";
 import { ARRAY_ITER_SUFFIX } from "../generators/typescript/shared.js";
+import { inlineGroupTypeName } from "./inline-groups.js";
 
 export interface ValidationError {
   path: string;
@@ -27,9 +28,9 @@ export interface ValidationResult {
  */
 const BUILT_IN_TYPES = [
   "bit", "int", "bool", "uint8", "uint16", "uint32", "uint64",
-  "int8", "int16", "int32", "int64", "varlength", "float32", "float64",
-  "string", "array", "bytes", "optional", "bitfield", "discriminated_union", "back_reference", "choice",
-  "padding"
+  "int8", "int16", "int32", "int64", "varlength", "float32", "float64", "custom_float", "uuid",
+  "string", "array", "bytes", "message_bytes", "optional", "bitfield", "discriminated_union", "back_reference", "choice",
+  "padding", "group"
 ];
 
 /**
@@ -44,7 +45,7 @@ const RESERVED_FIELD_PATTERNS = [
  * Check if a type is a composite (has sequence/fields) or a type alias
  */
 function isTypeAlias(typeDef: TypeDef): boolean {
-  if (isEnumType(typeDef)) return false;
+  if (isEnumType(typeDef) || isFlagsType(typeDef) || isTlvType(typeDef)) return false;
   return !('sequence' in typeDef);
 }
 
@@ -106,6 +107,10 @@ export function validateSchema(schema: BinarySchema): ValidationResult {
     validateTypeDef(typeName, typeDef, schema, errors);
   }
 
+  validateNewtypes(schema, errors);
+  validateOffsetBinaryFields(schema, errors);
+  validateMessageBytesFields(schema, errors);
+
   // Check for circular dependencies
   for (const typeName of Object.keys(schema.types)) {
     const cycle = findCircularDependency(typeName, schema, new Set());
@@ -138,6 +143,16 @@ function validateTypeDef(
     return;
   }
 
+  if (isFlagsType(typeDef)) {
+    validateFlagsType(typeName, typeDef as any, errors);
+    return;
+  }
+
+  if (isTlvType(typeDef)) {
+    validateTlvType(typeName, typeDef as any, schema, errors);
+    return;
+  }
+
   // Check if this is a discriminated union or pointer type alias
   if (isTypeAlias(typeDef)) {
     const typeDefAny = typeDef as any;
@@ -196,6 +211,80 @@ function validateTypeDef(
     }
   }
 
+  // Instances are filled in by decode, so a builder couldn't construct the struct
+  const instances: any[] | undefined = (typeDef as any).instances;
+  if ((typeDef as any).builder && instances && instances.length > 0) {
+    errors.push({
+      path: `types.${typeName}.builder`,
+      message: `builder is not supported on type '${typeName}', which has instances`
+    });
+  }
+
+  // wire_order must be a permutation of the sequence field names
+  const wireOrder: string[] | undefined = (typeDef as any).wire_order;
+  if (wireOrder) {
+    const seen = new Set<string>();
+    for (const name of wireOrder) {
+      if (!fieldNames.has(name)) {
+        errors.push({
+          path: `types.${typeName}.wire_order`,
+          message: `wire_order references unknown field '${name}'`
+        });
+      } else if (seen.has(name)) {
+        errors.push({
+          path: `types.${typeName}.wire_order`,
+          message: `wire_order lists field '${name}' more than once`
+        });
+      }
+      seen.add(name);
+    }
+    if (fields.length !== fieldNames.size) {
+      errors.push({
+        path: `types.${typeName}.wire_order`,
+        message: `wire_order cannot be used in a type with unnamed fields`
+      });
+    }
+    for (const name of fieldNames) {
+      if (!seen.has(name)) {
+        errors.push({
+          path: `types.${typeName}.wire_order`,
+          message: `wire_order is missing field '${name}'`
+        });
+      }
+    }
+  }
+
+  const signatureField: string | undefined = (typeDef as any).signature_field;
+  if (signatureField !== undefined && !fieldNames.has(signatureField)) {
+    errors.push({
+      path: `types.${typeName}.signature_field`,
+      message: `signature_field references unknown field '${signatureField}'`
+    });
+  }
+
+  const endiannessMarker: string | undefined = (typeDef as any).endianness_marker;
+  if (endiannessMarker !== undefined) {
+    validateEndiannessMarker(typeName, endiannessMarker, fields, errors);
+  }
+
+  if ((typeDef as any).decode_into) {
+    validateDecodeInto(typeName, schema, errors);
+  }
+
+  // rust_name overrides must not collide within one struct
+  const rustNames = new Map<string, string>();
+  fields.forEach((field: any, i: number) => {
+    if (!field.rust_name) return;
+    const other = rustNames.get(field.rust_name);
+    if (other !== undefined) {
+      errors.push({
+        path: `types.${typeName}.${fieldsKey}[${i}].rust_name`,
+        message: `rust_name '${field.rust_name}' is already used by field '${other}'`
+      });
+    }
+    rustNames.set(field.rust_name, field.name);
+  });
+
   // Validate each sequence field
   // Pass typeName as rootTypeName so nested types can use _root references
   for (let i = 0; i < fields.length; i++) {
@@ -240,6 +329,160 @@ function validateTypeDef(
   }
 }
 
+/**
+ * Validate `newtype` annotations: one generated type per name, so every field
+ * naming it must wrap the same integer type, and the name can't shadow a
+ * schema type. Computed, const and conditional fields keep their plain types.
+ */
+function validateNewtypes(schema: BinarySchema, errors: ValidationError[]): void {
+  const wrapped = new Map<string, string>();
+  for (const [typeName, typeDef] of Object.entries(schema.types)) {
+    const fields: any[] = (typeDef as any).sequence ?? [];
+    fields.forEach((field, i) => {
+      if (!field.newtype) return;
+      const path = `types.${typeName}.sequence[${i}].newtype`;
+      if (schema.types[field.newtype]) {
+        errors.push({ path, message: `newtype '${field.newtype}' has the same name as a schema type` });
+      }
+      if (field.computed || field.const !== undefined || field.conditional || field.optional) {
+        errors.push({ path, message: `newtype can't be used on computed, const or conditional field '${field.name}'` });
+      }
+      const other = wrapped.get(field.newtype);
+      if (other !== undefined && other !== field.type) {
+        errors.push({ path, message: `newtype '${field.newtype}' wraps '${other}' elsewhere but '${field.name}' is '${field.type}'` });
+      }
+      wrapped.set(field.newtype, field.type);
+    });
+  }
+}
+
+/**
+ * Validate `signed_repr: "offset_binary"`: an unsigned 8-32 bit field with a
+ * bias its storage type can hold. The field's Rust type changes to a signed
+ * one, which computed, const and newtype fields can't follow.
+ */
+function validateOffsetBinaryFields(schema: BinarySchema, errors: ValidationError[]): void {
+  const storageBits: Record<string, number> = { uint8: 8, uint16: 16, uint32: 32 };
+  for (const [typeName, typeDef] of Object.entries(schema.types)) {
+    const fields: any[] = (typeDef as any).sequence ?? [];
+    fields.forEach((field, i) => {
+      if (field.signed_repr === undefined && field.bias === undefined) return;
+      const path = `types.${typeName}.sequence[${i}] (${field.name})`;
+      if (field.signed_repr !== "offset_binary") {
+        errors.push({ path, message: "bias requires signed_repr 'offset_binary'" });
+        return;
+      }
+      const bits = storageBits[field.type];
+      if (bits === undefined) {
+        errors.push({ path, message: `signed_repr 'offset_binary' applies to uint8, uint16 and uint32 fields, not '${field.type}'` });
+        return;
+      }
+      if (field.bias === undefined) {
+        errors.push({ path, message: "signed_repr 'offset_binary' requires bias" });
+      } else if (field.bias >= 2 ** bits) {
+        errors.push({ path, message: `bias ${field.bias} does not fit in ${field.type}` });
+      }
+      if (field.computed || field.const !== undefined || field.newtype) {
+        errors.push({ path, message: "signed_repr can't be combined with computed, const or newtype" });
+      }
+    });
+  }
+}
+
+/**
+ * Validate `message_bytes` fields: the length must come from an earlier
+ * length_of field measuring the payload, so encode can't write a stale prefix
+ */
+function validateMessageBytesFields(schema: BinarySchema, errors: ValidationError[]): void {
+  for (const [typeName, typeDef] of Object.entries(schema.types)) {
+    const fields: any[] = (typeDef as any).sequence ?? [];
+    fields.forEach((field, i) => {
+      if (field.type !== "message_bytes") return;
+      const path = `types.${typeName}.sequence[${i}] (${field.name})`;
+      const lengthField = fields.slice(0, i).find(f => f.name === field.length_field);
+      if (!lengthField) {
+        errors.push({ path, message: `message_bytes length_field '${field.length_field}' must name an earlier field in the same sequence` });
+      } else if (lengthField.computed?.type !== "length_of" || lengthField.computed.target !== field.name) {
+        errors.push({ path, message: `message_bytes length_field '${field.length_field}' must be computed as length_of '${field.name}'` });
+      }
+    });
+  }
+}
+
+/**
+ * Validate `endianness_marker`: a multi-byte const at a fixed offset whose
+ * value reads differently in the two byte orders
+ */
+function validateEndiannessMarker(typeName: string, markerName: string, fields: Field[], errors: ValidationError[]): void {
+  const path = `types.${typeName}.endianness_marker`;
+  const index = fields.findIndex((f: any) => f.name === markerName);
+  if (index === -1) {
+    errors.push({ path, message: `endianness_marker references unknown field '${markerName}'` });
+    return;
+  }
+  const marker = fields[index] as any;
+  const widths: Record<string, number> = { uint16: 2, uint32: 4, uint64: 8 };
+  const width = widths[marker.type];
+  if (width === undefined) {
+    errors.push({ path, message: `endianness_marker field '${markerName}' must be uint16, uint32 or uint64, got '${marker.type}'` });
+    return;
+  }
+  if (marker.const === undefined) {
+    errors.push({ path, message: `endianness_marker field '${markerName}' must have a 'const' value` });
+    return;
+  }
+  const fixedTypes = ["uint8", "int8", "bool", "uint16", "int16", "uint32", "int32", "float32", "uint64", "int64", "float64"];
+  for (const earlier of fields.slice(0, index) as any[]) {
+    if (!fixedTypes.includes(earlier.type) || earlier.conditional != null) {
+      errors.push({ path, message: `endianness_marker field '${markerName}' must be preceded only by fixed-size fields, but follows '${earlier.name}'` });
+      return;
+    }
+  }
+  const value = BigInt(marker.const);
+  let swapped = 0n;
+  for (let i = 0; i < width; i++) {
+    swapped = (swapped << 8n) | ((value >> BigInt(i * 8)) & 0xFFn);
+  }
+  if (swapped === value) {
+    errors.push({ path, message: `endianness_marker field '${markerName}' has a const that reads the same in both byte orders` });
+  }
+}
+
+/**
+ * Validate `decode_into`: the type and every sequence type it nests must be
+ * made only of fixed-size fields that decode without allocating
+ */
+function validateDecodeInto(typeName: string, schema: BinarySchema, errors: ValidationError[]): void {
+  const path = `types.${typeName}.decode_into`;
+  const fixedTypes = [
+    "uint8", "int8", "bool", "uint16", "int16", "uint32", "int32", "float32", "uint64", "int64", "float64",
+    "bit", "int", "custom_float", "bitfield", "padding"
+  ];
+  const visited = new Set<string>();
+  const check = (name: string): boolean => {
+    if (visited.has(name)) return true;
+    visited.add(name);
+    for (const field of getTypeFields(schema.types[name]) as any[]) {
+      const where = name === typeName ? `'${field.name}'` : `'${name}.${field.name}'`;
+      if (field.conditional != null || field.optional) {
+        errors.push({ path, message: `decode_into requires fixed-size fields, but ${where} is conditional` });
+        return false;
+      }
+      if (fixedTypes.includes(field.type)) continue;
+      const ref = schema.types[field.type];
+      if (ref && (isEnumType(ref) || isFlagsType(ref))) continue;
+      if (ref && "sequence" in ref) {
+        if (!check(field.type)) return false;
+        continue;
+      }
+      errors.push({ path, message: `decode_into requires fixed-size fields, but ${where} is '${field.type}'` });
+      return false;
+    }
+    return true;
+  };
+  check(typeName);
+}
+
 /**
  * Check if a field type is numeric
  */
@@ -290,6 +533,33 @@ function validateComputedField(
         message: `Computed field with type 'crc32_of' must have type 'uint32', got '${field.type}'`
       });
     }
+  } else if (computed.type === "discriminator_of") {
+    // discriminator_of writes the union variant's tag value
+    if (!isUnsignedIntType(field.type)) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `Computed field with type 'discriminator_of' must have unsigned integer or varlength type (uint8, uint16, uint32, uint64, varlength), got '${field.type}'`
+      });
+    }
+  } else if (computed.type === "crc_of") {
+    // crc_of needs a CRC variant and an unsigned field wide enough to hold it
+    const fieldBits: Record<string, number> = { uint8: 8, uint16: 16, uint32: 32, uint64: 64 };
+    const presetWidths: Record<string, number> = {
+      crc8: 8, crc16_ccitt_false: 16, crc16_xmodem: 16, crc16_kermit: 16, crc16_modbus: 16, crc16_arc: 16,
+      crc32: 32, crc32c: 32, crc32_bzip2: 32, crc64_xz: 64,
+    };
+    const crcWidth = typeof computed.crc === "string" ? presetWidths[computed.crc] : computed.crc?.width;
+    if (crcWidth === undefined) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `Computed field with type 'crc_of' must have a 'crc' preset name or { width, poly, init, refin, refout, xorout } parameters`
+      });
+    } else if (!(field.type in fieldBits) || fieldBits[field.type] < crcWidth) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `Computed field with type 'crc_of' needs an unsigned integer type of at least ${crcWidth} bits, got '${field.type}'`
+      });
+    }
   } else if (computed.type === "position_of") {
     // position_of requires unsigned integer type (to hold byte positions)
     if (!isUnsignedIntType(field.type)) {
@@ -315,6 +585,37 @@ function validateComputedField(
     }
     // Skip further validation for sum_of_sizes (parent references are validated at runtime)
     return;
+  } else if (computed.type === "xor_of" || computed.type === "complement_of") {
+    // Transforms of sibling fixed-width integers; decode re-derives the value from the same siblings
+    const fixedUnsigned = ["uint8", "uint16", "uint32", "uint64"];
+    if (!fixedUnsigned.includes(field.type)) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `Computed field with type '${computed.type}' must have unsigned integer type (uint8, uint16, uint32, uint64), got '${field.type}'`
+      });
+    }
+    const sources: unknown[] = computed.type === "xor_of" ? (Array.isArray(computed.targets) ? computed.targets : []) : [computed.target];
+    if (computed.type === "xor_of" && sources.length < 2) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `Computed field with type 'xor_of' must have 'targets' array with at least two elements`
+      });
+    }
+    for (const source of sources) {
+      const sourceField: any = typeof source === "string" ? parentFields.find((f: any) => f.name === source) : undefined;
+      if (!sourceField) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `Computed field '${field.name}' (${computed.type}) source '${source}' must name a sibling field of type '${typeName}'`
+        });
+      } else if (!fixedUnsigned.includes(sourceField.type) || sourceField.computed || sourceField.const !== undefined) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `Computed field '${field.name}' (${computed.type}) source '${source}' must be a plain unsigned integer field, got '${sourceField.type}'`
+        });
+      }
+    }
+    return;
   } else if (computed.type === "sum_of_type_sizes") {
     // sum_of_type_sizes requires unsigned integer type
     if (!isUnsignedIntType(field.type)) {
@@ -475,24 +776,39 @@ function validateComputedField(
           });
         }
       }
-    } else if (computed.type === "crc32_of") {
+    } else if (computed.type === "crc32_of" || computed.type === "crc_of") {
       const targetType = (targetField as any).type;
 
       // Target must be array of uint8 (byte array)
       if (targetType !== "array") {
         errors.push({
           path: `${path} (${field.name})`,
-          message: `Computed field 'crc32_of' target '${targetRef}' must be array, got '${targetType}'`
+          message: `Computed field '${computed.type}' target '${targetRef}' must be array, got '${targetType}'`
         });
       } else {
         const itemType = (targetField as any).items?.type;
         if (itemType !== "uint8") {
           errors.push({
             path: `${path} (${field.name})`,
-            message: `Computed field 'crc32_of' target '${targetRef}' must be array of uint8 (byte array), got array of '${itemType}'`
+            message: `Computed field '${computed.type}' target '${targetRef}' must be array of uint8 (byte array), got array of '${itemType}'`
           });
         }
       }
+    } else if (computed.type === "discriminator_of") {
+      // Target must be a discriminated union whose discriminator is this field
+      const targetAny = targetField as any;
+      const unionDef: any = targetAny.type === "discriminated_union" ? targetAny : schema.types[targetAny.type];
+      if (!unionDef || !Array.isArray(unionDef.variants)) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `Computed field 'discriminator_of' target '${targetRef}' must be a discriminated union`
+        });
+      } else if (unionDef.discriminator?.field !== field.name) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `Computed field 'discriminator_of' target '${targetRef}' must use '${field.name}' as its discriminator field`
+        });
+      }
     } else if (computed.type === "position_of") {
       // Target must be a field name (position where that field starts in the encoded output)
       // No specific type requirements - any field can have its position tracked
@@ -790,6 +1106,79 @@ function validatePositionField(
   // Alignment is validated by Zod schema (must be power of 2)
 }
 
+/**
+ * Validate a discriminated union selected by several earlier fields
+ * (`discriminator: { fields: [...] }`, variants keyed by `values`)
+ */
+function validateMultiFieldDiscriminator(
+  field: any,
+  path: string,
+  schema: BinarySchema,
+  errors: ValidationError[],
+  parentFields?: Field[]
+): void {
+  const disc = field.discriminator;
+  const where = `${path} (${field.name})`;
+
+  if (disc.peek !== undefined || disc.field !== undefined) {
+    errors.push({ path: where, message: "Discriminator 'fields' cannot be combined with 'peek' or 'field'" });
+  }
+  if (!Array.isArray(disc.fields) || disc.fields.length < 2) {
+    errors.push({ path: where, message: "Discriminator 'fields' must list at least two field names (use 'field' for one)" });
+    return;
+  }
+
+  if (parentFields) {
+    const fieldIndex = parentFields.findIndex((f: any) => f.name === field.name);
+    const integerTypes = ["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64"];
+    for (const name of disc.fields as string[]) {
+      const head = name.split('.')[0];
+      const index = parentFields.findIndex((f: any) => f.name === head);
+      if (index === -1) {
+        errors.push({ path: where, message: `Discriminator field '${name}' not found in parent struct` });
+      } else if (index >= fieldIndex) {
+        errors.push({ path: where, message: `Discriminator field '${name}' comes after this union (forward reference not allowed)` });
+      } else if (head === name && !integerTypes.includes((parentFields[index] as any).type)) {
+        errors.push({ path: where, message: `Discriminator field '${name}' must be an integer type, got '${(parentFields[index] as any).type}'` });
+      }
+    }
+  }
+
+  if (!Array.isArray(field.variants) || field.variants.length === 0) {
+    errors.push({ path: where, message: "Discriminated union missing 'variants' property" });
+    return;
+  }
+
+  const seen = new Map<string, number>();
+  field.variants.forEach((variant: any, i: number) => {
+    const variantPath = `${path}.variants[${i}]`;
+    if (!variant.type) {
+      errors.push({ path: variantPath, message: "Variant missing 'type' property" });
+    } else if (!schema.types[variant.type]) {
+      errors.push({ path: variantPath, message: `Variant type '${variant.type}' not found in schema.types` });
+    }
+    if (variant.when !== undefined) {
+      errors.push({ path: variantPath, message: "Variants of a multi-field discriminator use 'values', not 'when'" });
+    }
+    if (variant.values === undefined) {
+      if (i !== field.variants.length - 1) {
+        errors.push({ path: variantPath, message: "Fallback variant (no 'values') can only be in the last position" });
+      }
+      return;
+    }
+    if (!Array.isArray(variant.values) || variant.values.length !== disc.fields.length || !variant.values.every((v: any) => Number.isInteger(v))) {
+      errors.push({ path: variantPath, message: `Variant 'values' must list ${disc.fields.length} integers, one per discriminator field` });
+      return;
+    }
+    const key = variant.values.join(",");
+    if (seen.has(key)) {
+      errors.push({ path: variantPath, message: `Values (${variant.values.join(", ")}) already select variants[${seen.get(key)}]` });
+    } else {
+      seen.set(key, i);
+    }
+  });
+}
+
 /**
  * Validate a discriminated union
  */
@@ -808,6 +1197,12 @@ function validateDiscriminatedUnion(
   const disc = field.discriminator;
   const hasPeek = disc.peek !== undefined;
   const hasField = disc.field !== undefined;
+  const hasFields = disc.fields !== undefined;
+
+  if (hasFields) {
+    validateMultiFieldDiscriminator(field, path, schema, errors, parentFields);
+    return;
+  }
 
   // Must have exactly one of peek or field
   if (!hasPeek && !hasField) {
@@ -880,12 +1275,22 @@ function validateDiscriminatedUnion(
           message: `Discriminator field '${fieldName}' comes after this union (forward reference not allowed)`
         });
       } else {
-        // Verify the field is a bitfield and the sub-field exists
+        // Verify the field is a bitfield or a header struct and the sub-field exists
         const referencedField = parentFields[referencedFieldIndex] as any;
-        if (referencedField.type !== 'bitfield') {
+        const headerType = schema.types[referencedField.type] as any;
+        if (headerType && Array.isArray(headerType.sequence)) {
+          // Shared common header: the union body is selected by a header field
+          if (!headerType.sequence.some((f: any) => f.name === subFieldName)) {
+            const availableFields = headerType.sequence.map((f: any) => f.name).join(', ');
+            errors.push({
+              path: `${path} (${field.name})`,
+              message: `Header field '${subFieldName}' not found in '${referencedField.type}' (available: ${availableFields})`
+            });
+          }
+        } else if (referencedField.type !== 'bitfield') {
           errors.push({
             path: `${path} (${field.name})`,
-            message: `Discriminator field '${fieldName}' is not a bitfield (cannot reference sub-field '${subFieldName}')`
+            message: `Discriminator field '${fieldName}' is not a bitfield or struct (cannot reference sub-field '${subFieldName}')`
           });
         } else if (!referencedField.fields || !Array.isArray(referencedField.fields)) {
           errors.push({
@@ -1223,6 +1628,31 @@ function validateBackReference(
     }
   }
 
+  if (field.pointer_flag !== undefined) {
+    if (!/^0x[0-9A-Fa-f]+$/.test(field.pointer_flag)) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `Invalid pointer_flag format '${field.pointer_flag}' (must be hex starting with 0x, e.g., '0x8000')`
+      });
+    } else if (field.offset_mask && /^0x[0-9A-Fa-f]+$/.test(field.offset_mask)) {
+      const flagValue = parseInt(field.pointer_flag, 16);
+      const maskValue = parseInt(field.offset_mask, 16);
+      const storageBits: Record<string, number> = { uint8: 8, uint16: 16, uint32: 32 };
+      if (((flagValue & maskValue) >>> 0) !== 0) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `pointer_flag ${field.pointer_flag} overlaps offset_mask ${field.offset_mask}`
+        });
+      }
+      if (field.storage in storageBits && flagValue >= 2 ** storageBits[field.storage]) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `pointer_flag ${field.pointer_flag} exceeds ${field.storage} storage`
+        });
+      }
+    }
+  }
+
   if (!field.target_type) {
     errors.push({ path: `${path} (${field.name})`, message: "Back reference missing 'target_type' property" });
   } else if (!schema.types[field.target_type]) {
@@ -1366,6 +1796,31 @@ function validateField(
     validateComputedField(fieldAny, path, schema, errors, typeName, parentFields);
   }
 
+  // custom_float layouts must fit the 64-bit bit reader
+  if (fieldType === "custom_float") {
+    const width = (fieldAny.sign === false ? 0 : 1) + (fieldAny.exponent_bits || 0) + (fieldAny.mantissa_bits || 0);
+    if (width > 64) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `custom_float is ${width} bits wide; sign + exponent_bits + mantissa_bits must not exceed 64`,
+      });
+    }
+  }
+
+  // The truncation check only applies once the condition has said the field is there
+  if (fieldAny.when_truncated !== undefined && fieldAny.conditional == null) {
+    errors.push({
+      path: `${path} (${field.name})`,
+      message: "when_truncated requires a conditional field",
+    });
+  }
+
+  // Inline groups are lifted to a named type; validate their fields in place
+  if (fieldType === "group") {
+    validateInlineGroup(fieldAny, path, schema, errors, typeName, rootTypeName);
+    return;
+  }
+
   // Check array fields have items defined
   if (fieldType === "array") {
     if (!("items" in field) || !field.items) {
@@ -1379,6 +1834,99 @@ function validateField(
         message: "Array field missing 'kind' property (fixed|length_prefixed|null_terminated|field_referenced)",
       });
     } else {
+      // Columnar arrays rebuild records by index, so every column needs a fixed width
+      if ((field as any).kind === "columnar") {
+        const itemDef: any = schema.types[(field as any).items.type];
+        const numeric = ["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64", "float32", "float64"];
+        if (!itemDef || !Array.isArray(itemDef.sequence)) {
+          errors.push({
+            path: `${path} (${field.name})`,
+            message: `columnar array items must reference a sequence type, got '${(field as any).items.type}'`,
+          });
+        } else {
+          for (const column of itemDef.sequence) {
+            if (!numeric.includes(column.type) || column.computed || column.const !== undefined || column.conditional) {
+              errors.push({
+                path: `${path} (${field.name})`,
+                message: `columnar array column '${column.name}' of '${(field as any).items.type}' must be a plain numeric field, got '${column.type}'`,
+              });
+            }
+          }
+        }
+      }
+
+      // A bit count only divides back into items if every item has the same bit width
+      if ((field as any).kind === "bit_length_prefixed") {
+        const items: any = (field as any).items;
+        const fixedWidth = ["bit", "int", "uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64"];
+        if (!fixedWidth.includes(items.type) || items.computed || items.const !== undefined) {
+          errors.push({
+            path: `${path} (${field.name})`,
+            message: `bit_length_prefixed array items must be a fixed-width integer or bit field, got '${items.type}'`,
+          });
+        }
+      }
+
+      // The countdown seed is read before the array, and countdown_by must exist on the items
+      if ((field as any).kind === "countdown" && parentFields) {
+        const seedName = (field as any).length_field;
+        const seedIndex = parentFields.findIndex((f: any) => f.name === seedName);
+        const seed: any = parentFields[seedIndex];
+        const ownIndex = parentFields.findIndex((f: any) => f.name === field.name);
+        if (!seed || seedIndex > ownIndex || !["uint8", "uint16", "uint32", "uint64", "varlength"].includes(seed.type) || seed.computed) {
+          errors.push({
+            path: `${path} (${field.name})`,
+            message: `countdown array length_field '${seedName}' must be an earlier plain unsigned integer field`,
+          });
+        }
+        const countdownBy = (field as any).countdown_by;
+        if (countdownBy !== undefined) {
+          const itemDef: any = schema.types[(field as any).items.type];
+          const holders: string[] = itemDef?.sequence ? [(field as any).items.type] : (itemDef?.variants ?? []).map((v: any) => v.type);
+          const hasField = (typeName: string) => ((schema.types[typeName] as any)?.sequence ?? []).some((f: any) => f.name === countdownBy);
+          if (!holders.some(hasField)) {
+            errors.push({
+              path: `${path} (${field.name})`,
+              message: `countdown_by '${countdownBy}' is not a field of '${(field as any).items.type}' or any of its variants`,
+            });
+          }
+        }
+      }
+
+      // Deltas need whole integer items, and a count that doesn't come from
+      // the item bytes: a zero delta would read as a terminator
+      if ((field as any).encoding === "delta_zigzag") {
+        const items: any = (field as any).items;
+        const integers = ["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64"];
+        if (!integers.includes(items.type) || items.computed || items.const !== undefined) {
+          errors.push({
+            path: `${path} (${field.name})`,
+            message: `delta_zigzag array items must be a plain integer type (uint8-uint64, int8-int64), got '${items.type}'`,
+          });
+        }
+        const countKinds = ["fixed", "length_prefixed", "field_referenced", "computed_count", "eof_terminated"];
+        if (!countKinds.includes((field as any).kind)) {
+          errors.push({
+            path: `${path} (${field.name})`,
+            message: `delta_zigzag encoding requires a fixed, length_prefixed, field_referenced, computed_count or eof_terminated array, got '${(field as any).kind}'`,
+          });
+        }
+      }
+
+      // Per-element padding can't be sized up front or rebuilt by column
+      const recordAlignment = (field as any).record_alignment;
+      if (recordAlignment !== undefined && ["columnar", "byte_length_prefixed", "length_prefixed_items", "bit_length_prefixed"].includes((field as any).kind)) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: `record_alignment is not supported on ${(field as any).kind} arrays`,
+        });
+      } else if (recordAlignment !== undefined && (field as any).follow_pointers) {
+        errors.push({
+          path: `${path} (${field.name})`,
+          message: "record_alignment cannot be combined with follow_pointers",
+        });
+      }
+
       // Validate field_referenced arrays
       if ((field as any).kind === "field_referenced") {
         if (!("length_field" in field) || !(field as any).length_field) {
@@ -1527,6 +2075,23 @@ function validateField(
     }
   }
 
+  // Validate string max_length
+  if (fieldType === "string" && (field as any).max_length !== undefined) {
+    const fieldAny = field as any;
+    const prefixCapacity: Record<string, number> = { uint8: 0xff, uint16: 0xffff, uint32: 0xffffffff };
+    if (fieldAny.kind === "fixed") {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: "max_length is only supported on variable-length strings (fixed strings already have 'length')",
+      });
+    } else if (fieldAny.kind === "length_prefixed" && fieldAny.max_length > (prefixCapacity[fieldAny.length_type] ?? Infinity)) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: `max_length ${fieldAny.max_length} exceeds what a ${fieldAny.length_type} length prefix can hold (${prefixCapacity[fieldAny.length_type]})`,
+      });
+    }
+  }
+
   // Check discriminated union fields
   if (fieldType === "discriminated_union") {
     validateDiscriminatedUnion(field as any, path, schema, errors, parentFields);
@@ -1535,6 +2100,13 @@ function validateField(
   // Check back_reference fields
   if (fieldType === "back_reference") {
     validateBackReference(field as any, path, schema, errors);
+    // An inline back_reference field holds only the resolved value
+    if ((field as any).preserve_pointer) {
+      errors.push({
+        path: `${path} (${field.name})`,
+        message: "preserve_pointer is only supported on back_reference type definitions",
+      });
+    }
   }
 
   // Check choice fields
@@ -1589,6 +2161,48 @@ function validateField(
   }
 }
 
+/**
+ * Validate an inline group field: its lifted type name must be free and its
+ * fields are checked as if they were the sequence of that lifted type.
+ */
+function validateInlineGroup(
+  field: any,
+  path: string,
+  schema: BinarySchema,
+  errors: ValidationError[],
+  typeName?: string,
+  rootTypeName?: string
+): void {
+  const fieldPath = `${path} (${field.name})`;
+  if (!Array.isArray(field.sequence) || field.sequence.length === 0) {
+    errors.push({ path: fieldPath, message: "Group field must have a non-empty 'sequence'" });
+    return;
+  }
+  if (field.repeat !== undefined && (!Number.isInteger(field.repeat) || field.repeat < 1)) {
+    errors.push({ path: fieldPath, message: `Group 'repeat' must be a positive integer (got ${field.repeat})` });
+  }
+
+  const liftedName = inlineGroupTypeName(typeName ?? "", field.name);
+  if (typeName && schema.types[liftedName]) {
+    errors.push({
+      path: fieldPath,
+      message: `Inline group would be named '${liftedName}', which already exists in schema.types`,
+    });
+  }
+
+  const seen = new Set<string>();
+  field.sequence.forEach((inner: any, i: number) => {
+    const innerPath = `${path}.sequence[${i}]`;
+    if (inner.name) {
+      if (seen.has(inner.name)) {
+        errors.push({ path: innerPath, message: `Duplicate field name '${inner.name}' in group` });
+      }
+      seen.add(inner.name);
+    }
+    validateField(inner, innerPath, schema, errors, liftedName, field.sequence, rootTypeName);
+  });
+}
+
 /**
  * Validate an element type (array item - no 'name' required)
  */
@@ -1709,7 +2323,7 @@ function extractTypeReference(typeRef: string | undefined): string {
  */
 function validateEnumType(
   typeName: string,
-  typeDef: { type: "enum"; repr: string; variants: Record<string, number> },
+  typeDef: { type: "enum"; repr: string; variants: Record<string, number>; on_unknown_enum?: string },
   errors: ValidationError[]
 ): void {
   const variants = typeDef.variants;
@@ -1758,6 +2372,117 @@ function validateEnumType(
       });
     }
   }
+
+  // keep_raw adds its own Raw variant for unknown values
+  if (typeDef.on_unknown_enum === "keep_raw") {
+    for (const name of Object.keys(variants)) {
+      if (name.toLowerCase() === "raw") {
+        errors.push({
+          path: `types.${typeName}.variants.${name}`,
+          message: `Variant name '${name}' clashes with the Raw variant added by on_unknown_enum: keep_raw`
+        });
+      }
+    }
+  }
+}
+
+/**
+ * Validate a flags type definition
+ */
+function validateFlagsType(
+  typeName: string,
+  typeDef: { type: "flags"; storage: string; flags: Record<string, number> },
+  errors: ValidationError[]
+): void {
+  const flags = typeDef.flags;
+
+  if (Object.keys(flags).length === 0) {
+    errors.push({
+      path: `types.${typeName}`,
+      message: "Flags type must have at least one flag"
+    });
+  }
+
+  for (const name of Object.keys(flags)) {
+    if (!/^[A-Za-z_][A-Za-z0-9_]*$/.test(name)) {
+      errors.push({
+        path: `types.${typeName}.flags.${name}`,
+        message: `Flag name '${name}' is not a valid identifier`
+      });
+    }
+  }
+
+  // Two names for the same bit would make contains/insert ambiguous
+  const positions = Object.values(flags);
+  if (new Set(positions).size !== positions.length) {
+    errors.push({
+      path: `types.${typeName}.flags`,
+      message: "Flag bit positions must be unique"
+    });
+  }
+
+  const width: Record<string, number> = { uint8: 8, uint16: 16, uint32: 32 };
+  const bits = width[typeDef.storage];
+  for (const [name, position] of Object.entries(flags)) {
+    if (position < 0 || position >= bits) {
+      errors.push({
+        path: `types.${typeName}.flags.${name}`,
+        message: `Flag bit ${position} is outside ${typeDef.storage} (0-${bits - 1})`
+      });
+    }
+  }
+}
+
+/**
+ * Validate a TLV option type definition
+ */
+function validateTlvType(
+  typeName: string,
+  typeDef: { type: "tlv"; tag_type: string; length_type: string; options: Array<{ name: string; tag: number; type: string }> },
+  schema: BinarySchema,
+  errors: ValidationError[]
+): void {
+  const options = typeDef.options;
+
+  if (options.length === 0) {
+    errors.push({
+      path: `types.${typeName}`,
+      message: "TLV type must have at least one option"
+    });
+  }
+
+  const integerTypes = ["uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64"];
+  const maxTag = typeDef.tag_type === "uint8" ? 0xFF : 0xFFFF;
+  const names = new Set<string>();
+  const tags = new Set<number>();
+  for (let i = 0; i < options.length; i++) {
+    const option = options[i];
+    const path = `types.${typeName}.options[${i}]`;
+
+    if (!/^[A-Za-z_][A-Za-z0-9_]*$/.test(option.name)) {
+      errors.push({ path, message: `Option name '${option.name}' is not a valid identifier` });
+    }
+    // 'Unknown' is the generated catch-all for unlisted tags
+    if (option.name === "Unknown" || option.name === "unknown") {
+      errors.push({ path, message: `Option name '${option.name}' is reserved for unknown options` });
+    }
+    if (names.has(option.name)) {
+      errors.push({ path, message: `Duplicate option name '${option.name}'` });
+    }
+    names.add(option.name);
+
+    if (tags.has(option.tag)) {
+      errors.push({ path, message: `Duplicate option tag ${option.tag}` });
+    }
+    tags.add(option.tag);
+    if (option.tag > maxTag) {
+      errors.push({ path, message: `Option tag ${option.tag} does not fit ${typeDef.tag_type}` });
+    }
+
+    if (!integerTypes.includes(option.type) && option.type !== "bytes" && !schema.types[option.type]) {
+      errors.push({ path, message: `Option type '${option.type}' is not an integer primitive, 'bytes', or a defined type` });
+    }
+  }
 }
 
 /**
@@ -1787,8 +2512,9 @@ function findCircularDependency(
   visited.add(typeName);
   path.push(typeName);
 
-  // Enum types have no type dependencies
-  if (isEnumType(typeDef)) {
+  // Enum and flags types have no type dependencies; TLV options are
+  // data-selected per element, so like union variants they are weak edges
+  if (isEnumType(typeDef) || isFlagsType(typeDef) || isTlvType(typeDef)) {
     return null;
   }
 
@@ -103,6 +103,33 @@ export async function runTestSuite(suite: TestSuite, summaryMode = false): Promi
     return result;
   }
 
+  // Suites using a Rust-only option expect the generator to refuse the schema
+  if (suite.rejected_by?.includes("typescript")) {
+    let message = "Expected the TypeScript generator to reject this schema, but it generated code";
+    try {
+      generateTypeScript(suite.schema);
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      if (errorMessage.includes("does not support")) {
+        result.passed = suite.test_cases?.length ?? 0;
+        return result;
+      }
+      message = `Code generation failed with an unexpected error: ${errorMessage}`;
+    }
+    result.phase = "generation";
+    result.failed = suite.test_cases?.length ?? 0;
+    for (const testCase of suite.test_cases ?? []) {
+      result.failures.push({
+        description: testCase.description,
+        type: "generation",
+        expected: [],
+        actual: [],
+        message,
+      });
+    }
+    return result;
+  }
+
   // Generate TypeScript code
   let generatedCode: string;
   try {
@@ -0,0 +1,76 @@
+// ABOUTME: Tests for delta_zigzag encoded integer arrays
+// ABOUTME: Rust stores item differences as varints; the other generators must refuse the schema
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for delta_zigzag arrays
+ *
+ * The first item is written as a plain uint16, every later one as the zigzag
+ * LEB128 varint of its difference from the previous item. Only the Rust
+ * generator implements the encoding.
+ */
+export const deltaZigzagTestSuite = defineTestSuite({
+  name: "delta_zigzag",
+  description: "Length-prefixed uint16 array stored as differences",
+
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Samples": {
+        sequence: [
+          {
+            name: "values",
+            type: "array",
+            kind: "length_prefixed",
+            length_type: "uint8",
+            items: { type: "uint16" },
+            encoding: "delta_zigzag",
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "Samples",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Small deltas of either sign take one byte",
+      value: { values: [1000, 1001, 1003, 1000] },
+      bytes: [
+        0x04,       // length
+        0x03, 0xE8, // 1000 (first item, big endian)
+        0x02,       // +1 -> zigzag 2
+        0x04,       // +2 -> zigzag 4
+        0x05,       // -3 -> zigzag 5
+      ],
+    },
+    {
+      description: "Delta of 100 needs a second varint byte",
+      value: { values: [0, 100] },
+      bytes: [
+        0x02,       // length
+        0x00, 0x00, // 0
+        0xC8, 0x01, // +100 -> zigzag 200
+      ],
+    },
+    {
+      description: "Single item is written in full",
+      value: { values: [0x1234] },
+      bytes: [0x01, 0x12, 0x34],
+    },
+    {
+      description: "Empty array",
+      value: { values: [] },
+      bytes: [0x00],
+    },
+    {
+      description: "Decoding rejects a delta that takes a uint16 below zero",
+      bytes: [0x02, 0x00, 0x00, 0x01],
+      should_error: true,
+    },
+  ]
+});
@@ -87,6 +87,12 @@ function expectGenerate(argv: string[], expected: Partial<GenerateCommand>): voi
   if (expected.debug !== undefined) {
     assert(command.debug === expected.debug, `Expected debug=${expected.debug} but got ${command.debug}`);
   }
+  if (expected.modules !== undefined) {
+    assert(command.modules === expected.modules, `Expected modules=${expected.modules} but got ${command.modules}`);
+  }
+  if (expected.roundtripTests !== undefined) {
+    assert(command.roundtripTests === expected.roundtripTests, `Expected roundtripTests=${expected.roundtripTests} but got ${command.roundtripTests}`);
+  }
 }
 
 function expectValidate(argv: string[], expected: Partial<ValidateCommand>): void {
@@ -182,6 +188,16 @@ export function runCommandParserTests(): { passed: number; failed: number; check
     { schemaPath: "schema.json", outputDir: "./gen", language: "ts", debug: false },
   );
 
+  expectGenerate(
+    ["generate", "--schema", "schema.json", "--out", "./gen", "--language", "rust", "--modules"],
+    { schemaPath: "schema.json", outputDir: "./gen", language: "rust", modules: true },
+  );
+
+  expectGenerate(
+    ["generate", "--schema", "schema.json", "--out", "./gen", "--language", "rust", "--roundtrip-tests"],
+    { schemaPath: "schema.json", outputDir: "./gen", language: "rust", modules: false, roundtripTests: true },
+  );
+
   expectHelp(["help"], undefined);
   expectHelp(["help", "docs"], ["docs"]);
   expectHelp(["docs", "--help"], ["docs"]);
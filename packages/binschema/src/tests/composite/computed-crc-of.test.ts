@@ -0,0 +1,101 @@
+// ABOUTME: Tests for crc_of computed fields with a declared CRC variant
+// ABOUTME: Rust computes the checksum; the other generators must refuse the schema
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test CRC-16/MODBUS computed over a fixed byte array
+ *
+ * Modbus RTU frames end in this CRC, stored little-endian. Only the Rust
+ * generator implements crc_of.
+ */
+export const computedCrcOfModbusTestSuite = defineTestSuite({
+  name: "computed_crc_of_modbus",
+  description: "crc_of with the crc16_modbus preset",
+
+  schema: {
+    config: {
+      endianness: "little_endian",
+    },
+    types: {
+      "Frame": {
+        sequence: [
+          { name: "pdu", type: "array", kind: "fixed", length: 4, items: { type: "uint8" } },
+          {
+            name: "crc",
+            type: "uint16",
+            computed: { type: "crc_of", target: "pdu", crc: "crc16_modbus" },
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "Frame",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "CRC-16/MODBUS of [0x01, 0x02, 0x03, 0x04]",
+      value: { pdu: [0x01, 0x02, 0x03, 0x04] },
+      decoded_value: { pdu: [0x01, 0x02, 0x03, 0x04], crc: 0x2BA1 },
+      bytes: [
+        0x01, 0x02, 0x03, 0x04, // pdu
+        0xA1, 0x2B,             // CRC (little-endian)
+      ],
+    },
+    {
+      description: "CRC-16/MODBUS of four zero bytes",
+      value: { pdu: [0x00, 0x00, 0x00, 0x00] },
+      decoded_value: { pdu: [0x00, 0x00, 0x00, 0x00], crc: 0x2400 },
+      bytes: [0x00, 0x00, 0x00, 0x00, 0x00, 0x24],
+    },
+  ]
+});
+
+/**
+ * Test an explicitly parameterized CRC (CRC-16/XMODEM spelled out)
+ */
+export const computedCrcOfParamsTestSuite = defineTestSuite({
+  name: "computed_crc_of_params",
+  description: "crc_of with explicit width, poly, init, reflection and xorout",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "Block": {
+        sequence: [
+          { name: "data", type: "array", kind: "fixed", length: 4, items: { type: "uint8" } },
+          {
+            name: "crc",
+            type: "uint16",
+            computed: {
+              type: "crc_of",
+              target: "data",
+              crc: { width: 16, poly: 0x1021, init: 0x0000, refin: false, refout: false, xorout: 0x0000 },
+            },
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "Block",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "CRC-16/XMODEM of [0x01, 0x02, 0x03, 0x04]",
+      value: { data: [0x01, 0x02, 0x03, 0x04] },
+      decoded_value: { data: [0x01, 0x02, 0x03, 0x04], crc: 0x0D03 },
+      bytes: [
+        0x01, 0x02, 0x03, 0x04, // data
+        0x0D, 0x03,             // CRC (big-endian)
+      ],
+    },
+  ]
+});
@@ -0,0 +1,61 @@
+// ABOUTME: Tests for the xor_of and complement_of computed transforms
+// ABOUTME: Rust computes and checks the fields; the other generators must refuse the schema
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test xor_of and complement_of together
+ *
+ * masked is key XOR value, and inverted is the bitwise complement of key
+ * truncated to the field's width, as in length/inverted-length pairs.
+ * Decoding checks both against their sources.
+ */
+export const computedTransformsTestSuite = defineTestSuite({
+  name: "computed_transforms",
+  description: "xor_of and complement_of computed from sibling fields",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "GuardedHeader": {
+        sequence: [
+          { name: "key", type: "uint16" },
+          { name: "value", type: "uint16" },
+          { name: "masked", type: "uint16", computed: { type: "xor_of", targets: ["key", "value"] } },
+          { name: "inverted", type: "uint16", computed: { type: "complement_of", target: "key" } },
+        ]
+      }
+    }
+  },
+
+  test_type: "GuardedHeader",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "key=0x1234, value=0x00FF",
+      value: { key: 0x1234, value: 0x00FF },
+      decoded_value: { key: 0x1234, value: 0x00FF, masked: 0x12CB, inverted: 0xEDCB },
+      bytes: [
+        0x12, 0x34, // key
+        0x00, 0xFF, // value
+        0x12, 0xCB, // masked = key ^ value
+        0xED, 0xCB, // inverted = !key
+      ],
+    },
+    {
+      description: "All-zero sources",
+      value: { key: 0, value: 0 },
+      decoded_value: { key: 0, value: 0, masked: 0, inverted: 0xFFFF },
+      bytes: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF],
+    },
+    {
+      description: "Decoding rejects an inverted field that doesn't match",
+      bytes: [0x12, 0x34, 0x00, 0xFF, 0x12, 0xCB, 0x00, 0x00],
+      should_error: true,
+    },
+  ]
+});
@@ -0,0 +1,103 @@
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for when_truncated: absent
+ *
+ * An older sender may set the flag but end the message before the field.
+ * Decoding then treats the field as absent instead of failing. Only the Rust
+ * generator implements when_truncated.
+ */
+export const whenTruncatedAbsentTestSuite = defineTestSuite({
+  name: "when_truncated_absent",
+  description: "Flagged field cut off by the end of the message decodes as absent",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "ExtendedMessage": {
+        sequence: [
+          { name: "flags", type: "uint8" },
+          {
+            name: "timestamp",
+            type: "uint32",
+            conditional: "flags & 0x01",
+            when_truncated: "absent",
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "ExtendedMessage",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Flag set and timestamp present",
+      value: { flags: 0x01, timestamp: 1234567890 },
+      bytes: [
+        0x01,                   // flags
+        0x49, 0x96, 0x02, 0xD2, // timestamp = 1234567890
+      ],
+    },
+    {
+      description: "Flag set but message ends after flags",
+      value: { flags: 0x01 },
+      bytes: [0x01],
+    },
+    {
+      description: "Flag clear",
+      value: { flags: 0x00 },
+      bytes: [0x00],
+    },
+  ]
+});
+
+/**
+ * Test suite for when_truncated: error
+ *
+ * The same message, but a cut-off field fails before any of it is read.
+ */
+export const whenTruncatedErrorTestSuite = defineTestSuite({
+  name: "when_truncated_error",
+  description: "Flagged field cut off by the end of the message fails to decode",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "ExtendedMessage": {
+        sequence: [
+          { name: "flags", type: "uint8" },
+          {
+            name: "timestamp",
+            type: "uint32",
+            conditional: "flags & 0x01",
+            when_truncated: "error",
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "ExtendedMessage",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Flag set and timestamp present",
+      value: { flags: 0x01, timestamp: 1234567890 },
+      bytes: [0x01, 0x49, 0x96, 0x02, 0xD2],
+    },
+    {
+      description: "Flag set but only part of the timestamp follows",
+      bytes: [0x01, 0x49, 0x96],
+      should_error: true,
+    },
+  ]
+});
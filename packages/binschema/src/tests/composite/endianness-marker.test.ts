@@ -0,0 +1,52 @@
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for endianness_marker
+ *
+ * The marker only adds decode_autodetect_endianness(), which also accepts the
+ * byte-swapped form; encode and decode use the schema endianness as usual.
+ * Only the Rust generator implements it, so the others must refuse the
+ * schema rather than drop the method.
+ */
+export const endiannessMarkerTestSuite = defineTestSuite({
+  name: "endianness_marker",
+  description: "Record whose magic number identifies its byte order",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "Record": {
+        sequence: [
+          { name: "magic", type: "uint16", const: 0xFEFF },
+          { name: "version", type: "uint8" },
+          { name: "count", type: "uint32" },
+        ],
+        endianness_marker: "magic",
+      }
+    }
+  },
+
+  test_type: "Record",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Big-endian record",
+      value: { version: 1, count: 0x01020304 },
+      decoded_value: { magic: 0xFEFF, version: 1, count: 0x01020304 },
+      bytes: [
+        0xFE, 0xFF,             // magic
+        0x01,                   // version
+        0x01, 0x02, 0x03, 0x04, // count
+      ],
+    },
+    {
+      description: "Byte-swapped magic is rejected by plain decode",
+      bytes: [0xFF, 0xFE, 0x01, 0x04, 0x03, 0x02, 0x01],
+      should_error: true,
+    },
+  ]
+});
@@ -0,0 +1,50 @@
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for on_unknown_enum: "keep_raw"
+ *
+ * Values outside the declared variants decode to a Raw variant holding the
+ * value and encode back unchanged, so newer senders' codes survive a
+ * round trip. Only the Rust generator implements keep_raw; the others must
+ * refuse the schema rather than fail on those values.
+ */
+export const enumKeepRawTestSuite = defineTestSuite({
+  name: "enum_keep_raw",
+  description: "uint8 enum that keeps unknown values",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "Status": {
+        type: "enum",
+        repr: "uint8",
+        variants: { "Ok": 0, "Retry": 1, "Failed": 2 },
+        on_unknown_enum: "keep_raw",
+      }
+    }
+  },
+
+  test_type: "Status",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Known variant Retry (1)",
+      value: 1,
+      bytes: [0x01],
+    },
+    {
+      description: "Unknown value 7 round-trips as Raw",
+      value: 7,
+      bytes: [0x07],
+    },
+    {
+      description: "Unknown value 255 round-trips as Raw",
+      value: 255,
+      bytes: [0xFF],
+    },
+  ]
+});
@@ -0,0 +1,98 @@
+// ABOUTME: Tests for protocol length limits checked on encode
+// ABOUTME: Strings with max_length and arrays with max_encoded_length fail instead of writing out-of-spec bytes
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test max_length on a length-prefixed string
+ *
+ * The limit counts encoded bytes, not the prefix. A value at the limit
+ * encodes normally; one byte over fails.
+ */
+export const stringMaxLengthTestSuite = defineTestSuite({
+  name: "string_max_length",
+  description: "Length-prefixed string limited to 5 bytes",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "Tag": {
+        sequence: [
+          { name: "label", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii", max_length: 5 },
+        ]
+      }
+    }
+  },
+
+  test_type: "Tag",
+
+  test_cases: [
+    {
+      description: "Label at the limit",
+      value: { label: "abcde" },
+      bytes: [0x05, 0x61, 0x62, 0x63, 0x64, 0x65],
+    },
+    {
+      description: "Empty label",
+      value: { label: "" },
+      bytes: [0x00],
+    },
+    {
+      description: "Label one byte over the limit",
+      value: { label: "abcdef" },
+      should_error_on_encode: true,
+    },
+  ]
+});
+
+/**
+ * Test max_encoded_length on a length-prefixed array
+ *
+ * The limit covers the whole encoded array, prefix included: two uint16
+ * items take 1 + 4 = 5 bytes, three take 7.
+ */
+export const arrayMaxEncodedLengthTestSuite = defineTestSuite({
+  name: "array_max_encoded_length",
+  description: "Length-prefixed uint16 array limited to 5 encoded bytes",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "Route": {
+        sequence: [
+          {
+            name: "hops",
+            type: "array",
+            kind: "length_prefixed",
+            length_type: "uint8",
+            items: { type: "uint16" },
+            max_encoded_length: 5,
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "Route",
+
+  test_cases: [
+    {
+      description: "Two hops fill the limit",
+      value: { hops: [0x0102, 0x0304] },
+      bytes: [
+        0x02,       // count
+        0x01, 0x02, // hops[0]
+        0x03, 0x04, // hops[1]
+      ],
+    },
+    {
+      description: "Three hops exceed it",
+      value: { hops: [1, 2, 3] },
+      should_error_on_encode: true,
+    },
+  ]
+});
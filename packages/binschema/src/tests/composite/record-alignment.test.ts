@@ -0,0 +1,55 @@
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for record_alignment
+ *
+ * Each array element is padded with zero bytes to the next 4-byte boundary
+ * from the start of the message, including the last one. Only the Rust
+ * generator implements record_alignment; the others must refuse the schema
+ * rather than leave the padding out.
+ */
+export const recordAlignmentTestSuite = defineTestSuite({
+  name: "record_alignment",
+  description: "uint16 records padded to 4-byte boundaries",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "RecordTable": {
+        sequence: [
+          {
+            name: "records",
+            type: "array",
+            kind: "length_prefixed",
+            length_type: "uint8",
+            items: { type: "uint16" },
+            record_alignment: 4,
+          },
+        ]
+      }
+    }
+  },
+
+  test_type: "RecordTable",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Two records after a one-byte count",
+      value: { records: [0x0102, 0x0304] },
+      bytes: [
+        0x02,                   // count
+        0x01, 0x02, 0x00,       // records[0] ends at 3, padded to 4
+        0x03, 0x04, 0x00, 0x00, // records[1] ends at 6, padded to 8
+      ],
+    },
+    {
+      description: "No records, no padding",
+      value: { records: [] },
+      bytes: [0x00],
+    },
+  ]
+});
@@ -0,0 +1,51 @@
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for signature_field
+ *
+ * The signature is encoded in place like any other field; signature_field
+ * only adds signed_bytes(), which encodes the message with the signature
+ * zeroed. Only the Rust generator implements it, so the others must refuse
+ * the schema rather than drop the method.
+ */
+export const signatureFieldTestSuite = defineTestSuite({
+  name: "signature_field",
+  description: "Message with a trailing signature field",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "SignedMessage": {
+        sequence: [
+          { name: "version", type: "uint8" },
+          { name: "body", type: "array", kind: "length_prefixed", length_type: "uint16", items: { type: "uint8" } },
+          { name: "signature", type: "array", kind: "fixed", length: 4, items: { type: "uint8" } },
+        ],
+        signature_field: "signature",
+      }
+    }
+  },
+
+  test_type: "SignedMessage",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "Signature follows the body",
+      value: { version: 1, body: [0xAA, 0xBB], signature: [0xDE, 0xAD, 0xBE, 0xEF] },
+      bytes: [
+        0x01,                   // version
+        0x00, 0x02, 0xAA, 0xBB, // body (uint16 length prefix)
+        0xDE, 0xAD, 0xBE, 0xEF, // signature
+      ],
+    },
+    {
+      description: "Empty body",
+      value: { version: 2, body: [], signature: [0, 0, 0, 0] },
+      bytes: [0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    },
+  ]
+});
@@ -0,0 +1,51 @@
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test suite for wire_order
+ *
+ * Fields are declared in struct order but encoded in wire_order, here with
+ * the one-byte tag and flags ahead of the id. Only the Rust generator
+ * implements wire_order; the others must refuse the schema rather than
+ * encode in declaration order.
+ */
+export const wireOrderTestSuite = defineTestSuite({
+  name: "wire_order",
+  description: "Fields encoded in wire_order rather than declaration order",
+
+  schema: {
+    config: {
+      endianness: "big_endian",
+    },
+    types: {
+      "TaggedRecord": {
+        sequence: [
+          { name: "id", type: "uint16" },
+          { name: "kind", type: "uint8" },
+          { name: "flags", type: "uint8" },
+        ],
+        wire_order: ["kind", "flags", "id"],
+      }
+    }
+  },
+
+  test_type: "TaggedRecord",
+
+  rejected_by: ["typescript", "go", "python"],
+
+  test_cases: [
+    {
+      description: "kind and flags precede id",
+      value: { id: 0x1234, kind: 7, flags: 0x80 },
+      bytes: [
+        0x07,       // kind
+        0x80,       // flags
+        0x12, 0x34, // id (big endian)
+      ],
+    },
+    {
+      description: "Zero id",
+      value: { id: 0, kind: 1, flags: 0 },
+      bytes: [0x01, 0x00, 0x00, 0x00],
+    },
+  ]
+});
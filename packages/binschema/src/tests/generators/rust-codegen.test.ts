@@ -1,7 +1,7 @@
 // ABOUTME: Tests for the Rust code generator
 // ABOUTME: Verifies that generated Rust code has correct syntax and structure
 
-import { generateRust } from "../../generators/rust.js";
+import { generateRust, generateRustModules } from "../../generators/rust.js";
 import type { BinarySchema } from "../../schema/binary-schema.js";
 
 interface TestCheck {
@@ -34,9 +34,12 @@ export function runRustGeneratorTests(): { passed: number; failed: number; check
     const hasStruct = result.code.includes("pub struct SimpleStruct");
     const hasEncodeMethod = result.code.includes("pub fn encode(&self)");
     const hasDecodeMethod = result.code.includes("pub fn decode(bytes: &[u8])");
+    // decode is a thin wrapper so nested types can share one decoder
+    const hasSharedDecoder = result.code.includes("        Self::decode_with_decoder(&mut decoder)\n") &&
+                             result.code.includes("pub fn decode_with_decoder(decoder: &mut BitStreamDecoder) -> Result<Self> {");
     const hasFields = result.code.includes("pub id: u8") && result.code.includes("pub value: u32");
 
-    if (hasStruct && hasEncodeMethod && hasDecodeMethod && hasFields) {
+    if (hasStruct && hasEncodeMethod && hasDecodeMethod && hasSharedDecoder && hasFields) {
       passed++;
       checks.push({ description: "Simple struct with primitives", passed: true });
     } else {
@@ -44,7 +47,7 @@ export function runRustGeneratorTests(): { passed: number; failed: number; check
       checks.push({
         description: "Simple struct with primitives",
         passed: false,
-        message: `Missing expected content: struct=${hasStruct}, encode=${hasEncodeMethod}, decode=${hasDecodeMethod}, fields=${hasFields}`
+        message: `Missing expected content: struct=${hasStruct}, encode=${hasEncodeMethod}, decode=${hasDecodeMethod}, decode_with_decoder=${hasSharedDecoder}, fields=${hasFields}`
       });
     }
   } catch (error: any) {
@@ -120,11 +123,11 @@ export function runRustGeneratorTests(): { passed: number; failed: number; check
 
     const result = generateRust(schema, "Flags");
 
-    const hasBitWrite = result.code.includes("encoder.write_bits(");
+    const hasBitWrite = result.code.includes("encoder.try_write_bits(");
     const hasBitRead = result.code.includes("decoder.read_bits(");
-    const hasCorrectSizes = result.code.includes("write_bits(self.reserved as u64, 4)") &&
-                            result.code.includes("write_bits(self.enabled as u64, 1)") &&
-                            result.code.includes("write_bits(self.mode as u64, 3)");
+    const hasCorrectSizes = result.code.includes("try_write_bits(self.reserved as u64, 4)?;") &&
+                            result.code.includes("try_write_bits(self.enabled as u64, 1)?;") &&
+                            result.code.includes("try_write_bits(self.mode as u64, 3)?;");
 
     if (hasBitWrite && hasBitRead && hasCorrectSizes) {
       passed++;
@@ -641,5 +644,3089 @@ export function runRustGeneratorTests(): { passed: number; failed: number; check
     });
   }
 
+  // Test: types with optional fields get an apply_patch method that only
+  // overwrites fields present in the patch.
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        DeviceConfig: {
+          sequence: [
+            { name: "version", type: "uint8" },
+            { name: "baud_rate", type: "optional", value_type: "uint32" },
+            { name: "timeout_ms", type: "optional", value_type: "uint16" },
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "DeviceConfig");
+    const required = [
+      "pub fn apply_patch(&mut self, patch: &Self)",
+      "if patch.baud_rate.is_some() {",
+      "self.baud_rate = patch.baud_rate.clone();",
+      "if patch.timeout_ms.is_some() {",
+      "self.timeout_ms = patch.timeout_ms.clone();",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    // Non-optional fields must never be overwritten by a patch.
+    const patchesVersion = result.code.includes("self.version = patch.version");
+    if (missing.length === 0 && !patchesVersion) {
+      passed++;
+      checks.push({ description: "apply_patch overwrites only present optional fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "apply_patch overwrites only present optional fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; patches non-optional field: ${patchesVersion}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "apply_patch overwrites only present optional fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: byte_order "pdp" routes 32-bit fields through the middle-endian helpers
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        VintageHeader: {
+          sequence: [
+            { name: "block_count", type: "uint32", byte_order: "pdp" },
+            { name: "offset", type: "int32", byte_order: "pdp" },
+            { name: "checksum", type: "uint32" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "VintageHeader");
+    const required = [
+      "encoder.write_uint32_pdp(self.block_count);",
+      "encoder.write_uint32_pdp(self.offset as u32);",
+      "let block_count = decoder.read_uint32_pdp()?;",
+      "let offset = decoder.read_uint32_pdp()? as i32;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    // Fields without the override keep the regular endianness path.
+    const checksumUsesPdp = result.code.includes("write_uint32_pdp(self.checksum)");
+    if (missing.length === 0 && !checksumUsesPdp) {
+      passed++;
+      checks.push({ description: "PDP byte order for 32-bit fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "PDP byte order for 32-bit fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; checksum uses pdp: ${checksumUsesPdp}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "PDP byte order for 32-bit fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: union body selected by an enum field inside a shared common header
+  // (DNS resource record shape: fixed RR header, then type-specific rdata)
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        RecordType: {
+          type: "enum",
+          repr: "uint16",
+          variants: { A: 1, MX: 15 },
+        } as any,
+        RrHeader: {
+          sequence: [
+            { name: "rtype", type: "RecordType" },
+            { name: "class", type: "uint16" },
+            { name: "ttl", type: "uint32" },
+          ]
+        },
+        ARdata: { sequence: [{ name: "address", type: "uint32" }] },
+        MxRdata: { sequence: [{ name: "preference", type: "uint16" }] },
+        ResourceRecord: {
+          sequence: [
+            { name: "header", type: "RrHeader" },
+            {
+              name: "rdata",
+              type: "discriminated_union",
+              discriminator: { field: "header.rtype" },
+              variants: [
+                { when: "value == 1", type: "ARdata" },
+                { when: "value == 15", type: "MxRdata" },
+              ]
+            } as any,
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "ResourceRecord");
+    const required = [
+      "let rdata = if header.rtype == RecordType::A {",
+      "} else if header.rtype == RecordType::MX {",
+      "unknown discriminator value",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "Union body discriminated by shared header field", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Union body discriminated by shared header field",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Union body discriminated by shared header field",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: flags type generates a bitflags-style newtype (DNS header flags word)
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        DnsFlags: {
+          type: "flags",
+          storage: "uint16",
+          flags: { QR: 15, AA: 10, TC: 9, RD: 8, RA: 7 },
+        } as any,
+        DnsHeader: {
+          sequence: [
+            { name: "id", type: "uint16" },
+            { name: "flags", type: "DnsFlags" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "DnsHeader");
+    const required = [
+      "pub struct DnsFlags(u16);",
+      "pub const QR: Self = Self(1 << 15);",
+      "pub const RA: Self = Self(1 << 7);",
+      "pub const fn contains(&self, other: Self) -> bool {",
+      "pub fn insert(&mut self, other: Self) {",
+      "pub fn remove(&mut self, other: Self) {",
+      "encoder.write_uint16(self.0, Endianness::BigEndian);",
+      "pub flags: DnsFlags,",
+      "self.flags.encode_into(encoder)?;",
+      "let flags = DnsFlags::decode_with_decoder(decoder)?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "Flags type generates bitflags-style newtype", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Flags type generates bitflags-style newtype",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Flags type generates bitflags-style newtype",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: decode_bounded limits decoding to a transport-supplied length
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Datagram: {
+          sequence: [
+            { name: "kind", type: "uint8" },
+            { name: "payload", type: "array", kind: "eof_terminated", items: { type: "uint8" } } as any,
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "Datagram");
+    const hasMethod = result.code.includes("pub fn decode_bounded(bytes: &[u8], len: usize) -> Result<Self> {");
+    const usesBoundedDecoder = result.code.includes("BitStreamDecoder::new_bounded(bytes, len, BitOrder::MsbFirst)?");
+    if (hasMethod && usesBoundedDecoder) {
+      passed++;
+      checks.push({ description: "decode_bounded generation", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "decode_bounded generation",
+        passed: false,
+        message: `Missing: method=${hasMethod}, bounded decoder=${usesBoundedDecoder}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "decode_bounded generation",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: offsets relative to a named anchor (section start) instead of message start
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        SectionHeader: {
+          sequence: [
+            { name: "tag", type: "uint16" },
+            { name: "flags", type: "uint16" },
+          ]
+        },
+        Glyph: {
+          sequence: [{ name: "advance", type: "uint16" }]
+        },
+        FontResource: {
+          sequence: [
+            { name: "version", type: "uint32" },
+            { name: "section", type: "SectionHeader" },
+            {
+              name: "glyph_offset",
+              type: "uint16",
+              computed: { type: "position_of", target: "glyph", relative_to: "section" }
+            } as any,
+            { name: "glyph", type: "Glyph" },
+          ],
+          instances: [
+            { name: "glyph_at_offset", type: "Glyph", position: "glyph_offset", relative_to: "section" } as any,
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "FontResource");
+    const required = [
+      // Encoder records the anchor and stores target - anchor
+      "let section_anchor = encoder.byte_offset();",
+      "let glyph_offset_computed = glyph_offset_computed - section_anchor;",
+      // Decoder records the anchor and adds it back before seeking
+      "let section_anchor = decoder.position();",
+      "let position = section_anchor.saturating_add(position);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const anchorBeforeSeek = result.code.indexOf("let position = section_anchor.saturating_add(position);") <
+      result.code.indexOf("decoder.seek(position)?;");
+    if (missing.length === 0 && anchorBeforeSeek) {
+      passed++;
+      checks.push({ description: "Anchor-relative position offsets", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Anchor-relative position offsets",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; anchor applied before seek: ${anchorBeforeSeek}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Anchor-relative position offsets",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: parse returns the decoded value and the unconsumed tail
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Record: {
+          sequence: [
+            { name: "id", type: "uint16" },
+            { name: "value", type: "uint8" },
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "Record");
+    const required = [
+      "pub fn parse(input: &[u8]) -> Result<(Self, &[u8])> {",
+      "let value = Self::decode_with_decoder(&mut decoder)?;",
+      "Ok((value, decoder.remaining_slice()))",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "parse returning leftover slice", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "parse returning leftover slice",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "parse returning leftover slice",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: length_of is validated on decode against the target's decoded span
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Payload: {
+          sequence: [
+            { name: "kind", type: "uint8" },
+            { name: "label", type: "string", kind: "null_terminated", encoding: "utf8" } as any,
+          ]
+        },
+        Frame: {
+          sequence: [
+            { name: "payload_len", type: "uint16", computed: { type: "length_of", target: "payload" } } as any,
+            { name: "payload", type: "Payload" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Frame");
+    const required = [
+      "let payload_decode_start = decoder.position();",
+      "let payload_decoded_len = decoder.position() - payload_decode_start;",
+      "if payload_len as i64 != payload_decoded_len as i64 {",
+      "length field 'payload_len' is {} but 'payload' decoded to {} bytes",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    // The check must run after the payload is decoded, not before
+    const decodeAt = result.code.indexOf("let payload = Payload::decode_with_decoder(decoder)?;");
+    const ordered = decodeAt !== -1 && decodeAt < result.code.indexOf("let payload_decoded_len");
+    if (missing.length === 0 && ordered) {
+      passed++;
+      checks.push({ description: "Decode-side length_of validation", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Decode-side length_of validation",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; check after decode: ${ordered}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Decode-side length_of validation",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: wire_order controls encode/decode order while the struct keeps declaration order
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Entry: {
+          sequence: [
+            { name: "id", type: "uint8" },
+            { name: "size", type: "uint16" },
+          ],
+          wire_order: ["size", "id"],
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Entry");
+    const code = result.code;
+    const declaredInOrder = code.indexOf("pub id: u8,") !== -1 && code.indexOf("pub id: u8,") < code.indexOf("pub size: u16,");
+    // Byte-aligned fields may use the fast-path writers, so match on the value only
+    const sizeWrite = code.search(/encoder\.write_\w+\(self\.size\b/);
+    const idWrite = code.search(/encoder\.write_\w+\(self\.id\b/);
+    const encodedInWireOrder = sizeWrite !== -1 && idWrite !== -1 && sizeWrite < idWrite;
+    const decodedInWireOrder = code.indexOf("let size = decoder.") !== -1
+      && code.indexOf("let size = decoder.") < code.indexOf("let id = decoder.");
+    if (declaredInOrder && encodedInWireOrder && decodedInWireOrder) {
+      passed++;
+      checks.push({ description: "wire_order sequencing", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "wire_order sequencing",
+        passed: false,
+        message: `declared in order: ${declaredInOrder}; encoded in wire order: ${encodedInWireOrder}; decoded in wire order: ${decodedInWireOrder}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "wire_order sequencing",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: signature_field generates signed_bytes() with the signature zeroed
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        SignedMessage: {
+          sequence: [
+            { name: "version", type: "uint8" },
+            { name: "body", type: "array", kind: "length_prefixed", length_type: "uint16", items: { type: "uint8" } },
+            { name: "signature", type: "array", kind: "fixed", length: 64, items: { type: "uint8" } },
+          ],
+          signature_field: "signature",
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "SignedMessage");
+    const required = [
+      "pub fn signed_bytes(&self) -> Result<Vec<u8>> {",
+      "let mut unsigned = self.clone();",
+      "unsigned.signature.fill(0);",
+      "unsigned.encode()",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "signed_bytes for signature_field", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "signed_bytes for signature_field",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "signed_bytes for signature_field",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: TLV option type generates an enum with an Unknown catch-all
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        ClientId: {
+          sequence: [
+            { name: "hardware_type", type: "uint8" },
+            { name: "id", type: "uint16" },
+          ]
+        },
+        DhcpOption: {
+          type: "tlv",
+          tag_type: "uint8",
+          length_type: "uint8",
+          options: [
+            { name: "SubnetMask", tag: 1, type: "uint32" },
+            { name: "HostName", tag: 12, type: "bytes" },
+            { name: "ClientId", tag: 61, type: "ClientId" },
+          ],
+        },
+        DhcpOptions: {
+          sequence: [
+            { name: "options", type: "array", kind: "eof_terminated", items: { type: "DhcpOption" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "DhcpOptions");
+    const required = [
+      "pub enum DhcpOption {",
+      "    SubnetMask(u32),",
+      "    HostName(Vec<u8>),",
+      "    ClientId(ClientId),",
+      "    Unknown { tag: u8, value: Vec<u8> },",
+      "DhcpOption::ClientId(_) => 61,",
+      "1 => DhcpOption::SubnetMask(inner.read_uint32(Endianness::BigEndian)?),",
+      "12 => return Ok(DhcpOption::HostName(value)),",
+      "61 => DhcpOption::ClientId(ClientId::decode_with_decoder(&mut inner)?),",
+      "_ => return Ok(DhcpOption::Unknown { tag, value }),",
+      "DhcpOption::Unknown { value, .. } => value.clone(),",
+      "if inner.position() != value.len() {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "TLV option type", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "TLV option type",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "TLV option type",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: no-panic invariant — decode never unwraps and never sizes an
+  // allocation directly from an untrusted count
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Item: {
+          sequence: [
+            { name: "id", type: "uint16" },
+            { name: "name", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "utf8" } as any,
+          ]
+        },
+        Table: {
+          sequence: [
+            { name: "count", type: "uint32" },
+            { name: "items", type: "array", kind: "field_referenced", length_field: "count", items: { type: "Item" } },
+            { name: "tags", type: "array", kind: "length_prefixed", length_type: "uint32", items: { type: "uint16" } },
+            { name: "title", type: "string", kind: "length_prefixed", length_type: "uint16", encoding: "utf16" } as any,
+            { name: "blob", type: "array", kind: "byte_length_prefixed", length_type: "uint32", items: { type: "uint8" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Table");
+    const code = result.code;
+    const panicking = [".unwrap()", ".expect(", "panic!(", "unreachable!("].filter((p) => code.includes(p));
+    // Only compile-time literal capacities may bypass capacity_hint
+    const rawCapacities = (code.match(/Vec::with_capacity\((?!decoder\.capacity_hint\()[^)]*\)/g) || [])
+      .filter((m) => !/^Vec::with_capacity\(\d+\)$/.test(m));
+    const overflowingBound = code.includes("start_pos + byte_length");
+    if (panicking.length === 0 && rawCapacities.length === 0 && !overflowingBound) {
+      passed++;
+      checks.push({ description: "No-panic decode invariant", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "No-panic decode invariant",
+        passed: false,
+        message: `panicking calls: ${panicking.join(", ")}; unbounded capacities: ${rawCapacities.join(", ")}; overflowing bound: ${overflowingBound}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "No-panic decode invariant",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: back_reference pointer flag follows the schema instead of DNS's 0xC000
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: {
+          sequence: [
+            { name: "text", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "utf8" } as any,
+          ]
+        },
+        LabelRef: {
+          type: "back_reference",
+          storage: "uint16",
+          offset_mask: "0x7FFF",
+          pointer_flag: "0x8000",
+          offset_from: "message_start",
+          target_type: "Label",
+          endianness: "little_endian",
+        },
+        DnsLabelRef: {
+          type: "back_reference",
+          storage: "uint16",
+          offset_mask: "0x3FFF",
+          offset_from: "message_start",
+          target_type: "Label",
+          endianness: "big_endian",
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "LabelRef");
+    const required = [
+      "encoder.write_uint16(binschema_runtime::encode_pointer(offset, 0x7FFF, 0x8000)? as u16, Endianness::LittleEndian);",
+      "let reference_value = decoder.read_uint16(Endianness::LittleEndian)?;",
+      "if reference_value & !0x7FFF != 0x8000 {",
+      // Without pointer_flag the DNS convention is derived from the mask, and
+      // offsets past 14 bits are rejected instead of truncated
+      "encoder.write_uint16(binschema_runtime::encode_compression_pointer(offset)?, Endianness::BigEndian);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "Configurable back_reference pointer flag", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Configurable back_reference pointer flag",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Configurable back_reference pointer flag",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: untrusted array counts are checked against the remaining input
+  // before allocating (minimum item size derived from the schema)
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Question: {
+          sequence: [
+            { name: "qname", type: "string", kind: "null_terminated", encoding: "ascii" } as any,
+            { name: "qtype", type: "uint16" },
+            { name: "qclass", type: "uint16" },
+          ]
+        },
+        Message: {
+          sequence: [
+            { name: "qdcount", type: "uint16" },
+            { name: "questions", type: "array", kind: "field_referenced", length_field: "qdcount", items: { type: "Question" } },
+            { name: "extras", type: "array", kind: "length_prefixed", length_type: "uint32", items: { type: "uint32" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Message");
+    const code = result.code;
+    // qname (at least its terminator) + qtype + qclass = 40 bits
+    const questionsCheck = code.indexOf("decoder.ensure_count_fits(qdcount as usize, 40)?;");
+    const questionsAlloc = code.indexOf("Vec::with_capacity(decoder.capacity_hint(qdcount as usize))");
+    const extrasCheck = code.indexOf("decoder.ensure_count_fits(length, 32)?;");
+    const ok = questionsCheck !== -1 && questionsCheck < questionsAlloc && extrasCheck !== -1;
+    if (ok) {
+      passed++;
+      checks.push({ description: "Count check before Vec::with_capacity", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Count check before Vec::with_capacity",
+        passed: false,
+        message: `questions check at ${questionsCheck} (alloc at ${questionsAlloc}); extras check at ${extrasCheck}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Count check before Vec::with_capacity",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: rust_name overrides the generated field identifier everywhere
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        ResourceRecord: {
+          sequence: [
+            { name: "type", type: "uint16", rust_name: "record_type" },
+            { name: "rdlength", type: "uint16", rust_name: "data_len" },
+            { name: "rdata", type: "array", kind: "field_referenced", length_field: "rdlength", items: { type: "uint8" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "ResourceRecord");
+    const required = [
+      "pub record_type: u16,",
+      "pub data_len: u16,",
+      "self.record_type",
+      "data_len as usize",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const leaked = ["r#type", "self.rdlength"].filter((s) => result.code.includes(s));
+    if (missing.length === 0 && leaked.length === 0) {
+      passed++;
+      checks.push({ description: "rust_name field override", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "rust_name field override",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; leaked wire names: ${leaked.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "rust_name field override",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: decode_lenient collects independent recoverable field errors
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Header: {
+          sequence: [
+            { name: "magic", type: "uint16", const: 0xCAFE },
+            { name: "version", type: "uint8", const: 1 },
+            { name: "length", type: "uint32" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Header");
+    const required = [
+      "pub fn decode_lenient(bytes: &[u8]) -> (Option<Self>, Vec<binschema_runtime::FieldError>) {",
+      "decoder.set_lenient();",
+      "decoder.recover(\"Header.magic\", binschema_runtime::BinSchemaError::InvalidValue(format!(\"Header.magic is {}, expected {}\", magic, 51966u16)))?;",
+      "decoder.recover(\"Header.version\", binschema_runtime::BinSchemaError::InvalidValue(format!(\"Header.version is {}, expected {}\", version, 1u8)))?;",
+      "errors.push(binschema_runtime::FieldError { field: \"Header\".to_string(), error });",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "decode_lenient recoverable field errors", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "decode_lenient recoverable field errors",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "decode_lenient recoverable field errors",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: custom_float packs through the runtime CustomFloat layout
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Sample: {
+          sequence: [
+            { name: "level", type: "custom_float", sign: true, exponent_bits: 5, mantissa_bits: 10, bias: 15 },
+            { name: "gain", type: "custom_float", sign: false, exponent_bits: 4, mantissa_bits: 4, bias: 7, implied_leading_bit: false },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Sample");
+    const half = "binschema_runtime::CustomFloat { sign: true, exponent_bits: 5, mantissa_bits: 10, bias: 15, implied_leading_bit: true }";
+    const required = [
+      "pub level: f64,",
+      "pub gain: f64,",
+      `encoder.write_bits(${half}.from_f64(self.level)?, 16);`,
+      `let level = ${half}.to_f64(decoder.read_bits(16)?);`,
+      "let gain = binschema_runtime::CustomFloat { sign: false, exponent_bits: 4, mantissa_bits: 4, bias: 7, implied_leading_bit: false }.to_f64(decoder.read_bits(8)?);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "custom_float field", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "custom_float field",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "custom_float field",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: strict_utf8_boundaries: false trims a cut UTF-8 tail in fixed strings
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: {
+          sequence: [
+            { name: "lenient", type: "string", kind: "fixed", length: 9, encoding: "utf8", strict_utf8_boundaries: false },
+            { name: "strict", type: "string", kind: "fixed", length: 9, encoding: "utf8" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Label");
+    const required = [
+      "let mut bytes: Vec<u8> = raw_bytes.into_iter().filter(|&b| b != 0).collect();",
+      "bytes.truncate(binschema_runtime::complete_utf8_len(&bytes));",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const trimCount = result.code.split("complete_utf8_len").length - 1;
+    if (missing.length === 0 && trimCount === 1) {
+      passed++;
+      checks.push({ description: "strict_utf8_boundaries trimming", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "strict_utf8_boundaries trimming",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; complete_utf8_len emitted ${trimCount} times (want 1)`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "strict_utf8_boundaries trimming",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: crc_of computes a schema-declared CRC variant
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "little_endian" },
+      types: {
+        ModbusFrame: {
+          sequence: [
+            { name: "pdu", type: "array", kind: "length_prefixed", length_type: "uint8", items: { type: "uint8" } },
+            { name: "crc", type: "uint16", computed: { type: "crc_of", target: "pdu", crc: "crc16_modbus" } },
+            { name: "crc5", type: "uint8", computed: { type: "crc_of", target: "pdu", crc: { width: 5, poly: "0x05", init: "0x1F", refin: true, refout: true, xorout: "0x1F" } } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "ModbusFrame");
+    const required = [
+      "binschema_runtime::crc(&self.pdu, &binschema_runtime::CrcParams::CRC_16_MODBUS)?",
+      "binschema_runtime::crc(&self.pdu, &binschema_runtime::CrcParams { width: 5, poly: 0x05, init: 0x1F, refin: true, refout: true, xorout: 0x1F })?",
+      "as u16, Endianness::LittleEndian);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "crc_of with CRC presets and parameters", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "crc_of with CRC presets and parameters",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "crc_of with CRC presets and parameters",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: discriminator_of derives the tag field from the union variant
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        ARecord: { sequence: [{ name: "address", type: "uint32" }] },
+        MxRecord: { sequence: [{ name: "preference", type: "uint16" }] },
+        OpaqueRecord: { sequence: [{ name: "data", type: "uint8" }] },
+        Record: {
+          sequence: [
+            { name: "rtype", type: "uint16", computed: { type: "discriminator_of", target: "rdata" } },
+            {
+              name: "rdata",
+              type: "discriminated_union",
+              discriminator: { field: "rtype" },
+              variants: [
+                { when: "value == 0x01", type: "ARecord" },
+                { when: "value == 15", type: "MxRecord" },
+                { type: "OpaqueRecord" },
+              ],
+            },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Record");
+    const required = [
+      "let rtype_computed = match &self.rdata {",
+      "RecordRdata::ARecord(_) => 0x01u64,",
+      "RecordRdata::MxRecord(_) => 15u64,",
+      "RecordRdata::OpaqueRecord(_) => return Err(binschema_runtime::BinSchemaError::InvalidValue(",
+      "encoder.write_uint16(rtype_computed as u16, Endianness::BigEndian);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "discriminator_of tag auto-fill", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "discriminator_of tag auto-fill",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "discriminator_of tag auto-fill",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: columnar arrays store each item field as one contiguous column
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Point: { sequence: [{ name: "x", type: "uint16" }, { name: "y", type: "int16" }] },
+        Table: {
+          sequence: [
+            { name: "points", type: "array", kind: "columnar", length_type: "uint8", items: { type: "Point" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Table");
+    const required = [
+      "for item in self.points.iter() {",
+      "let mut points_x = Vec::with_capacity(decoder.capacity_hint(length));",
+      "let mut points_y = Vec::with_capacity(decoder.capacity_hint(length));",
+      "let points: Vec<Point> = (0..length).map(|i| Point { x: points_x[i], y: points_y[i] }).collect();",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "columnar array encode/decode", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "columnar array encode/decode",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "columnar array encode/decode",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: decode_with_pointer_log is offered where back-references can occur
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: {
+          sequence: [
+            { name: "text", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "utf8" } as any,
+          ]
+        },
+        LabelRef: {
+          type: "back_reference",
+          storage: "uint16",
+          offset_mask: "0x3FFF",
+          offset_from: "message_start",
+          target_type: "Label",
+        },
+        Answer: {
+          sequence: [
+            { name: "name", type: "LabelRef" },
+            { name: "ttl", type: "uint32" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Answer");
+    const required = [
+      "pub fn decode_with_pointer_log(bytes: &[u8]) -> Result<(Self, Vec<(usize, usize)>)> {",
+      "decoder.log_pointers();",
+      "Ok((value, decoder.take_pointer_log()))",
+      "let pointer_pos = decoder.position();",
+      "decoder.record_pointer(pointer_pos, decoder.position());",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    // Label itself can't contain a pointer, so it gets no pointer-log entry point
+    const labelImpl = result.code.slice(result.code.indexOf("impl Label {"), result.code.indexOf("impl LabelRef {"));
+    if (labelImpl.includes("decode_with_pointer_log")) missing.push("(no decode_with_pointer_log on Label)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "decode_with_pointer_log for back-reference types", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "decode_with_pointer_log for back-reference types",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "decode_with_pointer_log for back-reference types",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: generateRustModules splits types into modules that import each other
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Point: { sequence: [{ name: "x", type: "uint16" }, { name: "y", type: "uint16" }] },
+        Color: { sequence: [{ name: "rgb", type: "uint32" }] },
+        DrawCommand: {
+          sequence: [
+            { name: "origin", type: "Point" },
+            { name: "fill", type: "Color" },
+            { name: "path", type: "array", kind: "length_prefixed", length_type: "uint8", items: { type: "Point" } },
+          ]
+        }
+      }
+    } as any;
+
+    const files = generateRustModules(schema);
+    const draw = files.get("draw_command.rs") ?? "";
+    const mod = files.get("mod.rs") ?? "";
+    const required: [string, string][] = [
+      ["draw_command.rs", "use super::color::Color;"],
+      ["draw_command.rs", "use super::point::Point;"],
+      ["draw_command.rs", "pub struct DrawCommand {"],
+      ["mod.rs", "pub mod draw_command;"],
+      ["mod.rs", "pub use point::*;"],
+    ];
+    const missing = required.filter(([file, s]) => !(file === "mod.rs" ? mod : draw).includes(s)).map(([file, s]) => `${file}: ${s}`);
+    if (files.has("shared.rs")) missing.push("(no shared.rs without inline unions or bitfields)");
+    if ((files.get("point.rs") ?? "").includes("use super::")) missing.push("(point.rs has no sibling imports)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "generateRustModules cross-module imports", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "generateRustModules cross-module imports",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "generateRustModules cross-module imports",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: partial_decoder and unions that report truncation as end of input
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Short: { sequence: [{ name: "tag", type: "uint8", const: 1 }, { name: "v", type: "uint8" }] },
+        Long: { sequence: [{ name: "tag", type: "uint8", const: 2 }, { name: "v", type: "uint32" }] },
+        Entry: {
+          sequence: [
+            { name: "data", type: "choice", choices: [{ type: "Short" }, { type: "Long" }] },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Entry");
+    const required = [
+      "pub fn partial_decoder() -> binschema_runtime::PartialDecoder<Self> {",
+      "binschema_runtime::PartialDecoder::new(BitOrder::MsbFirst, Self::decode_with_decoder)",
+      "let mut truncated = false;",
+      "truncated |= matches!(e, binschema_runtime::BinSchemaError::UnexpectedEof);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "partial_decoder and truncated union variants", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "partial_decoder and truncated union variants",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "partial_decoder and truncated union variants",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: TryFrom<&T> for Vec<u8> on the type that encodes
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Ping: { sequence: [{ name: "seq", type: "uint16" }] },
+        Frame: {
+          sequence: [
+            { name: "len", type: "uint8", computed: { type: "length_of", target: "payload" } },
+            { name: "payload", type: "array", kind: "field_referenced", length_field: "len", items: { type: "uint8" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Frame");
+    const required = [
+      "impl TryFrom<&Ping> for Vec<u8> {",
+      "    fn try_from(value: &Ping) -> Result<Self> {",
+      // Split types convert from the Input struct, which owns encode
+      "impl TryFrom<&FrameInput> for Vec<u8> {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (result.code.includes("impl TryFrom<&FrameOutput>")) missing.push("(no conversion from FrameOutput)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "TryFrom<&T> for Vec<u8>", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "TryFrom<&T> for Vec<u8>",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "TryFrom<&T> for Vec<u8>",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: union selected by a combination of two earlier fields
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Ping: { sequence: [{ name: "nonce", type: "uint16" }] },
+        Data: { sequence: [{ name: "value", type: "uint8" }] },
+        Message: {
+          sequence: [
+            { name: "version", type: "uint8" },
+            { name: "msg_type", type: "uint8" },
+            {
+              name: "body",
+              type: "discriminated_union",
+              discriminator: { fields: ["version", "msg_type"] },
+              variants: [
+                { values: [1, 1], type: "Ping" },
+                { values: [2, 1], type: "Data" },
+              ]
+            },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Message");
+    const required = [
+      "let body = match (version, msg_type) {",
+      "    (1, 1) => {",
+      "        MessageBody::Ping(Ping::decode_with_decoder(decoder)?)",
+      "    (2, 1) => {",
+      "        MessageBody::Data(Data::decode_with_decoder(decoder)?)",
+      "unmapped => return Err(binschema_runtime::BinSchemaError::InvalidValue(format!(",
+      "\"no variant for (version, msg_type) = {:?}\", unmapped",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "multi-field discriminator match", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "multi-field discriminator match",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "multi-field discriminator match",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: field_offset for fixed header fields
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Header: {
+          sequence: [
+            { name: "magic", type: "uint32", const: 0x89504E47 },
+            { name: "version", type: "uint8" },
+            { name: "major", type: "bit", size: 4 },
+            { name: "minor", type: "bit", size: 4 },
+            { name: "len", type: "uint16", computed: { type: "length_of", target: "data" } },
+            { name: "data", type: "array", kind: "field_referenced", length_field: "len", items: { type: "uint8" } },
+            { name: "checksum", type: "uint32" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Header");
+    const required = [
+      "    pub fn field_offset(name: &str) -> Option<usize> {",
+      "            \"magic\" => Some(0),",
+      "            \"version\" => Some(4),",
+      "            \"major\" => Some(5),",
+      "            \"len\" => Some(6),",
+      // The variable array itself starts at a known offset
+      "            \"data\" => Some(8),",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    // Starts mid-byte, and after the variable array
+    if (result.code.includes("\"minor\" => Some(")) missing.push("(no offset for mid-byte minor)");
+    if (result.code.includes("\"checksum\" => Some(")) missing.push("(no offset for checksum after data)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "field_offset for fixed header fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "field_offset for fixed header fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "field_offset for fixed header fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: decode_autodetect_endianness probes the marker constant
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Record: {
+          endianness_marker: "magic",
+          sequence: [
+            { name: "version", type: "uint16" },
+            { name: "magic", type: "uint32", const: 0x12345678 },
+            { name: "count", type: "uint32" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Record");
+    const required = [
+      "    pub fn decode_autodetect_endianness(bytes: &[u8]) -> Result<Self> {",
+      "        decoder.seek(2)?;",
+      "        let marker = decoder.read_uint32(Endianness::BigEndian)?;",
+      "        if marker != 0x12345678u32 && marker.swap_bytes() == 0x12345678u32 {",
+      "            decoder.swap_byte_order();",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const plain = generateRust({ ...schema, types: { Record: { sequence: (schema.types.Record as any).sequence } } } as any, "Record");
+    if (plain.code.includes("decode_autodetect_endianness")) missing.push("(only generated with endianness_marker)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "decode_autodetect_endianness", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "decode_autodetect_endianness",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "decode_autodetect_endianness",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+
+  // Test: an inline group with repeat becomes a fixed-size array, unrolled
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Mesh: {
+          sequence: [
+            { name: "id", type: "uint8" },
+            {
+              name: "coords",
+              type: "group",
+              repeat: 3,
+              sequence: [
+                { name: "x", type: "int16" },
+                { name: "y", type: "int16" },
+                { name: "z", type: "int16" },
+              ]
+            },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Mesh");
+    const required = [
+      "pub struct MeshCoords {",
+      "    pub coords: [MeshCoords; 3],",
+      "        self.coords[0].encode_into(encoder)?;",
+      "        self.coords[2].encode_into(encoder)?;",
+      "        let coords = [",
+      "            MeshCoords::decode_with_decoder(decoder)?,",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (result.code.includes("self.coords[3]")) missing.push("(exactly 3 encode calls)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "repeated inline group", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "repeated inline group",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "repeated inline group",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+
+  // Test: follow_pointers splices the labels a DNS pointer refers to
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: { type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii" },
+        LabelPointer: { type: "back_reference", storage: "uint16", offset_mask: "0x3FFF", offset_from: "message_start", target_type: "Label" },
+        CompressedLabel: {
+          type: "discriminated_union",
+          discriminator: { peek: "uint8" },
+          variants: [
+            { type: "Label", when: "value < 0xC0" },
+            { type: "LabelPointer", when: "value >= 0xC0" },
+          ]
+        },
+        DomainName: {
+          type: "array",
+          kind: "null_terminated",
+          items: { type: "CompressedLabel" },
+          terminal_variants: ["LabelPointer"],
+          follow_pointers: true,
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "DomainName");
+    const required = [
+      "            let tag = decoder.peek_uint8()?;",
+      "            if tag >= 0xC0 {",
+      "                let reference_value = decoder.read_uint16(Endianness::BigEndian)?;",
+      "                if value_visited.contains(&target) {",
+      "                decoder.seek(target)?;",
+      "                decoder.limits().check_pointer_jumps(value_visited.len())?;",
+      "            decoder.limits().check_label_len(label_len)?;",
+      "            decoder.limits().check_name_len(value_name_len + 1)?;",
+      "        if let Some(pos) = value_resume {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "follow_pointers label decode", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "follow_pointers label decode",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "follow_pointers label decode",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+
+  // Test: decode_into writes each field of a fixed-size type in place
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "little_endian" },
+      types: {
+        Vec3: {
+          sequence: [
+            { name: "x", type: "float32" },
+            { name: "y", type: "float32" },
+            { name: "z", type: "float32" },
+          ]
+        },
+        Sample: {
+          decode_into: true,
+          sequence: [
+            { name: "id", type: "uint16" },
+            { name: "position", type: "Vec3" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Sample");
+    const required = [
+      "    pub fn decode_into(bytes: &[u8], out: &mut Self) -> Result<()> {",
+      "        out.id = id;",
+      "        Vec3::decode_into_with_decoder(decoder, &mut out.position)?;",
+      "        out.z = z;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const plain = generateRust({ ...schema, types: { ...schema.types, Sample: { sequence: (schema.types.Sample as any).sequence } } } as any, "Sample");
+    if (plain.code.includes("decode_into")) missing.push("(only generated with decode_into)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "decode_into for fixed-size types", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "decode_into for fixed-size types",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "decode_into for fixed-size types",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: string max_length, length prefix overflow and array max_encoded_length are checked on encode
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: {
+          sequence: [
+            { name: "text", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii", max_length: 63 },
+          ]
+        },
+        Name: {
+          sequence: [
+            { name: "labels", type: "array", kind: "null_terminated", items: { type: "Label" }, max_encoded_length: 255 },
+            { name: "comment", type: "string", kind: "length_prefixed", length_type: "uint16" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Name");
+    const required = [
+      "        if self.text.chars().count() > 63 {",
+      "            return Err(BinSchemaError::InvalidValue(format!(\"'text' is {} bytes, which exceeds max_length 63\", self.text.chars().count())));",
+      "        let labels_start = encoder.byte_offset();",
+      "        if encoder.byte_offset() - labels_start > 255 {",
+      "        if self.comment.len() > u16::MAX as usize {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "encode-side length limits", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "encode-side length limits",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "encode-side length limits",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: roundtripTests emits a property test per type it can build values for
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "little_endian" },
+      types: {
+        Color: { type: "enum", repr: "uint8", variants: { Red: 1, Green: 2 } },
+        Point: {
+          sequence: [
+            { name: "x", type: "int16" },
+            { name: "scale", type: "float32" },
+            { name: "color", type: "Color" },
+            { name: "label", type: "string", kind: "length_prefixed", length_type: "uint8", max_length: 4 },
+          ]
+        },
+        Path: {
+          sequence: [
+            { name: "points", type: "array", kind: "length_prefixed", length_type: "uint16", items: { type: "Point" } },
+          ]
+        },
+        Framed: {
+          sequence: [
+            { name: "len", type: "uint8", computed: { type: "length_of", target: "body" } },
+            { name: "body", type: "array", kind: "field_referenced", length_field: "len", items: { type: "uint8" } },
+          ]
+        },
+      }
+    } as any;
+
+    const result = generateRust(schema, "Path", { roundtripTests: true });
+    const required = [
+      "#[cfg(test)]\nmod roundtrip_tests {",
+      "    impl Arbitrary for Point {",
+      "                color: [Color::Red, Color::Green][rng.below(2) as usize],",
+      "                label: rng.text(4),",
+      "                points: (0..rng.below(9)).map(|_| Point::arbitrary(rng)).collect(),",
+      "    fn path_roundtrips() {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (result.code.includes("impl Arbitrary for Framed")) missing.push("(Framed skipped)");
+    if (generateRust(schema, "Path").code.includes("roundtrip_tests")) missing.push("(only generated with roundtripTests)");
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "roundtrip property test module", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "roundtrip property test module",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "roundtrip property test module",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: record_alignment pads after every array element on both paths
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Entry: {
+          sequence: [
+            { name: "id", type: "uint16" },
+            { name: "flags", type: "uint8" },
+          ]
+        },
+        Table: {
+          sequence: [
+            { name: "entries", type: "array", kind: "length_prefixed", length_type: "uint32", items: { type: "Entry" }, record_alignment: 4 },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Table");
+    const required = [
+      "            encoder.align_to(4);",
+      "            decoder.align_to(4)?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "record_alignment between array elements", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "record_alignment between array elements",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "record_alignment between array elements",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: xor_of / complement_of are written from their sources and re-checked on decode
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Masked: {
+          sequence: [
+            { name: "key", type: "uint16" },
+            { name: "value", type: "uint16" },
+            { name: "masked", type: "uint16", computed: { type: "xor_of", targets: ["key", "value"] } },
+            { name: "inverted", type: "uint8", computed: { type: "complement_of", target: "key" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Masked");
+    const required = [
+      "let masked_computed = (self.key as u64) ^ (self.value as u64);",
+      "let inverted_computed = !(self.key as u64);",
+      "let masked_expected = ((key as u64) ^ (value as u64)) as u16;",
+      "let inverted_expected = (!(key as u64)) as u8;",
+      "decoder.recover(\"Masked.masked\"",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "xor_of / complement_of transforms", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "xor_of / complement_of transforms",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "xor_of / complement_of transforms",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: a try-in-order union that matches nothing reports every attempt
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Short: { sequence: [{ name: "tag", type: "uint8", const: 1 }, { name: "v", type: "uint8" }] },
+        Long: { sequence: [{ name: "tag", type: "uint8", const: 2 }, { name: "v", type: "uint32" }] },
+        Entry: {
+          sequence: [
+            { name: "data", type: "choice", choices: [{ type: "Short" }, { type: "Long" }] },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Entry");
+    const required = [
+      "let mut attempts: Vec<(&str, binschema_runtime::BinSchemaError)> = Vec::new();",
+      "attempts.push((\"Short\", e));",
+      "decoder.rewind_to(start_pos);",
+      "attempts.push((\"Long\", e));",
+      "binschema_runtime::BinSchemaError::no_variant_matched(\"EntryData\", &attempts)",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "union decode aggregates variant errors", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "union decode aggregates variant errors",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "union decode aggregates variant errors",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: bit_length_prefixed arrays write and check a total bit count
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Packed: {
+          sequence: [
+            { name: "values", type: "array", kind: "bit_length_prefixed", length_bits: 7, items: { type: "bit", size: 5 } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Packed");
+    const required = [
+      "let values_bits = (self.values.len() as u64) * 5;",
+      "if values_bits >= 1u64 << 7 {",
+      "encoder.write_bits(values_bits, 7);",
+      "let bit_length = decoder.read_bits(7)?;",
+      "if bit_length % 5 != 0 {",
+      "let count = (bit_length / 5) as usize;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "bit_length_prefixed arrays", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "bit_length_prefixed arrays",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "bit_length_prefixed arrays",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: countdown arrays count a seed field down across union elements
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Literal: { sequence: [{ name: "value", type: "uint8" }] },
+        Run: { sequence: [{ name: "flag", type: "uint8" }, { name: "count", type: "uint8" }, { name: "value", type: "uint8" }] },
+        Chunk: {
+          type: "discriminated_union",
+          discriminator: { peek: "uint8" },
+          variants: [
+            { type: "Literal", when: "value < 0x80" },
+            { type: "Run", when: "value >= 0x80" },
+          ]
+        },
+        Image: {
+          sequence: [
+            { name: "pixel_count", type: "uint16" },
+            { name: "chunks", type: "array", kind: "countdown", length_field: "pixel_count", countdown_by: "count", items: { type: "Chunk" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Image");
+    const required = [
+      "let mut chunks_remaining = pixel_count as u64;",
+      "while chunks_remaining > 0 {",
+      "let consumed = match &item { Chunk::Literal(_) => 1, Chunk::Run(v) => v.count as u64 };",
+      "chunks_remaining -= consumed;",
+      "let chunks_total: u64 = self.chunks.iter().map(|item| match &item { Chunk::Literal(_) => 1, Chunk::Run(v) => v.count as u64 }).sum();",
+      "if chunks_total != self.pixel_count as u64 {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "countdown arrays", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "countdown arrays",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "countdown arrays",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: newtype-annotated integer fields get their own transparent wrapper
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Soa: {
+          sequence: [
+            { name: "serial", type: "uint32", newtype: "Serial" },
+            { name: "refresh", type: "uint32", newtype: "Ttl" },
+            { name: "retry", type: "uint32", newtype: "Ttl" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Soa");
+    const required = [
+      "#[repr(transparent)]",
+      "pub struct Serial(pub u32);",
+      "pub struct Ttl(pub u32);",
+      "impl From<Ttl> for u32 {",
+      "pub refresh: Ttl,",
+      "encoder.write_u32_be(self.refresh.0);",
+      "refresh: Ttl(refresh),",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const ttlCount = result.code.split("pub struct Ttl(").length - 1;
+    if (missing.length === 0 && ttlCount === 1) {
+      passed++;
+      checks.push({ description: "newtype integer fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "newtype integer fields",
+        passed: false,
+        message: missing.length ? `Missing: ${missing.join(", ")}` : `Ttl defined ${ttlCount} times`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "newtype integer fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: keep_raw enums carry unknown discriminants in a Raw variant
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Rcode: {
+          type: "enum",
+          repr: "uint16",
+          on_unknown_enum: "keep_raw",
+          variants: { NoError: 0, ServFail: 2 }
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Rcode");
+    const required = [
+      "    Raw(u64),",
+      "_ => Ok(Rcode::Raw(val as u64)),",
+      "Rcode::ServFail => 2,",
+      "Rcode::Raw(val) => *val,",
+      "let val = u16::try_from(self.value())",
+      "encoder.write_uint16(val, Endianness::BigEndian);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0 && !result.code.includes("#[repr(u16)]")) {
+      passed++;
+      checks.push({ description: "keep_raw enum", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "keep_raw enum",
+        passed: false,
+        message: missing.length ? `Missing: ${missing.join(", ")}` : "keep_raw enum still has #[repr(u16)]",
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "keep_raw enum",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: delta_zigzag arrays write the first item in full, then varint deltas
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Series: {
+          sequence: [
+            { name: "timestamps", type: "array", kind: "length_prefixed", length_type: "uint8", encoding: "delta_zigzag", items: { type: "uint32" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Series");
+    const required = [
+      "let mut timestamps_prev: Option<u32> = None;",
+      "encoder.write_varint_i64(i64::from(*item).wrapping_sub(i64::from(prev)));",
+      "encoder.write_u32_be(*item);",
+      "let item = if let Some(&prev) = timestamps.last() {",
+      "let value = i64::from(prev).wrapping_add(decoder.read_varint_i64()?);",
+      "u32::try_from(value)",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "delta_zigzag arrays", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "delta_zigzag arrays",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "delta_zigzag arrays",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: when_truncated checks the remaining bytes of a flagged field
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Hello: {
+          sequence: [
+            { name: "flags", type: "uint8" },
+            { name: "extensions", type: "uint16", conditional: "flags & 0x01", when_truncated: "absent" },
+            { name: "padding_len", type: "uint32", conditional: "flags & 0x02", when_truncated: "error" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Hello");
+    const required = [
+      "let extensions = if (flags & 0x01 != 0) && decoder.remaining_slice().len() >= 2 {",
+      "if decoder.remaining_slice().len() < 4 {",
+      "return Err(BinSchemaError::UnexpectedEof);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "when_truncated conditional fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "when_truncated conditional fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "when_truncated conditional fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: builder setters check each field's constraints as they are set
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Header: {
+          builder: true,
+          sequence: [
+            { name: "version", type: "bit", size: 4 },
+            { name: "offset", type: "int", size: 12, signed: true },
+            { name: "name", type: "string", kind: "length_prefixed", length_type: "uint8", max_length: 8 },
+            { name: "options", type: "uint16", conditional: "version > 4" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Header");
+    const required = [
+      "pub struct HeaderBuilder {",
+      "pub fn with_version(mut self, value: u8) -> Result<Self> {",
+      "if u64::from(value) >= 1u64 << 4 {",
+      "if !(-(1i64 << 11)..1i64 << 11).contains(&i64::from(value)) {",
+      "exceeds max_length 8",
+      "pub fn with_options(mut self, value: u16) -> Result<Self> {",
+      "options: self.options,",
+      "BinSchemaError::InvalidValue(\"Header.version is not set\".to_string())",
+      "pub fn builder() -> HeaderBuilder {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "validating builder", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "validating builder",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "validating builder",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: uuid fields read and write through the layout's runtime method
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "little_endian" },
+      types: {
+        PartitionEntry: {
+          sequence: [
+            { name: "type_guid", type: "uuid", layout: "guid" },
+            { name: "object_id", type: "uuid", layout: "rfc4122" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "PartitionEntry");
+    const required = [
+      "pub type_guid: [u8; 16],",
+      "pub object_id: [u8; 16],",
+      "encoder.write_guid(&self.type_guid);",
+      "encoder.write_uuid(&self.object_id);",
+      "let type_guid = decoder.read_guid()?;",
+      "let object_id = decoder.read_uuid()?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "uuid field layouts", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "uuid field layouts",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "uuid field layouts",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: preserve_pointer keeps the decoded pointer and re-emits it on encode
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: {
+          sequence: [
+            { name: "text", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii" }
+          ]
+        },
+        LabelPointer: {
+          type: "back_reference",
+          storage: "uint16",
+          endianness: "big_endian",
+          offset_mask: "0x3FFF",
+          offset_from: "message_start",
+          target_type: "Label",
+          preserve_pointer: true
+        },
+        Record: {
+          sequence: [
+            { name: "first", type: "Label" },
+            { name: "again", type: "LabelPointer" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Record");
+    const required = [
+      "pub struct LabelPointer(pub Label, pub Option<u16>);",
+      "if let Some(reference_value) = self.1 {",
+      "encoder.write_uint16(reference_value, Endianness::BigEndian);",
+      "Ok(Self(value, Some(reference_value)))",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "back_reference preserve_pointer", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "back_reference preserve_pointer",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "back_reference preserve_pointer",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: signed int fields sign-extend from their top bit
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Sample: {
+          sequence: [
+            { name: "adc", type: "int", size: 12, signed: true },
+            { name: "trim", type: "int", size: 4, signed: true },
+            { name: "history", type: "array", kind: "fixed", length: 2, items: { type: "int", size: 40, signed: true } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Sample");
+    const required = [
+      "pub adc: i16,",
+      "encoder.write_bits_signed(i64::from(self.adc), 12);",
+      "let adc = decoder.read_bits_signed(12)? as i16;",
+      "let trim = decoder.read_bits_signed(4)? as i8;",
+      "let item = decoder.read_bits_signed(40)?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "signed int fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "signed int fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "signed int fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: padding fields align on both paths rather than writing or reading bytes one by one
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Record: {
+          sequence: [
+            { name: "tag", type: "uint8" },
+            { name: "pad", type: "padding", align_to: 4 },
+            { name: "value", type: "uint32" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Record");
+    const required = [
+      "encoder.align_to(4);",
+      "decoder.align_to(4)?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "padding aligns on encode and decode", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "padding aligns on encode and decode",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "padding aligns on encode and decode",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: offset binary fields add the bias on encode and subtract it on decode
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Sample: {
+          sequence: [
+            { name: "level", type: "uint8", signed_repr: "offset_binary", bias: 128 },
+            { name: "gain", type: "uint16", signed_repr: "offset_binary", bias: 1000 },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Sample");
+    const required = [
+      "pub level: i8,",
+      "pub gain: i32,",
+      "u8::try_from(i64::from(self.level) + 128).map_err(|_| BinSchemaError::InvalidValue(format!(\"level is {}, outside its offset binary range\", self.level)))?",
+      "let level = (i64::from(decoder.read_byte()?) - 128) as i8;",
+      "let gain = (i64::from(decoder.read_u16_be()?) - 1000) as i32;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "offset binary fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "offset binary fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "offset binary fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: variant-terminated arrays fail cleanly when input ends before the terminal variant
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        DataItem: { sequence: [{ name: "value", type: "uint8" }] },
+        EndMarker: { sequence: [{ name: "code", type: "uint8", const: 0xFF }] },
+        ItemOrEnd: {
+          type: "discriminated_union",
+          discriminator: { peek: "uint8" },
+          variants: [
+            { type: "EndMarker", when: "value === 0xFF" },
+            { type: "DataItem", when: "value !== 0xFF" },
+          ]
+        },
+        Container: {
+          sequence: [
+            { name: "items", type: "array", kind: "variant_terminated", terminal_variants: ["EndMarker"], items: { type: "ItemOrEnd" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Container");
+    const required = [
+      "if !decoder.has_remaining() {",
+      "return Err(BinSchemaError::UnexpectedEof);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "variant-terminated EOF guard", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "variant-terminated EOF guard",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "variant-terminated EOF guard",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: message_bytes reads the payload in one call, sized by its length_of field
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Envelope: {
+          sequence: [
+            { name: "inner_len", type: "uint16", computed: { type: "length_of", target: "payload" } },
+            { name: "payload", type: "message_bytes", length_field: "inner_len" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Envelope");
+    const required = [
+      "pub payload: Vec<u8>,",
+      "let payload = decoder.read_bytes_vec(inner_len as usize)?;",
+      "encoder.write_bytes(&self.payload);",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "message_bytes fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "message_bytes fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "message_bytes fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: a field after padding that follows bit fields uses the byte-aligned fast path
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Header: {
+          sequence: [
+            { name: "version", type: "bit", size: 3 },
+            { name: "pad", type: "padding", align_to: 2 },
+            { name: "length", type: "uint16" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Header");
+    const required = [
+      "encoder.align_to(2);",
+      "decoder.align_to(2)?;",
+      "let length = decoder.read_u16_be()?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "padding after bit fields realigns", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "padding after bit fields realigns",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "padding after bit fields realigns",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: decode_with_limits installs the limits, and struct bodies decode one nesting level down
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Node: {
+          sequence: [
+            { name: "value", type: "uint8" },
+            { name: "children", type: "array", kind: "length_prefixed", length_type: "uint8", items: { type: "Node" } },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Node");
+    const required = [
+      "pub fn decode_with_limits(bytes: &[u8], limits: &binschema_runtime::DecodeLimits) -> Result<Self> {",
+      "decoder.set_limits(*limits)?;",
+      "        decoder.nested(|decoder| {",
+      "            let value = decoder.read_byte()?;",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "decode limits entry point and nesting", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "decode limits entry point and nesting",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "decode limits entry point and nesting",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Schema bit_order reaches every encoder and decoder
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "little_endian", bit_order: "lsb_first" },
+      types: {
+        UsbFlags: {
+          sequence: [
+            { name: "low", type: "bit", size: 3 },
+            { name: "high", type: "bit", size: 5 },
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "UsbFlags");
+    const required = [
+      "BitStreamEncoder::new(BitOrder::LsbFirst)",
+      "BitStreamDecoder::new(bytes, BitOrder::LsbFirst)",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (result.code.includes("BitOrder::MsbFirst")) {
+      missing.push("(no BitOrder::MsbFirst)");
+    }
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "lsb_first bit order", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "lsb_first bit order",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "lsb_first bit order",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Generated data types derive serde behind the `serde` feature
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        SensorReading: {
+          sequence: [
+            { name: "sensor_type", type: "bit", size: 4 },
+            { name: "battery_low", type: "bit", size: 1 },
+            { name: "error", type: "bit", size: 1 },
+            { name: "reserved", type: "bit", size: 2 },
+            { name: "timestamp", type: "uint32" },
+            { name: "value", type: "float32" },
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "SensorReading");
+    const serdeDerive = `#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]`;
+    const required = [
+      `#[derive(Debug, Clone, PartialEq, Default)]\n${serdeDerive}\npub struct SensorReading {`,
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "serde derives behind feature", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "serde derives behind feature",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "serde derives behind feature",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // sum_of_type_sizes over a sibling array builds FieldValue::TypeSizes
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        ARecord: { sequence: [{ name: "type_tag", type: "uint8", const: 1 }, { name: "ttl", type: "uint32" }] },
+        TxtRecord: { sequence: [{ name: "type_tag", type: "uint8", const: 16 }, { name: "len", type: "uint8" }] },
+        Answers: {
+          sequence: [
+            { name: "a_bytes", type: "uint16", computed: { type: "sum_of_type_sizes", target: "records", element_type: "ARecord" } },
+            {
+              name: "records", type: "array", kind: "length_prefixed", length_type: "uint8",
+              items: { type: "choice", choices: [{ type: "ARecord" }, { type: "TxtRecord" }] }
+            },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Answers");
+    const required = [
+      ".map(|item| -> Result<(std::string::String, usize)> { Ok((item.type_name().to_string(), item.encode()?.len())) })",
+      `FieldValue::TypeSizes(a_bytes_computed_sizes).sum_type_sizes("ARecord")`,
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "sum_of_type_sizes over sibling array", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "sum_of_type_sizes over sibling array",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "sum_of_type_sizes over sibling array",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Input structs derive Default where every field has one, and get a builder behind the `builder` feature
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Question: { sequence: [{ name: "qname", type: "string", kind: "null_terminated" }, { name: "qtype", type: "uint16" }] },
+        Message: {
+          sequence: [
+            { name: "id", type: "uint16" },
+            { name: "qdcount", type: "uint16", computed: { type: "length_of", target: "questions" } },
+            { name: "questions", type: "array", kind: "field_referenced", length_field: "qdcount", items: { type: "Question" } },
+            { name: "extra", type: "uint8", conditional: "id == 1" },
+          ]
+        },
+        Tagged: {
+          sequence: [
+            { name: "tag", type: "uint8" },
+            { name: "body", type: "choice", choices: [{ type: "Question" }] },
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "Message");
+    const serdeDerive = `#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]`;
+    const required = [
+      `#[derive(Debug, Clone, PartialEq, Default)]\n${serdeDerive}\npub struct MessageInput {`,
+      `#[derive(Debug, Clone, PartialEq, Default)]\n${serdeDerive}\npub struct Question {`,
+      `#[cfg(feature = "builder")]\n#[derive(Debug, Clone, Default)]\npub struct MessageBuilder {\n    value: MessageInput,\n}`,
+      "    pub fn id(mut self, value: u16) -> Self {\n        self.value.id = value;",
+      "        self.value.extra = Some(value);",
+      `#[cfg(feature = "builder")]\nimpl MessageInput {\n    pub fn builder() -> MessageBuilder {`,
+      `#[derive(Debug, Clone, PartialEq)]\n${serdeDerive}\npub struct Tagged {`,
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (result.code.includes("pub fn qdcount(") || result.code.includes("pub struct TaggedBuilder")) {
+      missing.push("no setter for computed fields, no builder for types without Default");
+    }
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "Default and defaulting builder", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Default and defaulting builder",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Default and defaulting builder",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Checksums over context values serialize scalars in the schema's byte order
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Trailer: {
+          sequence: [{ name: "check", type: "uint32", computed: { type: "crc32_of", target: "../length" } }]
+        },
+        Frame: {
+          sequence: [
+            { name: "length", type: "uint16" },
+            { name: "trailer", type: "Trailer" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Frame");
+    const required = ["Some(field_value) => binschema_runtime::crc32(&field_value.to_bytes_with_endianness(Endianness::BigEndian)),"];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "crc32_of context bytes in schema byte order", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "crc32_of context bytes in schema byte order",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "crc32_of context bytes in schema byte order",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Null-terminated arrays of composite items stop at the zero byte and consume it
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Label: { type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii" },
+        DomainName: { type: "array", kind: "null_terminated", items: { type: "Label" } },
+      }
+    } as any;
+
+    const result = generateRust(schema, "DomainName");
+    const required = ["    if decoder.peek_uint8()? == 0 {\n", "; // Consume the null byte\n", "        break;\n"];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "null-terminated array decode stops at terminator", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "null-terminated array decode stops at terminator",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "null-terminated array decode stops at terminator",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Computed fields split a type into Input/Output with a From conversion; plain types stay unified
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Packet: {
+          sequence: [
+            { name: "len", type: "uint8", computed: { type: "length_of", target: "payload" } },
+            { name: "payload", type: "array", kind: "field_referenced", length_field: "len", items: { type: "uint8" } },
+          ]
+        },
+        Point: { sequence: [{ name: "x", type: "uint8" }, { name: "y", type: "uint8" }] },
+      }
+    } as any;
+
+    const result = generateRust(schema, "Packet");
+    const required = [
+      "pub struct PacketInput {\n    pub payload: Vec<u8>,\n}",
+      "pub struct PacketOutput {\n    pub len: u8,\n    pub payload: Vec<u8>,\n}",
+      "pub type Packet = PacketOutput;",
+      "impl From<PacketOutput> for PacketInput {\n    fn from(o: PacketOutput) -> Self {",
+      "pub struct Point {",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (result.code.includes("PointInput") || result.code.includes("PointOutput")) {
+      missing.push("no split for Point");
+    }
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "Input/Output split only with computed fields", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Input/Output split only with computed fields",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Input/Output split only with computed fields",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: unknown discriminants of a closed enum fail with InvalidVariant
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        QClass: {
+          type: "enum",
+          repr: "uint16",
+          variants: { IN: 1, CH: 3 }
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "QClass");
+    const required = [
+      "#[repr(u16)]",
+      "3 => Ok(QClass::CH),",
+      "_ => Err(BinSchemaError::InvalidVariant(val as u64)),",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "closed enum rejects unknown values with InvalidVariant", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "closed enum rejects unknown values with InvalidVariant",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "closed enum rejects unknown values with InvalidVariant",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: counts of items with no minimum size are still checked against max_alloc
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Marker: { sequence: [] },
+        Markers: {
+          sequence: [
+            { name: "markers", type: "array", kind: "length_prefixed", length_type: "uint32", items: { type: "Marker" } }
+          ]
+        }
+      }
+    } as any;
+
+    const code = generateRust(schema, "Markers").code;
+    const check = code.indexOf("decoder.ensure_count_fits(length, 0)?;");
+    const alloc = code.indexOf("Vec::with_capacity(decoder.capacity_hint(length))");
+    if (check !== -1 && alloc !== -1 && check < alloc) {
+      passed++;
+      checks.push({ description: "zero-size items get max_alloc guard", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "zero-size items get max_alloc guard",
+        passed: false,
+        message: check === -1 ? "Missing ensure_count_fits(length, 0) before the allocation" : "Guard comes after the allocation",
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "zero-size items get max_alloc guard",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: every generated type implements the runtime's Decodable
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "little_endian", bit_order: "lsb_first" },
+      types: {
+        Kind: { type: "enum", repr: "uint8", variants: { A: 1, B: 2 } },
+        Frame: {
+          sequence: [
+            { name: "len", type: "uint8", computed: { type: "length_of", target: "body" } },
+            { name: "kind", type: "Kind" },
+            { name: "body", type: "array", kind: "field_referenced", length_field: "len", items: { type: "uint8" } }
+          ]
+        }
+      }
+    } as any;
+
+    const code = generateRust(schema, "Frame").code;
+    const missing = ["Kind", "Frame"].filter((name) =>
+      !code.includes(
+        `impl binschema_runtime::Decodable for ${name} {\n` +
+        `    const BIT_ORDER: BitOrder = BitOrder::LsbFirst;\n\n` +
+        `    fn decode_with_decoder(decoder: &mut BitStreamDecoder<'_>) -> Result<Self> {\n` +
+        `        ${name}::decode_with_decoder(decoder)\n`
+      )
+    );
+    if (missing.length === 0 && !code.includes("Decodable for FrameInput")) {
+      passed++;
+      checks.push({ description: "Decodable impl per type", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "Decodable impl per type",
+        passed: false,
+        message: missing.length ? `Missing Decodable impl for: ${missing.join(", ")}` : "Decodable implemented for the Input struct",
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "Decodable impl per type",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: length_of a later composite is reserved, then back-patched with its encoded size
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Mx: { sequence: [{ name: "preference", type: "uint16" }, { name: "exchange", type: "uint32" }] },
+        ResourceRecord: {
+          sequence: [
+            { name: "rdlength", type: "uint16", computed: { type: "length_of", target: "rdata" } },
+            { name: "ttl", type: "uint32" },
+            { name: "rdata", type: "Mx" },
+            { name: "trailer", type: "uint8" }
+          ]
+        },
+        Chunk: {
+          sequence: [
+            { name: "size", type: "uint32", endianness: "little_endian", computed: { type: "length_of", target: "body" } },
+            { name: "body", type: "Mx" }
+          ]
+        }
+      }
+    } as any;
+
+    const code = generateRust(schema, "ResourceRecord").code;
+    const ordered = [
+      "let rdlength_slot = encoder.reserve_u16()?;",
+      "let rdata_start = encoder.byte_offset();",
+      "let rdlength_computed = encoder.byte_offset() - rdata_start;",
+      "encoder.patch_u16(rdlength_slot, rdlength_computed, Endianness::BigEndian)?;",
+      "(self.trailer);",
+    ];
+    const positions = ordered.map((s) => code.indexOf(s));
+    const inOrder = positions.every((p, i) => p !== -1 && (i === 0 || p > positions[i - 1]));
+    const chunkPatched = code.includes("encoder.patch_u32(size_slot, size_computed, Endianness::LittleEndian)?;");
+    if (inOrder && chunkPatched && !code.includes("self.rdata.encode()?.len()")) {
+      passed++;
+      checks.push({ description: "length_of back-patch", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "length_of back-patch",
+        passed: false,
+        message: !inOrder
+          ? `Missing or out of order: ${ordered.filter((_, i) => positions[i] === -1 || (i > 0 && positions[i] < positions[i - 1])).join(", ")}`
+          : !chunkPatched ? "Trailing 32-bit length is not patched" : "rdata is still encoded twice to measure it",
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "length_of back-patch",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // Test: maps handed to EncodeContext use the runtime's own map type, which
+  // is a BTreeMap whatever features the runtime is built with
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Inner: { sequence: [{ name: "value", type: "uint8" }] },
+        Outer: {
+          sequence: [
+            { name: "version", type: "uint8" },
+            { name: "inner", type: "Inner" },
+          ]
+        }
+      }
+    };
+
+    const result = generateRust(schema, "Outer");
+    const required = [
+      "use binschema_runtime::context::Map as FieldMap;",
+      "let mut parent_fields: FieldMap<std::string::String, FieldValue> = FieldMap::new();",
+    ];
+    const missing = required.filter((s) => !result.code.includes(s));
+    const usesHashMap = result.code.includes("HashMap<std::string::String, FieldValue>");
+    if (missing.length === 0 && !usesHashMap) {
+      passed++;
+      checks.push({ description: "parent field maps use the runtime's context::Map", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "parent field maps use the runtime's context::Map",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}; still uses HashMap for FieldValue maps: ${usesHashMap}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "parent field maps use the runtime's context::Map",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
+  // ...using the byte order the referenced field declares, not the schema default
+  try {
+    const schema: BinarySchema = {
+      config: { endianness: "big_endian" },
+      types: {
+        Trailer: {
+          sequence: [{ name: "check", type: "uint32", computed: { type: "crc32_of", target: "../length" } }]
+        },
+        Frame: {
+          sequence: [
+            { name: "length", type: "uint16", endianness: "little_endian" },
+            { name: "trailer", type: "Trailer" },
+          ]
+        }
+      }
+    } as any;
+
+    const result = generateRust(schema, "Frame");
+    const required = ["Some(field_value) => binschema_runtime::crc32(&field_value.to_bytes_with_endianness(Endianness::LittleEndian)),"];
+    const missing = required.filter((s) => !result.code.includes(s));
+    if (missing.length === 0) {
+      passed++;
+      checks.push({ description: "crc32_of context bytes in the referenced field's byte order", passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description: "crc32_of context bytes in the referenced field's byte order",
+        passed: false,
+        message: `Missing: ${missing.join(", ")}`,
+      });
+    }
+  } catch (error: any) {
+    failed++;
+    checks.push({
+      description: "crc32_of context bytes in the referenced field's byte order",
+      passed: false,
+      message: `Exception: ${error.message}`,
+    });
+  }
+
   return { passed, failed, checks };
 }
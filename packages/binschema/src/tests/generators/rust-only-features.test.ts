@@ -0,0 +1,214 @@
+// ABOUTME: Tests that generators without the Rust-only schema options reject them
+// ABOUTME: instead of silently encoding the schema some other way
+
+import { generateTypeScript } from "../../generators/typescript.js";
+import { generateGo } from "../../generators/go.js";
+import { generatePython } from "../../generators/python.js";
+import { findRustOnlyFeatures } from "../../schema/rust-only-features.js";
+import type { BinarySchema } from "../../schema/binary-schema.js";
+
+interface TestCheck {
+  description: string;
+  passed: boolean;
+  message?: string;
+}
+
+export function runRustOnlyFeaturesTests(): { passed: number; failed: number; checks: TestCheck[] } {
+  let passed = 0;
+  let failed = 0;
+  const checks: TestCheck[] = [];
+
+  const check = (description: string, actual: unknown, expected: unknown): void => {
+    if (JSON.stringify(actual) === JSON.stringify(expected)) {
+      passed++;
+      checks.push({ description, passed: true });
+    } else {
+      failed++;
+      checks.push({
+        description,
+        passed: false,
+        message: `Expected ${JSON.stringify(expected)}, got ${JSON.stringify(actual)}`,
+      });
+    }
+  };
+
+  const schema: BinarySchema = {
+    config: { endianness: "big_endian" },
+    types: {
+      VintageHeader: {
+        sequence: [
+          { name: "block_count", type: "uint32", byte_order: "pdp" },
+          { name: "checksum", type: "uint32" },
+        ]
+      },
+      Volume: {
+        sequence: [
+          { name: "header", type: "VintageHeader" },
+          { name: "offset", type: "int32", byte_order: "pdp" },
+        ]
+      }
+    }
+  };
+
+  check(
+    "findRustOnlyFeatures lists every field using byte_order",
+    findRustOnlyFeatures(schema),
+    [
+      { option: "byte_order", path: "VintageHeader.block_count" },
+      { option: "byte_order", path: "Volume.offset" },
+    ]
+  );
+
+  const generators: [string, (schema: BinarySchema) => unknown][] = [
+    ["TypeScript", schema => generateTypeScript(schema)],
+    ["Go", schema => generateGo(schema, "Volume")],
+    ["Python", schema => generatePython(schema, "Volume")],
+  ];
+  const expectRejected = (schema: BinarySchema, option: string, path: string): void => {
+    for (const [name, generate] of generators) {
+      const description = `${name} generator rejects ${option}`;
+      try {
+        generate(schema);
+        failed++;
+        checks.push({ description, passed: false, message: `Generated code for a schema using ${option}` });
+      } catch (error: any) {
+        const message: string = error.message;
+        if (message.includes(option) && message.includes(path)) {
+          passed++;
+          checks.push({ description, passed: true });
+        } else {
+          failed++;
+          checks.push({ description, passed: false, message: `Unexpected error: ${message}` });
+        }
+      }
+    }
+  };
+
+  expectRejected(schema, "byte_order", "VintageHeader.block_count");
+
+  // One minimal schema per remaining option, with the path the error must name
+  const rustOnlySchemas: [string, string, BinarySchema][] = [
+    ["wire_order", "Volume", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "payload", type: "uint16" },
+            { name: "kind", type: "uint8" },
+          ],
+          wire_order: ["kind", "payload"],
+        }
+      }
+    }],
+    ["signature_field", "Volume", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "payload", type: "uint16" },
+            { name: "signature", type: "array", kind: "fixed", length: 4, items: { type: "uint8" } },
+          ],
+          signature_field: "signature",
+        }
+      }
+    }],
+    ["crc_of", "Volume.checksum", {
+      config: { endianness: "little_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "data", type: "array", kind: "fixed", length: 4, items: { type: "uint8" } },
+            { name: "checksum", type: "uint16", computed: { type: "crc_of", target: "data", crc: "crc16_modbus" } },
+          ]
+        }
+      }
+    }],
+    ["endianness_marker", "Volume", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "magic", type: "uint16", const: 0xFEFF },
+            { name: "count", type: "uint32" },
+          ],
+          endianness_marker: "magic",
+        }
+      }
+    }],
+    ["record_alignment", "Volume.entries", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "entries", type: "array", kind: "length_prefixed", length_type: "uint8", items: { type: "uint16" }, record_alignment: 4 },
+          ]
+        }
+      }
+    }],
+    ["xor_of", "Volume.masked", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "key", type: "uint8" },
+            { name: "value", type: "uint8" },
+            { name: "masked", type: "uint8", computed: { type: "xor_of", targets: ["key", "value"] } },
+          ]
+        }
+      }
+    }],
+    ["complement_of", "Volume.inverted", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "length", type: "uint16" },
+            { name: "inverted", type: "uint16", computed: { type: "complement_of", target: "length" } },
+          ]
+        }
+      }
+    }],
+    ["on_unknown_enum: keep_raw", "Volume", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          type: "enum",
+          repr: "uint8",
+          variants: { Ok: 0, Error: 1 },
+          on_unknown_enum: "keep_raw",
+        }
+      }
+    }],
+    ["delta_zigzag", "Volume.samples", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "samples", type: "array", kind: "length_prefixed", length_type: "uint8", items: { type: "uint16" }, encoding: "delta_zigzag" },
+          ]
+        }
+      }
+    }],
+    ["when_truncated", "Volume.extra", {
+      config: { endianness: "big_endian" },
+      types: {
+        Volume: {
+          sequence: [
+            { name: "flags", type: "uint8" },
+            { name: "extra", type: "uint32", conditional: "flags & 0x01", when_truncated: "absent" },
+          ]
+        }
+      }
+    }],
+  ];
+  for (const [option, path, rustOnlySchema] of rustOnlySchemas) {
+    check(
+      `findRustOnlyFeatures reports ${option} at ${path}`,
+      findRustOnlyFeatures(rustOnlySchema),
+      [{ option, path }]
+    );
+    expectRejected(rustOnlySchema, option, path);
+  }
+
+  return { passed, failed, checks };
+}
@@ -746,6 +746,60 @@ const DISCRIMINATED_UNION_VALIDATION_TESTS: DiscriminatedUnionTestCase[] = [
     },
   },
 
+  {
+    description: "Field-based discriminator selected by a shared header struct field",
+    shouldPass: true,
+    schema: {
+      types: {
+        "RrHeader": {
+          sequence: [
+            { name: "rtype", type: "uint16" },
+            { name: "rdlength", type: "uint16" },
+          ],
+        },
+        "ARdata": { sequence: [{ name: "address", type: "uint32" }] },
+        "MxRdata": { sequence: [{ name: "preference", type: "uint16" }] },
+        "ResourceRecord": {
+          sequence: [
+            { name: "header", type: "RrHeader" },
+            {
+              name: "rdata",
+              type: "discriminated_union",
+              discriminator: { field: "header.rtype" },
+              variants: [
+                { when: "value == 1", type: "ARdata" },
+                { when: "value == 15", type: "MxRdata" },
+              ],
+            } as any,
+          ],
+        },
+      },
+    },
+  },
+
+  {
+    description: "Header-struct discriminator referencing a missing header field",
+    shouldPass: false,
+    expectedErrors: ["Header field", "kind", "not found"],
+    schema: {
+      types: {
+        "RrHeader": { sequence: [{ name: "rtype", type: "uint16" }] },
+        "ARdata": { sequence: [{ name: "address", type: "uint32" }] },
+        "Invalid": {
+          sequence: [
+            { name: "header", type: "RrHeader" },
+            {
+              name: "rdata",
+              type: "discriminated_union",
+              discriminator: { field: "header.kind" },
+              variants: [{ when: "value == 1", type: "ARdata" }],
+            } as any,
+          ],
+        },
+      },
+    },
+  },
+
   {
     description: "Discriminator with both 'peek' and 'field' (ambiguous)",
     shouldPass: false,
@@ -0,0 +1,48 @@
+// ABOUTME: Validation tests for bit_length_prefixed arrays
+// ABOUTME: Tests that the bit-count prefix can always be divided back into items
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: items without a fixed bit width can't be counted from a bit total
+ */
+export const bitLengthPrefixedStringItemsTestSuite = defineTestSuite({
+  name: "error_bit_length_prefixed_variable_items",
+  description: "bit_length_prefixed arrays of strings are rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Names": {
+        sequence: [
+          { name: "names", type: "array", kind: "bit_length_prefixed", length_bits: 12,
+            items: { type: "string", kind: "null_terminated" } }
+        ]
+      }
+    }
+  },
+  test_type: "Names",
+  schema_validation_error: true,
+  error_message: "bit_length_prefixed array items must be a fixed-width integer or bit field, got 'string'"
+});
+
+/**
+ * Test: padding between elements would not be counted in the bit total
+ */
+export const bitLengthPrefixedRecordAlignmentTestSuite = defineTestSuite({
+  name: "error_bit_length_prefixed_record_alignment",
+  description: "record_alignment can't be used on bit_length_prefixed arrays",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Packed": {
+        sequence: [
+          { name: "values", type: "array", kind: "bit_length_prefixed", length_bits: 7,
+            items: { type: "bit", size: 5 }, record_alignment: 2 }
+        ]
+      }
+    }
+  },
+  test_type: "Packed",
+  schema_validation_error: true,
+  error_message: "record_alignment is not supported on bit_length_prefixed arrays"
+});
@@ -0,0 +1,34 @@
+// ABOUTME: Validation tests for generated builders
+// ABOUTME: Tests that builders are rejected on types a builder can't construct
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: instance fields are decoded from the input, so a builder can't set them
+ */
+export const builderWithInstancesTestSuite = defineTestSuite({
+  name: "error_builder_with_instances",
+  description: "builder on a type with instances is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Trailer": {
+        sequence: [
+          { name: "crc", type: "uint32" }
+        ]
+      },
+      "Archive": {
+        builder: true,
+        sequence: [
+          { name: "count", type: "uint16" }
+        ],
+        instances: [
+          { name: "trailer", type: "Trailer", position: -4, size: 4 }
+        ]
+      }
+    }
+  },
+  test_type: "Archive",
+  schema_validation_error: true,
+  error_message: "builder is not supported on type 'Archive', which has instances"
+});
@@ -0,0 +1,70 @@
+// ABOUTME: Validation tests for the xor_of and complement_of computed transforms
+// ABOUTME: Tests that transform sources are plain sibling integer fields
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: xor_of needs at least two sources to combine
+ */
+export const xorOfSingleTargetTestSuite = defineTestSuite({
+  name: "error_xor_of_single_target",
+  description: "xor_of with one target is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Masked": {
+        sequence: [
+          { name: "value", type: "uint16" },
+          { name: "masked", type: "uint16", computed: { type: "xor_of", targets: ["value"] } }
+        ]
+      }
+    }
+  },
+  test_type: "Masked",
+  schema_validation_error: true,
+  error_message: "must have 'targets' array with at least two elements"
+});
+
+/**
+ * Test: transforms are re-derived on decode, so they can't reach into a parent struct
+ */
+export const complementOfParentTargetTestSuite = defineTestSuite({
+  name: "error_complement_of_parent_target",
+  description: "complement_of with a ../ target is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Inverted": {
+        sequence: [
+          { name: "check", type: "uint8", computed: { type: "complement_of", target: "../flags" } }
+        ]
+      }
+    }
+  },
+  test_type: "Inverted",
+  schema_validation_error: true,
+  error_message: "source '../flags' must name a sibling field"
+});
+
+/**
+ * Test: a string can't be XOR-ed
+ */
+export const xorOfStringSourceTestSuite = defineTestSuite({
+  name: "error_xor_of_string_source",
+  description: "xor_of over a string field is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Masked": {
+        sequence: [
+          { name: "key", type: "uint8" },
+          { name: "name", type: "string", kind: "length_prefixed", length_type: "uint8" },
+          { name: "masked", type: "uint8", computed: { type: "xor_of", targets: ["key", "name"] } }
+        ]
+      }
+    }
+  },
+  test_type: "Masked",
+  schema_validation_error: true,
+  error_message: "must be a plain unsigned integer field, got 'string'"
+});
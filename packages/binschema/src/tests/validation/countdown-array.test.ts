@@ -0,0 +1,55 @@
+// ABOUTME: Validation tests for countdown arrays
+// ABOUTME: Tests that the seed field and countdown_by field exist where decode needs them
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: the seed must already be decoded when the array starts
+ */
+export const countdownSeedAfterArrayTestSuite = defineTestSuite({
+  name: "error_countdown_seed_after_array",
+  description: "countdown array seeded by a later field is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Image": {
+        sequence: [
+          { name: "pixels", type: "array", kind: "countdown", length_field: "pixel_count", items: { type: "uint8" } },
+          { name: "pixel_count", type: "uint16" }
+        ]
+      }
+    }
+  },
+  test_type: "Image",
+  schema_validation_error: true,
+  error_message: "countdown array length_field 'pixel_count' must be an earlier plain unsigned integer field"
+});
+
+/**
+ * Test: countdown_by must name a field that some item actually carries
+ */
+export const countdownByMissingFieldTestSuite = defineTestSuite({
+  name: "error_countdown_by_missing_field",
+  description: "countdown_by naming a field the items don't have is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Run": {
+        sequence: [
+          { name: "count", type: "uint8" },
+          { name: "value", type: "uint8" }
+        ]
+      },
+      "Image": {
+        sequence: [
+          { name: "pixel_count", type: "uint16" },
+          { name: "runs", type: "array", kind: "countdown", length_field: "pixel_count", countdown_by: "length",
+            items: { type: "Run" } }
+        ]
+      }
+    }
+  },
+  test_type: "Image",
+  schema_validation_error: true,
+  error_message: "countdown_by 'length' is not a field of 'Run' or any of its variants"
+});
@@ -0,0 +1,32 @@
+// ABOUTME: Validation tests for the decode_into type flag
+// ABOUTME: Tests that in-place decoding is only enabled for fixed-size types
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: A variable-length field in a nested type rules out decode_into
+ */
+export const decodeIntoVariableFieldTestSuite = defineTestSuite({
+  name: "error_decode_into_variable_length_field",
+  description: "decode_into requires every field, including nested ones, to be fixed-size",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Tag": {
+        sequence: [
+          { name: "name", type: "string", kind: "length_prefixed", length_type: "uint8" }
+        ]
+      },
+      "Record": {
+        decode_into: true,
+        sequence: [
+          { name: "id", type: "uint32" },
+          { name: "tag", type: "Tag" }
+        ]
+      }
+    }
+  },
+  test_type: "Record",
+  schema_validation_error: true,
+  error_message: "decode_into requires fixed-size fields, but 'Tag.name' is 'string'"
+});
@@ -0,0 +1,46 @@
+// ABOUTME: Validation tests for delta_zigzag array encoding
+// ABOUTME: Tests that delta arrays hold plain integers and have a known count
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: deltas are only defined between integers
+ */
+export const deltaZigzagFloatItemsTestSuite = defineTestSuite({
+  name: "error_delta_zigzag_float_items",
+  description: "A delta_zigzag array of float32 items is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Series": {
+        sequence: [
+          { name: "samples", type: "array", kind: "length_prefixed", length_type: "uint8", encoding: "delta_zigzag", items: { type: "float32" } }
+        ]
+      }
+    }
+  },
+  test_type: "Series",
+  schema_validation_error: true,
+  error_message: "delta_zigzag array items must be a plain integer type (uint8-uint64, int8-int64), got 'float32'"
+});
+
+/**
+ * Test: a zero delta would end a null_terminated array early
+ */
+export const deltaZigzagNullTerminatedTestSuite = defineTestSuite({
+  name: "error_delta_zigzag_null_terminated",
+  description: "A null_terminated delta_zigzag array is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Series": {
+        sequence: [
+          { name: "samples", type: "array", kind: "null_terminated", encoding: "delta_zigzag", items: { type: "uint16" } }
+        ]
+      }
+    }
+  },
+  test_type: "Series",
+  schema_validation_error: true,
+  error_message: "delta_zigzag encoding requires a fixed, length_prefixed, field_referenced, computed_count or eof_terminated array, got 'null_terminated'"
+});
@@ -0,0 +1,50 @@
+// ABOUTME: Validation tests for endianness_marker on composite types
+// ABOUTME: Tests that the marker is a multi-byte const that distinguishes the byte orders
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: Marker must have a const value to compare against
+ */
+export const endiannessMarkerWithoutConstTestSuite = defineTestSuite({
+  name: "error_endianness_marker_without_const",
+  description: "endianness_marker field must be a const",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Header": {
+        endianness_marker: "magic",
+        sequence: [
+          { name: "magic", type: "uint32" },  // Missing const!
+          { name: "count", type: "uint16" }
+        ]
+      }
+    }
+  },
+  test_type: "Header",
+  schema_validation_error: true,
+  error_message: "endianness_marker field 'magic' must have a 'const' value"
+});
+
+/**
+ * Test: A marker that reads the same either way can't tell the orders apart
+ */
+export const endiannessMarkerPalindromeTestSuite = defineTestSuite({
+  name: "error_endianness_marker_palindrome",
+  description: "endianness_marker const must differ from its byte-swapped value",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Header": {
+        endianness_marker: "magic",
+        sequence: [
+          { name: "magic", type: "uint16", const: 0x4D4D },  // "MM" both ways
+          { name: "count", type: "uint16" }
+        ]
+      }
+    }
+  },
+  test_type: "Header",
+  schema_validation_error: true,
+  error_message: "endianness_marker field 'magic' has a const that reads the same in both byte orders"
+});
@@ -0,0 +1,26 @@
+// ABOUTME: Validation tests for enums that keep unknown values
+// ABOUTME: Tests that keep_raw enums don't declare their own Raw variant
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: a keep_raw enum can't declare a variant named Raw
+ */
+export const enumKeepRawVariantClashTestSuite = defineTestSuite({
+  name: "error_enum_keep_raw_variant_clash",
+  description: "A keep_raw enum with its own Raw variant is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Rcode": {
+        type: "enum",
+        repr: "uint8",
+        on_unknown_enum: "keep_raw",
+        variants: { "NoError": 0, "Raw": 1 }
+      }
+    }
+  },
+  test_type: "Rcode",
+  schema_validation_error: true,
+  error_message: "Variant name 'Raw' clashes with the Raw variant added by on_unknown_enum: keep_raw"
+});
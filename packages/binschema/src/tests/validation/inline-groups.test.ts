@@ -0,0 +1,66 @@
+// ABOUTME: Validation tests for inline group fields
+// ABOUTME: Tests that groups get a free lifted type name and a valid repeat count
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: The type a group is lifted to must not already exist
+ */
+export const inlineGroupNameCollisionTestSuite = defineTestSuite({
+  name: "error_inline_group_name_collision",
+  description: "An inline group cannot shadow an existing type with its lifted name",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "MeshCoords": {
+        sequence: [
+          { name: "value", type: "uint8" }
+        ]
+      },
+      "Mesh": {
+        sequence: [
+          {
+            name: "coords",
+            type: "group",
+            repeat: 3,
+            sequence: [
+              { name: "x", type: "int16" },
+              { name: "y", type: "int16" }
+            ]
+          }
+        ]
+      }
+    }
+  },
+  test_type: "Mesh",
+  schema_validation_error: true,
+  error_message: "Inline group would be named 'MeshCoords', which already exists in schema.types"
+});
+
+/**
+ * Test: Fields inside a group are validated like any other sequence
+ */
+export const inlineGroupUnknownTypeTestSuite = defineTestSuite({
+  name: "error_inline_group_unknown_field_type",
+  description: "Fields inside an inline group must reference existing types",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Mesh": {
+        sequence: [
+          {
+            name: "coords",
+            type: "group",
+            repeat: 2,
+            sequence: [
+              { name: "x", type: "Coordinate" }
+            ]
+          }
+        ]
+      }
+    }
+  },
+  test_type: "Mesh",
+  schema_validation_error: true,
+  error_message: "Type 'Coordinate' not found in schema.types"
+});
@@ -0,0 +1,48 @@
+// ABOUTME: Validation tests for message_bytes fields
+// ABOUTME: Tests that the length field precedes the payload and is computed from it
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: a length field set by hand could disagree with the payload, so it's rejected
+ */
+export const messageBytesPlainLengthTestSuite = defineTestSuite({
+  name: "error_message_bytes_plain_length",
+  description: "message_bytes needs its length field computed as length_of the payload",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Envelope": {
+        sequence: [
+          { name: "inner_len", type: "uint16" },
+          { name: "payload", type: "message_bytes", length_field: "inner_len" }
+        ]
+      }
+    }
+  },
+  test_type: "Envelope",
+  schema_validation_error: true,
+  error_message: "message_bytes length_field 'inner_len' must be computed as length_of 'payload'"
+});
+
+/**
+ * Test: the length field must come before the payload
+ */
+export const messageBytesLengthAfterPayloadTestSuite = defineTestSuite({
+  name: "error_message_bytes_length_after_payload",
+  description: "message_bytes can't be sized by a field decoded after it",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Envelope": {
+        sequence: [
+          { name: "payload", type: "message_bytes", length_field: "inner_len" },
+          { name: "inner_len", type: "uint16", computed: { type: "length_of", target: "payload" } }
+        ]
+      }
+    }
+  },
+  test_type: "Envelope",
+  schema_validation_error: true,
+  error_message: "message_bytes length_field 'inner_len' must name an earlier field in the same sequence"
+});
@@ -0,0 +1,113 @@
+// ABOUTME: Validation tests for discriminated unions keyed on several fields
+// ABOUTME: Tests that variant value tuples match the field list and don't overlap
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+const payloadTypes = {
+  "Ping": {
+    sequence: [
+      { name: "nonce", type: "uint16" }
+    ]
+  },
+  "Data": {
+    sequence: [
+      { name: "value", type: "uint8" }
+    ]
+  }
+};
+
+/**
+ * Test: Each variant needs one value per discriminator field
+ */
+export const multiFieldWrongArityTestSuite = defineTestSuite({
+  name: "error_multi_field_discriminator_wrong_arity",
+  description: "Variant values must have one entry per discriminator field",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      ...payloadTypes,
+      "Message": {
+        sequence: [
+          { name: "version", type: "uint8" },
+          { name: "msg_type", type: "uint8" },
+          {
+            name: "body",
+            type: "discriminated_union",
+            discriminator: { fields: ["version", "msg_type"] },
+            variants: [
+              { values: [1, 1], type: "Ping" },
+              { values: [2], type: "Data" }  // Missing msg_type!
+            ]
+          }
+        ]
+      }
+    }
+  },
+  test_type: "Message",
+  schema_validation_error: true,
+  error_message: "Variant 'values' must list 2 integers, one per discriminator field"
+});
+
+/**
+ * Test: Two variants cannot claim the same combination
+ */
+export const multiFieldDuplicateCombinationTestSuite = defineTestSuite({
+  name: "error_multi_field_discriminator_duplicate_combination",
+  description: "A combination of discriminator values can select only one variant",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      ...payloadTypes,
+      "Message": {
+        sequence: [
+          { name: "version", type: "uint8" },
+          { name: "msg_type", type: "uint8" },
+          {
+            name: "body",
+            type: "discriminated_union",
+            discriminator: { fields: ["version", "msg_type"] },
+            variants: [
+              { values: [1, 1], type: "Ping" },
+              { values: [1, 1], type: "Data" }  // Duplicate!
+            ]
+          }
+        ]
+      }
+    }
+  },
+  test_type: "Message",
+  schema_validation_error: true,
+  error_message: "Values (1, 1) already select variants[0]"
+});
+
+/**
+ * Test: Discriminator fields must be read before the union
+ */
+export const multiFieldForwardReferenceTestSuite = defineTestSuite({
+  name: "error_multi_field_discriminator_forward_reference",
+  description: "Every discriminator field must come before the union",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      ...payloadTypes,
+      "Message": {
+        sequence: [
+          { name: "version", type: "uint8" },
+          {
+            name: "body",
+            type: "discriminated_union",
+            discriminator: { fields: ["version", "msg_type"] },
+            variants: [
+              { values: [1, 1], type: "Ping" },
+              { values: [1, 2], type: "Data" }
+            ]
+          },
+          { name: "msg_type", type: "uint8" }
+        ]
+      }
+    }
+  },
+  test_type: "Message",
+  schema_validation_error: true,
+  error_message: "Discriminator field 'msg_type' comes after this union (forward reference not allowed)"
+});
@@ -0,0 +1,52 @@
+// ABOUTME: Validation tests for newtype annotations on integer fields
+// ABOUTME: Tests that each newtype name maps to one wrapped integer type
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: one newtype can't wrap two different integer types
+ */
+export const newtypeConflictingTypesTestSuite = defineTestSuite({
+  name: "error_newtype_conflicting_types",
+  description: "A newtype used on a uint32 and a uint16 field is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Record": {
+        sequence: [
+          { name: "ttl", type: "uint32", newtype: "Ttl" },
+          { name: "min_ttl", type: "uint16", newtype: "Ttl" }
+        ]
+      }
+    }
+  },
+  test_type: "Record",
+  schema_validation_error: true,
+  error_message: "newtype 'Ttl' wraps 'uint32' elsewhere but 'min_ttl' is 'uint16'"
+});
+
+/**
+ * Test: a newtype can't shadow a schema type
+ */
+export const newtypeShadowsTypeTestSuite = defineTestSuite({
+  name: "error_newtype_shadows_type",
+  description: "A newtype named like a schema type is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Port": {
+        sequence: [
+          { name: "number", type: "uint16" }
+        ]
+      },
+      "Endpoint": {
+        sequence: [
+          { name: "port", type: "uint16", newtype: "Port" }
+        ]
+      }
+    }
+  },
+  test_type: "Endpoint",
+  schema_validation_error: true,
+  error_message: "newtype 'Port' has the same name as a schema type"
+});
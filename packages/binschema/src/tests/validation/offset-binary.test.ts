@@ -0,0 +1,46 @@
+// ABOUTME: Validation tests for signed_repr "offset_binary" integer fields
+// ABOUTME: Tests that the bias is present and fits the field's storage type
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: offset binary without a bias is rejected
+ */
+export const offsetBinaryWithoutBiasTestSuite = defineTestSuite({
+  name: "error_offset_binary_without_bias",
+  description: "signed_repr 'offset_binary' needs the bias to subtract",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Sample": {
+        sequence: [
+          { name: "level", type: "uint8", signed_repr: "offset_binary" }
+        ]
+      }
+    }
+  },
+  test_type: "Sample",
+  schema_validation_error: true,
+  error_message: "signed_repr 'offset_binary' requires bias"
+});
+
+/**
+ * Test: a bias the storage type can't hold is rejected
+ */
+export const offsetBinaryBiasTooLargeTestSuite = defineTestSuite({
+  name: "error_offset_binary_bias_too_large",
+  description: "A uint8 offset binary field can't have a bias of 256",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Sample": {
+        sequence: [
+          { name: "level", type: "uint8", signed_repr: "offset_binary", bias: 256 }
+        ]
+      }
+    }
+  },
+  test_type: "Sample",
+  schema_validation_error: true,
+  error_message: "bias 256 does not fit in uint8"
+});
@@ -0,0 +1,40 @@
+// ABOUTME: Validation tests for preserve_pointer on back_reference types
+// ABOUTME: Tests that only back_reference type definitions can keep their raw pointer
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: preserve_pointer on an inline back_reference field is rejected
+ */
+export const preservePointerOnFieldTestSuite = defineTestSuite({
+  name: "error_preserve_pointer_on_field",
+  description: "An inline back_reference field has nowhere to keep the pointer it read",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Label": {
+        sequence: [
+          { name: "text", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii" }
+        ]
+      },
+      "Record": {
+        sequence: [
+          { name: "first", type: "Label" },
+          {
+            name: "again",
+            type: "back_reference",
+            storage: "uint16",
+            endianness: "big_endian",
+            offset_mask: "0x3FFF",
+            offset_from: "message_start",
+            target_type: "Label",
+            preserve_pointer: true
+          }
+        ]
+      }
+    }
+  },
+  test_type: "Record",
+  schema_validation_error: true,
+  error_message: "preserve_pointer is only supported on back_reference type definitions"
+});
@@ -0,0 +1,26 @@
+// ABOUTME: Validation tests for the record_alignment array option
+// ABOUTME: Tests that per-element padding is rejected where sizes are written up front
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: byte_length_prefixed arrays write their size before the padding is known
+ */
+export const recordAlignmentByteLengthTestSuite = defineTestSuite({
+  name: "error_record_alignment_byte_length_prefixed",
+  description: "record_alignment can't be used when the array's byte length is written before its elements",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Table": {
+        sequence: [
+          { name: "entries", type: "array", kind: "byte_length_prefixed", length_type: "uint16",
+            items: { type: "uint16" }, record_alignment: 4 }
+        ]
+      }
+    }
+  },
+  test_type: "Table",
+  schema_validation_error: true,
+  error_message: "record_alignment is not supported on byte_length_prefixed arrays"
+});
@@ -0,0 +1,25 @@
+// ABOUTME: Validation tests for the string max_length option
+// ABOUTME: Tests that a protocol maximum must fit the string's length prefix
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: max_length larger than the length prefix can express
+ */
+export const maxLengthExceedsPrefixTestSuite = defineTestSuite({
+  name: "error_max_length_exceeds_length_prefix",
+  description: "A uint8 length prefix cannot describe a 300-byte string, so max_length 300 is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Label": {
+        sequence: [
+          { name: "text", type: "string", kind: "length_prefixed", length_type: "uint8", encoding: "ascii", max_length: 300 }
+        ]
+      }
+    }
+  },
+  test_type: "Label",
+  schema_validation_error: true,
+  error_message: "max_length 300 exceeds what a uint8 length prefix can hold (255)"
+});
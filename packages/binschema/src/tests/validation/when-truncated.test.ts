@@ -0,0 +1,26 @@
+// ABOUTME: Validation tests for when_truncated on conditional fields
+// ABOUTME: Tests that the truncation check is only accepted alongside a condition
+
+import { defineTestSuite } from "../../schema/test-schema.js";
+
+/**
+ * Test: when_truncated on an unconditional field is rejected
+ */
+export const whenTruncatedWithoutConditionTestSuite = defineTestSuite({
+  name: "error_when_truncated_without_condition",
+  description: "when_truncated on a field with no conditional is rejected",
+  schema: {
+    config: { endianness: "big_endian" },
+    types: {
+      "Hello": {
+        sequence: [
+          { name: "version", type: "uint8" },
+          { name: "extensions", type: "uint16", when_truncated: "absent" }
+        ]
+      }
+    }
+  },
+  test_type: "Hello",
+  schema_validation_error: true,
+  error_message: "when_truncated requires a conditional field"
+});
@@ -161,6 +161,20 @@ def run_test_suite(suite: dict) -> list[dict]:
     if suite.get("schema_validation_error"):
         return results
 
+    # Suites using a Rust-only option expect the generator to refuse the schema
+    if "python" in suite.get("rejected_by", []):
+        error = "Expected the Python generator to reject this schema, but it generated code"
+        try:
+            generate_python_source(schema, test_type)
+        except RuntimeError as e:
+            error = None if "does not support" in str(e) else f"Code generation failed with an unexpected error: {e}"
+        for tc in test_cases:
+            result = {"description": tc["description"], "pass": error is None}
+            if error is not None:
+                result["error"] = error
+            results.append(result)
+        return results
+
     # Generate code
     try:
         code = generate_python_source(schema, test_type)
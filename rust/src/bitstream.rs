@@ -1,7 +1,12 @@
 // ABOUTME: Bit-level stream operations for encoding and decoding binary data
 // ABOUTME: Supports MSB-first and LSB-first bit ordering with byte-aligned operations
 
-use crate::{BinSchemaError, Result};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{BinSchemaError, Checksum, DecodeLimits, FieldError, Result};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endianness {
@@ -15,12 +20,35 @@ pub enum BitOrder {
     LsbFirst,
 }
 
+/// Placeholder bytes handed out by `BitStreamEncoder::reserve`, to be filled
+/// in with `patch_reservation` once their value is known (a checksum or length
+/// covering data written after them).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reservation {
+    offset: usize,
+    len: usize,
+}
+
+impl Reservation {
+    /// Byte offset of the first reserved byte
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Byte offset just past the reserved bytes, where the data after them starts
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
 /// Encoder for writing bit-level data to a byte stream
 pub struct BitStreamEncoder {
     buffer: Vec<u8>,
     current_byte: u8,
     bit_position: u8,
     bit_order: BitOrder,
+    /// Byte order of `write_u16`/`write_u32`/`write_u64`; big-endian unless set
+    default_endianness: Endianness,
 }
 
 impl BitStreamEncoder {
@@ -31,6 +59,7 @@ impl BitStreamEncoder {
             current_byte: 0,
             bit_position: 0,
             bit_order,
+            default_endianness: Endianness::BigEndian,
         }
     }
 
@@ -41,9 +70,18 @@ impl BitStreamEncoder {
             current_byte: 0,
             bit_position: 0,
             bit_order,
+            default_endianness: Endianness::BigEndian,
         }
     }
 
+    /// Sets the byte order used by the methods that don't take one, for
+    /// formats that are uniformly big- or little-endian.
+    #[inline]
+    pub fn with_default_endianness(mut self, endianness: Endianness) -> Self {
+        self.default_endianness = endianness;
+        self
+    }
+
     /// Reset the encoder for reuse without deallocating the internal buffer.
     #[inline]
     pub fn clear(&mut self) {
@@ -58,8 +96,34 @@ impl BitStreamEncoder {
         &self.buffer
     }
 
+    /// Drops the complete bytes once a `BitStreamWriter` has written them out,
+    /// keeping a partly written byte
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn discard_complete_bytes(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Like `write_bits`, but rejects a bit count outside 1..=64 or a value
+    /// wider than `num_bits` with `InvalidValue` instead of writing nothing or
+    /// truncating it.
+    #[inline]
+    pub fn try_write_bits(&mut self, value: u64, num_bits: u8) -> Result<()> {
+        if num_bits == 0 || num_bits > 64 {
+            return Err(BinSchemaError::InvalidValue(format!("cannot write {} bits at once", num_bits)));
+        }
+        if num_bits < 64 && value >> num_bits != 0 {
+            return Err(BinSchemaError::InvalidValue(format!("{} does not fit in {} bits", value, num_bits)));
+        }
+        self.write_bits(value, num_bits);
+        Ok(())
+    }
+
+    /// Writes the low `num_bits` bits of `value`. A bit count outside 1..=64
+    /// writes nothing; use `try_write_bits` to have it reported.
     #[inline]
     pub fn write_bits(&mut self, value: u64, num_bits: u8) {
+        debug_assert!((1..=64).contains(&num_bits), "write_bits called with {} bits", num_bits);
         if num_bits == 0 || num_bits > 64 {
             return;
         }
@@ -94,6 +158,40 @@ impl BitStreamEncoder {
         }
     }
 
+    /// Write the low `num_bits` bits of a two's complement value, for signed
+    /// fields narrower than their Rust type. Out-of-range values are
+    /// truncated like in `write_bits`.
+    #[inline]
+    pub fn write_bits_signed(&mut self, value: i64, num_bits: u8) {
+        self.write_bits(value as u64, num_bits);
+    }
+
+    /// Writes the low `num_bits` bits of `value`, for fields up to 128 bits
+    /// wide. Bits go out in the same order a single `write_bits` call would
+    /// use. A bit count outside 1..=128 writes nothing.
+    #[inline]
+    pub fn write_bits_u128(&mut self, value: u128, num_bits: u8) {
+        debug_assert!((1..=128).contains(&num_bits), "write_bits_u128 called with {} bits", num_bits);
+        if num_bits <= 64 {
+            self.write_bits(value as u64, num_bits);
+            return;
+        }
+        if num_bits > 128 {
+            return;
+        }
+        let (high, low) = ((value >> 64) as u64, value as u64);
+        match self.bit_order {
+            BitOrder::MsbFirst => {
+                self.write_bits(high, num_bits - 64);
+                self.write_bits(low, 64);
+            }
+            BitOrder::LsbFirst => {
+                self.write_bits(low, 64);
+                self.write_bits(high, num_bits - 64);
+            }
+        }
+    }
+
     #[inline]
     fn write_single_bit(&mut self, bit: u8) {
         let bit_index = match self.bit_order {
@@ -129,12 +227,25 @@ impl BitStreamEncoder {
             // When a byte value crosses a bit boundary, the bits are written
             // starting from the LSB of the value.
             for i in 0..8u8 {
-                let bit = ((value >> i) & 1) as u8;
+                let bit = (value >> i) & 1;
                 self.write_single_bit(bit);
             }
         }
     }
 
+    /// Writes each byte as `write_uint8` would, copying the whole slice into
+    /// the buffer at once when byte-aligned.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        if self.bit_position == 0 {
+            self.buffer.extend_from_slice(bytes);
+        } else {
+            for &byte in bytes {
+                self.write_uint8(byte);
+            }
+        }
+    }
+
     #[inline]
     pub fn write_uint16(&mut self, value: u16, endianness: Endianness) {
         if self.bit_position == 0 {
@@ -205,6 +316,31 @@ impl BitStreamEncoder {
         }
     }
 
+    /// Writes a 128-bit value as two 64-bit halves, high half first when
+    /// big-endian, so the 16 bytes come out in plain big- or little-endian order
+    #[inline]
+    pub fn write_uint128(&mut self, value: u128, endianness: Endianness) {
+        let (high, low) = ((value >> 64) as u64, value as u64);
+        match endianness {
+            Endianness::BigEndian => {
+                self.write_uint64(high, endianness);
+                self.write_uint64(low, endianness);
+            }
+            Endianness::LittleEndian => {
+                self.write_uint64(low, endianness);
+                self.write_uint64(high, endianness);
+            }
+        }
+    }
+
+    /// Writes a 32-bit value in PDP-11 "middle-endian" order: the high 16-bit
+    /// word first, each word little-endian. 0x0A0B0C0D is stored as 0B 0A 0D 0C.
+    #[inline]
+    pub fn write_uint32_pdp(&mut self, value: u32) {
+        self.write_uint16((value >> 16) as u16, Endianness::LittleEndian);
+        self.write_uint16(value as u16, Endianness::LittleEndian);
+    }
+
     #[inline]
     pub fn write_int8(&mut self, value: i8) {
         self.write_uint8(value as u8);
@@ -225,6 +361,11 @@ impl BitStreamEncoder {
         self.write_uint64(value as u64, endianness);
     }
 
+    #[inline]
+    pub fn write_int128(&mut self, value: i128, endianness: Endianness) {
+        self.write_uint128(value as u128, endianness);
+    }
+
     #[inline]
     pub fn write_float32(&mut self, value: f32, endianness: Endianness) {
         self.write_uint32(value.to_bits(), endianness);
@@ -235,6 +376,31 @@ impl BitStreamEncoder {
         self.write_uint64(value.to_bits(), endianness);
     }
 
+    /// Writes `value` as an IEEE 754 binary16, rounding to nearest even.
+    /// Magnitudes beyond the half range become infinity and tiny ones
+    /// subnormals or zero.
+    #[inline]
+    pub fn write_float16(&mut self, value: f32, endianness: Endianness) {
+        self.write_uint16(f32_to_f16_bits(value), endianness);
+    }
+
+    // --- Default byte order methods ---
+
+    #[inline]
+    pub fn write_u16(&mut self, value: u16) {
+        self.write_uint16(value, self.default_endianness);
+    }
+
+    #[inline]
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_uint32(value, self.default_endianness);
+    }
+
+    #[inline]
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_uint64(value, self.default_endianness);
+    }
+
     // --- Byte-aligned fast-path methods ---
     // These skip the bit_position check and endianness branch.
     // The generator emits these when it can prove at code-gen time
@@ -282,6 +448,20 @@ impl BitStreamEncoder {
         self.buffer.extend_from_slice(&value.to_be_bytes());
     }
 
+    /// Write a UUID held in RFC 4122 order (the order of its text form)
+    /// byte for byte
+    #[inline]
+    pub fn write_uuid(&mut self, value: &[u8; 16]) {
+        self.write_bytes(value);
+    }
+
+    /// Write a UUID held in RFC 4122 order in Microsoft GUID layout, with
+    /// the first three groups little-endian
+    #[inline]
+    pub fn write_guid(&mut self, value: &[u8; 16]) {
+        self.write_uuid(&guid_swap(value));
+    }
+
     /// Write variable-length integer with specified encoding
     /// Supported encodings: "der", "leb128", "ebml", "vlq"
     #[inline]
@@ -295,6 +475,22 @@ impl BitStreamEncoder {
         }
     }
 
+    /// Write an unsigned LEB128 varint: 7 bits per byte, least significant
+    /// group first, continuation bit in the MSB. A partly written byte is
+    /// first completed with zero bits, so the varint starts on a byte boundary.
+    #[inline]
+    pub fn write_varint_u64(&mut self, value: u64) {
+        self.align_to_byte();
+        self.write_leb128(value);
+    }
+
+    /// Write a signed integer as a zigzag-mapped LEB128 varint, so that
+    /// values of small magnitude take one byte whichever their sign
+    #[inline]
+    pub fn write_varint_i64(&mut self, value: i64) {
+        self.write_varint_u64(((value << 1) ^ (value >> 63)) as u64);
+    }
+
     /// DER encoding: Short form (0-127) or long form (0x80+N followed by N bytes)
     #[inline]
     fn write_varlength_der(&mut self, value: u64) -> Result<()> {
@@ -323,19 +519,22 @@ impl BitStreamEncoder {
     /// LEB128 encoding: 7 bits per byte, continuation bit in MSB, little-endian
     #[inline]
     fn write_varlength_leb128(&mut self, value: u64) -> Result<()> {
+        self.write_leb128(value);
+        Ok(())
+    }
+
+    /// LEB128 bytes written from the current bit position
+    #[inline]
+    fn write_leb128(&mut self, value: u64) {
         let mut val = value;
         loop {
-            let mut byte = (val & 0x7F) as u8;
+            let byte = (val & 0x7F) as u8;
             val >>= 7;
-            if val != 0 {
-                byte |= 0x80; // Set continuation bit
-            }
-            self.write_uint8(byte);
+            self.write_uint8(if val != 0 { byte | 0x80 } else { byte });
             if val == 0 {
                 break;
             }
         }
-        Ok(())
     }
 
     /// EBML encoding: Leading zeros indicate width, self-synchronizing
@@ -396,30 +595,205 @@ impl BitStreamEncoder {
         Ok(())
     }
 
-    /// Get the current byte offset (number of complete bytes written)
+    /// Writes `count` copies of `value`, e.g. for padding or fills.
+    /// Byte-aligned writes extend the buffer in one step.
+    #[inline]
+    pub fn write_repeated(&mut self, value: u8, count: usize) {
+        if self.bit_position == 0 {
+            self.buffer.resize(self.buffer.len() + count, value);
+        } else {
+            for _ in 0..count {
+                self.write_uint8(value);
+            }
+        }
+    }
+
+    /// Writes `n_bytes` zero bytes to be overwritten later with
+    /// `patch_reservation`. The encoder must be byte-aligned.
+    pub fn reserve(&mut self, n_bytes: usize) -> Result<Reservation> {
+        if self.bit_position != 0 {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "cannot reserve bytes mid-byte (bit {} of the current byte)",
+                self.bit_position
+            )));
+        }
+        let offset = self.buffer.len();
+        self.buffer.resize(offset + n_bytes, 0);
+        Ok(Reservation { offset, len: n_bytes })
+    }
+
+    /// Overwrites the bytes of `reservation` with `bytes`, which must be
+    /// exactly as long as the reservation.
+    pub fn patch_reservation(&mut self, reservation: Reservation, bytes: &[u8]) -> Result<()> {
+        if bytes.len() != reservation.len {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "patch is {} bytes but the reservation is {}",
+                bytes.len(),
+                reservation.len
+            )));
+        }
+        let written = self.buffer.len();
+        let target = self.buffer.get_mut(reservation.offset..reservation.end()).ok_or_else(|| {
+            BinSchemaError::InvalidValue(format!(
+                "reservation {}..{} is outside the {} bytes written",
+                reservation.offset,
+                reservation.end(),
+                written
+            ))
+        })?;
+        target.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Reserves a 16-bit slot for a value only known later, such as a length
+    /// prefix covering data written after it. Fill it with `patch_u16`.
+    pub fn reserve_u16(&mut self) -> Result<Reservation> {
+        self.reserve(2)
+    }
+
+    /// Reserves a 32-bit slot; the `reserve_u16` counterpart for `patch_u32`
+    pub fn reserve_u32(&mut self) -> Result<Reservation> {
+        self.reserve(4)
+    }
+
+    /// Writes `value` into a slot from `reserve_u16`
+    pub fn patch_u16(&mut self, reservation: Reservation, value: u16, endianness: Endianness) -> Result<()> {
+        let bytes = match endianness {
+            Endianness::BigEndian => value.to_be_bytes(),
+            Endianness::LittleEndian => value.to_le_bytes(),
+        };
+        self.patch_reservation(reservation, &bytes)
+    }
+
+    /// Writes `value` into a slot from `reserve_u32`
+    pub fn patch_u32(&mut self, reservation: Reservation, value: u32, endianness: Endianness) -> Result<()> {
+        let bytes = match endianness {
+            Endianness::BigEndian => value.to_be_bytes(),
+            Endianness::LittleEndian => value.to_le_bytes(),
+        };
+        self.patch_reservation(reservation, &bytes)
+    }
+
+    /// Fills `reservation` with `checksum` computed over the bytes written at
+    /// `covered`, stored in `endianness`, and returns the checksum. `covered`
+    /// may come before the reservation (a trailer such as a PNG chunk CRC) or
+    /// after it (a header checksum patched once the body is written), but must
+    /// already be written in full and must not overlap the reservation.
+    pub fn patch_checksum(
+        &mut self,
+        reservation: Reservation,
+        checksum: Checksum,
+        covered: core::ops::Range<usize>,
+        endianness: Endianness,
+    ) -> Result<u64> {
+        if covered.start < reservation.end() && reservation.offset < covered.end {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "checksum range {}..{} overlaps its own bytes {}..{}",
+                covered.start,
+                covered.end,
+                reservation.offset,
+                reservation.end()
+            )));
+        }
+        let written = self.buffer.len();
+        let bytes = self.buffer.get(covered.clone()).ok_or_else(|| {
+            BinSchemaError::InvalidValue(format!(
+                "checksum range {}..{} is outside the {} bytes written",
+                covered.start, covered.end, written
+            ))
+        })?;
+        let value = checksum.compute(bytes)?;
+        let size = checksum.size();
+        let patch = match endianness {
+            Endianness::BigEndian => value.to_be_bytes()[8 - size..].to_vec(),
+            Endianness::LittleEndian => value.to_le_bytes()[..size].to_vec(),
+        };
+        self.patch_reservation(reservation, &patch)?;
+        Ok(value)
+    }
+
+    /// Get the current byte offset: the number of bytes the output occupies
+    /// so far. A partly written byte counts as a whole one, so mid-byte this is
+    /// one more than the number of complete bytes, and it always equals the
+    /// length `finish()` would return at this point. Offsets recorded for
+    /// byte-aligned data (compression dictionary entries) are unaffected.
     #[inline]
     pub fn byte_offset(&self) -> usize {
-        self.buffer.len()
+        self.buffer.len() + usize::from(self.bit_position != 0)
     }
 
+    /// Number of bits already written into the current partial byte (0 means aligned)
     #[inline]
-    pub fn finish(mut self) -> Vec<u8> {
+    pub fn bit_position_in_byte(&self) -> u8 {
+        self.bit_position
+    }
+
+    /// Whether the next write starts on a byte boundary
+    #[inline]
+    pub fn is_byte_aligned(&self) -> bool {
+        self.bit_position == 0
+    }
+
+    /// Completes a partly written byte with zero bits, so the next write
+    /// starts on a byte boundary. Does nothing when already aligned.
+    #[inline]
+    pub fn align_to_byte(&mut self) {
         if self.bit_position > 0 {
             self.flush_byte();
         }
+    }
+
+    /// Aligns to a byte, then writes zero bytes until the output length is a
+    /// multiple of `boundary_bytes`. A boundary of 0 or 1 only aligns to a byte.
+    #[inline]
+    pub fn align_to(&mut self, boundary_bytes: usize) {
+        self.align_to_byte();
+        if boundary_bytes > 1 {
+            let padding = (boundary_bytes - self.buffer.len() % boundary_bytes) % boundary_bytes;
+            self.buffer.resize(self.buffer.len() + padding, 0);
+        }
+    }
+
+    #[inline]
+    pub fn finish(mut self) -> Vec<u8> {
+        self.align_to_byte();
         self.buffer
     }
 }
 
 /// Decoder for reading bit-level data from a byte stream
+///
+/// Safe for untrusted input: every read is bounds-checked against the message
+/// and reports truncated or malformed data as `Err`, never by panicking.
+/// Generated decoders rely on this and size allocations via `capacity_hint`.
 pub struct BitStreamDecoder<'a> {
     bytes: &'a [u8],
     byte_offset: usize,
     bit_offset: u8,
     bit_order: BitOrder,
+    /// Length of the message being decoded (equals `bytes.len()` unless bounded)
+    message_len: usize,
+    /// Current read bound: `message_len`, or the full buffer after seeking past it
+    limit: usize,
+    /// Recoverable field errors collected in lenient mode; `None` when strict
+    field_errors: Option<Vec<FieldError>>,
+    /// `(pointer_position, target_offset)` of each back-reference followed; `None` unless enabled
+    pointer_log: Option<Vec<(usize, usize)>>,
+    /// Multi-byte values are read in the opposite byte order to the one requested
+    swap_byte_order: bool,
+    /// Byte order of `read_u16`/`read_u32`/`read_u64`; big-endian unless set
+    default_endianness: Endianness,
+    limits: DecodeLimits,
+    /// Composite types currently being decoded, counted by `nested`
+    depth: usize,
+    /// The input may end before the message does (see `set_input_incomplete`)
+    input_incomplete: bool,
 }
 
 impl<'a> BitStreamDecoder<'a> {
+    /// Creates a decoder reading `bytes` in place. The input is borrowed, not
+    /// copied, so generated `decode(&[u8])` functions don't allocate for it and
+    /// `remaining_slice` hands back a subslice of the caller's buffer.
     #[inline]
     pub fn new(bytes: &'a [u8], bit_order: BitOrder) -> Self {
         Self {
@@ -427,7 +801,53 @@ impl<'a> BitStreamDecoder<'a> {
             byte_offset: 0,
             bit_offset: 0,
             bit_order,
+            message_len: bytes.len(),
+            limit: bytes.len(),
+            field_errors: None,
+            pointer_log: None,
+            swap_byte_order: false,
+            default_endianness: Endianness::BigEndian,
+            limits: DecodeLimits::baseline(),
+            depth: 0,
+            input_incomplete: false,
+        }
+    }
+
+    /// Sets the byte order used by the methods that don't take one. A later
+    /// `swap_byte_order` applies to them as to every other multi-byte read.
+    #[inline]
+    pub fn with_default_endianness(mut self, endianness: Endianness) -> Self {
+        self.default_endianness = endianness;
+        self
+    }
+
+    /// Creates a decoder for a message occupying the first `len` bytes of `bytes`,
+    /// e.g. when a transport already knows the frame length.
+    ///
+    /// Sequential reads stop at `len` and `bytes_len()` reports `len`, so
+    /// EOF-terminated and position-from-end logic sees only the message. The rest
+    /// of the buffer stays reachable by seeking past `len` (back-references that
+    /// point outside the message); such reads are bounded by the full buffer.
+    #[inline]
+    pub fn new_bounded(bytes: &'a [u8], len: usize, bit_order: BitOrder) -> Result<Self> {
+        if len > bytes.len() {
+            return Err(BinSchemaError::UnexpectedEof);
         }
+        Ok(Self {
+            bytes,
+            byte_offset: 0,
+            bit_offset: 0,
+            bit_order,
+            message_len: len,
+            limit: len,
+            field_errors: None,
+            pointer_log: None,
+            swap_byte_order: false,
+            default_endianness: Endianness::BigEndian,
+            limits: DecodeLimits::baseline(),
+            depth: 0,
+            input_incomplete: false,
+        })
     }
 
     #[inline]
@@ -438,7 +858,7 @@ impl<'a> BitStreamDecoder<'a> {
 
         // Fast path: MSB-first reads of <=8 bits
         if self.bit_order == BitOrder::MsbFirst && num_bits <= 8 {
-            if self.byte_offset >= self.bytes.len() {
+            if self.byte_offset >= self.limit {
                 return Err(BinSchemaError::UnexpectedEof);
             }
             let bits_available = 8 - self.bit_offset;
@@ -456,7 +876,7 @@ impl<'a> BitStreamDecoder<'a> {
                 return Ok(result);
             }
             // Cross byte boundary — read from two bytes
-            if self.byte_offset + 1 >= self.bytes.len() {
+            if self.byte_offset + 1 >= self.limit {
                 return Err(BinSchemaError::UnexpectedEof);
             }
             let bits_from_first = bits_available;
@@ -489,9 +909,90 @@ impl<'a> BitStreamDecoder<'a> {
         Ok(result)
     }
 
+    /// Reads `num_bits` bits as a two's complement value, sign-extending
+    /// from the top bit read
+    #[inline]
+    pub fn read_bits_signed(&mut self, num_bits: u8) -> Result<i64> {
+        let value = self.read_bits(num_bits)?;
+        let shift = 64 - u32::from(num_bits);
+        Ok(((value << shift) as i64) >> shift)
+    }
+
+    /// Reads a `num_bits`-bit value of up to 128 bits, in the same bit order
+    /// as `read_bits`. Fails with `UnexpectedEof`, consuming nothing, if
+    /// fewer than `num_bits` bits remain.
+    #[inline]
+    pub fn read_bits_u128(&mut self, num_bits: u8) -> Result<u128> {
+        if num_bits <= 64 {
+            return self.read_bits(num_bits).map(u128::from);
+        }
+        if num_bits > 128 {
+            return Err(BinSchemaError::InvalidValue("Invalid number of bits".to_string()));
+        }
+        if usize::from(num_bits) > self.remaining_bits() {
+            return Err(BinSchemaError::UnexpectedEof);
+        }
+        let (high, low) = match self.bit_order {
+            BitOrder::MsbFirst => {
+                let high = self.read_bits(num_bits - 64)?;
+                (high, self.read_bits(64)?)
+            }
+            BitOrder::LsbFirst => {
+                let low = self.read_bits(64)?;
+                (self.read_bits(num_bits - 64)?, low)
+            }
+        };
+        Ok((u128::from(high) << 64) | u128::from(low))
+    }
+
+    /// Reads a `num_bits`-bit value of any width into `buf` as a big-endian
+    /// number, right-aligned, with the unused leading bits and bytes zeroed.
+    /// Fails with `InvalidValue` if `buf` is too short to hold `num_bits`
+    /// and with `UnexpectedEof` if fewer bits remain; neither consumes input.
+    pub fn read_bits_into(&mut self, buf: &mut [u8], num_bits: usize) -> Result<()> {
+        let width = num_bits.div_ceil(8);
+        if width > buf.len() {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "{} bits do not fit in a {}-byte buffer",
+                num_bits,
+                buf.len()
+            )));
+        }
+        if num_bits > self.remaining_bits() {
+            return Err(BinSchemaError::UnexpectedEof);
+        }
+        buf.fill(0);
+        let start = buf.len() - width;
+        let value = &mut buf[start..];
+        let partial = (num_bits % 8) as u8;
+        match self.bit_order {
+            // Most significant bits come first: the partial top byte, then whole bytes
+            BitOrder::MsbFirst => {
+                let mut bytes = value.iter_mut();
+                if partial != 0 {
+                    *bytes.next().expect("width covers the partial byte") = self.read_bits(partial)? as u8;
+                }
+                for byte in bytes {
+                    *byte = self.read_bits(8)? as u8;
+                }
+            }
+            // Least significant bits come first: whole bytes from the end, then the partial top byte
+            BitOrder::LsbFirst => {
+                let mut bytes = value.iter_mut().rev();
+                for _ in 0..num_bits / 8 {
+                    *bytes.next().expect("width covers every whole byte") = self.read_bits(8)? as u8;
+                }
+                if partial != 0 {
+                    *bytes.next().expect("width covers the partial byte") = self.read_bits(partial)? as u8;
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn read_single_bit(&mut self) -> Result<u8> {
-        if self.byte_offset >= self.bytes.len() {
+        if self.byte_offset >= self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
 
@@ -512,26 +1013,144 @@ impl<'a> BitStreamDecoder<'a> {
         Ok(bit)
     }
 
-    /// Reads `n` bytes as a slice, advancing the byte offset.
-    /// Only valid when byte-aligned.
+    /// Reads `n` whole bytes into a new vector. Byte-aligned reads copy the
+    /// slice in one go; otherwise each byte is read like `read_uint8`.
+    /// Fails with `UnexpectedEof`, consuming nothing, if fewer than `n`
+    /// bytes remain, and with `LimitExceeded` if `n` is above `max_alloc`.
     #[inline]
     pub fn read_bytes_vec(&mut self, n: usize) -> Result<Vec<u8>> {
+        if n > self.whole_bytes_left() {
+            return Err(BinSchemaError::UnexpectedEof);
+        }
+        self.limits.check_alloc(n)?;
+        if self.bit_offset == 0 {
+            let vec = self.bytes[self.byte_offset..self.byte_offset + n].to_vec();
+            self.byte_offset += n;
+            return Ok(vec);
+        }
+        let mut vec = vec![0; n];
+        self.read_bytes_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Fills `buf` with the next `buf.len()` bytes, without allocating.
+    /// Same alignment handling and errors as `read_bytes_vec`.
+    #[inline]
+    pub fn read_bytes_into(&mut self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() > self.whole_bytes_left() {
+            return Err(BinSchemaError::UnexpectedEof);
+        }
+        if self.bit_offset == 0 {
+            buf.copy_from_slice(&self.bytes[self.byte_offset..self.byte_offset + buf.len()]);
+            self.byte_offset += buf.len();
+        } else {
+            for byte in buf.iter_mut() {
+                *byte = self.read_uint8()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whole bytes that can still be read from the current bit position.
+    /// Compared against rather than summed, so an untrusted length can't overflow.
+    #[inline]
+    fn whole_bytes_left(&self) -> usize {
+        let left = self.limit.saturating_sub(self.byte_offset);
+        if self.bit_offset == 0 { left } else { left.saturating_sub(1) }
+    }
+
+    /// Bits that can still be read before the end of the input.
+    #[inline]
+    pub fn remaining_bits(&self) -> usize {
+        (self.limit.saturating_sub(self.byte_offset) * 8).saturating_sub(self.bit_offset as usize)
+    }
+
+    /// Whole bytes that can still be read. Mid-byte, the bits left in the
+    /// current byte only count once they complete a byte with the next one.
+    #[inline]
+    pub fn remaining_bytes(&self) -> usize {
+        self.remaining_bits() / 8
+    }
+
+    /// True while at least one bit is left to read.
+    #[inline]
+    pub fn has_remaining(&self) -> bool {
+        self.remaining_bits() > 0
+    }
+
+    /// Discards the unread bits of a partly read byte, so the next read starts
+    /// on a byte boundary. Does nothing when already aligned.
+    #[inline]
+    pub fn align_to_byte(&mut self) {
         if self.bit_offset != 0 {
-            return Err(BinSchemaError::InvalidValue("read_bytes_vec requires byte alignment".to_string()));
+            self.byte_offset += 1;
+            self.bit_offset = 0;
+        }
+    }
+
+    /// Aligns to a byte, then skips until the position is a multiple of
+    /// `boundary_bytes` from the start of the input. Fails with
+    /// `UnexpectedEof`, moving nothing, if the padding runs past the end.
+    #[inline]
+    pub fn align_to(&mut self, boundary_bytes: usize) -> Result<()> {
+        let start = self.byte_offset + usize::from(self.bit_offset != 0);
+        let padding = if boundary_bytes > 1 { (boundary_bytes - start % boundary_bytes) % boundary_bytes } else { 0 };
+        if padding > self.limit.saturating_sub(start) {
+            return Err(BinSchemaError::UnexpectedEof);
         }
-        if self.byte_offset + n > self.bytes.len() {
+        self.byte_offset = start + padding;
+        self.bit_offset = 0;
+        Ok(())
+    }
+
+    /// Advances past `n` bits without reading them. Fails with
+    /// `UnexpectedEof`, moving nothing, if fewer than `n` bits remain.
+    #[inline]
+    pub fn skip_bits(&mut self, n: u64) -> Result<()> {
+        if n > self.remaining_bits() as u64 {
             return Err(BinSchemaError::UnexpectedEof);
         }
-        let vec = self.bytes[self.byte_offset..self.byte_offset + n].to_vec();
-        self.byte_offset += n;
-        Ok(vec)
+        let total = u64::from(self.bit_offset) + n;
+        self.byte_offset += (total / 8) as usize;
+        self.bit_offset = (total % 8) as u8;
+        Ok(())
+    }
+
+    /// Advances past `n` bytes without reading them, keeping the position
+    /// within the current byte. Fails like `skip_bits`.
+    #[inline]
+    pub fn skip_bytes(&mut self, n: usize) -> Result<()> {
+        self.skip_bits((n as u64).checked_mul(8).ok_or(BinSchemaError::UnexpectedEof)?)
+    }
+
+    /// Consumes `expected.len()` bytes, failing if they differ from `expected`.
+    /// Only valid when byte-aligned. On any error (misalignment, EOF or
+    /// mismatch) the cursor is left where it was before the call.
+    #[inline]
+    pub fn expect_bytes(&mut self, expected: &[u8]) -> Result<()> {
+        if self.bit_offset != 0 {
+            return Err(BinSchemaError::InvalidValue("expect_bytes requires byte alignment".to_string()));
+        }
+        let end = self.byte_offset + expected.len();
+        if end > self.limit {
+            return Err(BinSchemaError::UnexpectedEof);
+        }
+        let actual = &self.bytes[self.byte_offset..end];
+        if actual != expected {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "Expected bytes {:02x?} at offset {}, got {:02x?}",
+                expected, self.byte_offset, actual
+            )));
+        }
+        self.byte_offset = end;
+        Ok(())
     }
 
     #[inline]
     pub fn read_uint8(&mut self) -> Result<u8> {
         if self.bit_offset == 0 {
             // Byte-aligned: read directly (same as TypeScript fast path)
-            if self.byte_offset >= self.bytes.len() {
+            if self.byte_offset >= self.limit {
                 return Err(BinSchemaError::UnexpectedEof);
             }
             let value = self.bytes[self.byte_offset];
@@ -552,8 +1171,9 @@ impl<'a> BitStreamDecoder<'a> {
 
     #[inline]
     pub fn read_uint16(&mut self, endianness: Endianness) -> Result<u16> {
+        let endianness = self.effective_endianness(endianness);
         if self.bit_offset == 0 {
-            if self.byte_offset + 2 > self.bytes.len() {
+            if self.byte_offset + 2 > self.limit {
                 return Err(BinSchemaError::UnexpectedEof);
             }
             let v = match endianness {
@@ -582,8 +1202,9 @@ impl<'a> BitStreamDecoder<'a> {
 
     #[inline]
     pub fn read_uint32(&mut self, endianness: Endianness) -> Result<u32> {
+        let endianness = self.effective_endianness(endianness);
         if self.bit_offset == 0 {
-            if self.byte_offset + 4 > self.bytes.len() {
+            if self.byte_offset + 4 > self.limit {
                 return Err(BinSchemaError::UnexpectedEof);
             }
             let bytes: [u8; 4] = [
@@ -620,8 +1241,9 @@ impl<'a> BitStreamDecoder<'a> {
 
     #[inline]
     pub fn read_uint64(&mut self, endianness: Endianness) -> Result<u64> {
+        let endianness = self.effective_endianness(endianness);
         if self.bit_offset == 0 {
-            if self.byte_offset + 8 > self.bytes.len() {
+            if self.byte_offset + 8 > self.limit {
                 return Err(BinSchemaError::UnexpectedEof);
             }
             let mut bytes = [0u8; 8];
@@ -633,19 +1255,35 @@ impl<'a> BitStreamDecoder<'a> {
             self.byte_offset += 8;
             return Ok(v);
         }
-        // Not byte-aligned: fallback
-        match endianness {
-            Endianness::BigEndian => {
-                let high = self.read_uint32(endianness)? as u64;
-                let low = self.read_uint32(endianness)? as u64;
-                Ok((high << 32) | low)
-            }
-            Endianness::LittleEndian => {
-                let low = self.read_uint32(endianness)? as u64;
-                let high = self.read_uint32(endianness)? as u64;
-                Ok((high << 32) | low)
-            }
+        // Not byte-aligned: fallback (byte by byte, as `endianness` is already resolved)
+        let mut bytes = [0u8; 8];
+        for byte in &mut bytes {
+            *byte = self.read_uint8()?;
         }
+        Ok(match endianness {
+            Endianness::BigEndian => u64::from_be_bytes(bytes),
+            Endianness::LittleEndian => u64::from_le_bytes(bytes),
+        })
+    }
+
+    /// Reads a 128-bit value as two 64-bit halves (see `write_uint128`)
+    #[inline]
+    pub fn read_uint128(&mut self, endianness: Endianness) -> Result<u128> {
+        // Each half applies any byte order swap itself; it also decides which half came first
+        let first = u128::from(self.read_uint64(endianness)?);
+        let second = u128::from(self.read_uint64(endianness)?);
+        Ok(match self.effective_endianness(endianness) {
+            Endianness::BigEndian => (first << 64) | second,
+            Endianness::LittleEndian => (second << 64) | first,
+        })
+    }
+
+    /// Reads a 32-bit value stored in PDP-11 "middle-endian" order (see `write_uint32_pdp`).
+    #[inline]
+    pub fn read_uint32_pdp(&mut self) -> Result<u32> {
+        let high = self.read_uint16(Endianness::LittleEndian)? as u32;
+        let low = self.read_uint16(Endianness::LittleEndian)? as u32;
+        Ok((high << 16) | low)
     }
 
     #[inline]
@@ -668,6 +1306,11 @@ impl<'a> BitStreamDecoder<'a> {
         Ok(self.read_uint64(endianness)? as i64)
     }
 
+    #[inline]
+    pub fn read_int128(&mut self, endianness: Endianness) -> Result<i128> {
+        Ok(self.read_uint128(endianness)? as i128)
+    }
+
     #[inline]
     pub fn read_float32(&mut self, endianness: Endianness) -> Result<f32> {
         Ok(f32::from_bits(self.read_uint32(endianness)?))
@@ -678,6 +1321,29 @@ impl<'a> BitStreamDecoder<'a> {
         Ok(f64::from_bits(self.read_uint64(endianness)?))
     }
 
+    /// Reads an IEEE 754 binary16, widened exactly to f32
+    #[inline]
+    pub fn read_float16(&mut self, endianness: Endianness) -> Result<f32> {
+        Ok(f16_bits_to_f32(self.read_uint16(endianness)?))
+    }
+
+    // --- Default byte order methods ---
+
+    #[inline]
+    pub fn read_u16(&mut self) -> Result<u16> {
+        self.read_uint16(self.default_endianness)
+    }
+
+    #[inline]
+    pub fn read_u32(&mut self) -> Result<u32> {
+        self.read_uint32(self.default_endianness)
+    }
+
+    #[inline]
+    pub fn read_u64(&mut self) -> Result<u64> {
+        self.read_uint64(self.default_endianness)
+    }
+
     // --- Byte-aligned fast-path methods ---
     // These skip the bit_offset check and endianness branch.
     // The generator emits these when it can prove at code-gen time
@@ -686,7 +1352,7 @@ impl<'a> BitStreamDecoder<'a> {
     #[inline]
     pub fn read_byte(&mut self) -> Result<u8> {
         debug_assert_eq!(self.bit_offset, 0, "read_byte called when not byte-aligned");
-        if self.byte_offset >= self.bytes.len() {
+        if self.byte_offset >= self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let value = self.bytes[self.byte_offset];
@@ -697,29 +1363,29 @@ impl<'a> BitStreamDecoder<'a> {
     #[inline]
     pub fn read_u16_le(&mut self) -> Result<u16> {
         debug_assert_eq!(self.bit_offset, 0, "read_u16_le called when not byte-aligned");
-        if self.byte_offset + 2 > self.bytes.len() {
+        if self.byte_offset + 2 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let v = u16::from_le_bytes([self.bytes[self.byte_offset], self.bytes[self.byte_offset + 1]]);
         self.byte_offset += 2;
-        Ok(v)
+        Ok(if self.swap_byte_order { v.swap_bytes() } else { v })
     }
 
     #[inline]
     pub fn read_u16_be(&mut self) -> Result<u16> {
         debug_assert_eq!(self.bit_offset, 0, "read_u16_be called when not byte-aligned");
-        if self.byte_offset + 2 > self.bytes.len() {
+        if self.byte_offset + 2 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let v = u16::from_be_bytes([self.bytes[self.byte_offset], self.bytes[self.byte_offset + 1]]);
         self.byte_offset += 2;
-        Ok(v)
+        Ok(if self.swap_byte_order { v.swap_bytes() } else { v })
     }
 
     #[inline]
     pub fn read_u32_le(&mut self) -> Result<u32> {
         debug_assert_eq!(self.bit_offset, 0, "read_u32_le called when not byte-aligned");
-        if self.byte_offset + 4 > self.bytes.len() {
+        if self.byte_offset + 4 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let bytes: [u8; 4] = [
@@ -729,13 +1395,14 @@ impl<'a> BitStreamDecoder<'a> {
             self.bytes[self.byte_offset + 3],
         ];
         self.byte_offset += 4;
-        Ok(u32::from_le_bytes(bytes))
+        let v = u32::from_le_bytes(bytes);
+        Ok(if self.swap_byte_order { v.swap_bytes() } else { v })
     }
 
     #[inline]
     pub fn read_u32_be(&mut self) -> Result<u32> {
         debug_assert_eq!(self.bit_offset, 0, "read_u32_be called when not byte-aligned");
-        if self.byte_offset + 4 > self.bytes.len() {
+        if self.byte_offset + 4 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let bytes: [u8; 4] = [
@@ -745,31 +1412,48 @@ impl<'a> BitStreamDecoder<'a> {
             self.bytes[self.byte_offset + 3],
         ];
         self.byte_offset += 4;
-        Ok(u32::from_be_bytes(bytes))
+        let v = u32::from_be_bytes(bytes);
+        Ok(if self.swap_byte_order { v.swap_bytes() } else { v })
     }
 
     #[inline]
     pub fn read_u64_le(&mut self) -> Result<u64> {
         debug_assert_eq!(self.bit_offset, 0, "read_u64_le called when not byte-aligned");
-        if self.byte_offset + 8 > self.bytes.len() {
+        if self.byte_offset + 8 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let mut bytes = [0u8; 8];
         bytes.copy_from_slice(&self.bytes[self.byte_offset..self.byte_offset + 8]);
         self.byte_offset += 8;
-        Ok(u64::from_le_bytes(bytes))
+        let v = u64::from_le_bytes(bytes);
+        Ok(if self.swap_byte_order { v.swap_bytes() } else { v })
     }
 
     #[inline]
     pub fn read_u64_be(&mut self) -> Result<u64> {
         debug_assert_eq!(self.bit_offset, 0, "read_u64_be called when not byte-aligned");
-        if self.byte_offset + 8 > self.bytes.len() {
+        if self.byte_offset + 8 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         let mut bytes = [0u8; 8];
         bytes.copy_from_slice(&self.bytes[self.byte_offset..self.byte_offset + 8]);
         self.byte_offset += 8;
-        Ok(u64::from_be_bytes(bytes))
+        let v = u64::from_be_bytes(bytes);
+        Ok(if self.swap_byte_order { v.swap_bytes() } else { v })
+    }
+
+    /// Reads a UUID written by `write_uuid`
+    #[inline]
+    pub fn read_uuid(&mut self) -> Result<[u8; 16]> {
+        let mut bytes = [0u8; 16];
+        self.read_bytes_into(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Reads a GUID-layout UUID, returning it in RFC 4122 order
+    #[inline]
+    pub fn read_guid(&mut self) -> Result<[u8; 16]> {
+        Ok(guid_swap(&self.read_uuid()?))
     }
 
     /// Reads a variable-length integer with specified encoding
@@ -785,6 +1469,44 @@ impl<'a> BitStreamDecoder<'a> {
         }
     }
 
+    /// Reads an unsigned LEB128 varint written by `write_varint_u64`, first
+    /// skipping the rest of a partly read byte as the encoder zero-filled it.
+    /// Fails with `InvalidValue` if it runs past ten bytes or overflows a u64.
+    #[inline]
+    pub fn read_varint_u64(&mut self) -> Result<u64> {
+        self.align_to_byte();
+        self.read_leb128()
+    }
+
+    /// LEB128 bytes read from the current bit position
+    #[inline]
+    fn read_leb128(&mut self) -> Result<u64> {
+        let mut result = 0u64;
+        let mut shift = 0u32;
+
+        loop {
+            let byte = self.read_uint8()?;
+            // The tenth byte holds only the top bit of a u64
+            if shift >= 64 || (shift == 63 && byte & 0x7E != 0) {
+                return Err(BinSchemaError::InvalidValue("LEB128 value too large".to_string()));
+            }
+            result |= ((byte & 0x7F) as u64) << shift;
+            shift += 7;
+
+            if (byte & 0x80) == 0 {
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Reads a signed integer written by `write_varint_i64`
+    #[inline]
+    pub fn read_varint_i64(&mut self) -> Result<i64> {
+        let value = self.read_varint_u64()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
     /// DER encoding: Short form (0-127) or long form (0x80+N followed by N bytes)
     #[inline]
     fn read_varlength_der(&mut self) -> Result<u64> {
@@ -807,23 +1529,7 @@ impl<'a> BitStreamDecoder<'a> {
     /// LEB128 encoding: 7 bits per byte, continuation bit in MSB, little-endian
     #[inline]
     fn read_varlength_leb128(&mut self) -> Result<u64> {
-        let mut result = 0u64;
-        let mut shift = 0u32;
-
-        loop {
-            let byte = self.read_uint8()?;
-            result |= ((byte & 0x7F) as u64) << shift;
-            shift += 7;
-
-            if shift > 64 {
-                return Err(BinSchemaError::InvalidValue("LEB128 value too large".to_string()));
-            }
-
-            if (byte & 0x80) == 0 {
-                break;
-            }
-        }
-        Ok(result)
+        self.read_leb128()
     }
 
     /// EBML encoding: Leading zeros indicate width, self-synchronizing
@@ -881,34 +1587,215 @@ impl<'a> BitStreamDecoder<'a> {
         Ok(result)
     }
 
-    /// Returns the current byte position in the stream
+    /// Returns the current byte position in the stream. Mid-byte, this is the
+    /// byte holding the next unread bit.
     #[inline]
     pub fn position(&self) -> usize {
         self.byte_offset
     }
 
-    /// Returns the total number of bytes in the stream
+    /// Fails with `InvalidValue` if `count` elements of at least
+    /// `min_element_bits` bits each can't fit in the rest of the input, or if
+    /// `count` exceeds `max_alloc`; the message names the bound it broke.
+    /// Generated decoders call this before allocating for a count read from the
+    /// input, so a forged count on a short message is rejected up front. With
+    /// `min_element_bits` of 0 only the `max_alloc` check applies. When fed by
+    /// a `PartialDecoder` the input may simply not have arrived yet, so a count
+    /// that doesn't fit fails with `UnexpectedEof` instead.
+    #[inline]
+    pub fn ensure_count_fits(&self, count: usize, min_element_bits: usize) -> Result<()> {
+        let remaining = self.remaining_bits();
+        if count.saturating_mul(min_element_bits) > remaining {
+            if self.input_incomplete {
+                return Err(BinSchemaError::UnexpectedEof);
+            }
+            return Err(BinSchemaError::InvalidValue(format!(
+                "count {} of at least {} bits each exceeds the {} bits remaining",
+                count, min_element_bits, remaining
+            )));
+        }
+        if count > self.limits.max_alloc {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "count {} exceeds max_alloc {}",
+                count, self.limits.max_alloc
+            )));
+        }
+        Ok(())
+    }
+
+    /// Marks the input as possibly cut short of the message, as a
+    /// `PartialDecoder` buffer is until enough bytes arrive
+    #[inline]
+    pub(crate) fn set_input_incomplete(&mut self) {
+        self.input_incomplete = true;
+    }
+
+    /// Caps an element count read from the input to a safe `Vec::with_capacity`
+    /// hint. Each element takes at least one byte of the remaining input in
+    /// practice, so a forged count can't trigger a huge up-front allocation;
+    /// the vector still grows normally if elements turn out smaller.
+    #[inline]
+    pub fn capacity_hint(&self, count: usize) -> usize {
+        count.min(self.limit.saturating_sub(self.byte_offset))
+    }
+
+    /// Switches to lenient decoding: problems reported through `recover` are
+    /// collected instead of failing the decode.
+    pub fn set_lenient(&mut self) {
+        self.field_errors.get_or_insert_with(Vec::new);
+    }
+
+    /// Reports a recoverable problem with `field` (a bad const, a length field
+    /// that disagrees with the decoded data). Lenient decoders record it and
+    /// return `Ok` so the caller can substitute a value and carry on; strict
+    /// decoders return it as the error.
+    pub fn recover(&mut self, field: &str, error: BinSchemaError) -> Result<()> {
+        match &mut self.field_errors {
+            Some(errors) => {
+                errors.push(FieldError { field: field.to_string(), error });
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+
+    /// Runs `f` with lenient mode suspended. Trial decodes (choice variants)
+    /// rely on the first mismatch failing, so they must not be recovered.
+    pub fn strict<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let saved = self.field_errors.take();
+        let result = f(self);
+        self.field_errors = saved;
+        result
+    }
+
+    /// Installs hardening limits, failing if the message is already longer
+    /// than `max_message_len`
+    pub fn set_limits(&mut self, limits: DecodeLimits) -> Result<()> {
+        limits.check_message_len(self.message_len)?;
+        self.limits = limits;
+        Ok(())
+    }
+
+    /// The limits in force, for checks made by generated code
+    #[inline]
+    pub fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+
+    /// Runs `f` one nesting level deeper, failing first if that exceeds
+    /// `max_depth`. The level is released however `f` returns.
+    #[inline]
+    pub fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.limits.check_depth(self.depth + 1)?;
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Takes the field errors collected so far in lenient mode
+    pub fn take_field_errors(&mut self) -> Vec<FieldError> {
+        self.field_errors.as_mut().map(core::mem::take).unwrap_or_default()
+    }
+
+    /// Starts recording the back-references (compression pointers) followed
+    /// during decode.
+    pub fn log_pointers(&mut self) {
+        self.pointer_log.get_or_insert_with(Vec::new);
+    }
+
+    /// Records a back-reference read at `pointer_position` that resolved to
+    /// `target_offset`. A no-op unless `log_pointers` was called.
+    #[inline]
+    pub fn record_pointer(&mut self, pointer_position: usize, target_offset: usize) {
+        if let Some(log) = &mut self.pointer_log {
+            log.push((pointer_position, target_offset));
+        }
+    }
+
+    /// Takes the `(pointer_position, target_offset)` pairs recorded so far
+    pub fn take_pointer_log(&mut self) -> Vec<(usize, usize)> {
+        self.pointer_log.as_mut().map(core::mem::take).unwrap_or_default()
+    }
+
+    /// Reads every following multi-byte value in the opposite byte order to
+    /// the one the caller asks for. Used to decode a message written in the
+    /// other endianness with decoders generated for a fixed one.
+    pub fn swap_byte_order(&mut self) {
+        self.swap_byte_order = true;
+    }
+
+    /// `endianness`, or the opposite one after `swap_byte_order`
+    #[inline]
+    fn effective_endianness(&self, endianness: Endianness) -> Endianness {
+        match (self.swap_byte_order, endianness) {
+            (false, e) => e,
+            (true, Endianness::BigEndian) => Endianness::LittleEndian,
+            (true, Endianness::LittleEndian) => Endianness::BigEndian,
+        }
+    }
+
+    /// Returns the unconsumed tail of the message. A partially read byte counts
+    /// as consumed, so the tail always starts on a byte boundary.
+    #[inline]
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        let start = self.byte_offset + usize::from(self.bit_offset != 0);
+        self.bytes.get(start..self.message_len).unwrap_or_default()
+    }
+
+    /// Returns the total number of bytes in the stream (the message length when bounded)
     #[inline]
     pub fn bytes_len(&self) -> usize {
-        self.bytes.len()
+        self.message_len
     }
 
     /// Seeks to a specific byte position in the stream
-    /// Note: This resets the bit offset to 0
+    /// Note: This resets the bit offset to 0. Seeking past the end of a bounded
+    /// message lifts the read bound to the full buffer until seeking back.
+    /// Seeking past the end of the buffer fails with `UnexpectedEof`.
     #[inline]
     pub fn seek(&mut self, pos: usize) -> Result<()> {
         if pos > self.bytes.len() {
-            return Err(BinSchemaError::InvalidValue(format!("Seek position {} is past end of data", pos)));
+            return Err(BinSchemaError::UnexpectedEof);
         }
         self.byte_offset = pos;
         self.bit_offset = 0;
+        self.limit = if pos <= self.message_len { self.message_len } else { self.bytes.len() };
         Ok(())
     }
 
+    /// Rewinds to a byte position taken earlier from `position()`, dropping any
+    /// partially read byte. This is the "try variant" primitive: save the
+    /// position, attempt a decode, and rewind before attempting the next one.
+    /// A failed attempt may have stopped anywhere, including behind `pos` at
+    /// the target of a back-reference, so `pos` isn't required to be behind.
+    /// A `pos` past the end of the buffer is a caller bug: it panics in debug
+    /// builds and rewinds to the end otherwise.
+    #[inline]
+    pub fn rewind_to(&mut self, pos: usize) {
+        debug_assert!(
+            pos <= self.bytes.len(),
+            "rewind_to({}) past the end of a {}-byte buffer",
+            pos,
+            self.bytes.len()
+        );
+        let pos = pos.min(self.bytes.len());
+        self.byte_offset = pos;
+        self.bit_offset = 0;
+        self.limit = if pos <= self.message_len { self.message_len } else { self.bytes.len() };
+    }
+
+    /// Returns to the start of the message, keeping the limits, byte order
+    /// settings and nesting depth.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.rewind_to(0);
+    }
+
     /// Peeks at the next byte without consuming it
     #[inline]
     pub fn peek_uint8(&self) -> Result<u8> {
-        if self.byte_offset >= self.bytes.len() {
+        if self.byte_offset >= self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         // If we're in the middle of a byte, we can't peek properly
@@ -921,7 +1808,8 @@ impl<'a> BitStreamDecoder<'a> {
     /// Peeks at the next 2 bytes as uint16 without consuming them
     #[inline]
     pub fn peek_uint16(&self, endianness: Endianness) -> Result<u16> {
-        if self.byte_offset + 2 > self.bytes.len() {
+        let endianness = self.effective_endianness(endianness);
+        if self.byte_offset + 2 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         if self.bit_offset != 0 {
@@ -944,7 +1832,8 @@ impl<'a> BitStreamDecoder<'a> {
     /// Peeks at the next 4 bytes as uint32 without consuming them
     #[inline]
     pub fn peek_uint32(&self, endianness: Endianness) -> Result<u32> {
-        if self.byte_offset + 4 > self.bytes.len() {
+        let endianness = self.effective_endianness(endianness);
+        if self.byte_offset + 4 > self.limit {
             return Err(BinSchemaError::UnexpectedEof);
         }
         if self.bit_offset != 0 {
@@ -969,6 +1858,73 @@ impl<'a> BitStreamDecoder<'a> {
     }
 }
 
+/// Converts between RFC 4122 and GUID byte order by reversing the
+/// 4-, 2- and 2-byte leading groups; applying it twice is the identity
+fn guid_swap(value: &[u8; 16]) -> [u8; 16] {
+    let mut bytes = *value;
+    bytes[0..4].reverse();
+    bytes[4..6].reverse();
+    bytes[6..8].reverse();
+    bytes
+}
+
+/// Narrows an f32 to binary16 bits, rounding to nearest even. NaN stays a
+/// quiet NaN keeping the top of its payload.
+fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    if exponent == 0xFF {
+        let payload = if mantissa == 0 { 0 } else { 0x0200 | (mantissa >> 13) as u16 };
+        return sign | 0x7C00 | payload;
+    }
+    let unbiased = exponent - 127;
+    if unbiased > 15 {
+        return sign | 0x7C00;
+    }
+    if unbiased < -25 {
+        return sign;
+    }
+
+    // Keep the bits above `shift` and round on the rest; a carry out of the
+    // mantissa correctly bumps the exponent, up to infinity
+    let (kept, shift) = if unbiased >= -14 {
+        ((((unbiased + 15) as u32) << 23) | mantissa, 13)
+    } else {
+        (mantissa | 0x80_0000, (-unbiased - 1) as u32)
+    };
+    let mut half = kept >> shift;
+    let rest = kept & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    if rest > halfway || (rest == halfway && half & 1 == 1) {
+        half += 1;
+    }
+    sign | half as u16
+}
+
+/// Widens binary16 bits to the f32 with the same value
+fn f16_bits_to_f32(half: u16) -> f32 {
+    let sign = u32::from(half & 0x8000) << 16;
+    let exponent = u32::from((half >> 10) & 0x1F);
+    let mantissa = u32::from(half & 0x3FF);
+
+    match exponent {
+        0 => {
+            // Zero or subnormal: mantissa units of 2^-24, exact in f32
+            let magnitude = mantissa as f32 / (1u32 << 24) as f32;
+            if sign != 0 {
+                -magnitude
+            } else {
+                magnitude
+            }
+        }
+        0x1F => f32::from_bits(sign | 0x7F80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1000,6 +1956,804 @@ mod tests {
         assert_eq!(decoder.read_uint16(Endianness::BigEndian).unwrap(), 0x1234);
     }
 
+    #[test]
+    fn test_uint32_pdp_byte_order() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_uint32_pdp(0x0A0B0C0D);
+
+        let bytes = encoder.finish();
+        assert_eq!(bytes, vec![0x0B, 0x0A, 0x0D, 0x0C]);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_uint32_pdp().unwrap(), 0x0A0B0C0D);
+    }
+
+    /// Model of the stream a multi-byte write should produce after
+    /// `prefix_bits` one-bits: aligned bytes are copied as-is, unaligned bytes
+    /// are written LSB of the value first (matching the TypeScript runtime),
+    /// and each stream bit lands where `bit_order` puts it within its byte.
+    fn expected_stream(prefix_bits: usize, value_bytes: &[u8], bit_order: BitOrder) -> Vec<u8> {
+        if prefix_bits == 0 {
+            return value_bytes.to_vec();
+        }
+        let mut bits = vec![1u8; prefix_bits];
+        for byte in value_bytes {
+            bits.extend((0..8).map(|i| (byte >> i) & 1));
+        }
+        let mut out = vec![0u8; bits.len().div_ceil(8)];
+        for (k, bit) in bits.iter().enumerate() {
+            let shift = match bit_order {
+                BitOrder::MsbFirst => 7 - k % 8,
+                BitOrder::LsbFirst => k % 8,
+            };
+            out[k / 8] |= bit << shift;
+        }
+        out
+    }
+
+    #[test]
+    fn test_multi_byte_bit_order_matrix() {
+        const VALUE: u64 = 0x0123_4567_89AB_CDEF;
+        for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            for endianness in [Endianness::BigEndian, Endianness::LittleEndian] {
+                for width in [2usize, 4, 8] {
+                    let value = if width == 8 { VALUE } else { VALUE & ((1u64 << (width * 8)) - 1) };
+                    let be = value.to_be_bytes();
+                    let mut value_bytes = be[8 - width..].to_vec();
+                    if endianness == Endianness::LittleEndian {
+                        value_bytes.reverse();
+                    }
+
+                    for prefix_bits in [0usize, 1, 3, 7] {
+                        let case = format!("{:?} {:?} u{} after {} bits", bit_order, endianness, width * 8, prefix_bits);
+
+                        let mut encoder = BitStreamEncoder::new(bit_order);
+                        if prefix_bits > 0 {
+                            encoder.write_bits((1u64 << prefix_bits) - 1, prefix_bits as u8);
+                        }
+                        match width {
+                            2 => encoder.write_uint16(value as u16, endianness),
+                            4 => encoder.write_uint32(value as u32, endianness),
+                            _ => encoder.write_uint64(value, endianness),
+                        }
+                        let bytes = encoder.finish();
+                        assert_eq!(bytes, expected_stream(prefix_bits, &value_bytes, bit_order), "{}", case);
+
+                        let mut decoder = BitStreamDecoder::new(&bytes, bit_order);
+                        if prefix_bits > 0 {
+                            assert_eq!(decoder.read_bits(prefix_bits as u8).unwrap(), (1u64 << prefix_bits) - 1, "{}", case);
+                        }
+                        let decoded = match width {
+                            2 => decoder.read_uint16(endianness).unwrap() as u64,
+                            4 => decoder.read_uint32(endianness).unwrap() as u64,
+                            _ => decoder.read_uint64(endianness).unwrap(),
+                        };
+                        assert_eq!(decoded, value, "{}", case);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reserve_and_patch_checksum() {
+        let payload = b"binschema";
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_uint8(0x01);
+        let checksum = encoder.reserve(4).unwrap();
+        assert_eq!((checksum.offset(), checksum.end()), (1, 5));
+        for &b in payload {
+            encoder.write_uint8(b);
+        }
+        let crc = crate::crc32(&encoder.buffer()[checksum.end()..]);
+        encoder.patch_reservation(checksum, &crc.to_be_bytes()).unwrap();
+        let bytes = encoder.finish();
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_uint8().unwrap(), 0x01);
+        let stored = decoder.read_uint32(Endianness::BigEndian).unwrap();
+        let body = decoder.read_bytes_vec(payload.len()).unwrap();
+        assert_eq!(body, payload);
+        assert_eq!(stored, crate::crc32(&body));
+    }
+
+    #[test]
+    fn test_patch_checksum_png_chunk_trailer() {
+        // An empty IEND chunk: length, type, then the CRC of type and data
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_uint32(0, Endianness::BigEndian);
+        let start = encoder.byte_offset();
+        encoder.write_bytes(b"IEND");
+        let end = encoder.byte_offset();
+        let crc = encoder.reserve(4).unwrap();
+        let value = encoder.patch_checksum(crc, Checksum::Crc32, start..end, Endianness::BigEndian).unwrap();
+        assert_eq!(value, 0xAE42_6082);
+        assert_eq!(encoder.finish(), vec![0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+    }
+
+    #[test]
+    fn test_patch_checksum_header_before_body() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        let slot = encoder.reserve(2).unwrap();
+        encoder.write_bytes(b"123456789");
+        let body = slot.end()..encoder.byte_offset();
+        encoder.patch_checksum(slot, Checksum::Crc16Ccitt, body, Endianness::LittleEndian).unwrap();
+        assert_eq!(&encoder.buffer()[..2], &[0xB1, 0x29]);
+
+        // The range must be written and must not cover the checksum itself
+        assert!(encoder.patch_checksum(slot, Checksum::Crc16Ccitt, 0..4, Endianness::BigEndian).is_err());
+        assert!(encoder.patch_checksum(slot, Checksum::Crc16Ccitt, 2..20, Endianness::BigEndian).is_err());
+    }
+
+    #[test]
+    fn test_length_prefix_back_patch() {
+        // rdlength followed by the rdata it covers, then a trailing field
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        let rdlength = encoder.reserve_u16().unwrap();
+        let start = encoder.byte_offset();
+        encoder.write_bytes(&[93, 184, 216, 34]);
+        encoder.patch_u16(rdlength, (encoder.byte_offset() - start) as u16, Endianness::BigEndian).unwrap();
+        let total = encoder.reserve_u32().unwrap();
+        encoder.patch_u32(total, 0x0102_0304, Endianness::LittleEndian).unwrap();
+        assert_eq!(encoder.finish(), vec![0, 4, 93, 184, 216, 34, 4, 3, 2, 1]);
+
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        let slot = encoder.reserve_u16().unwrap();
+        assert!(encoder.patch_u32(slot, 4, Endianness::BigEndian).is_err());
+    }
+
+    #[test]
+    fn test_reservation_bounds_checks() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        let res = encoder.reserve(2).unwrap();
+        assert!(encoder.patch_reservation(res, &[1, 2, 3]).is_err());
+        assert!(encoder.patch_reservation(res, &[1]).is_err());
+
+        // A reservation from a longer stream doesn't fit after clear()
+        encoder.clear();
+        assert!(encoder.patch_reservation(res, &[1, 2]).is_err());
+
+        encoder.write_bits(1, 3);
+        assert!(encoder.reserve(1).is_err());
+    }
+
+    #[test]
+    fn test_swap_byte_order_reads_other_endianness() {
+        for prefix_bits in [0u8, 3] {
+            let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+            if prefix_bits > 0 {
+                encoder.write_bits(0, prefix_bits);
+            }
+            encoder.write_uint16(0x1234, Endianness::LittleEndian);
+            encoder.write_uint32(0x1234_5678, Endianness::LittleEndian);
+            encoder.write_uint64(0x0123_4567_89AB_CDEF, Endianness::LittleEndian);
+            let bytes = encoder.finish();
+
+            let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+            decoder.swap_byte_order();
+            if prefix_bits > 0 {
+                decoder.read_bits(prefix_bits).unwrap();
+            }
+            assert_eq!(decoder.read_uint16(Endianness::BigEndian).unwrap(), 0x1234);
+            assert_eq!(decoder.read_uint32(Endianness::BigEndian).unwrap(), 0x1234_5678);
+            assert_eq!(decoder.read_uint64(Endianness::BigEndian).unwrap(), 0x0123_4567_89AB_CDEF);
+        }
+
+        // Byte-aligned fast paths honour the swap too
+        let bytes = [0x34, 0x12, 0x78, 0x56, 0x34, 0x12];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.swap_byte_order();
+        assert_eq!(decoder.peek_uint16(Endianness::BigEndian).unwrap(), 0x1234);
+        assert_eq!(decoder.read_u16_be().unwrap(), 0x1234);
+        assert_eq!(decoder.read_u32_be().unwrap(), 0x1234_5678);
+    }
+
+    #[test]
+    fn test_unaligned_uint16_exact_bytes() {
+        // One flag bit, then 0x1234 big-endian: the bytes 12 34 are shifted
+        // into the stream one bit along, in the byte's bit order
+        let mut encoder = BitStreamEncoder::new(BitOrder::LsbFirst);
+        encoder.write_bits(1, 1);
+        encoder.write_uint16(0x1234, Endianness::BigEndian);
+        assert_eq!(encoder.finish(), vec![0x25, 0x68, 0x00]);
+
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_bits(1, 1);
+        encoder.write_uint16(0x1234, Endianness::BigEndian);
+        assert_eq!(encoder.finish(), vec![0xA4, 0x16, 0x00]);
+    }
+
+    #[test]
+    fn test_encoder_bit_alignment() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        assert!(encoder.is_byte_aligned());
+
+        encoder.write_bits(0b101, 3);
+        assert_eq!(encoder.bit_position_in_byte(), 3);
+        assert!(!encoder.is_byte_aligned());
+
+        encoder.write_bits(0, 5);
+        assert_eq!(encoder.bit_position_in_byte(), 0);
+        assert!(encoder.is_byte_aligned());
+        assert_eq!(encoder.byte_offset(), 1);
+    }
+
+    #[test]
+    fn test_byte_offset_counts_partial_byte() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        assert_eq!(encoder.byte_offset(), 0);
+        encoder.write_uint8(0xAB);
+        encoder.write_uint16(0x1234, Endianness::BigEndian);
+        assert_eq!(encoder.byte_offset(), 3);
+
+        // Three bits into the fourth byte: it already counts
+        encoder.write_bits(0b101, 3);
+        assert_eq!(encoder.byte_offset(), 4);
+        encoder.write_bits(0, 5);
+        assert_eq!(encoder.byte_offset(), 4);
+
+        encoder.write_bits(1, 1);
+        assert_eq!(encoder.byte_offset(), 5);
+        assert_eq!(encoder.finish().len(), 5);
+    }
+
+    #[test]
+    fn test_decoder_borrows_input() {
+        let bytes = vec![0x01, 0x02, 0x03];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.read_uint8().unwrap();
+        // The tail is the caller's own memory, not a copy
+        assert!(core::ptr::eq(decoder.remaining_slice(), &bytes[1..]));
+    }
+
+    #[test]
+    fn test_bounded_decoder_stops_at_message_len() {
+        // 3-byte message followed by unrelated trailing bytes in the same buffer
+        let bytes = [0x01, 0x02, 0x03, 0xAA, 0xBB];
+        let mut decoder = BitStreamDecoder::new_bounded(&bytes, 3, BitOrder::MsbFirst).unwrap();
+        assert_eq!(decoder.bytes_len(), 3);
+
+        let mut items = Vec::new();
+        while decoder.position() < decoder.bytes_len() {
+            items.push(decoder.read_uint8().unwrap());
+        }
+        assert_eq!(items, vec![0x01, 0x02, 0x03]);
+        assert!(matches!(decoder.read_uint8(), Err(BinSchemaError::UnexpectedEof)));
+
+        // A back-reference can still reach past the message, then return
+        decoder.seek(4).unwrap();
+        assert_eq!(decoder.read_uint8().unwrap(), 0xBB);
+        decoder.seek(3).unwrap();
+        assert!(matches!(decoder.read_uint8(), Err(BinSchemaError::UnexpectedEof)));
+
+        assert!(BitStreamDecoder::new_bounded(&bytes, 6, BitOrder::MsbFirst).is_err());
+    }
+
+    #[test]
+    fn test_remaining_slice_chains_messages() {
+        // Two length-prefixed messages back to back
+        let input = [0x02, 0xAA, 0xBB, 0x01, 0xCC];
+
+        fn parse(input: &[u8]) -> Result<(Vec<u8>, &[u8])> {
+            let mut decoder = BitStreamDecoder::new(input, BitOrder::MsbFirst);
+            let len = decoder.read_uint8()? as usize;
+            let body = decoder.read_bytes_vec(len)?;
+            Ok((body, decoder.remaining_slice()))
+        }
+
+        let (first, tail) = parse(&input).unwrap();
+        assert_eq!(first, vec![0xAA, 0xBB]);
+        let (second, tail) = parse(tail).unwrap();
+        assert_eq!(second, vec![0xCC]);
+        assert!(tail.is_empty());
+
+        // A partially consumed byte is skipped
+        let mut decoder = BitStreamDecoder::new(&input, BitOrder::MsbFirst);
+        decoder.read_bits(3).unwrap();
+        assert_eq!(decoder.remaining_slice(), &input[1..]);
+    }
+
+    #[test]
+    fn test_write_repeated() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_uint8(0x01);
+        encoder.write_repeated(0xFF, 1000);
+        assert!(encoder.is_byte_aligned());
+        let bytes = encoder.finish();
+        assert_eq!(bytes.len(), 1001);
+        assert_eq!(bytes[0], 0x01);
+        assert!(bytes[1..].iter().all(|&b| b == 0xFF));
+
+        // Aligned, the fill is one resize: the buffer grows once to exactly
+        // its length, where pushing byte by byte would double past it to 1024
+        let mut aligned = BitStreamEncoder::new(BitOrder::MsbFirst);
+        aligned.write_repeated(0x00, 1000);
+        assert_eq!(aligned.buffer.len(), 1000);
+        assert_eq!(aligned.buffer.capacity(), 1000);
+
+        // Unaligned fills match writing the bytes one at a time
+        let mut repeated = BitStreamEncoder::new(BitOrder::MsbFirst);
+        repeated.write_bits(1, 3);
+        repeated.write_repeated(0xA5, 3);
+        let mut looped = BitStreamEncoder::new(BitOrder::MsbFirst);
+        looped.write_bits(1, 3);
+        for _ in 0..3 {
+            looped.write_uint8(0xA5);
+        }
+        assert_eq!(repeated.finish(), looped.finish());
+    }
+
+    #[test]
+    fn test_skip_bits_and_bytes() {
+        let bytes = [0xE0, 0xAB, 0xCD, 0xEF];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.skip_bits(3).unwrap();
+        assert_eq!(decoder.read_bits(5).unwrap(), 0x00);
+        assert_eq!(decoder.read_uint8().unwrap(), 0xAB);
+
+        // Skipping 3 bits then a byte lands mid-byte; 5 more realign
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.skip_bits(3).unwrap();
+        decoder.skip_bytes(1).unwrap();
+        assert_eq!(decoder.read_bits(8).unwrap(), 0x5E);
+        decoder.skip_bits(5).unwrap();
+        assert_eq!(decoder.position(), 3);
+        assert_eq!(decoder.read_uint8().unwrap(), 0xEF);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.skip_bits(1).unwrap();
+        assert!(matches!(decoder.skip_bytes(4), Err(BinSchemaError::UnexpectedEof)));
+        decoder.skip_bits(31).unwrap();
+        assert!(decoder.skip_bits(1).is_err());
+        assert!(decoder.skip_bytes(0).is_ok());
+    }
+
+    #[test]
+    fn test_remaining_counts_mid_byte() {
+        let bytes = [0xAB, 0xCD, 0xEF];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!((decoder.remaining_bits(), decoder.remaining_bytes()), (24, 3));
+
+        decoder.read_bits(3).unwrap();
+        assert_eq!((decoder.remaining_bits(), decoder.remaining_bytes()), (21, 2));
+        decoder.read_bits(5).unwrap();
+        assert_eq!((decoder.remaining_bits(), decoder.remaining_bytes()), (16, 2));
+        decoder.read_bits(15).unwrap();
+        assert_eq!((decoder.remaining_bits(), decoder.remaining_bytes()), (1, 0));
+        assert!(decoder.has_remaining());
+        decoder.read_bits(1).unwrap();
+        assert!(!decoder.has_remaining());
+
+        // A bounded decoder only counts up to its bound
+        let decoder = BitStreamDecoder::new_bounded(&bytes, 2, BitOrder::MsbFirst).unwrap();
+        assert_eq!(decoder.remaining_bytes(), 2);
+    }
+
+    #[test]
+    fn test_align_mid_byte_round_trip() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_bits(0b101, 3);
+        encoder.align_to_byte();
+        encoder.align_to_byte();
+        encoder.write_bits(0b11, 2);
+        encoder.align_to(4);
+        encoder.write_uint8(0xAB);
+        encoder.align_to(4);
+        encoder.align_to(1);
+        let bytes = encoder.finish();
+        assert_eq!(bytes, vec![0xA0, 0xC0, 0x00, 0x00, 0xAB, 0x00, 0x00, 0x00]);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_bits(3).unwrap(), 0b101);
+        decoder.align_to_byte();
+        decoder.align_to_byte();
+        assert_eq!(decoder.read_bits(2).unwrap(), 0b11);
+        decoder.align_to(4).unwrap();
+        assert_eq!(decoder.position(), 4);
+        assert_eq!(decoder.read_uint8().unwrap(), 0xAB);
+        decoder.align_to(4).unwrap();
+        assert!(!decoder.has_remaining());
+
+        // Padding past the end fails without moving
+        let mut decoder = BitStreamDecoder::new(&bytes[..6], BitOrder::MsbFirst);
+        decoder.skip_bits(33).unwrap();
+        assert!(matches!(decoder.align_to(4), Err(BinSchemaError::UnexpectedEof)));
+        assert_eq!(decoder.remaining_bits(), 15);
+    }
+
+    #[test]
+    fn test_decoder_limits() {
+        let bytes = [0u8; 8];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        let limits = DecodeLimits { max_alloc: 4, max_depth: 2, ..DecodeLimits::unlimited() };
+        decoder.set_limits(limits).unwrap();
+        assert!(matches!(decoder.read_bytes_vec(5), Err(BinSchemaError::LimitExceeded(_))));
+        assert!(matches!(decoder.ensure_count_fits(5, 8), Err(BinSchemaError::InvalidValue(_))));
+        assert_eq!(decoder.read_bytes_vec(4).unwrap(), vec![0; 4]);
+
+        // Two levels nest; a third fails, and failures release their level
+        let depth = decoder.nested(|d| d.nested(|d| Ok(d.nested(|_| Ok(())).is_err())));
+        assert_eq!(depth, Ok(true));
+        assert!(decoder.nested(|_| Err::<(), _>(BinSchemaError::UnexpectedEof)).is_err());
+        assert!(decoder.nested(|d| d.nested(|_| Ok(()))).is_ok());
+
+        let too_short = DecodeLimits { max_message_len: 7, ..DecodeLimits::unlimited() };
+        assert!(matches!(decoder.set_limits(too_short), Err(BinSchemaError::LimitExceeded(_))));
+    }
+
+    #[test]
+    fn test_uint128_and_int128_round_trip() {
+        let pattern = 0x0001_0203_0405_0607_0809_0A0B_0C0D_0E0F_u128;
+        for endianness in [Endianness::BigEndian, Endianness::LittleEndian] {
+            let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+            encoder.write_uint128(u128::MAX, endianness);
+            encoder.write_uint128(pattern, endianness);
+            encoder.write_int128(i128::MIN, endianness);
+            encoder.write_bits(1, 1);
+            encoder.write_int128(-2, endianness);
+            let bytes = encoder.finish();
+            let expected = match endianness {
+                Endianness::BigEndian => pattern.to_be_bytes(),
+                Endianness::LittleEndian => pattern.to_le_bytes(),
+            };
+            assert_eq!(&bytes[16..32], &expected);
+
+            let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+            assert_eq!(decoder.read_uint128(endianness).unwrap(), u128::MAX);
+            assert_eq!(decoder.read_uint128(endianness).unwrap(), pattern);
+            assert_eq!(decoder.read_int128(endianness).unwrap(), i128::MIN);
+            assert_eq!(decoder.read_bits(1).unwrap(), 1);
+            assert_eq!(decoder.read_int128(endianness).unwrap(), -2);
+        }
+    }
+
+    #[test]
+    fn test_wide_bit_fields_round_trip_in_both_bit_orders() {
+        let mac72 = 0xA1_B2C3_D4E5_F607_1829_u128;
+        let flags128 = 0x8000_0000_0000_0001_0123_4567_89AB_CDEF_u128;
+        for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut encoder = BitStreamEncoder::new(bit_order);
+            encoder.write_bits(0b101, 3);
+            encoder.write_bits_u128(mac72, 72);
+            encoder.write_bits_u128(flags128, 128);
+            let bytes = encoder.finish();
+
+            // Same bits as writing the value one bit at a time in stream order
+            let mut reference = BitStreamEncoder::new(bit_order);
+            reference.write_bits(0b101, 3);
+            for (value, width) in [(mac72, 72), (flags128, 128)] {
+                for i in 0..width {
+                    let bit_index = match bit_order {
+                        BitOrder::MsbFirst => width - 1 - i,
+                        BitOrder::LsbFirst => i,
+                    };
+                    reference.write_bits((value >> bit_index) as u64 & 1, 1);
+                }
+            }
+            assert_eq!(bytes, reference.finish());
+
+            let mut decoder = BitStreamDecoder::new(&bytes, bit_order);
+            assert_eq!(decoder.read_bits(3).unwrap(), 0b101);
+            assert_eq!(decoder.read_bits_u128(72).unwrap(), mac72);
+            assert_eq!(decoder.read_bits_u128(128).unwrap(), flags128);
+
+            let mut decoder = BitStreamDecoder::new(&bytes, bit_order);
+            decoder.skip_bits(3).unwrap();
+            let mut mac = [0xFF; 10];
+            decoder.read_bits_into(&mut mac, 72).unwrap();
+            assert_eq!(mac, mac72.to_be_bytes()[6..]);
+            let mut flags = [0; 16];
+            decoder.read_bits_into(&mut flags, 128).unwrap();
+            assert_eq!(flags, flags128.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_wide_bit_reads_reject_bad_widths_without_consuming() {
+        let bytes = [0xAB; 8];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.read_bits(4).unwrap();
+        assert_eq!(decoder.read_bits_u128(72), Err(BinSchemaError::UnexpectedEof));
+        assert!(matches!(decoder.read_bits_u128(129), Err(BinSchemaError::InvalidValue(_))));
+        let mut short = [0; 1];
+        assert!(matches!(decoder.read_bits_into(&mut short, 9), Err(BinSchemaError::InvalidValue(_))));
+        let mut buf = [0; 9];
+        assert_eq!(decoder.read_bits_into(&mut buf, 72), Err(BinSchemaError::UnexpectedEof));
+
+        // 12 bits into two bytes: the top nibble stays zero
+        let mut two = [0; 2];
+        decoder.read_bits_into(&mut two, 12).unwrap();
+        assert_eq!(two, [0x0B, 0xAB]);
+        assert_eq!(decoder.remaining_bits(), 48);
+    }
+
+    #[test]
+    fn test_default_endianness_mixed_with_explicit() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst).with_default_endianness(Endianness::LittleEndian);
+        encoder.write_u16(0x0102);
+        encoder.write_uint16(0x0102, Endianness::BigEndian);
+        encoder.write_u32(0x0304_0506);
+        encoder.write_bits(0, 4);
+        encoder.write_u64(0x0708);
+        let bytes = encoder.finish();
+        assert_eq!(&bytes[..8], &[0x02, 0x01, 0x01, 0x02, 0x06, 0x05, 0x04, 0x03]);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst).with_default_endianness(Endianness::LittleEndian);
+        assert_eq!(decoder.read_u16().unwrap(), 0x0102);
+        assert_eq!(decoder.read_uint16(Endianness::BigEndian).unwrap(), 0x0102);
+        assert_eq!(decoder.read_u32().unwrap(), 0x0304_0506);
+        assert_eq!(decoder.read_bits(4).unwrap(), 0);
+        assert_eq!(decoder.read_u64().unwrap(), 0x0708);
+
+        // Without a default, the parameterless methods are big-endian
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_u16().unwrap(), 0x0201);
+    }
+
+    #[test]
+    fn test_rewind_to_retries_variants() {
+        // A union whose first variant expects a 0xAA tag after a 4-bit field,
+        // and whose second variant is a plain u16
+        let bytes = [0x5B, 0xCD];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        let start_pos = decoder.position();
+
+        assert_eq!(decoder.read_bits(4).unwrap(), 0x5);
+        assert_ne!(decoder.read_bits(8).unwrap(), 0xAA);
+        decoder.rewind_to(start_pos);
+
+        assert_eq!(decoder.read_uint16(Endianness::BigEndian).unwrap(), 0x5BCD);
+        assert!(!decoder.has_remaining());
+
+        decoder.reset();
+        assert_eq!(decoder.position(), 0);
+        assert_eq!(decoder.read_uint8().unwrap(), 0x5B);
+    }
+
+    #[test]
+    fn test_write_bytes_aligned_and_mid_byte() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_bits(0b101, 3);
+        encoder.write_bytes(&[0xFF, 0x00]);
+        encoder.write_bits(0, 5);
+        encoder.write_bytes(&[0xCA, 0xFE]);
+        let bytes = encoder.finish();
+
+        let mut expected = BitStreamEncoder::new(BitOrder::MsbFirst);
+        expected.write_bits(0b101, 3);
+        expected.write_uint8(0xFF);
+        expected.write_uint8(0x00);
+        expected.write_bits(0, 5);
+        expected.write_uint8(0xCA);
+        expected.write_uint8(0xFE);
+        assert_eq!(bytes, expected.finish());
+        assert_eq!(bytes, vec![0xBF, 0xE0, 0x00, 0xCA, 0xFE]);
+    }
+
+    #[test]
+    fn test_try_write_bits_rejects_bad_widths() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        assert!(matches!(encoder.try_write_bits(0, 0), Err(BinSchemaError::InvalidValue(_))));
+        assert!(matches!(encoder.try_write_bits(0, 65), Err(BinSchemaError::InvalidValue(_))));
+        assert_eq!(
+            encoder.try_write_bits(8, 3),
+            Err(BinSchemaError::InvalidValue("8 does not fit in 3 bits".to_string()))
+        );
+        assert_eq!(encoder.bit_position_in_byte(), 0);
+
+        encoder.try_write_bits(7, 3).unwrap();
+        encoder.try_write_bits(u64::MAX, 64).unwrap();
+        encoder.try_write_bits(0, 5).unwrap();
+        assert_eq!(encoder.finish(), vec![0xFF; 8].into_iter().chain([0xE0]).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_float16_special_values() {
+        let values = [1.0f32, 2f32.powi(-24), f32::INFINITY, f32::NEG_INFINITY, -0.0, 65504.0, 65520.0];
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        for &value in &values {
+            encoder.write_float16(value, Endianness::BigEndian);
+        }
+        encoder.write_float16(f32::NAN, Endianness::LittleEndian);
+        let bytes = encoder.finish();
+        assert_eq!(
+            bytes,
+            vec![0x3C, 0x00, 0x00, 0x01, 0x7C, 0x00, 0xFC, 0x00, 0x80, 0x00, 0x7B, 0xFF, 0x7C, 0x00, 0x00, 0x7E]
+        );
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        for &value in &values[..6] {
+            let decoded = decoder.read_float16(Endianness::BigEndian).unwrap();
+            assert_eq!(decoded.to_bits(), value.to_bits());
+        }
+        // 65520 is halfway to the next binade and rounds up to infinity
+        assert_eq!(decoder.read_float16(Endianness::BigEndian).unwrap(), f32::INFINITY);
+        assert!(decoder.read_float16(Endianness::LittleEndian).unwrap().is_nan());
+
+        // Half of the smallest subnormal rounds to even (zero); just above it rounds up
+        assert_eq!(f32_to_f16_bits(2f32.powi(-25)), 0x0000);
+        assert_eq!(f32_to_f16_bits(2f32.powi(-25) * 1.5), 0x0001);
+    }
+
+    #[test]
+    fn test_float16_round_trips_every_pattern() {
+        for half in 0..=u16::MAX {
+            let widened = f16_bits_to_f32(half);
+            if widened.is_nan() {
+                assert!(f32_to_f16_bits(widened) & 0x7C00 == 0x7C00 && f32_to_f16_bits(widened) & 0x3FF != 0);
+            } else {
+                assert_eq!(f32_to_f16_bits(widened), half, "pattern {:#06x}", half);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_bits() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_bits_signed(-1, 4);
+        encoder.write_bits_signed(-2048, 12);
+        encoder.write_bits_signed(2047, 12);
+        encoder.write_bits_signed(i64::MIN, 64);
+        encoder.write_bits_signed(-1, 4);
+        let bytes = encoder.finish();
+        assert_eq!(&bytes[..3], &[0xF8, 0x00, 0x7F]);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_bits_signed(4).unwrap(), -1);
+        assert_eq!(decoder.read_bits_signed(12).unwrap(), -2048);
+        assert_eq!(decoder.read_bits_signed(12).unwrap(), 2047);
+        assert_eq!(decoder.read_bits_signed(64).unwrap(), i64::MIN);
+        assert_eq!(decoder.read_bits_signed(4).unwrap(), -1);
+
+        // 0b1111 is -1 as a signed nibble but 15 unsigned
+        let mut decoder = BitStreamDecoder::new(&[0xF0], BitOrder::MsbFirst);
+        assert_eq!(decoder.read_bits_signed(4).unwrap(), -1);
+        let mut decoder = BitStreamDecoder::new(&[0xF0], BitOrder::MsbFirst);
+        assert_eq!(decoder.read_bits(4).unwrap(), 15);
+        assert!(decoder.read_bits_signed(0).is_err());
+
+        for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let values = [-1i64, -3, 5, -2048, 2047, 0];
+            let mut encoder = BitStreamEncoder::new(bit_order);
+            encoder.write_bits_signed(values[0], 1);
+            for &v in &values[1..] {
+                encoder.write_bits_signed(v, 12);
+            }
+            let bytes = encoder.finish();
+            let mut decoder = BitStreamDecoder::new(&bytes, bit_order);
+            assert_eq!(decoder.read_bits_signed(1).unwrap(), values[0]);
+            for &v in &values[1..] {
+                assert_eq!(decoder.read_bits_signed(12).unwrap(), v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uuid_and_guid_layouts() {
+        // 00112233-4455-6677-8899-aabbccddeeff
+        let uuid = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        ];
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_uuid(&uuid);
+        encoder.write_guid(&uuid);
+        let bytes = encoder.finish();
+        assert_eq!(&bytes[..16], &uuid);
+        assert_eq!(
+            &bytes[16..],
+            &[
+                0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66,
+                0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+            ]
+        );
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_uuid().unwrap(), uuid);
+        assert_eq!(decoder.read_guid().unwrap(), uuid);
+        assert!(decoder.read_guid().is_err());
+    }
+
+    #[test]
+    fn test_varint_u64_starts_on_a_byte_boundary() {
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_varint_u64(300);
+        encoder.write_varint_u64(u64::MAX);
+        let bytes = encoder.finish();
+        assert_eq!(&bytes[..2], &[0xAC, 0x02]);
+        assert_eq!(bytes.len(), 2 + 10);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_varint_u64().unwrap(), 300);
+        assert_eq!(decoder.read_varint_u64().unwrap(), u64::MAX);
+
+        // Mid-byte, the partial byte is zero-filled and the varint follows it
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        encoder.write_bits(1, 1);
+        encoder.write_varint_u64(300);
+        encoder.write_bits(1, 1);
+        encoder.write_varint_u64(1);
+        let bytes = encoder.finish();
+        assert_eq!(bytes, [0x80, 0xAC, 0x02, 0x80, 0x01]);
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_bits(1).unwrap(), 1);
+        assert_eq!(decoder.read_varint_u64().unwrap(), 300);
+        assert_eq!(decoder.read_bits(1).unwrap(), 1);
+        assert_eq!(decoder.read_varint_u64().unwrap(), 1);
+
+        let truncated = [0x80, 0x80];
+        assert!(BitStreamDecoder::new(&truncated, BitOrder::MsbFirst).read_varint_u64().is_err());
+        let too_long = [0x80; 11];
+        assert!(matches!(
+            BitStreamDecoder::new(&too_long, BitOrder::MsbFirst).read_varint_u64(),
+            Err(BinSchemaError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_varint_i64_round_trip() {
+        let values = [0i64, -1, 1, -64, 63, 64, i64::MIN, i64::MAX];
+        let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
+        for &v in &values {
+            encoder.write_varint_i64(v);
+        }
+        let bytes = encoder.finish();
+        // Small magnitudes take a byte each, the extremes the full ten
+        assert_eq!(&bytes[..6], &[0x00, 0x01, 0x02, 0x7F, 0x7E, 0x80]);
+        assert_eq!(bytes.len(), 5 + 2 + 10 + 10);
+
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        for &v in &values {
+            assert_eq!(decoder.read_varint_i64().unwrap(), v);
+        }
+
+        // An eleventh byte, or a tenth with more than the top bit, overflows
+        let too_long = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x81, 0x00];
+        assert!(BitStreamDecoder::new(&too_long, BitOrder::MsbFirst).read_varint_i64().is_err());
+        let too_wide = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+        assert!(BitStreamDecoder::new(&too_wide, BitOrder::MsbFirst).read_varint_i64().is_err());
+    }
+
+    #[test]
+    fn test_read_bytes_aligned_and_unaligned() {
+        let bytes = [0x12, 0x34, 0x56, 0x78, 0x9A];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        assert_eq!(decoder.read_bytes_vec(2).unwrap(), vec![0x12, 0x34]);
+        let mut buf = [0u8; 2];
+        decoder.read_bytes_into(&mut buf).unwrap();
+        assert_eq!(buf, [0x56, 0x78]);
+        assert!(matches!(decoder.read_bytes_vec(2), Err(BinSchemaError::UnexpectedEof)));
+        assert_eq!(decoder.position(), 4);
+
+        // Mid-byte, each byte matches what read_uint8 would return
+        let mut unaligned = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        unaligned.read_bits(4).unwrap();
+        let mut reference = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        reference.read_bits(4).unwrap();
+        let expected: Vec<u8> = (0..4).map(|_| reference.read_uint8().unwrap()).collect();
+        assert_eq!(unaligned.read_bytes_vec(4).unwrap(), expected);
+
+        // Only the last four bits are left, not a whole byte
+        assert!(matches!(unaligned.read_bytes_into(&mut [0u8; 1]), Err(BinSchemaError::UnexpectedEof)));
+        assert_eq!(unaligned.read_bits(4).unwrap(), 0xA);
+    }
+
+    #[test]
+    fn test_expect_bytes() {
+        let bytes = [0x89, b'P', b'N', b'G', 0x01];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+
+        decoder.expect_bytes(&[0x89, b'P', b'N', b'G']).unwrap();
+        assert_eq!(decoder.position(), 4);
+
+        // Mismatch and EOF both leave the cursor untouched
+        assert!(matches!(decoder.expect_bytes(&[0x02]), Err(BinSchemaError::InvalidValue(_))));
+        assert_eq!(decoder.position(), 4);
+        assert!(matches!(decoder.expect_bytes(&[0x01, 0x00]), Err(BinSchemaError::UnexpectedEof)));
+        assert_eq!(decoder.position(), 4);
+        assert_eq!(decoder.read_uint8().unwrap(), 0x01);
+    }
+
     #[test]
     fn test_float32_special_values() {
         let mut encoder = BitStreamEncoder::new(BitOrder::MsbFirst);
@@ -1099,4 +2853,229 @@ mod tests {
 
         assert_eq!(enc_aligned.finish(), enc_generic.finish());
     }
+
+    #[test]
+    fn test_seek_backward_forward_and_out_of_bounds() {
+        let bytes = [0x10, 0x20, 0x30, 0x40];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.read_uint8().unwrap();
+        decoder.read_bits(4).unwrap();
+        // Mid-byte, position is the partly read byte
+        assert_eq!(decoder.position(), 1);
+
+        // Backward: realigns to the start of the byte
+        decoder.seek(0).unwrap();
+        assert_eq!(decoder.position(), 0);
+        assert_eq!(decoder.read_uint8().unwrap(), 0x10);
+
+        // Forward, including to the very end
+        decoder.seek(3).unwrap();
+        assert_eq!(decoder.read_uint8().unwrap(), 0x40);
+        decoder.seek(4).unwrap();
+        assert_eq!(decoder.read_uint8(), Err(BinSchemaError::UnexpectedEof));
+
+        // Past the end: rejected, position unchanged
+        assert_eq!(decoder.seek(5), Err(BinSchemaError::UnexpectedEof));
+        assert_eq!(decoder.position(), 4);
+    }
+
+    #[test]
+    fn test_capacity_hint_caps_to_remaining_input() {
+        let bytes = [0u8; 6];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.read_uint16(Endianness::BigEndian).unwrap();
+        assert_eq!(decoder.capacity_hint(2), 2);
+        assert_eq!(decoder.capacity_hint(65535), 4);
+        assert_eq!(decoder.capacity_hint(usize::MAX), 4);
+    }
+
+    #[test]
+    fn test_ensure_count_fits() {
+        let bytes = [0u8; 4];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        decoder.read_bits(4).unwrap();
+        // 28 bits left
+        assert_eq!(decoder.ensure_count_fits(28, 1), Ok(()));
+        assert_eq!(
+            decoder.ensure_count_fits(29, 1),
+            Err(BinSchemaError::InvalidValue("count 29 of at least 1 bits each exceeds the 28 bits remaining".into()))
+        );
+        assert!(matches!(decoder.ensure_count_fits(usize::MAX, 16), Err(BinSchemaError::InvalidValue(_))));
+        // Elements of unknown size are only bounded by max_alloc
+        assert_eq!(decoder.ensure_count_fits(DecodeLimits::DEFAULT_MAX_ALLOC, 0), Ok(()));
+        assert_eq!(
+            decoder.ensure_count_fits(DecodeLimits::DEFAULT_MAX_ALLOC + 1, 0),
+            Err(BinSchemaError::InvalidValue(format!(
+                "count {} exceeds max_alloc {}",
+                DecodeLimits::DEFAULT_MAX_ALLOC + 1,
+                DecodeLimits::DEFAULT_MAX_ALLOC
+            )))
+        );
+        decoder.set_limits(DecodeLimits::unlimited()).unwrap();
+        assert_eq!(decoder.ensure_count_fits(usize::MAX, 0), Ok(()));
+        // Input still arriving: a count that doesn't fit yet means wait for more
+        decoder.set_input_incomplete();
+        assert_eq!(decoder.ensure_count_fits(29, 1), Err(BinSchemaError::UnexpectedEof));
+    }
+
+    #[test]
+    fn test_forged_count_rejected_before_allocation() {
+        // Shape of a generated decoder for `count: u16` followed by that many u16 items
+        fn decode_items(bytes: &[u8]) -> Result<Vec<u16>> {
+            let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
+            let count = decoder.read_uint16(Endianness::BigEndian)? as usize;
+            decoder.ensure_count_fits(count, 16)?;
+            let mut items = Vec::with_capacity(decoder.capacity_hint(count));
+            for _ in 0..count {
+                items.push(decoder.read_uint16(Endianness::BigEndian)?);
+            }
+            Ok(items)
+        }
+
+        assert_eq!(decode_items(&[0x00, 0x02, 0x00, 0x01, 0x00, 0x02]), Ok(vec![1, 2]));
+        // A forged 65535 count on a two-item buffer fails before reserving anything
+        assert!(matches!(decode_items(&[0xFF, 0xFF, 0x00, 0x01, 0x00, 0x02]), Err(BinSchemaError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_lenient_mode_collects_independent_field_errors() {
+        // Shape of generated decode_lenient for `magic: u8 = 0xAB, version: u8 = 1, len: u16`
+        fn decode_lenient(bytes: &[u8]) -> (Option<(u8, u8, u16)>, Vec<FieldError>) {
+            let mut decoder = BitStreamDecoder::new(bytes, BitOrder::MsbFirst);
+            decoder.set_lenient();
+            let result = (|| {
+                let magic = decoder.read_uint8()?;
+                let magic = if magic != 0xAB {
+                    decoder.recover(
+                        "Header.magic",
+                        BinSchemaError::InvalidValue(format!(
+                            "Header.magic is {}, expected {}",
+                            magic, 0xAB
+                        )),
+                    )?;
+                    0xAB
+                } else {
+                    magic
+                };
+                let version = decoder.read_uint8()?;
+                let version = if version != 1 {
+                    decoder.recover(
+                        "Header.version",
+                        BinSchemaError::InvalidValue(format!(
+                            "Header.version is {}, expected {}",
+                            version, 1
+                        )),
+                    )?;
+                    1
+                } else {
+                    version
+                };
+                let len = decoder.read_uint16(Endianness::BigEndian)?;
+                Ok((magic, version, len))
+            })();
+            let mut errors = decoder.take_field_errors();
+            match result {
+                Ok(value) => (Some(value), errors),
+                Err(error) => {
+                    errors.push(FieldError {
+                        field: "Header".to_string(),
+                        error,
+                    });
+                    (None, errors)
+                }
+            }
+        }
+
+        let (value, errors) = decode_lenient(&[0x00, 0x07, 0x00, 0x10]);
+        assert_eq!(value, Some((0xAB, 1, 16)));
+        assert_eq!(
+            errors,
+            vec![
+                FieldError {
+                    field: "Header.magic".into(),
+                    error: BinSchemaError::InvalidValue("Header.magic is 0, expected 171".into()),
+                },
+                FieldError {
+                    field: "Header.version".into(),
+                    error: BinSchemaError::InvalidValue("Header.version is 7, expected 1".into()),
+                },
+            ]
+        );
+
+        // Truncation is fatal: earlier recoverable errors are kept, then the EOF
+        let (value, errors) = decode_lenient(&[0x00, 0x01]);
+        assert_eq!(value, None);
+        assert_eq!(
+            errors,
+            vec![
+                FieldError {
+                    field: "Header.magic".into(),
+                    error: BinSchemaError::InvalidValue("Header.magic is 0, expected 171".into()),
+                },
+                FieldError {
+                    field: "Header".into(),
+                    error: BinSchemaError::UnexpectedEof
+                },
+            ]
+        );
+
+        // Strict decoders and strict() scopes fail on the first problem
+        let mismatch = || BinSchemaError::InvalidValue("Header.magic is 0, expected 171".into());
+        let mut decoder = BitStreamDecoder::new(&[], BitOrder::MsbFirst);
+        assert_eq!(decoder.recover("Header.magic", mismatch()), Err(mismatch()));
+        decoder.set_lenient();
+        assert_eq!(
+            decoder.strict(|d| d.recover("Header.magic", mismatch())),
+            Err(mismatch())
+        );
+        assert!(decoder.take_field_errors().is_empty());
+    }
+
+    #[test]
+    fn test_random_input_never_panics() {
+        // xorshift64: deterministic so any failure reproduces
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..2000 {
+            let len = (next() % 24) as usize;
+            let bytes: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            let bit_order = if next() % 2 == 0 { BitOrder::MsbFirst } else { BitOrder::LsbFirst };
+            let bound = (next() % 32) as usize;
+            let mut decoder = match BitStreamDecoder::new_bounded(&bytes, bound, bit_order) {
+                Ok(decoder) => decoder,
+                Err(_) => BitStreamDecoder::new(&bytes, bit_order),
+            };
+
+            for _ in 0..16 {
+                let endianness = if next() % 2 == 0 { Endianness::BigEndian } else { Endianness::LittleEndian };
+                // Results are deliberately ignored: only a panic fails the test
+                match next() % 14 {
+                    0 => { let _ = decoder.read_bits((next() % 70) as u8); }
+                    1 => { let _ = decoder.read_uint8(); }
+                    2 => { let _ = decoder.read_uint16(endianness); }
+                    3 => { let _ = decoder.read_uint32(endianness); }
+                    4 => { let _ = decoder.read_uint64(endianness); }
+                    5 => { let _ = decoder.read_float64(endianness); }
+                    // Fast paths assume the alignment the generator proves statically
+                    6 if decoder.bit_offset == 0 => { let _ = decoder.read_u32_le(); }
+                    7 => {
+                        let encoding = ["der", "leb128", "ebml", "vlq", "bogus"][(next() % 5) as usize];
+                        let _ = decoder.read_varlength(encoding);
+                    }
+                    8 => { let _ = decoder.read_bytes_vec(if next() % 4 == 0 { usize::MAX } else { (next() % 8) as usize }); }
+                    9 => { let _ = decoder.seek((next() % 40) as usize); }
+                    10 => { let _ = decoder.peek_uint32(endianness); }
+                    11 => { let _ = decoder.expect_bytes(&[0x00, 0xFF]); }
+                    12 => { let _ = decoder.remaining_slice(); }
+                    _ => { let _ = decoder.capacity_hint(next() as usize); }
+                }
+            }
+        }
+    }
 }
@@ -2,9 +2,20 @@
 // ABOUTME: Enables nested structs to access parent fields via ../field syntax
 // ABOUTME: Supports compression dictionary for back_reference encoding (DNS-style)
 
-use std::collections::HashMap;
-use std::rc::Rc;
-use std::cell::RefCell;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::{BinSchemaError, Endianness, Result};
+
+/// Map behind the context's field lookups and compression dictionary.
+/// Always `BTreeMap` rather than `HashMap`, so enabling `std` anywhere in
+/// the dependency graph can't change the type, or its `Ord` key bound, for
+/// no_std users. Generated code builds the maps it passes in with this alias.
+pub type Map<K, V> = alloc::collections::BTreeMap<K, V>;
 
 /// Dynamic field value for parent context.
 /// Used to pass parent field values down to nested struct encoders.
@@ -14,10 +25,12 @@ pub enum FieldValue {
     U16(u16),
     U32(u32),
     U64(u64),
+    U128(u128),
     I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
+    I128(i128),
     F32(f32),
     F64(f64),
     Bool(bool),
@@ -25,11 +38,11 @@ pub enum FieldValue {
     Bytes(Vec<u8>),
     /// Array of (type_name, encoded_byte_size) pairs.
     /// Used by sum_of_type_sizes to compute total encoded size of elements by type.
-    TypeSizes(Vec<(std::string::String, usize)>),
+    TypeSizes(Vec<(String, usize)>),
     /// Array of items with their type names and sub-field values.
     /// Used by corresponding<Type> selectors to access sub-fields of correlated items.
     /// Each item is (type_name, field_name_to_value_map).
-    Items(Vec<(std::string::String, HashMap<std::string::String, FieldValue>)>),
+    Items(Vec<(String, Map<String, FieldValue>)>),
 }
 
 impl FieldValue {
@@ -53,7 +66,7 @@ impl FieldValue {
     pub fn len(&self) -> usize {
         match self {
             FieldValue::Bytes(b) => b.len(),
-            FieldValue::String(s) => s.as_bytes().len(), // UTF-8 byte length
+            FieldValue::String(s) => s.len(), // UTF-8 byte length
             FieldValue::TypeSizes(entries) => entries.len(), // Number of array items
             FieldValue::Items(items) => items.len(), // Number of array items
             _ => 0,
@@ -105,7 +118,7 @@ impl FieldValue {
 
     /// Find the Nth occurrence of a type in an Items list and return a reference to its fields.
     /// Used by corresponding<Type> selectors to access sub-fields of correlated items.
-    pub fn get_nth_item_of_type(&self, type_name: &str, n: usize) -> Option<&HashMap<std::string::String, FieldValue>> {
+    pub fn get_nth_item_of_type(&self, type_name: &str, n: usize) -> Option<&Map<String, FieldValue>> {
         match self {
             FieldValue::Items(items) => {
                 let mut count = 0;
@@ -133,12 +146,12 @@ impl FieldValue {
     }
 
     /// Convenience accessor for `first<Type>` selectors.
-    pub fn first_item_of_type(&self, type_name: &str) -> Option<&HashMap<std::string::String, FieldValue>> {
+    pub fn first_item_of_type(&self, type_name: &str) -> Option<&Map<String, FieldValue>> {
         self.get_nth_item_of_type(type_name, 0)
     }
 
     /// Convenience accessor for `last<Type>` selectors.
-    pub fn last_item_of_type(&self, type_name: &str) -> Option<&HashMap<std::string::String, FieldValue>> {
+    pub fn last_item_of_type(&self, type_name: &str) -> Option<&Map<String, FieldValue>> {
         let count = self.count_items_of_type(type_name);
         if count == 0 { None } else { self.get_nth_item_of_type(type_name, count - 1) }
     }
@@ -153,15 +166,17 @@ impl FieldValue {
             FieldValue::U16(v) => *v as usize,
             FieldValue::U32(v) => *v as usize,
             FieldValue::U64(v) => *v as usize,
+            FieldValue::U128(v) => *v as usize,
             FieldValue::I8(v) => *v as usize,
             FieldValue::I16(v) => *v as usize,
             FieldValue::I32(v) => *v as usize,
             FieldValue::I64(v) => *v as usize,
+            FieldValue::I128(v) => *v as usize,
             FieldValue::F32(v) => *v as usize,
             FieldValue::F64(v) => *v as usize,
             FieldValue::Bool(v) => if *v { 1 } else { 0 },
             FieldValue::Bytes(b) => b.len(),
-            FieldValue::String(s) => s.as_bytes().len(),
+            FieldValue::String(s) => s.len(),
             FieldValue::TypeSizes(entries) => entries.len(),
             FieldValue::Items(items) => items.len(),
         }
@@ -172,19 +187,38 @@ impl FieldValue {
         self.len() == 0
     }
 
-    /// Get the raw bytes of the value (for CRC32 calculation)
+    /// Raw bytes of the value, with numbers always little-endian. That is the
+    /// wrong order to checksum a field of a big-endian format; use
+    /// `to_bytes_with_endianness` with the schema's byte order instead.
     pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_endianness(Endianness::LittleEndian)
+    }
+
+    /// Raw bytes of the value, with numbers in `endianness` order, as fed to
+    /// a checksum over the field. Strings and byte arrays are their bytes as
+    /// is; `TypeSizes` and `Items` have none.
+    pub fn to_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        macro_rules! ordered {
+            ($v:expr) => {
+                match endianness {
+                    Endianness::BigEndian => $v.to_be_bytes().to_vec(),
+                    Endianness::LittleEndian => $v.to_le_bytes().to_vec(),
+                }
+            };
+        }
         match self {
             FieldValue::U8(v) => vec![*v],
-            FieldValue::U16(v) => v.to_le_bytes().to_vec(),
-            FieldValue::U32(v) => v.to_le_bytes().to_vec(),
-            FieldValue::U64(v) => v.to_le_bytes().to_vec(),
+            FieldValue::U16(v) => ordered!(v),
+            FieldValue::U32(v) => ordered!(v),
+            FieldValue::U64(v) => ordered!(v),
+            FieldValue::U128(v) => ordered!(v),
             FieldValue::I8(v) => vec![*v as u8],
-            FieldValue::I16(v) => v.to_le_bytes().to_vec(),
-            FieldValue::I32(v) => v.to_le_bytes().to_vec(),
-            FieldValue::I64(v) => v.to_le_bytes().to_vec(),
-            FieldValue::F32(v) => v.to_le_bytes().to_vec(),
-            FieldValue::F64(v) => v.to_le_bytes().to_vec(),
+            FieldValue::I16(v) => ordered!(v),
+            FieldValue::I32(v) => ordered!(v),
+            FieldValue::I64(v) => ordered!(v),
+            FieldValue::I128(v) => ordered!(v),
+            FieldValue::F32(v) => ordered!(v),
+            FieldValue::F64(v) => ordered!(v),
             FieldValue::Bool(v) => vec![if *v { 1 } else { 0 }],
             FieldValue::String(s) => s.as_bytes().to_vec(),
             FieldValue::Bytes(b) => b.clone(),
@@ -194,6 +228,34 @@ impl FieldValue {
     }
 }
 
+/// Shared compression dictionary: encoded target bytes -> absolute byte offset.
+pub type CompressionDict = Rc<RefCell<Map<Vec<u8>, usize>>>;
+
+/// Largest offset a DNS compression pointer can reach: its low 14 bits
+pub const MAX_COMPRESSION_OFFSET: usize = 0x3FFF;
+
+/// Two-byte DNS compression pointer (RFC 1035 4.1.4) to `offset`: the 0b11
+/// marker in the top bits and the offset in the low 14. Fails with
+/// `InvalidValue` for an offset past `MAX_COMPRESSION_OFFSET`, which would
+/// otherwise be truncated into a pointer to the wrong place.
+pub fn encode_compression_pointer(offset: usize) -> Result<u16> {
+    encode_pointer(offset, MAX_COMPRESSION_OFFSET as u64, 0xC000).map(|pointer| pointer as u16)
+}
+
+/// Back-reference storage value for `offset` under an arbitrary
+/// `offset_mask` and marker `flag`, with the same overflow check as
+/// `encode_compression_pointer`.
+pub fn encode_pointer(offset: usize, offset_mask: u64, flag: u64) -> Result<u64> {
+    let offset = offset as u64;
+    if offset & !offset_mask != 0 {
+        return Err(BinSchemaError::InvalidValue(format!(
+            "pointer offset {} does not fit in mask {:#x}",
+            offset, offset_mask
+        )));
+    }
+    Ok(flag | offset)
+}
+
 /// Helper trait for converting types to FieldValue
 pub trait IntoFieldValue {
     fn into_field_value(self) -> FieldValue;
@@ -211,6 +273,9 @@ impl IntoFieldValue for u32 {
 impl IntoFieldValue for u64 {
     fn into_field_value(self) -> FieldValue { FieldValue::U64(self) }
 }
+impl IntoFieldValue for u128 {
+    fn into_field_value(self) -> FieldValue { FieldValue::U128(self) }
+}
 impl IntoFieldValue for i8 {
     fn into_field_value(self) -> FieldValue { FieldValue::I8(self) }
 }
@@ -223,6 +288,9 @@ impl IntoFieldValue for i32 {
 impl IntoFieldValue for i64 {
     fn into_field_value(self) -> FieldValue { FieldValue::I64(self) }
 }
+impl IntoFieldValue for i128 {
+    fn into_field_value(self) -> FieldValue { FieldValue::I128(self) }
+}
 impl IntoFieldValue for f32 {
     fn into_field_value(self) -> FieldValue { FieldValue::F32(self) }
 }
@@ -256,19 +324,19 @@ impl IntoFieldValue for &[u8] {
 pub struct EncodeContext {
     /// Stack of parent field maps. Last element is immediate parent.
     /// Each map contains field name -> field value mappings.
-    parents: Vec<HashMap<String, FieldValue>>,
+    parents: Vec<Map<String, FieldValue>>,
 
     /// Position tracking for first/last/corresponding selectors.
     /// Key format: "{array_name}_{type_name}" -> Vec of byte positions
-    positions: HashMap<String, Vec<usize>>,
+    positions: Map<String, Vec<usize>>,
 
     /// Array iteration context for corresponding<Type> correlation.
     /// Key: array field name -> current iteration index
-    array_iterations: HashMap<String, usize>,
+    array_iterations: Map<String, usize>,
 
     /// Type occurrence counters for corresponding<Type> correlation.
     /// Key: "{array_name}_{type_name}" -> count of occurrences seen so far
-    type_indices: HashMap<String, usize>,
+    type_indices: Map<String, usize>,
 
     /// The most recently set array iteration name, for cross-array correlation.
     current_array: Option<String>,
@@ -276,7 +344,7 @@ pub struct EncodeContext {
     /// Shared compression dictionary for back_reference encoding (DNS-style compression).
     /// Maps encoded target bytes to their absolute byte offset in the output stream.
     /// Uses Rc<RefCell> for shared mutable access across nested encoders.
-    compression_dict: Option<Rc<RefCell<HashMap<Vec<u8>, usize>>>>,
+    compression_dict: Option<CompressionDict>,
 
     /// Base byte offset from the start of the message/output.
     /// Used to compute absolute offsets for compression dictionary entries.
@@ -289,9 +357,9 @@ impl EncodeContext {
     pub fn new() -> Self {
         Self {
             parents: Vec::new(),
-            positions: HashMap::new(),
-            array_iterations: HashMap::new(),
-            type_indices: HashMap::new(),
+            positions: Map::new(),
+            array_iterations: Map::new(),
+            type_indices: Map::new(),
             current_array: None,
             compression_dict: None,
             base_offset: 0,
@@ -301,7 +369,7 @@ impl EncodeContext {
     /// Create a new context with an additional parent added.
     /// The new parent becomes the immediate parent (innermost).
     /// Position tracking data is preserved and carried forward.
-    pub fn extend_with_parent(&self, parent: HashMap<String, FieldValue>) -> Self {
+    pub fn extend_with_parent(&self, parent: Map<String, FieldValue>) -> Self {
         let mut new_parents = self.parents.clone();
         new_parents.push(parent);
         Self {
@@ -417,17 +485,46 @@ impl EncodeContext {
         self.type_indices.get(key).copied().unwrap_or(0)
     }
 
+    // === corresponding<Type> resolution ===
+
+    /// Index among the `type_name` items of `array_name` that a
+    /// `corresponding<type_name>` selector picks for the item being encoded.
+    ///
+    /// - Same array (`array_name` is the array currently being iterated): the
+    ///   Nth item of another type pairs with the Nth `type_name` item seen so
+    ///   far, counted by `increment_type_index` before each item is encoded.
+    ///   Before any `type_name` item has been seen this is 0.
+    /// - Sibling array: the item at iteration index N of the array being
+    ///   encoded pairs with the Nth `type_name` item of `array_name`.
+    pub fn corresponding_index(&self, array_name: &str, type_name: &str) -> usize {
+        if self.is_current_array(array_name) {
+            self.get_type_index(&format!("{}_{}", array_name, type_name)).saturating_sub(1)
+        } else {
+            self.get_any_array_iteration().map(|(_, idx)| idx).unwrap_or(0)
+        }
+    }
+
+    /// Resolves `corresponding<type_name>.sub_field` in the parent field
+    /// `array_name`, which must hold `FieldValue::Items`. An empty `sub_field`
+    /// refers to the whole item and yields its `_encoded_size`. Returns None
+    /// if the array, the correlated item or the sub-field is missing.
+    pub fn resolve_corresponding(&self, array_name: &str, type_name: &str, sub_field: &str) -> Option<&FieldValue> {
+        let index = self.corresponding_index(array_name, type_name);
+        let item = self.find_parent_field(array_name)?.get_nth_item_of_type(type_name, index)?;
+        item.get(if sub_field.is_empty() { "_encoded_size" } else { sub_field })
+    }
+
     // === Compression dictionary for back_reference encoding ===
 
     /// Ensure the compression dictionary exists (creates if None).
     pub fn ensure_compression_dict(&mut self) {
         if self.compression_dict.is_none() {
-            self.compression_dict = Some(Rc::new(RefCell::new(HashMap::new())));
+            self.compression_dict = Some(Rc::new(RefCell::new(Map::new())));
         }
     }
 
     /// Get a reference to the compression dictionary (if it exists).
-    pub fn compression_dict(&self) -> Option<&Rc<RefCell<HashMap<Vec<u8>, usize>>>> {
+    pub fn compression_dict(&self) -> Option<&CompressionDict> {
         self.compression_dict.as_ref()
     }
 
@@ -468,7 +565,7 @@ mod tests {
     fn test_encode_context_single_parent() {
         let ctx = EncodeContext::new();
 
-        let mut parent_fields = HashMap::new();
+        let mut parent_fields = Map::new();
         parent_fields.insert("data".to_string(), FieldValue::Bytes(vec![1, 2, 3, 4]));
         parent_fields.insert("name".to_string(), FieldValue::String("test".to_string()));
 
@@ -497,12 +594,12 @@ mod tests {
         let ctx = EncodeContext::new();
 
         // Grandparent (root) context
-        let mut grandparent_fields = HashMap::new();
+        let mut grandparent_fields = Map::new();
         grandparent_fields.insert("payload".to_string(), FieldValue::Bytes(vec![0xDE, 0xAD, 0xBE, 0xEF]));
         let parent_ctx = ctx.extend_with_parent(grandparent_fields);
 
         // Parent context
-        let mut parent_fields = HashMap::new();
+        let mut parent_fields = Map::new();
         parent_fields.insert("header_value".to_string(), FieldValue::U32(42));
         let child_ctx = parent_ctx.extend_with_parent(parent_fields);
 
@@ -535,4 +632,79 @@ mod tests {
         assert_eq!(FieldValue::Bytes(vec![1, 2, 3]).to_bytes(), vec![1, 2, 3]);
         assert_eq!(FieldValue::String("AB".to_string()).to_bytes(), vec![0x41, 0x42]);
     }
+
+    #[test]
+    fn test_field_value_to_bytes_with_endianness() {
+        let value = FieldValue::U16(0x1234);
+        assert_eq!(value.to_bytes_with_endianness(Endianness::BigEndian), vec![0x12, 0x34]);
+        assert_eq!(value.to_bytes_with_endianness(Endianness::LittleEndian), vec![0x34, 0x12]);
+        assert_eq!(value.to_bytes(), value.to_bytes_with_endianness(Endianness::LittleEndian));
+        assert_eq!(FieldValue::I32(-2).to_bytes_with_endianness(Endianness::BigEndian), vec![0xFF, 0xFF, 0xFF, 0xFE]);
+        // Byte strings have no byte order
+        assert_eq!(FieldValue::Bytes(vec![1, 2]).to_bytes_with_endianness(Endianness::BigEndian), vec![1, 2]);
+    }
+
+    fn item(type_name: &str, fields: &[(&str, FieldValue)]) -> (String, Map<String, FieldValue>) {
+        let fields = fields.iter().map(|(name, value)| (name.to_string(), value.clone())).collect();
+        (type_name.to_string(), fields)
+    }
+
+    #[test]
+    fn test_resolve_corresponding_across_sibling_arrays() {
+        // Each block is described by the header at the same index in `headers`
+        let headers = FieldValue::Items(vec![
+            item("BlockHeader", &[("length", FieldValue::U32(3)), ("_encoded_size", FieldValue::U64(6))]),
+            item("Padding", &[]),
+            item("BlockHeader", &[("length", FieldValue::U32(5)), ("_encoded_size", FieldValue::U64(6))]),
+        ]);
+        let blocks = FieldValue::Items(vec![item("Block", &[]), item("Block", &[])]);
+        let mut ctx = EncodeContext::new()
+            .extend_with_parent(Map::from([("headers".to_string(), headers), ("blocks".to_string(), blocks)]));
+
+        ctx.set_array_iteration("blocks", 0);
+        assert_eq!(ctx.resolve_corresponding("headers", "BlockHeader", "length").unwrap().length_of_value(), 3);
+        ctx.set_array_iteration("blocks", 1);
+        assert_eq!(ctx.corresponding_index("headers", "BlockHeader"), 1);
+        assert_eq!(ctx.resolve_corresponding("headers", "BlockHeader", "length").unwrap().length_of_value(), 5);
+        assert_eq!(ctx.resolve_corresponding("headers", "BlockHeader", "").unwrap().length_of_value(), 6);
+
+        ctx.set_array_iteration("blocks", 2);
+        assert!(ctx.resolve_corresponding("headers", "BlockHeader", "length").is_none());
+        assert!(ctx.resolve_corresponding("missing", "BlockHeader", "length").is_none());
+    }
+
+    #[test]
+    fn test_resolve_corresponding_within_one_array() {
+        // Headers and bodies alternate; each body pairs with the header before it
+        let entries = FieldValue::Items(vec![
+            item("Header", &[("size", FieldValue::U16(10))]),
+            item("Body", &[]),
+            item("Header", &[("size", FieldValue::U16(20))]),
+            item("Body", &[]),
+        ]);
+        let mut ctx = EncodeContext::new().extend_with_parent(Map::from([("entries".to_string(), entries)]));
+
+        let mut sizes = Vec::new();
+        for (idx, type_name) in ["Header", "Body", "Header", "Body"].iter().enumerate() {
+            ctx.set_array_iteration("entries", idx);
+            ctx.increment_type_index(&format!("entries_{}", type_name));
+            if *type_name == "Body" {
+                sizes.push(ctx.resolve_corresponding("entries", "Header", "size").unwrap().length_of_value());
+            }
+        }
+        assert_eq!(sizes, vec![10, 20]);
+    }
+
+    #[test]
+    fn test_compression_pointer_rejects_offsets_past_14_bits() {
+        assert_eq!(encode_compression_pointer(12), Ok(0xC00C));
+        assert_eq!(encode_compression_pointer(MAX_COMPRESSION_OFFSET), Ok(0xFFFF));
+        // 16384 would truncate to a pointer at offset 0
+        assert_eq!(
+            encode_compression_pointer(16384),
+            Err(BinSchemaError::InvalidValue("pointer offset 16384 does not fit in mask 0x3fff".to_string()))
+        );
+        assert!(encode_pointer(0x100, 0x7F, 0x80).is_err());
+        assert_eq!(encode_pointer(0x7F, 0x7F, 0x80), Ok(0xFF));
+    }
 }
@@ -0,0 +1,241 @@
+// ABOUTME: Parameterized CRC computation (Rocksoft/Williams model) for checksum fields
+// ABOUTME: Covers any width up to 64 bits, with presets for common protocol variants and Adler-32
+
+use alloc::format;
+
+use crate::{BinSchemaError, Result};
+
+/// CRC variant in the Rocksoft model: register `width`, generator `poly`
+/// (normal form, implicit top bit), initial register value, input/output
+/// reflection and the final XOR. Presets carry their catalogue "check" value,
+/// the CRC of the ASCII bytes `123456789`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcParams {
+    pub width: u8,
+    pub poly: u64,
+    pub init: u64,
+    pub refin: bool,
+    pub refout: bool,
+    pub xorout: u64,
+}
+
+impl CrcParams {
+    /// CRC-8 (SMBus), check 0xF4
+    pub const CRC_8: CrcParams = CrcParams { width: 8, poly: 0x07, init: 0x00, refin: false, refout: false, xorout: 0x00 };
+    /// CRC-16/CCITT-FALSE (IBM-3740), check 0x29B1
+    pub const CRC_16_CCITT_FALSE: CrcParams = CrcParams { width: 16, poly: 0x1021, init: 0xFFFF, refin: false, refout: false, xorout: 0x0000 };
+    /// CRC-16/XMODEM, check 0x31C3
+    pub const CRC_16_XMODEM: CrcParams = CrcParams { width: 16, poly: 0x1021, init: 0x0000, refin: false, refout: false, xorout: 0x0000 };
+    /// CRC-16/KERMIT (CCITT true), check 0x2189
+    pub const CRC_16_KERMIT: CrcParams = CrcParams { width: 16, poly: 0x1021, init: 0x0000, refin: true, refout: true, xorout: 0x0000 };
+    /// CRC-16/MODBUS, check 0x4B37
+    pub const CRC_16_MODBUS: CrcParams = CrcParams { width: 16, poly: 0x8005, init: 0xFFFF, refin: true, refout: true, xorout: 0x0000 };
+    /// CRC-16/ARC, check 0xBB3D
+    pub const CRC_16_ARC: CrcParams = CrcParams { width: 16, poly: 0x8005, init: 0x0000, refin: true, refout: true, xorout: 0x0000 };
+    /// CRC-32 (IEEE, as used by zip and Ethernet; same as `crc32`), check 0xCBF43926
+    pub const CRC_32: CrcParams = CrcParams { width: 32, poly: 0x04C1_1DB7, init: 0xFFFF_FFFF, refin: true, refout: true, xorout: 0xFFFF_FFFF };
+    /// CRC-32C (Castagnoli, iSCSI/SCTP), check 0xE3069283
+    pub const CRC_32C: CrcParams = CrcParams { width: 32, poly: 0x1EDC_6F41, init: 0xFFFF_FFFF, refin: true, refout: true, xorout: 0xFFFF_FFFF };
+    /// CRC-32/BZIP2, check 0xFC891918
+    pub const CRC_32_BZIP2: CrcParams = CrcParams { width: 32, poly: 0x04C1_1DB7, init: 0xFFFF_FFFF, refin: false, refout: false, xorout: 0xFFFF_FFFF };
+    /// CRC-64/XZ, check 0x995DC9BBDF1939FA
+    pub const CRC_64_XZ: CrcParams = CrcParams {
+        width: 64,
+        poly: 0x42F0_E1EB_A9EA_3693,
+        init: u64::MAX,
+        refin: true,
+        refout: true,
+        xorout: u64::MAX,
+    };
+
+    fn mask(&self) -> u64 {
+        if self.width >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.width) - 1
+        }
+    }
+}
+
+/// Computes the CRC of `data` for any `width` in 1..=64, failing with
+/// `InvalidValue` for other widths. Bit-at-a-time, so it favours generality
+/// over speed; `crc32` remains the table-driven fast path for the IEEE variant.
+pub fn crc(data: &[u8], params: &CrcParams) -> Result<u64> {
+    if !(1..=64).contains(&params.width) {
+        return Err(BinSchemaError::InvalidValue(format!(
+            "CRC width is {}, expected 1 to 64 bits",
+            params.width
+        )));
+    }
+    Ok(crc_unchecked(data, params))
+}
+
+/// `crc` for parameters whose width is known to be in 1..=64
+fn crc_unchecked(data: &[u8], params: &CrcParams) -> u64 {
+    let mask = params.mask();
+    let top = 1u64 << (params.width - 1);
+    let poly = params.poly & mask;
+    let mut reg = params.init & mask;
+
+    for &byte in data {
+        let byte = if params.refin { byte.reverse_bits() } else { byte };
+        for i in (0..8).rev() {
+            let feedback = (reg & top != 0) ^ ((byte >> i) & 1 == 1);
+            reg = (reg << 1) & mask;
+            if feedback {
+                reg ^= poly;
+            }
+        }
+    }
+
+    if params.refout {
+        reg = reg.reverse_bits() >> (64 - params.width as u32);
+    }
+    (reg ^ params.xorout) & mask
+}
+
+/// CRC-32 (IEEE) of `bytes`, as stored in PNG chunks and zip entries
+pub fn compute_crc32(bytes: &[u8]) -> u32 {
+    crate::crc32(bytes)
+}
+
+/// CRC-16/CCITT-FALSE of `bytes`
+pub fn compute_crc16_ccitt(bytes: &[u8]) -> u16 {
+    crc_unchecked(bytes, &CrcParams::CRC_16_CCITT_FALSE) as u16
+}
+
+/// Adler-32 of `bytes`, the zlib stream trailer
+pub fn compute_adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    // Largest run of bytes whose sums can't overflow a u32 before reducing
+    const NMAX: usize = 5552;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(NMAX) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// Checksum a computed field stores over other encoded bytes, for use with
+/// `BitStreamEncoder::patch_checksum`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    Crc32,
+    Crc16Ccitt,
+    Adler32,
+    /// Any other CRC variant
+    Crc(CrcParams),
+}
+
+impl Checksum {
+    /// Fails only for a `Crc` whose width is outside 1..=64
+    pub fn compute(&self, bytes: &[u8]) -> Result<u64> {
+        match self {
+            Checksum::Crc32 => Ok(compute_crc32(bytes) as u64),
+            Checksum::Crc16Ccitt => Ok(compute_crc16_ccitt(bytes) as u64),
+            Checksum::Adler32 => Ok(compute_adler32(bytes) as u64),
+            Checksum::Crc(params) => crc(bytes, params),
+        }
+    }
+
+    /// Bytes the checksum occupies when stored
+    pub fn size(&self) -> usize {
+        match self {
+            Checksum::Crc32 | Checksum::Adler32 => 4,
+            Checksum::Crc16Ccitt => 2,
+            Checksum::Crc(params) => (params.width as usize).div_ceil(8),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK_INPUT: &[u8] = b"123456789";
+
+    #[test]
+    fn test_preset_check_values() {
+        let cases = [
+            (CrcParams::CRC_8, 0xF4),
+            (CrcParams::CRC_16_CCITT_FALSE, 0x29B1),
+            (CrcParams::CRC_16_XMODEM, 0x31C3),
+            (CrcParams::CRC_16_KERMIT, 0x2189),
+            (CrcParams::CRC_16_MODBUS, 0x4B37),
+            (CrcParams::CRC_16_ARC, 0xBB3D),
+            (CrcParams::CRC_32, 0xCBF4_3926),
+            (CrcParams::CRC_32C, 0xE306_9283),
+            (CrcParams::CRC_32_BZIP2, 0xFC89_1918),
+            (CrcParams::CRC_64_XZ, 0x995D_C9BB_DF19_39FA),
+        ];
+        for (params, check) in cases {
+            assert_eq!(crc(CHECK_INPUT, &params), Ok(check), "{:?}", params);
+        }
+    }
+
+    #[test]
+    fn test_matches_table_driven_crc32() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(crc(data, &CrcParams::CRC_32), Ok(crate::crc32(data) as u64));
+        assert_eq!(crc(&[], &CrcParams::CRC_32), Ok(0));
+    }
+
+    #[test]
+    fn test_narrow_width() {
+        // CRC-5/USB: width 5, poly 0x05, init 0x1F, reflected, xorout 0x1F; check 0x19
+        let usb = CrcParams { width: 5, poly: 0x05, init: 0x1F, refin: true, refout: true, xorout: 0x1F };
+        assert_eq!(crc(CHECK_INPUT, &usb), Ok(0x19));
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_width() {
+        for width in [0, 65, u8::MAX] {
+            let params = CrcParams { width, ..CrcParams::CRC_8 };
+            assert_eq!(
+                crc(CHECK_INPUT, &params),
+                Err(BinSchemaError::InvalidValue(format!("CRC width is {}, expected 1 to 64 bits", width)))
+            );
+            assert!(Checksum::Crc(params).compute(CHECK_INPUT).is_err());
+        }
+        let one_bit = CrcParams { width: 1, poly: 0x1, init: 0, refin: false, refout: false, xorout: 0 };
+        assert!(crc(CHECK_INPUT, &one_bit).is_ok());
+    }
+
+    #[test]
+    fn test_crc32_known_vectors() {
+        let cases: [(&[u8], u32); 5] = [
+            (b"", 0),
+            (b"a", 0xE8B7_BE43),
+            (CHECK_INPUT, 0xCBF4_3926),
+            (b"The quick brown fox jumps over the lazy dog", 0x414F_A339),
+            // CRC of a PNG IEND chunk (type, no data)
+            (b"IEND", 0xAE42_6082),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(compute_crc32(input), expected, "{:?}", input);
+        }
+        assert_eq!(compute_crc16_ccitt(CHECK_INPUT), 0x29B1);
+    }
+
+    #[test]
+    fn test_adler32_known_vectors() {
+        assert_eq!(compute_adler32(b""), 1);
+        assert_eq!(compute_adler32(b"Wikipedia"), 0x11E6_0398);
+        // Long enough to need the sums reduced part-way through
+        assert_eq!(compute_adler32(&[0xFF; 100_000]), 0x149A_302C);
+    }
+
+    #[test]
+    fn test_checksum_sizes() {
+        assert_eq!(Checksum::Crc16Ccitt.size(), 2);
+        assert_eq!(Checksum::Adler32.size(), 4);
+        assert_eq!(Checksum::Crc(CrcParams::CRC_64_XZ).size(), 8);
+        assert_eq!(Checksum::Crc(CrcParams::CRC_8).compute(CHECK_INPUT), Ok(0xF4));
+    }
+}
@@ -0,0 +1,224 @@
+// ABOUTME: Non-IEEE floating point layouts with configurable widths and exponent bias
+// ABOUTME: Packs and unpacks sign/exponent/mantissa bit patterns to and from f64
+
+use alloc::format;
+
+use crate::{BinSchemaError, Result};
+
+/// Bit layout of a custom float: an optional sign bit (sign-magnitude), then an
+/// excess-`bias` exponent, then the mantissa, most significant bit first.
+///
+/// With an implied leading bit, exponent 0 holds denormals and every other
+/// exponent is normalized (`1.m`). Without one, the mantissa's top bit is the
+/// explicit integer bit (`m.mmm`). Unlike IEEE 754 no exponent is reserved for
+/// infinities or NaN, so every bit pattern decodes to a finite value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomFloat {
+    pub sign: bool,
+    pub exponent_bits: u8,
+    pub mantissa_bits: u8,
+    pub bias: i32,
+    pub implied_leading_bit: bool,
+}
+
+impl CustomFloat {
+    /// Total encoded width in bits
+    pub const fn width(&self) -> u8 {
+        self.sign as u8 + self.exponent_bits + self.mantissa_bits
+    }
+
+    fn max_exponent(&self) -> i32 {
+        ((1u64 << self.exponent_bits) - 1) as i32
+    }
+
+    /// Weight of the mantissa's least significant bit relative to the leading 1
+    fn fraction_bits(&self) -> i32 {
+        if self.implied_leading_bit {
+            self.mantissa_bits as i32
+        } else {
+            self.mantissa_bits as i32 - 1
+        }
+    }
+
+    /// Decodes a raw bit pattern (right-aligned in `raw`)
+    pub fn to_f64(&self, raw: u64) -> f64 {
+        let mantissa = raw & ((1u64 << self.mantissa_bits) - 1);
+        let exponent = ((raw >> self.mantissa_bits) & ((1u64 << self.exponent_bits) - 1)) as i32;
+        let negative = self.sign && (raw >> (self.mantissa_bits + self.exponent_bits)) & 1 == 1;
+
+        let significand = if self.implied_leading_bit && exponent != 0 {
+            mantissa | (1u64 << self.mantissa_bits)
+        } else {
+            mantissa
+        };
+        // Denormals share the smallest normal exponent
+        let unbiased = if self.implied_leading_bit && exponent == 0 { 1 } else { exponent } - self.bias;
+        let magnitude = significand as f64 * pow2(unbiased - self.fraction_bits());
+
+        if negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Encodes `value` to the nearest representable bit pattern. Fails for NaN,
+    /// infinities, magnitudes above the largest finite value and, without a
+    /// sign bit, negative values.
+    pub fn from_f64(&self, value: f64) -> Result<u64> {
+        if !value.is_finite() {
+            return Err(BinSchemaError::InvalidValue(format!("{} cannot be encoded as a custom float", value)));
+        }
+        let negative = value.is_sign_negative() && value != 0.0;
+        if negative && !self.sign {
+            return Err(BinSchemaError::InvalidValue(format!("{} is negative but the custom float has no sign bit", value)));
+        }
+        let sign_bit = u64::from(self.sign && value.is_sign_negative()) << (self.mantissa_bits + self.exponent_bits);
+
+        let magnitude = value.abs();
+        if magnitude == 0.0 {
+            return Ok(sign_bit);
+        }
+
+        // magnitude = fraction * 2^exp with fraction in [1, 2)
+        let mut exp = floor_log2(magnitude);
+        let fraction = magnitude / pow2(exp);
+        if fraction >= 2.0 {
+            exp += 1;
+        } else if fraction < 1.0 {
+            exp -= 1;
+        }
+
+        let min_exponent = if self.implied_leading_bit { 1 } else { 0 };
+        let mut exponent = (exp + self.bias).max(min_exponent);
+        let scale = exponent - self.bias - self.fraction_bits();
+        let mut significand = round_half_away(magnitude / pow2(scale));
+
+        // Rounding can carry into the next binade
+        let normal_limit = 1u64 << (self.fraction_bits() + 1);
+        if significand >= normal_limit {
+            significand >>= 1;
+            exponent += 1;
+        }
+
+        let mantissa = if self.implied_leading_bit {
+            if significand < (1u64 << self.mantissa_bits) {
+                // Denormal: only reachable at the minimum exponent
+                exponent = 0;
+                significand
+            } else {
+                significand & ((1u64 << self.mantissa_bits) - 1)
+            }
+        } else {
+            significand
+        };
+
+        if exponent > self.max_exponent() {
+            return Err(BinSchemaError::InvalidValue(format!("{} is out of range for the custom float", value)));
+        }
+        Ok(sign_bit | ((exponent as u64) << self.mantissa_bits) | mantissa)
+    }
+}
+
+// Bit-level stand-ins for powi, log2 and round, which core doesn't provide
+
+/// 2^`exp` as an f64, exact while it is representable (0 below the smallest
+/// subnormal, infinity above the largest normal)
+fn pow2(exp: i32) -> f64 {
+    match exp {
+        ..=-1075 => 0.0,
+        -1074..=-1023 => f64::from_bits(1u64 << (exp + 1074)),
+        -1022..=1023 => f64::from_bits(((exp + 1023) as u64) << 52),
+        _ => f64::INFINITY,
+    }
+}
+
+/// floor(log2(`value`)) for a finite positive `value`
+fn floor_log2(value: f64) -> i32 {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7FF) as i32;
+    if exponent == 0 {
+        // Subnormal: position of the top set mantissa bit
+        63 - bits.leading_zeros() as i32 - 1074
+    } else {
+        exponent - 1023
+    }
+}
+
+/// `value` rounded to the nearest integer, halves away from zero, for a
+/// non-negative `value` (saturating like an `as` cast)
+fn round_half_away(value: f64) -> u64 {
+    let whole = value as u64;
+    if value - whole as f64 >= 0.5 {
+        whole.saturating_add(1)
+    } else {
+        whole
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1-5-10 layout with bias 15 (the same shape as IEEE binary16)
+    const HALF: CustomFloat = CustomFloat { sign: true, exponent_bits: 5, mantissa_bits: 10, bias: 15, implied_leading_bit: true };
+
+    #[test]
+    fn test_known_bit_patterns() {
+        assert_eq!(HALF.width(), 16);
+        assert_eq!(HALF.to_f64(0x3C00), 1.0);
+        assert_eq!(HALF.to_f64(0xC000), -2.0);
+        assert_eq!(HALF.to_f64(0x3555), 0.333251953125);
+        // Smallest denormal and largest value (no exponent reserved for infinity)
+        assert_eq!(HALF.to_f64(0x0001), 2f64.powi(-24));
+        assert_eq!(HALF.to_f64(0x7FFF), 131008.0);
+
+        assert_eq!(HALF.from_f64(1.0), Ok(0x3C00));
+        assert_eq!(HALF.from_f64(-2.0), Ok(0xC000));
+        assert_eq!(HALF.from_f64(2f64.powi(-24)), Ok(0x0001));
+        assert_eq!(HALF.from_f64(-0.0), Ok(0x8000));
+    }
+
+    #[test]
+    fn test_round_trip_within_precision() {
+        for raw in 0..=0xFFFFu64 {
+            assert_eq!(HALF.from_f64(HALF.to_f64(raw)), Ok(raw), "raw {:#06x}", raw);
+        }
+        for &value in &[0.1, -2.71, 1000.5, 6.0e-5, 65504.0] {
+            let decoded = HALF.to_f64(HALF.from_f64(value).unwrap());
+            assert!((decoded - value).abs() <= value.abs() * 2f64.powi(-11), "{} -> {}", value, decoded);
+        }
+    }
+
+    #[test]
+    fn test_bit_level_math_matches_float_methods() {
+        for exp in [-1000, -24, 0, 1, 1023] {
+            assert_eq!(pow2(exp), 2f64.powi(exp), "2^{}", exp);
+        }
+        // Exact down through the subnormals, where powi already underflows
+        assert_eq!(pow2(-1022), f64::MIN_POSITIVE);
+        assert_eq!(pow2(-1074), f64::from_bits(1));
+        assert_eq!(pow2(-1075), 0.0);
+        assert_eq!(pow2(1024), f64::INFINITY);
+        for &value in &[f64::from_bits(1), 1.0e-310, 0.75, 1.0, 3.0, 1.0e300] {
+            assert_eq!(floor_log2(value), value.log2().floor() as i32, "{}", value);
+        }
+        // log2 rounds up to 1024 here
+        assert_eq!(floor_log2(f64::MAX), 1023);
+        for &value in &[0.0, 0.49999999999999994, 0.5, 1.5, 2.5, 4503599627370497.0] {
+            assert_eq!(round_half_away(value), value.round() as u64, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_explicit_leading_bit_and_unsigned() {
+        // 0-4-4: unsigned, explicit integer bit, bias 7
+        let fmt = CustomFloat { sign: false, exponent_bits: 4, mantissa_bits: 4, bias: 7, implied_leading_bit: false };
+        assert_eq!(fmt.to_f64(0b0111_1000), 1.0);
+        assert_eq!(fmt.from_f64(1.0), Ok(0b0111_1000));
+        assert_eq!(fmt.from_f64(1.5), Ok(0b0111_1100));
+        assert!(fmt.from_f64(-1.0).is_err());
+        assert!(fmt.from_f64(1.0e9).is_err());
+        assert!(HALF.from_f64(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+// ABOUTME: Decodable trait implemented by every generated type
+// ABOUTME: try_decode decodes any Decodable from untrusted bytes under default limits
+
+use crate::{BitOrder, BitStreamDecoder, DecodeLimits, Result};
+
+/// A type with a generated decoder, so generic code (fuzz targets, protocol
+/// dispatch tables) can decode it without naming it.
+pub trait Decodable: Sized {
+    /// Bit order the type's own `decode` reads with
+    const BIT_ORDER: BitOrder;
+
+    /// Decodes one value starting at the decoder's position
+    fn decode_with_decoder(decoder: &mut BitStreamDecoder<'_>) -> Result<Self>;
+}
+
+/// Decodes a `T` from bytes that may be arbitrary or hostile, such as a
+/// fuzzer's input.
+///
+/// Decoding runs under `DecodeLimits::default()`, so besides rejecting
+/// malformed input with an error it bounds recursion depth and allocation
+/// size: no input makes it panic, overflow the stack or exhaust memory. A
+/// cargo-fuzz target only needs `let _ = try_decode::<Message>(data);`.
+pub fn try_decode<T: Decodable>(bytes: &[u8]) -> Result<T> {
+    let mut decoder = BitStreamDecoder::new(bytes, T::BIT_ORDER);
+    decoder.set_limits(DecodeLimits::default())?;
+    T::decode_with_decoder(&mut decoder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinSchemaError, Endianness};
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    /// Shaped like a generated recursive struct: a length-prefixed name that
+    /// may instead be a back-reference to an earlier one, then an optional child
+    struct Node {
+        name: Vec<u8>,
+        child: Option<Box<Node>>,
+    }
+
+    impl Node {
+        fn decode_name(decoder: &mut BitStreamDecoder) -> Result<Vec<u8>> {
+            let start_pos = decoder.position();
+            let len = decoder.read_uint8()?;
+            if len < 0xC0 {
+                return decoder.read_bytes_vec(len as usize);
+            }
+            decoder.rewind_to(start_pos);
+            let offset = (decoder.read_uint16(Endianness::BigEndian)? & 0x3FFF) as usize;
+            let saved_pos = decoder.position();
+            decoder.seek(offset)?;
+            let len = decoder.read_uint8()? as usize;
+            let name = decoder.read_bytes_vec(len)?;
+            decoder.seek(saved_pos)?;
+            Ok(name)
+        }
+    }
+
+    impl Decodable for Node {
+        const BIT_ORDER: BitOrder = BitOrder::MsbFirst;
+
+        fn decode_with_decoder(decoder: &mut BitStreamDecoder<'_>) -> Result<Self> {
+            decoder.nested(|decoder| {
+                let name = Node::decode_name(decoder)?;
+                let child = if decoder.read_uint8()? != 0 {
+                    Some(Box::new(Node::decode_with_decoder(decoder)?))
+                } else {
+                    None
+                };
+                Ok(Node { name, child })
+            })
+        }
+    }
+
+    #[test]
+    fn test_try_decode_survives_random_input() {
+        // xorshift64, so the inputs are the same on every run
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..10_000 {
+            let len = (next() % 64) as usize;
+            let bytes: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            let _ = try_decode::<Node>(&bytes);
+        }
+    }
+
+    #[test]
+    fn test_try_decode_bounds_recursion() {
+        // Each level: empty name, "has child"
+        let deep: Vec<u8> = [0x00, 0x01].repeat(100_000);
+        assert!(matches!(try_decode::<Node>(&deep), Err(BinSchemaError::LimitExceeded(_))));
+
+        let node = try_decode::<Node>(&[0x01, b'a', 0x01, 0xC0, 0x00, 0x00]).unwrap();
+        assert_eq!(node.child.unwrap().name, b"a");
+    }
+}
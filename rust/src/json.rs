@@ -0,0 +1,92 @@
+// ABOUTME: JSON view of generated types for inspecting and editing decoded messages
+// ABOUTME: BinSchemaValue converts any type with the optional serde derives to and from serde_json::Value
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{BinSchemaError, Result};
+
+/// Converts a value to and from JSON.
+///
+/// Generated types derive `Serialize` and `Deserialize` when the generated
+/// crate's `serde` feature is on, and pick this trait up through the blanket
+/// impl. Fields come out under their schema names; optional fields that are
+/// absent become `null`, and unions and enums use serde's default externally
+/// tagged form (`{"Variant": ...}`, or just `"Variant"` for a plain enum).
+pub trait BinSchemaValue: Sized {
+    /// Panics if a field holds a number JSON can't represent, which for
+    /// generated types means a 128-bit integer outside the 64-bit range.
+    fn to_json(&self) -> Value;
+
+    /// Fails with `InvalidValue` naming the first field that is missing or
+    /// has the wrong type or range.
+    fn from_json(value: &Value) -> Result<Self>;
+}
+
+impl<T: Serialize + DeserializeOwned> BinSchemaValue for T {
+    fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("value is representable as JSON")
+    }
+
+    fn from_json(value: &Value) -> Result<Self> {
+        T::deserialize(value).map_err(|e| BinSchemaError::InvalidValue(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    /// Shaped like the generated type for sensornet's SensorReading
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SensorReading {
+        sensor_type: u8,
+        battery_low: u8,
+        error: u8,
+        reserved: u8,
+        timestamp: u32,
+        value: f32,
+        location: Option<u32>,
+    }
+
+    #[test]
+    fn test_sensor_reading_round_trips_through_json() {
+        let reading = SensorReading {
+            sensor_type: 0,
+            battery_low: 0,
+            error: 1,
+            reserved: 0,
+            timestamp: 1633017600,
+            value: 23.5,
+            location: Some(42),
+        };
+        let json = reading.to_json();
+        assert_eq!(
+            json,
+            json!({
+                "sensor_type": 0, "battery_low": 0, "error": 1, "reserved": 0,
+                "timestamp": 1633017600, "value": 23.5, "location": 42
+            })
+        );
+        assert_eq!(SensorReading::from_json(&json).unwrap(), reading);
+
+        let without_location = SensorReading { location: None, ..reading };
+        assert_eq!(without_location.to_json()["location"], Value::Null);
+        assert_eq!(SensorReading::from_json(&without_location.to_json()).unwrap(), without_location);
+    }
+
+    #[test]
+    fn test_from_json_rejects_out_of_range_field() {
+        let json = json!({
+            "sensor_type": 300, "battery_low": 0, "error": 0, "reserved": 0,
+            "timestamp": 0, "value": 0.0, "location": null
+        });
+        match SensorReading::from_json(&json) {
+            Err(BinSchemaError::InvalidValue(msg)) => assert!(msg.contains("300"), "{}", msg),
+            other => panic!("expected InvalidValue, got {:?}", other),
+        }
+    }
+}
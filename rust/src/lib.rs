@@ -1,12 +1,49 @@
 // ABOUTME: Rust runtime for BinSchema bit-level serialization
 // ABOUTME: Provides BitStreamEncoder and BitStreamDecoder for byte-compatible encoding/decoding
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
 pub mod bitstream;
+#[cfg(feature = "std")]
 pub mod test_schema;
+#[cfg(feature = "std")]
+pub mod schema_validation;
+#[cfg(feature = "std")]
+pub mod schema_compat;
+#[cfg(feature = "std")]
+pub mod schema_introspect;
+#[cfg(feature = "std")]
+pub mod schema_size;
 pub mod context;
+pub mod crc;
+pub mod custom_float;
+pub mod decodable;
+pub mod partial;
+pub mod limits;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "serde")]
+pub mod json;
 
-pub use bitstream::{BitStreamEncoder, BitStreamDecoder, Endianness, BitOrder};
-pub use context::{EncodeContext, FieldValue};
+pub use bitstream::{BitStreamEncoder, BitStreamDecoder, Endianness, BitOrder, Reservation};
+pub use context::{encode_compression_pointer, encode_pointer, EncodeContext, FieldValue, MAX_COMPRESSION_OFFSET};
+pub use crc::{compute_adler32, compute_crc16_ccitt, compute_crc32, crc, Checksum, CrcParams};
+pub use custom_float::CustomFloat;
+pub use decodable::{try_decode, Decodable};
+pub use partial::{PartialDecoder, Progress};
+pub use limits::DecodeLimits;
+#[cfg(feature = "std")]
+pub use stream::{BitStreamReader, BitStreamWriter};
+#[cfg(feature = "serde")]
+pub use json::BinSchemaValue;
+#[cfg(feature = "std")]
+pub use schema_validation::SchemaError;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BinSchemaError {
@@ -16,10 +53,14 @@ pub enum BinSchemaError {
     InvalidVariant(u64),
     NotImplemented(String),
     ContextMissing(String),
+    /// A `DecodeLimits` cap was exceeded
+    LimitExceeded(String),
+    /// Reading from or writing to an `std::io` stream failed
+    Io(String),
 }
 
-impl std::fmt::Display for BinSchemaError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for BinSchemaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             BinSchemaError::UnexpectedEof => write!(f, "Unexpected end of input"),
             BinSchemaError::InvalidUtf8 => write!(f, "Invalid UTF-8 data"),
@@ -27,13 +68,38 @@ impl std::fmt::Display for BinSchemaError {
             BinSchemaError::InvalidVariant(v) => write!(f, "Invalid variant discriminator: {}", v),
             BinSchemaError::NotImplemented(msg) => write!(f, "Not implemented: {}", msg),
             BinSchemaError::ContextMissing(field) => write!(f, "Context missing required field: {}", field),
+            BinSchemaError::LimitExceeded(msg) => write!(f, "Decode limit exceeded: {}", msg),
+            BinSchemaError::Io(msg) => write!(f, "I/O error: {}", msg),
         }
     }
 }
 
-impl std::error::Error for BinSchemaError {}
+impl core::error::Error for BinSchemaError {}
+
+pub type Result<T> = core::result::Result<T, BinSchemaError>;
+
+impl BinSchemaError {
+    /// Error for a try-in-order union where every variant failed, naming each
+    /// attempted variant with the reason it was rejected
+    pub fn no_variant_matched(union: &str, attempts: &[(&str, BinSchemaError)]) -> Self {
+        let reasons: Vec<String> = attempts.iter().map(|(variant, e)| format!("{}: {}", variant, e)).collect();
+        BinSchemaError::InvalidValue(format!("no variant of {} matched ({})", union, reasons.join("; ")))
+    }
+}
+
+/// A recoverable problem found while decoding in lenient mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// `Type.field` path of the offending field (just `Type` for fatal errors)
+    pub field: String,
+    pub error: BinSchemaError,
+}
 
-pub type Result<T> = std::result::Result<T, BinSchemaError>;
+impl core::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.field, self.error)
+    }
+}
 
 /// CRC32 implementation (IEEE polynomial)
 /// Used for computed crc32_of fields
@@ -81,3 +147,91 @@ pub fn crc32(data: &[u8]) -> u32 {
     }
     !crc
 }
+
+/// Length of `bytes` without a trailing UTF-8 sequence that was cut short, e.g.
+/// by a fixed-size field. Only an incomplete sequence at the very end is
+/// excluded; invalid bytes elsewhere are left for UTF-8 validation to reject.
+pub fn complete_utf8_len(bytes: &[u8]) -> usize {
+    match core::str::from_utf8(bytes) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => bytes.len(),
+    }
+}
+
+/// Renders bytes as an `xxd`-style dump: an 8-digit hex offset, sixteen bytes
+/// per line in two-byte groups, and an ASCII sidebar with non-printable bytes
+/// shown as `.`. Every line, including the last, ends with a newline.
+pub fn hexdump(bytes: &[u8]) -> String {
+    use core::fmt::Write;
+
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let mut hex = String::with_capacity(39);
+        for (i, byte) in chunk.iter().enumerate() {
+            if i > 0 && i % 2 == 0 {
+                hex.push(' ');
+            }
+            let _ = write!(hex, "{:02x}", byte);
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect();
+        let _ = writeln!(out, "{:08x}: {:<39}  {}", line * 16, hex, ascii);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_utf8_len_drops_cut_tail() {
+        // "héllo wörld" in a 9-byte fixed field: 'ö' (c3 b6) is cut after c3
+        let field = &"héllo wörld".as_bytes()[..9];
+        assert_eq!(field.last(), Some(&0xC3));
+        assert!(String::from_utf8(field.to_vec()).is_err());
+        let len = complete_utf8_len(field);
+        assert_eq!(String::from_utf8(field[..len].to_vec()).unwrap(), "héllo w");
+
+        // Complete text and invalid bytes mid-field are left alone
+        assert_eq!(complete_utf8_len("héllo".as_bytes()), 6);
+        assert_eq!(complete_utf8_len(&[b'a', 0xFF, b'b']), 3);
+        // A cut 4-byte sequence is dropped whole
+        assert_eq!(complete_utf8_len(&[b'a', 0xF0, 0x9F, 0x98]), 1);
+    }
+
+    #[test]
+    fn test_no_variant_matched_lists_attempts() {
+        let err = BinSchemaError::no_variant_matched(
+            "Rdata",
+            &[("ARdata", BinSchemaError::InvalidValue("bad".into())), ("NSRdata", BinSchemaError::InvalidUtf8)],
+        );
+        assert_eq!(
+            err,
+            BinSchemaError::InvalidValue(
+                "no variant of Rdata matched (ARdata: Invalid value: bad; NSRdata: Invalid UTF-8 data)".into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_hexdump_short_buffer() {
+        assert_eq!(hexdump(b""), "");
+        assert_eq!(
+            hexdump(b"Hi\x00\xff!"),
+            "00000000: 4869 00ff 21                             Hi..!\n"
+        );
+    }
+
+    #[test]
+    fn test_hexdump_multi_line() {
+        let bytes: Vec<u8> = b"Hello, World!\n\x01\x7f binschema".to_vec();
+        assert_eq!(
+            hexdump(&bytes),
+            "00000000: 4865 6c6c 6f2c 2057 6f72 6c64 210a 017f  Hello, World!...\n\
+             00000010: 2062 696e 7363 6865 6d61                  binschema\n"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// ABOUTME: Tunable hardening limits for decoding untrusted input
+// ABOUTME: Gathers allocation, nesting, compression and size caps in one DecodeLimits struct
+
+use alloc::format;
+
+use crate::{BinSchemaError, Result};
+
+/// Caps a decoder enforces on top of its input bounds, so an application can
+/// set its safety posture in one place. Install them with
+/// `BitStreamDecoder::set_limits` or a generated `decode_with_limits`; plain
+/// `decode` runs with `baseline()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Largest element count or byte length a single field may allocate for
+    pub max_alloc: usize,
+    /// Deepest nesting of composite types, which bounds recursion on the stack
+    pub max_depth: usize,
+    /// Most compression pointers followed while decoding one name
+    pub max_pointer_jumps: usize,
+    /// Longest single label of a pointer-following name, in encoded bytes
+    /// including its length prefix
+    pub max_label_len: usize,
+    /// Longest pointer-following name, in encoded bytes including the
+    /// terminating zero, summed across the pointers followed
+    pub max_name_len: usize,
+    /// Longest message accepted at all
+    pub max_message_len: usize,
+}
+
+impl Default for DecodeLimits {
+    /// Conservative limits: 64 MiB messages and allocations, 64 levels of
+    /// nesting, and the DNS bounds on names (63-byte labels, 255-byte names)
+    /// with at most 16 pointers per name.
+    fn default() -> Self {
+        Self {
+            max_alloc: Self::DEFAULT_MAX_ALLOC,
+            max_depth: 64,
+            max_pointer_jumps: 16,
+            max_label_len: 64,
+            max_name_len: 255,
+            max_message_len: Self::DEFAULT_MAX_ALLOC,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// Allocation cap of `default()` and `baseline()`
+    pub const DEFAULT_MAX_ALLOC: usize = 64 << 20;
+
+    /// What a decoder enforces until `set_limits` is called: only the default
+    /// `max_alloc`, so a forged length can't request gigabytes even from
+    /// callers that never think about limits
+    pub fn baseline() -> Self {
+        Self { max_alloc: Self::DEFAULT_MAX_ALLOC, ..Self::unlimited() }
+    }
+
+    /// No limits beyond the input itself
+    pub fn unlimited() -> Self {
+        Self {
+            max_alloc: usize::MAX,
+            max_depth: usize::MAX,
+            max_pointer_jumps: usize::MAX,
+            max_label_len: usize::MAX,
+            max_name_len: usize::MAX,
+            max_message_len: usize::MAX,
+        }
+    }
+
+    pub fn check_alloc(&self, len: usize) -> Result<()> {
+        check("max_alloc", len, self.max_alloc)
+    }
+
+    pub fn check_depth(&self, depth: usize) -> Result<()> {
+        check("max_depth", depth, self.max_depth)
+    }
+
+    pub fn check_pointer_jumps(&self, jumps: usize) -> Result<()> {
+        check("max_pointer_jumps", jumps, self.max_pointer_jumps)
+    }
+
+    pub fn check_label_len(&self, len: usize) -> Result<()> {
+        check("max_label_len", len, self.max_label_len)
+    }
+
+    pub fn check_name_len(&self, len: usize) -> Result<()> {
+        check("max_name_len", len, self.max_name_len)
+    }
+
+    pub fn check_message_len(&self, len: usize) -> Result<()> {
+        check("max_message_len", len, self.max_message_len)
+    }
+}
+
+fn check(limit: &str, value: usize, max: usize) -> Result<()> {
+    if value > max {
+        return Err(BinSchemaError::LimitExceeded(format!("{} is {}, got {}", limit, max, value)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_checks_allow_up_to_the_limit() {
+        let limits = DecodeLimits::default();
+        assert!(limits.check_name_len(255).is_ok());
+        assert_eq!(
+            limits.check_name_len(256),
+            Err(BinSchemaError::LimitExceeded("max_name_len is 255, got 256".to_string()))
+        );
+        assert!(DecodeLimits::unlimited().check_name_len(usize::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_baseline_only_caps_allocation() {
+        let baseline = DecodeLimits::baseline();
+        assert!(baseline.check_alloc(64 << 20).is_ok());
+        assert!(baseline.check_alloc((64 << 20) + 1).is_err());
+        assert!(baseline.check_depth(usize::MAX).is_ok());
+        assert!(baseline.check_message_len(usize::MAX).is_ok());
+    }
+}
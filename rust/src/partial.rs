@@ -0,0 +1,113 @@
+// ABOUTME: Push-based decoding for messages that arrive in chunks (e.g. TCP reads)
+// ABOUTME: Buffers fed bytes and reports NeedMoreData until a whole value is available
+
+use alloc::vec::Vec;
+
+use crate::{BinSchemaError, BitOrder, BitStreamDecoder, Result};
+
+/// Outcome of feeding bytes to a `PartialDecoder`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Progress<T> {
+    /// A whole value was decoded; its bytes have been removed from the buffer
+    Complete(T),
+    /// The buffered bytes end before the value does
+    NeedMoreData,
+}
+
+/// Accumulates chunks of input and decodes a value once enough has arrived.
+///
+/// Generated decoders read straight from a slice, so a value can't be
+/// suspended halfway through a field. Instead, when an attempt runs out of
+/// input the decoder remembers how far the buffer had got and only retries
+/// once more bytes are fed. Bytes after a completed value stay buffered for
+/// the next one, so a stream of back-to-back messages can be fed as it comes.
+///
+/// Only `UnexpectedEof` means "wait for more": any other error is returned
+/// from `feed` as-is. Types whose length is taken from the end of the input
+/// (EOF-terminated arrays, `position` from the end) would decode early from a
+/// partial buffer and need a framed transport instead.
+pub struct PartialDecoder<T> {
+    buffer: Vec<u8>,
+    bit_order: BitOrder,
+    decode: fn(&mut BitStreamDecoder) -> Result<T>,
+    /// Buffer length at the last attempt that hit end of input
+    attempted_len: Option<usize>,
+}
+
+impl<T> PartialDecoder<T> {
+    /// Creates a decoder around a generated `decode_with_decoder` function
+    pub fn new(bit_order: BitOrder, decode: fn(&mut BitStreamDecoder) -> Result<T>) -> Self {
+        Self { buffer: Vec::new(), bit_order, decode, attempted_len: None }
+    }
+
+    /// Appends `bytes` and tries to decode a value from the buffered input
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Progress<T>> {
+        self.buffer.extend_from_slice(bytes);
+        if self.attempted_len == Some(self.buffer.len()) {
+            return Ok(Progress::NeedMoreData);
+        }
+
+        let mut decoder = BitStreamDecoder::new(&self.buffer, self.bit_order);
+        decoder.set_input_incomplete();
+        match (self.decode)(&mut decoder) {
+            Ok(value) => {
+                let consumed = self.buffer.len() - decoder.remaining_slice().len();
+                self.buffer.drain(..consumed);
+                self.attempted_len = None;
+                Ok(Progress::Complete(value))
+            }
+            Err(BinSchemaError::UnexpectedEof) => {
+                self.attempted_len = Some(self.buffer.len());
+                Ok(Progress::NeedMoreData)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Bytes received but not yet consumed by a completed value
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+    use alloc::vec;
+
+    /// Shape of a generated decoder: `len: u8` followed by that many bytes
+    fn decode_record(decoder: &mut BitStreamDecoder) -> Result<Vec<u8>> {
+        let len = decoder.read_uint8()? as usize;
+        decoder.ensure_count_fits(len, 8)?;
+        (0..len).map(|_| decoder.read_uint8()).collect()
+    }
+
+    #[test]
+    fn test_completes_once_enough_bytes_arrive() {
+        let mut partial = PartialDecoder::new(BitOrder::MsbFirst, decode_record);
+        assert_eq!(partial.feed(&[0x03, 0xAA]), Ok(Progress::NeedMoreData));
+        assert_eq!(partial.feed(&[]), Ok(Progress::NeedMoreData));
+        assert_eq!(partial.feed(&[0xBB]), Ok(Progress::NeedMoreData));
+        // The tail of this chunk starts the next record
+        assert_eq!(partial.feed(&[0xCC, 0x01]), Ok(Progress::Complete(vec![0xAA, 0xBB, 0xCC])));
+        assert_eq!(partial.buffered(), &[0x01]);
+        assert_eq!(partial.feed(&[0xDD]), Ok(Progress::Complete(vec![0xDD])));
+        assert!(partial.buffered().is_empty());
+    }
+
+    #[test]
+    fn test_other_errors_are_returned() {
+        fn decode_magic(decoder: &mut BitStreamDecoder) -> Result<u16> {
+            let magic = decoder.read_uint16(Endianness::BigEndian)?;
+            if magic != 0xCAFE {
+                return Err(BinSchemaError::InvalidVariant(magic as u64));
+            }
+            Ok(magic)
+        }
+
+        let mut partial = PartialDecoder::new(BitOrder::MsbFirst, decode_magic);
+        assert_eq!(partial.feed(&[0xBE]), Ok(Progress::NeedMoreData));
+        assert_eq!(partial.feed(&[0xEF]), Err(BinSchemaError::InvalidVariant(0xBEEF)));
+    }
+}
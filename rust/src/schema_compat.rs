@@ -0,0 +1,344 @@
+// ABOUTME: Wire-layout comparison between types of two loaded schemas
+// ABOUTME: Ignores field and type names so refactored schemas can be checked for byte compatibility
+
+use crate::test_schema::{Field, Schema, TypeDef};
+use std::collections::HashSet;
+
+/// Primitive types whose encoding depends on byte order
+const MULTI_BYTE_TYPES: &[&str] = &["uint16", "uint32", "uint64", "int16", "int32", "int64", "float32", "float64"];
+
+/// Types packed bit by bit, whose encoding depends on bit order
+const BIT_PACKED_TYPES: &[&str] = &["bit", "int", "bitfield", "custom_float"];
+
+impl Schema {
+    /// Whether `type_a` in this schema and `type_b` in `other` encode every
+    /// value to the same bytes: same field order, types, widths, endianness
+    /// and bit order, with nested types compared the same way.
+    ///
+    /// Field and type names are ignored. Fields that reference a sibling by
+    /// name (`length_field`, a union's discriminator `field`, computed targets)
+    /// are compared by the sibling's position instead. Expressions
+    /// (`conditional`, `when`, `count_expr`) are compared as written.
+    pub fn wire_compatible(&self, other: &Schema, type_a: &str, type_b: &str) -> bool {
+        Comparison { a: self, b: other, assumed: HashSet::new() }.types(type_a, type_b)
+    }
+
+    fn default_endianness(&self) -> &str {
+        self.config.as_ref().and_then(|c| c.endianness.as_deref()).unwrap_or("big_endian")
+    }
+
+    fn default_bit_order(&self) -> &str {
+        self.config.as_ref().and_then(|c| c.bit_order.as_deref()).unwrap_or("msb_first")
+    }
+}
+
+struct Comparison<'s> {
+    a: &'s Schema,
+    b: &'s Schema,
+    /// Type pairs already being compared; assumed compatible so recursive types terminate
+    assumed: HashSet<(String, String)>,
+}
+
+impl Comparison<'_> {
+    fn types(&mut self, type_a: &str, type_b: &str) -> bool {
+        let (Some(def_a), Some(def_b)) = (self.a.types.get(type_a), self.b.types.get(type_b)) else {
+            return false;
+        };
+        if !self.assumed.insert((type_a.to_string(), type_b.to_string())) {
+            return true;
+        }
+
+        match (def_a, def_b) {
+            (TypeDef::Sequence { sequence: seq_a, .. }, TypeDef::Sequence { sequence: seq_b, .. }) => {
+                seq_a.len() == seq_b.len()
+                    && seq_a.iter().zip(seq_b).all(|(fa, fb)| self.fields(fa, fb, Some((seq_a, seq_b))))
+            }
+            (TypeDef::Enum { repr: repr_a, variants: va, .. }, TypeDef::Enum { repr: repr_b, variants: vb, .. }) => {
+                let mut values_a: Vec<u64> = va.values().copied().collect();
+                let mut values_b: Vec<u64> = vb.values().copied().collect();
+                values_a.sort_unstable();
+                values_b.sort_unstable();
+                repr_a == repr_b
+                    && (repr_a == "uint8" || self.a.default_endianness() == self.b.default_endianness())
+                    && values_a == values_b
+            }
+            (
+                TypeDef::DiscriminatedUnion { discriminator: da, variants: va, .. },
+                TypeDef::DiscriminatedUnion { discriminator: db, variants: vb, .. },
+            ) => {
+                da == db
+                    && va.len() == vb.len()
+                    && va.iter().zip(vb).all(|(x, y)| x.when == y.when && self.types(&x.type_name, &y.type_name))
+            }
+            (
+                TypeDef::BackReference { storage: sa, offset_mask: ma, offset_from: fa, target_type: ta, endianness: ea, .. },
+                TypeDef::BackReference { storage: sb, offset_mask: mb, offset_from: fb, target_type: tb, endianness: eb, .. },
+            ) => {
+                sa == sb
+                    && ma == mb
+                    && fa == fb
+                    && ea.as_deref().unwrap_or(self.a.default_endianness()) == eb.as_deref().unwrap_or(self.b.default_endianness())
+                    && self.types(ta, tb)
+            }
+            (TypeDef::Array { kind: ka, items: ia, .. }, TypeDef::Array { kind: kb, items: ib, .. }) => {
+                ka == kb && self.fields(ia, ib, None)
+            }
+            (
+                TypeDef::Direct { type_name: ta, kind: ka, encoding: ea, length: la, length_type: lta, .. },
+                TypeDef::Direct { type_name: tb, kind: kb, encoding: eb, length: lb, length_type: ltb, .. },
+            ) => ka == kb && ea == eb && la == lb && lta == ltb && self.type_names(ta, tb),
+            _ => false,
+        }
+    }
+
+    /// Built-in types must match exactly; named types are compared structurally
+    fn type_names(&mut self, type_a: &str, type_b: &str) -> bool {
+        match (self.a.types.contains_key(type_a), self.b.types.contains_key(type_b)) {
+            (true, true) => self.types(type_a, type_b),
+            (false, false) => type_a == type_b,
+            _ => false,
+        }
+    }
+
+    fn fields(&mut self, fa: &Field, fb: &Field, siblings: Option<(&[Field], &[Field])>) -> bool {
+        if !self.type_names(&fa.field_type, &fb.field_type) {
+            return false;
+        }
+
+        let byte_ordered = [Some(&fa.field_type), fa.length_type.as_ref(), fa.item_length_type.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|t| MULTI_BYTE_TYPES.contains(&t.as_str()));
+        if byte_ordered
+            && fa.endianness.as_deref().unwrap_or(self.a.default_endianness())
+                != fb.endianness.as_deref().unwrap_or(self.b.default_endianness())
+        {
+            return false;
+        }
+        if BIT_PACKED_TYPES.contains(&fa.field_type.as_str())
+            && fa.bit_order.as_deref().unwrap_or(self.a.default_bit_order())
+                != fb.bit_order.as_deref().unwrap_or(self.b.default_bit_order())
+        {
+            return false;
+        }
+
+        let same_layout = fa.kind == fb.kind
+            && fa.length == fb.length
+            && fa.length_type == fb.length_type
+            && fa.item_length_type == fb.item_length_type
+            && fa.encoding == fb.encoding
+            && fa.layout == fb.layout
+            && fa.conditional == fb.conditional
+            && fa.presence_type == fb.presence_type
+            && fa.align_to == fb.align_to
+            && fa.r#const == fb.r#const
+            && fa.size == fb.size
+            && fa.length_encoding == fb.length_encoding
+            && fa.terminator_value == fb.terminator_value
+            && fa.terminator_type == fb.terminator_type
+            && fa.terminator_endianness == fb.terminator_endianness
+            && fa.count_expr == fb.count_expr;
+        if !same_layout {
+            return false;
+        }
+
+        let same_refs = match siblings {
+            Some((sa, sb)) => {
+                sibling_refs_match(fa.length_field.as_deref(), fb.length_field.as_deref(), sa, sb)
+                    && sibling_refs_match(discriminator_field(fa), discriminator_field(fb), sa, sb)
+                    && discriminator_without_field(fa) == discriminator_without_field(fb)
+                    && computed_match(fa, fb, sa, sb)
+            }
+            None => fa.length_field == fb.length_field && fa.discriminator == fb.discriminator && fa.computed == fb.computed,
+        };
+        if !same_refs {
+            return false;
+        }
+
+        let items_match = match (&fa.items, &fb.items) {
+            (Some(ia), Some(ib)) => self.fields(ia, ib, None),
+            (None, None) => true,
+            _ => false,
+        };
+        let value_types_match = match (&fa.value_type, &fb.value_type) {
+            (Some(va), Some(vb)) => self.type_names(va, vb),
+            (None, None) => true,
+            _ => false,
+        };
+        items_match
+            && value_types_match
+            && bitfields_match(fa, fb)
+            && self.variant_types(fa, fb)
+    }
+
+    /// Choices and union variants must name compatible types in the same order
+    fn variant_types(&mut self, fa: &Field, fb: &Field) -> bool {
+        let choices_a: Vec<&str> = fa.choices.iter().flatten().map(|c| c.type_name.as_str()).collect();
+        let choices_b: Vec<&str> = fb.choices.iter().flatten().map(|c| c.type_name.as_str()).collect();
+        let variants_a = fa.variants.as_deref().unwrap_or_default();
+        let variants_b = fb.variants.as_deref().unwrap_or_default();
+
+        choices_a.len() == choices_b.len()
+            && choices_a.iter().zip(&choices_b).all(|(a, b)| self.types(a, b))
+            && variants_a.len() == variants_b.len()
+            && variants_a.iter().zip(variants_b).all(|(a, b)| a.when == b.when && self.types(&a.type_name, &b.type_name))
+    }
+}
+
+/// A union's `discriminator.field`, if it selects on a sibling
+fn discriminator_field(field: &Field) -> Option<&str> {
+    field.discriminator.as_ref()?.get("field")?.as_str()
+}
+
+/// The rest of a union's discriminator (`peek`, `endianness`, ...)
+fn discriminator_without_field(field: &Field) -> Option<serde_json::Value> {
+    let mut discriminator = field.discriminator.clone()?;
+    if let Some(map) = discriminator.as_object_mut() {
+        map.remove("field");
+    }
+    Some(discriminator)
+}
+
+/// Position of the sibling a reference like `len` or `header.len` starts at,
+/// with the rest of the path; `None` for references outside the struct
+fn sibling_ref<'f>(reference: &'f str, siblings: &[Field]) -> Option<(usize, &'f str)> {
+    let (head, rest) = reference.split_once('.').unwrap_or((reference, ""));
+    siblings.iter().position(|s| s.name.as_deref() == Some(head)).map(|index| (index, rest))
+}
+
+fn sibling_refs_match(ra: Option<&str>, rb: Option<&str>, sa: &[Field], sb: &[Field]) -> bool {
+    match (ra, rb) {
+        (Some(ra), Some(rb)) => match (sibling_ref(ra, sa), sibling_ref(rb, sb)) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => ra == rb,
+            _ => false,
+        },
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Same computation kind over the same sibling
+fn computed_match(fa: &Field, fb: &Field, sa: &[Field], sb: &[Field]) -> bool {
+    match (&fa.computed, &fb.computed) {
+        (Some(ca), Some(cb)) => {
+            ca.get("type") == cb.get("type")
+                && sibling_refs_match(
+                    ca.get("target").and_then(|t| t.as_str()),
+                    cb.get("target").and_then(|t| t.as_str()),
+                    sa,
+                    sb,
+                )
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Bitfield sub-fields occupy the same bit ranges
+fn bitfields_match(fa: &Field, fb: &Field) -> bool {
+    let ranges = |field: &Field| {
+        let mut ranges: Vec<(u32, u32)> = field.fields.iter().flatten().map(|sub| (sub.offset, sub.size)).collect();
+        ranges.sort_unstable();
+        ranges
+    };
+    ranges(fa) == ranges(fb)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::test_schema::Schema;
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn header_schema() -> Schema {
+        schema(json!({
+            "config": { "endianness": "big_endian" },
+            "types": {
+                "Entry": { "sequence": [
+                    { "name": "key", "type": "uint16" },
+                    { "name": "value", "type": "uint32" }
+                ]},
+                "Header": { "sequence": [
+                    { "name": "magic", "type": "uint32", "const": 0xCAFE },
+                    { "name": "count", "type": "uint8" },
+                    { "name": "entries", "type": "array", "kind": "field_referenced", "length_field": "count", "items": { "type": "Entry" } }
+                ]}
+            }
+        }))
+    }
+
+    #[test]
+    fn test_renamed_fields_and_types_are_compatible() {
+        let renamed = schema(json!({
+            "config": { "endianness": "big_endian" },
+            "types": {
+                "Pair": { "sequence": [
+                    { "name": "id", "type": "uint16" },
+                    { "name": "data", "type": "uint32" }
+                ]},
+                "FileHeader": { "sequence": [
+                    { "name": "signature", "type": "uint32", "const": 0xCAFE },
+                    { "name": "num_pairs", "type": "uint8" },
+                    { "name": "pairs", "type": "array", "kind": "field_referenced", "length_field": "num_pairs", "items": { "type": "Pair" } }
+                ]}
+            }
+        }));
+        assert!(header_schema().wire_compatible(&renamed, "Header", "FileHeader"));
+        assert!(header_schema().wire_compatible(&header_schema(), "Header", "Header"));
+    }
+
+    #[test]
+    fn test_reordered_field_is_incompatible() {
+        let reordered = schema(json!({
+            "config": { "endianness": "big_endian" },
+            "types": {
+                "Entry": { "sequence": [
+                    { "name": "value", "type": "uint32" },
+                    { "name": "key", "type": "uint16" }
+                ]},
+                "Header": { "sequence": [
+                    { "name": "magic", "type": "uint32", "const": 0xCAFE },
+                    { "name": "count", "type": "uint8" },
+                    { "name": "entries", "type": "array", "kind": "field_referenced", "length_field": "count", "items": { "type": "Entry" } }
+                ]}
+            }
+        }));
+        assert!(!header_schema().wire_compatible(&reordered, "Header", "Header"));
+        assert!(!header_schema().wire_compatible(&reordered, "Entry", "Entry"));
+    }
+
+    /// `header_schema` with one change applied to its JSON form
+    fn edited_header(edit: impl FnOnce(&mut serde_json::Value)) -> Schema {
+        let mut value = serde_json::to_value(header_schema()).unwrap();
+        edit(&mut value);
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_widened_or_reendianed_field_is_incompatible() {
+        let widened = edited_header(|s| s["types"]["Entry"]["sequence"][0]["type"] = json!("uint32"));
+        let little_endian = edited_header(|s| s["types"]["Entry"]["sequence"][1]["endianness"] = json!("little_endian"));
+        let other_length_field = edited_header(|s| s["types"]["Header"]["sequence"][2]["length_field"] = json!("magic"));
+
+        assert!(!header_schema().wire_compatible(&widened, "Header", "Header"));
+        assert!(!header_schema().wire_compatible(&little_endian, "Header", "Header"));
+        assert!(!header_schema().wire_compatible(&other_length_field, "Header", "Header"));
+        assert!(!header_schema().wire_compatible(&header_schema(), "Header", "Missing"));
+    }
+
+    #[test]
+    fn test_uuid_layouts_are_incompatible() {
+        let with_layout = |layout: &str| {
+            schema(json!({
+                "types": { "Id": { "sequence": [{ "name": "id", "type": "uuid", "layout": layout }] } }
+            }))
+        };
+        assert!(with_layout("guid").wire_compatible(&with_layout("guid"), "Id", "Id"));
+        assert!(!with_layout("guid").wire_compatible(&with_layout("rfc4122"), "Id", "Id"));
+    }
+}
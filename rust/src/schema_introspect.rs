@@ -0,0 +1,94 @@
+// ABOUTME: Read-only queries over a loaded schema's type definitions
+// ABOUTME: Lets schema explorers and doc generators enumerate a type's fields
+
+use crate::test_schema::{Field, Schema, TypeDef};
+
+impl TypeDef {
+    /// The fields of a sequence type, in wire order. Other kinds of type
+    /// (enums, unions, aliases) have no fields and return an empty slice.
+    pub fn fields(&self) -> &[Field] {
+        match self {
+            TypeDef::Sequence { sequence, .. } => sequence,
+            _ => &[],
+        }
+    }
+}
+
+impl Schema {
+    /// Names of `type_name`'s fields in wire order, skipping unnamed ones.
+    /// Empty if the type doesn't exist or isn't a sequence.
+    pub fn field_names(&self, type_name: &str) -> Vec<&str> {
+        self.types
+            .get(type_name)
+            .map(|def| def.fields().iter().filter_map(|f| f.name.as_deref()).collect())
+            .unwrap_or_default()
+    }
+
+    /// The definition of field `field` of `type_name`, if both exist.
+    pub fn field_type(&self, type_name: &str, field: &str) -> Option<&Field> {
+        self.types
+            .get(type_name)?
+            .fields()
+            .iter()
+            .find(|f| f.name.as_deref() == Some(field))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn packet_schema() -> Schema {
+        serde_json::from_value(json!({
+            "types": {
+                "Header": { "sequence": [
+                    { "name": "version", "type": "uint8" },
+                    { "name": "flags", "type": "bitfield", "size": 8, "fields": [
+                        { "name": "urgent", "offset": 0, "size": 1 },
+                        { "name": "reserved", "offset": 1, "size": 7 }
+                    ]},
+                    { "name": "len", "type": "uint16" }
+                ]},
+                "Packet": { "sequence": [
+                    { "name": "header", "type": "Header" },
+                    { "name": "pad", "type": "padding", "align_to": 4 },
+                    { "name": "body", "type": "array", "kind": "field_referenced", "length_field": "header.len",
+                      "items": { "type": "uint8" } }
+                ]},
+                "Kind": { "type": "enum", "repr": "uint8", "variants": { "A": 1 } }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_enumerates_fields_of_nested_type() {
+        let schema = packet_schema();
+        assert_eq!(schema.field_names("Packet"), vec!["header", "pad", "body"]);
+
+        // Follow the header field into its own type
+        let header = schema.field_type("Packet", "header").unwrap();
+        assert_eq!(header.field_type, "Header");
+        assert_eq!(schema.field_names(&header.field_type), vec!["version", "flags", "len"]);
+
+        let flags = schema.field_type("Header", "flags").unwrap();
+        assert_eq!(flags.field_type, "bitfield");
+        let subfields: Vec<&str> = flags.fields.as_ref().unwrap().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(subfields, vec!["urgent", "reserved"]);
+
+        let body = schema.field_type("Packet", "body").unwrap();
+        assert_eq!(body.length_field.as_deref(), Some("header.len"));
+        assert_eq!(body.items.as_ref().unwrap().field_type, "uint8");
+    }
+
+    #[test]
+    fn test_unknown_types_and_fields() {
+        let schema = packet_schema();
+        assert!(schema.field_names("Missing").is_empty());
+        assert!(schema.field_names("Kind").is_empty());
+        assert!(schema.types["Kind"].fields().is_empty());
+        assert!(schema.field_type("Packet", "trailer").is_none());
+        assert!(schema.field_type("Missing", "header").is_none());
+    }
+}
@@ -0,0 +1,294 @@
+// ABOUTME: Minimum and maximum encoded size of a loaded schema's types
+// ABOUTME: Lets callers size buffers and reject implausible inputs before decoding
+
+use crate::test_schema::{Field, Schema, TypeDef};
+use std::collections::HashSet;
+
+/// Size range of an encoding in bits; `max` is `None` when unbounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bits {
+    min: u64,
+    max: Option<u64>,
+}
+
+impl Bits {
+    fn exact(bits: u64) -> Self {
+        Bits { min: bits, max: Some(bits) }
+    }
+
+    fn unbounded(min: u64) -> Self {
+        Bits { min, max: None }
+    }
+
+    /// Both encodings one after the other
+    fn then(self, next: Bits) -> Self {
+        Bits {
+            min: self.min.saturating_add(next.min),
+            max: self.max.zip(next.max).map(|(a, b)| a.saturating_add(b)),
+        }
+    }
+
+    /// Either encoding
+    fn or(self, other: Bits) -> Self {
+        Bits {
+            min: self.min.min(other.min),
+            max: self.max.zip(other.max).map(|(a, b)| a.max(b)),
+        }
+    }
+
+    /// Between zero and `count` repetitions
+    fn up_to(self, count: Option<u64>) -> Self {
+        Bits { min: 0, max: count.zip(self.max).map(|(n, m)| n.saturating_mul(m)) }
+    }
+
+    fn times(self, count: u64) -> Self {
+        Bits {
+            min: self.min.saturating_mul(count),
+            max: self.max.map(|m| m.saturating_mul(count)),
+        }
+    }
+}
+
+impl Schema {
+    /// Minimum and maximum encoded size of `type_name` in bytes. The maximum
+    /// is `None` when the encoding has no upper bound (null-terminated or
+    /// field-referenced data, recursive types) or the type doesn't exist.
+    ///
+    /// Length prefixes bound their data by the largest count they can hold,
+    /// so a `uint8`-prefixed string has a finite maximum.
+    pub fn size_bounds(&self, type_name: &str) -> (usize, Option<usize>) {
+        let bits = Sizer { schema: self, visiting: HashSet::new() }.named(type_name);
+        let to_bytes = |b: u64| usize::try_from(b.div_ceil(8)).unwrap_or(usize::MAX);
+        (to_bytes(bits.min), bits.max.map(to_bytes))
+    }
+}
+
+struct Sizer<'s> {
+    schema: &'s Schema,
+    /// Types on the current path, so recursive types terminate
+    visiting: HashSet<String>,
+}
+
+impl Sizer<'_> {
+    fn named(&mut self, type_name: &str) -> Bits {
+        if let Some(bits) = primitive_bits(type_name) {
+            return Bits::exact(bits);
+        }
+        let Some(def) = self.schema.types.get(type_name) else {
+            return Bits::unbounded(0);
+        };
+        if !self.visiting.insert(type_name.to_string()) {
+            return Bits::unbounded(0);
+        }
+        let bits = match def {
+            TypeDef::Sequence { sequence, .. } => {
+                sequence.iter().fold(Bits::exact(0), |acc, f| acc.then(self.field(f)))
+            }
+            TypeDef::Enum { repr, .. } => Bits::exact(primitive_bits(repr).unwrap_or(0)),
+            TypeDef::DiscriminatedUnion { variants, .. } => self.any_of(variants.iter().map(|v| v.type_name.as_str())),
+            TypeDef::BackReference { storage, target_type, .. } => {
+                Bits::exact(primitive_bits(storage).unwrap_or(0)).or(self.named(target_type))
+            }
+            TypeDef::Array { kind, items, terminal_variants, .. } => {
+                let item = self.field(items);
+                let layout = Layout { kind: Some(kind), terminal_variants: terminal_variants.as_deref(), ..Layout::default() };
+                self.sequence_of(&layout, item)
+            }
+            TypeDef::Direct { type_name: inner, kind, length, length_type, .. } => {
+                let layout = Layout {
+                    kind: kind.as_deref(),
+                    length: length.map(u64::from),
+                    length_type: length_type.as_deref(),
+                    ..Layout::default()
+                };
+                match inner.as_str() {
+                    "string" | "bytes" => self.sequence_of(&layout, Bits::exact(8)),
+                    other => self.named(other),
+                }
+            }
+        };
+        self.visiting.remove(type_name);
+        bits
+    }
+
+    fn field(&mut self, field: &Field) -> Bits {
+        let bits = self.unconditional(field);
+        if field.conditional.is_some() {
+            Bits { min: 0, max: bits.max }
+        } else {
+            bits
+        }
+    }
+
+    fn unconditional(&mut self, field: &Field) -> Bits {
+        match field.field_type.as_str() {
+            "bit" | "int" | "bitfield" | "custom_float" => Bits::exact(field.size.unwrap_or(0) as u64),
+            "uuid" => Bits::exact(128),
+            "varlength" => Bits { min: 8, max: varlength_max_bytes(field.encoding.as_deref()).map(|n| n * 8) },
+            "padding" => Bits { min: 0, max: Some(field.align_to.unwrap_or(4).saturating_sub(1) as u64 * 8) },
+            "string" | "bytes" => self.sequence_of(&Layout::of(field), Bits::exact(8)),
+            "message_bytes" => Bits::unbounded(0),
+            "array" => {
+                let mut item = field.items.as_deref().map_or(Bits::unbounded(0), |items| self.field(items));
+                if field.encoding.as_deref() == Some("delta_zigzag") {
+                    // Items after the first are zigzag LEB128 deltas of up to ten bytes
+                    item = item.or(Bits { min: 8, max: Some(80) });
+                }
+                self.sequence_of(&Layout::of(field), item)
+            }
+            "optional" => {
+                let presence = if field.presence_type.as_deref() == Some("bit") { 1 } else { 8 };
+                let value = field.value_type.as_deref().map_or(Bits::unbounded(0), |t| self.named(t));
+                Bits::exact(presence).then(value.up_to(Some(1)))
+            }
+            "choice" => {
+                let choices = field.choices.iter().flatten().map(|c| c.type_name.as_str());
+                self.any_of(choices)
+            }
+            "discriminated_union" => {
+                let variants = field.variants.iter().flatten().map(|v| v.type_name.as_str());
+                self.any_of(variants)
+            }
+            other => self.named(other),
+        }
+    }
+
+    /// Bounds of a string, bytes or array made of `item`s, by its kind
+    fn sequence_of(&mut self, layout: &Layout, item: Bits) -> Bits {
+        match layout.kind {
+            Some("fixed") => item.times(layout.length.unwrap_or(0)),
+            Some("length_prefixed") => match layout.length_type.and_then(primitive_bits) {
+                Some(bits) => Bits::exact(bits).then(item.up_to(max_count(bits))),
+                None => Bits::unbounded(8),
+            },
+            Some("byte_length_prefixed") => match layout.length_type.and_then(primitive_bits) {
+                Some(bits) => Bits::exact(bits).then(Bits::exact(8).up_to(max_count(bits))),
+                None => Bits::unbounded(8),
+            },
+            Some("length_prefixed_items") => {
+                let count = layout.length_type.and_then(primitive_bits).unwrap_or(0);
+                let item_prefix = layout.item_length_type.and_then(primitive_bits).unwrap_or(0);
+                let framed = Bits::exact(item_prefix).then(item);
+                Bits::exact(count).then(framed.up_to(max_count(count)))
+            }
+            Some("null_terminated") => {
+                let terminated_by_item = layout.terminal_variants.is_some_and(|v| !v.is_empty());
+                Bits::unbounded(if terminated_by_item { item.min.min(8) } else { 8 })
+            }
+            _ => Bits::unbounded(0),
+        }
+    }
+
+    fn any_of<'a>(&mut self, type_names: impl Iterator<Item = &'a str>) -> Bits {
+        type_names
+            .map(|name| self.named(name))
+            .reduce(Bits::or)
+            .unwrap_or(Bits::unbounded(0))
+    }
+}
+
+/// How a string, bytes or array value is framed on the wire
+#[derive(Default)]
+struct Layout<'f> {
+    kind: Option<&'f str>,
+    length: Option<u64>,
+    length_type: Option<&'f str>,
+    item_length_type: Option<&'f str>,
+    terminal_variants: Option<&'f [String]>,
+}
+
+impl<'f> Layout<'f> {
+    fn of(field: &'f Field) -> Self {
+        Layout {
+            kind: field.kind.as_deref(),
+            length: field.length.as_ref().and_then(|l| l.as_u64()),
+            length_type: field.length_type.as_deref(),
+            item_length_type: field.item_length_type.as_deref(),
+            terminal_variants: field.terminal_variants.as_deref(),
+        }
+    }
+}
+
+fn primitive_bits(type_name: &str) -> Option<u64> {
+    match type_name {
+        "uint8" | "int8" | "bool" => Some(8),
+        "uint16" | "int16" => Some(16),
+        "uint32" | "int32" | "float32" => Some(32),
+        "uint64" | "int64" | "float64" => Some(64),
+        _ => None,
+    }
+}
+
+/// Largest count a length prefix of `bits` can hold, if it fits in a u64
+fn max_count(bits: u64) -> Option<u64> {
+    (bits < 64).then(|| (1u64 << bits) - 1)
+}
+
+/// Longest encoding `write_varlength` produces for a u64
+fn varlength_max_bytes(encoding: Option<&str>) -> Option<u64> {
+    match encoding.unwrap_or("der") {
+        "der" => Some(9),
+        "leb128" => Some(10),
+        "ebml" => Some(8),
+        "vlq" => Some(4),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "types": {
+                "Point": { "sequence": [
+                    { "name": "x", "type": "int32" },
+                    { "name": "y", "type": "int32" },
+                    { "name": "flags", "type": "bitfield", "size": 8, "fields": [
+                        { "name": "visible", "offset": 0, "size": 1 }
+                    ]}
+                ]},
+                "Label": { "sequence": [
+                    { "name": "kind", "type": "Kind" },
+                    { "name": "text", "type": "string", "kind": "length_prefixed", "length_type": "uint8" },
+                    { "name": "extra", "type": "uint16", "conditional": "kind == 2" }
+                ]},
+                "Kind": { "type": "enum", "repr": "uint8", "variants": { "A": 1, "B": 2 } },
+                "Series": { "sequence": [
+                    { "name": "samples", "type": "array", "kind": "fixed", "length": 3,
+                      "encoding": "delta_zigzag", "items": { "type": "uint32" } }
+                ]},
+                "Path": { "sequence": [
+                    { "name": "origin", "type": "Point" },
+                    { "name": "points", "type": "array", "kind": "null_terminated", "items": { "type": "Point" } }
+                ]}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fixed_type_has_equal_bounds() {
+        assert_eq!(schema().size_bounds("Point"), (9, Some(9)));
+        assert_eq!(schema().size_bounds("Kind"), (1, Some(1)));
+    }
+
+    #[test]
+    fn test_delta_encoded_items_vary_in_size() {
+        assert_eq!(schema().size_bounds("Series"), (3, Some(30)));
+    }
+
+    #[test]
+    fn test_length_prefix_bounds_the_maximum() {
+        // kind + empty string prefix, up to kind + prefix + 255 bytes + extra
+        assert_eq!(schema().size_bounds("Label"), (2, Some(1 + 1 + 255 + 2)));
+    }
+
+    #[test]
+    fn test_unbounded_array_has_no_maximum() {
+        assert_eq!(schema().size_bounds("Path"), (10, None));
+        assert_eq!(schema().size_bounds("Missing"), (0, None));
+    }
+}
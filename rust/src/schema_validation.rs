@@ -0,0 +1,301 @@
+// ABOUTME: Structural validation of loaded schemas before code generation
+// ABOUTME: Collects every unresolved reference and malformed bitfield in one pass
+
+use crate::test_schema::{Field, Schema, TypeDef};
+use std::fmt;
+
+/// Field types understood without a definition in `schema.types`
+const BUILT_IN_TYPES: &[&str] = &[
+    "bit", "int", "bool", "uint8", "uint16", "uint32", "uint64",
+    "int8", "int16", "int32", "int64", "varlength", "float32", "float64", "custom_float", "uuid",
+    "string", "array", "bytes", "message_bytes", "optional", "bitfield", "discriminated_union", "back_reference", "choice",
+    "padding", "group",
+];
+
+/// A single problem found by `Schema::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// A field's `type` is neither built-in nor defined in `types`
+    UnknownFieldType { path: String, field_type: String },
+    /// A union variant, choice, back_reference target or optional value names an undefined type
+    UndefinedTypeReference { path: String, type_name: String },
+    /// A bitfield sub-field extends past the bitfield's declared size
+    BitfieldOverflow { path: String, subfield: String, end_bit: u32, size: u32 },
+    /// A bitfield sub-field doesn't start where the previous one ended (gap or overlap)
+    BitfieldNotContiguous { path: String, subfield: String, expected_offset: u32, offset: u32 },
+    /// A `length_field` doesn't name a sibling field
+    UnknownLengthField { path: String, length_field: String },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::UnknownFieldType { path, field_type } => {
+                write!(f, "{}: unknown field type '{}'", path, field_type)
+            }
+            SchemaError::UndefinedTypeReference { path, type_name } => {
+                write!(f, "{}: references undefined type '{}'", path, type_name)
+            }
+            SchemaError::BitfieldOverflow { path, subfield, end_bit, size } => {
+                write!(f, "{}: sub-field '{}' ends at bit {} but the bitfield is {} bits", path, subfield, end_bit, size)
+            }
+            SchemaError::BitfieldNotContiguous { path, subfield, expected_offset, offset } => {
+                write!(f, "{}: sub-field '{}' starts at bit {}, expected {}", path, subfield, offset, expected_offset)
+            }
+            SchemaError::UnknownLengthField { path, length_field } => {
+                write!(f, "{}: length_field '{}' is not a sibling field", path, length_field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl Schema {
+    /// Checks that type references resolve, bitfields are well-formed and length
+    /// fields point at siblings. Returns every problem found, not just the first.
+    pub fn validate(&self) -> std::result::Result<(), Vec<SchemaError>> {
+        let mut errors = Vec::new();
+
+        // Sort for a stable error order regardless of HashMap iteration
+        let mut names: Vec<&String> = self.types.keys().collect();
+        names.sort();
+
+        for name in names {
+            match &self.types[name] {
+                TypeDef::Sequence { sequence, .. } => {
+                    for field in sequence {
+                        let path = format!("{}.{}", name, field.name.as_deref().unwrap_or("_"));
+                        self.validate_field(field, Some(sequence), &path, &mut errors);
+                    }
+                }
+                TypeDef::Enum { .. } => {}
+                TypeDef::DiscriminatedUnion { variants, .. } => {
+                    for variant in variants {
+                        self.check_reference(&variant.type_name, name, &mut errors);
+                    }
+                }
+                TypeDef::BackReference { target_type, .. } => {
+                    self.check_reference(target_type, name, &mut errors);
+                }
+                TypeDef::Array { items, .. } => {
+                    self.validate_field(items, None, &format!("{}.items", name), &mut errors);
+                }
+                // Flags and TLV types load as Direct; their bits/options aren't captured here
+                TypeDef::Direct { type_name, .. } if type_name == "flags" || type_name == "tlv" => {}
+                TypeDef::Direct { type_name, .. } => {
+                    if !self.is_known_type(type_name) {
+                        errors.push(SchemaError::UnknownFieldType { path: name.clone(), field_type: type_name.clone() });
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn is_known_type(&self, type_name: &str) -> bool {
+        BUILT_IN_TYPES.contains(&type_name) || self.types.contains_key(type_name)
+    }
+
+    fn check_reference(&self, type_name: &str, path: &str, errors: &mut Vec<SchemaError>) {
+        if !self.types.contains_key(type_name) {
+            errors.push(SchemaError::UndefinedTypeReference { path: path.to_string(), type_name: type_name.to_string() });
+        }
+    }
+
+    fn validate_field(&self, field: &Field, siblings: Option<&[Field]>, path: &str, errors: &mut Vec<SchemaError>) {
+        if !self.is_known_type(&field.field_type) {
+            errors.push(SchemaError::UnknownFieldType { path: path.to_string(), field_type: field.field_type.clone() });
+        }
+
+        if let Some(items) = &field.items {
+            self.validate_field(items, None, &format!("{}.items", path), errors);
+        }
+        if let Some(value_type) = &field.value_type {
+            if !self.is_known_type(value_type) {
+                errors.push(SchemaError::UndefinedTypeReference { path: path.to_string(), type_name: value_type.clone() });
+            }
+        }
+        for variant in field.variants.iter().flatten() {
+            self.check_reference(&variant.type_name, path, errors);
+        }
+        for choice in field.choices.iter().flatten() {
+            self.check_reference(&choice.type_name, path, errors);
+        }
+
+        if field.field_type == "bitfield" {
+            Self::validate_bitfield(field, path, errors);
+        }
+
+        // An inline group's fields are siblings of each other, not of the group
+        for member in field.sequence.iter().flatten() {
+            let member_path = format!("{}.{}", path, member.name.as_deref().unwrap_or("_"));
+            self.validate_field(member, field.sequence.as_deref(), &member_path, errors);
+        }
+
+        if let (Some(length_field), Some(siblings)) = (&field.length_field, siblings) {
+            // Parent and root references resolve outside this struct
+            let is_external = length_field.starts_with("_root") || length_field.starts_with("_parent") || length_field.starts_with("../");
+            let head = length_field.split('.').next().unwrap_or_default();
+            if !is_external && !siblings.iter().any(|s| s.name.as_deref() == Some(head)) {
+                errors.push(SchemaError::UnknownLengthField { path: path.to_string(), length_field: length_field.clone() });
+            }
+        }
+    }
+
+    fn validate_bitfield(field: &Field, path: &str, errors: &mut Vec<SchemaError>) {
+        let Some(subfields) = &field.fields else {
+            return;
+        };
+        let mut ordered: Vec<_> = subfields.iter().collect();
+        ordered.sort_by_key(|sub| sub.offset);
+
+        let mut expected_offset = 0;
+        for sub in ordered {
+            if sub.offset != expected_offset {
+                errors.push(SchemaError::BitfieldNotContiguous {
+                    path: path.to_string(),
+                    subfield: sub.name.clone(),
+                    expected_offset,
+                    offset: sub.offset,
+                });
+            }
+            let end_bit = sub.offset + sub.size;
+            if let Some(size) = field.size {
+                if end_bit > size {
+                    errors.push(SchemaError::BitfieldOverflow {
+                        path: path.to_string(),
+                        subfield: sub.name.clone(),
+                        end_bit,
+                        size,
+                    });
+                }
+            }
+            expected_offset = end_bit;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_valid_schema_passes() {
+        let s = schema(json!({
+            "types": {
+                "Header": { "sequence": [
+                    { "name": "flags", "type": "bitfield", "size": 8, "fields": [
+                        { "name": "version", "offset": 0, "size": 4 },
+                        { "name": "kind", "offset": 4, "size": 4 }
+                    ]},
+                    { "name": "len", "type": "uint8" },
+                    { "name": "data", "type": "array", "kind": "field_referenced", "length_field": "len", "items": { "type": "uint8" } }
+                ]},
+                "Status": { "type": "flags", "storage": "uint8", "flags": { "READY": 0 } },
+                "Packet": { "sequence": [
+                    { "name": "status", "type": "Status" },
+                    { "name": "header", "type": "Header" },
+                    { "name": "body", "type": "array", "kind": "field_referenced", "length_field": "header.len", "items": { "type": "uint8" } }
+                ]}
+            }
+        }));
+        assert_eq!(s.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_unknown_field_type_and_undefined_references() {
+        let s = schema(json!({
+            "types": {
+                "Msg": { "sequence": [
+                    { "name": "a", "type": "uint24" },
+                    { "name": "b", "type": "Missing" },
+                    { "name": "c", "type": "optional", "value_type": "AlsoMissing" }
+                ]},
+                "Either": { "type": "discriminated_union", "discriminator": { "peek": "uint8" }, "variants": [
+                    { "type": "Msg", "when": "value == 1" },
+                    { "type": "Ghost", "when": "value == 2" }
+                ]}
+            }
+        }));
+        let errors = s.validate().unwrap_err();
+        assert_eq!(errors, vec![
+            SchemaError::UndefinedTypeReference { path: "Either".into(), type_name: "Ghost".into() },
+            SchemaError::UnknownFieldType { path: "Msg.a".into(), field_type: "uint24".into() },
+            SchemaError::UnknownFieldType { path: "Msg.b".into(), field_type: "Missing".into() },
+            SchemaError::UndefinedTypeReference { path: "Msg.c".into(), type_name: "AlsoMissing".into() },
+        ]);
+    }
+
+    #[test]
+    fn test_reports_bitfield_overflow_and_gaps() {
+        let s = schema(json!({
+            "types": {
+                "Flags": { "sequence": [
+                    { "name": "bits", "type": "bitfield", "size": 8, "fields": [
+                        { "name": "low", "offset": 0, "size": 3 },
+                        { "name": "high", "offset": 4, "size": 5 }
+                    ]}
+                ]}
+            }
+        }));
+        let errors = s.validate().unwrap_err();
+        assert_eq!(errors, vec![
+            SchemaError::BitfieldNotContiguous { path: "Flags.bits".into(), subfield: "high".into(), expected_offset: 3, offset: 4 },
+            SchemaError::BitfieldOverflow { path: "Flags.bits".into(), subfield: "high".into(), end_bit: 9, size: 8 },
+        ]);
+    }
+
+    #[test]
+    fn test_validates_inline_group_fields() {
+        let s = schema(json!({
+            "types": {
+                "Mesh": { "sequence": [
+                    { "name": "coords", "type": "group", "repeat": 3, "sequence": [
+                        { "name": "x", "type": "int16" },
+                        { "name": "y", "type": "int16" }
+                    ]},
+                    { "name": "faces", "type": "group", "sequence": [
+                        { "name": "count", "type": "uint8" },
+                        { "name": "indices", "type": "array", "kind": "field_referenced", "length_field": "count", "items": { "type": "uint16" } },
+                        { "name": "normal", "type": "Vec3" },
+                        { "name": "tail", "type": "array", "kind": "field_referenced", "length_field": "coords", "items": { "type": "uint8" } }
+                    ]}
+                ]}
+            }
+        }));
+        let errors = s.validate().unwrap_err();
+        assert_eq!(errors, vec![
+            SchemaError::UnknownFieldType { path: "Mesh.faces.normal".into(), field_type: "Vec3".into() },
+            SchemaError::UnknownLengthField { path: "Mesh.faces.tail".into(), length_field: "coords".into() },
+        ]);
+    }
+
+    #[test]
+    fn test_reports_unknown_length_field() {
+        let s = schema(json!({
+            "types": {
+                "Blob": { "sequence": [
+                    { "name": "count", "type": "uint8" },
+                    { "name": "data", "type": "array", "kind": "field_referenced", "length_field": "cnt", "items": { "type": "uint8" } },
+                    { "name": "tail", "type": "array", "kind": "field_referenced", "length_field": "_root.total", "items": { "type": "uint8" } }
+                ]}
+            }
+        }));
+        let errors = s.validate().unwrap_err();
+        assert_eq!(errors, vec![
+            SchemaError::UnknownLengthField { path: "Blob.data".into(), length_field: "cnt".into() },
+        ]);
+        assert_eq!(errors[0].to_string(), "Blob.data: length_field 'cnt' is not a sibling field");
+    }
+}
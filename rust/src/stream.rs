@@ -0,0 +1,426 @@
+// ABOUTME: Bit-level reading and writing over std::io sources and sinks
+// ABOUTME: BitStreamReader pulls bytes on demand; BitStreamWriter pushes complete bytes as they fill
+
+use std::io::{ErrorKind, Read, Write};
+
+use crate::{BinSchemaError, BitOrder, BitStreamEncoder, Endianness, Result};
+
+/// Bytes a `BitStreamReader` keeps behind its position by default, enough
+/// for a DNS compression pointer (14-bit offset) to reach anywhere it can
+pub const DEFAULT_WINDOW: usize = 16 << 10;
+
+/// Most bytes requested from the source in one `read` call
+const READ_CHUNK: usize = 8 << 10;
+
+/// Complete bytes a `BitStreamWriter` holds before writing them to its sink
+const WRITE_CHUNK: usize = 8 << 10;
+
+/// Reads bit-level data from any `Read`, e.g. a `TcpStream` or `File`, without
+/// buffering the whole message first.
+///
+/// Bytes are requested from the source only once a read needs them, and never
+/// more than that read needs, so `into_inner` hands back a source positioned
+/// just after the last byte consumed. Wrap unbuffered sources in a
+/// `BufReader` to avoid one system call per read.
+///
+/// Bit and byte order behave exactly as in `BitStreamDecoder`, positions count
+/// from the first byte read. Seeking forward pulls and discards bytes; seeking
+/// backward, as a compression pointer does, only works within the last
+/// `window` bytes before the position (`DEFAULT_WINDOW` unless set with
+/// `with_window`), and fails with `InvalidValue` further back.
+pub struct BitStreamReader<R: Read> {
+    inner: R,
+    /// Bytes already pulled from `inner`, starting at stream offset `window_start`
+    window: Vec<u8>,
+    window_start: usize,
+    window_size: usize,
+    byte_offset: usize,
+    bit_offset: u8,
+    bit_order: BitOrder,
+}
+
+impl<R: Read> BitStreamReader<R> {
+    pub fn new(inner: R, bit_order: BitOrder) -> Self {
+        Self {
+            inner,
+            window: Vec::new(),
+            window_start: 0,
+            window_size: DEFAULT_WINDOW,
+            byte_offset: 0,
+            bit_offset: 0,
+            bit_order,
+        }
+    }
+
+    /// Sets how many bytes behind the position stay reachable by `seek`
+    pub fn with_window(mut self, bytes: usize) -> Self {
+        self.window_size = bytes;
+        self
+    }
+
+    /// Returns the source. Nothing is read ahead, so no bytes are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the current byte position in the stream. Mid-byte, this is the
+    /// byte holding the next unread bit.
+    pub fn position(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Moves to a byte position, dropping any partially read byte. Fails with
+    /// `InvalidValue` if `pos` has already left the window, and with
+    /// `UnexpectedEof` if the source ends before it.
+    pub fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos < self.window_start {
+            return Err(BinSchemaError::InvalidValue(format!(
+                "cannot seek back to {}, the window starts at {}",
+                pos, self.window_start
+            )));
+        }
+        if pos > self.byte_offset {
+            self.fill_to(pos - 1)?;
+        }
+        self.byte_offset = pos;
+        self.bit_offset = 0;
+        Ok(())
+    }
+
+    /// Discards the unread bits of a partly read byte, so the next read starts
+    /// on a byte boundary. Does nothing when already aligned.
+    pub fn align_to_byte(&mut self) {
+        if self.bit_offset != 0 {
+            self.byte_offset += 1;
+            self.bit_offset = 0;
+        }
+    }
+
+    pub fn read_bits(&mut self, num_bits: u8) -> Result<u64> {
+        if num_bits == 0 || num_bits > 64 {
+            return Err(BinSchemaError::InvalidValue("Invalid number of bits".to_string()));
+        }
+        let mut result = 0u64;
+        for i in 0..num_bits {
+            let bit = self.read_single_bit()?;
+            let bit_index = match self.bit_order {
+                BitOrder::MsbFirst => num_bits - 1 - i,
+                BitOrder::LsbFirst => i,
+            };
+            result |= (bit as u64) << bit_index;
+        }
+        Ok(result)
+    }
+
+    fn read_single_bit(&mut self) -> Result<u8> {
+        let byte = self.current_byte()?;
+        let bit_index = match self.bit_order {
+            BitOrder::MsbFirst => 7 - self.bit_offset,
+            BitOrder::LsbFirst => self.bit_offset,
+        };
+        self.bit_offset += 1;
+        if self.bit_offset == 8 {
+            self.byte_offset += 1;
+            self.bit_offset = 0;
+        }
+        Ok((byte >> bit_index) & 1)
+    }
+
+    /// Reads a byte; mid-byte its bits are taken LSB first, as `BitStreamDecoder` does
+    pub fn read_uint8(&mut self) -> Result<u8> {
+        if self.bit_offset == 0 {
+            let byte = self.current_byte()?;
+            self.byte_offset += 1;
+            return Ok(byte);
+        }
+        let mut value = 0u8;
+        for i in 0..8u8 {
+            value |= self.read_single_bit()? << i;
+        }
+        Ok(value)
+    }
+
+    pub fn read_uint16(&mut self, endianness: Endianness) -> Result<u16> {
+        Ok(self.read_uint(2, endianness)? as u16)
+    }
+
+    pub fn read_uint32(&mut self, endianness: Endianness) -> Result<u32> {
+        Ok(self.read_uint(4, endianness)? as u32)
+    }
+
+    pub fn read_uint64(&mut self, endianness: Endianness) -> Result<u64> {
+        self.read_uint(8, endianness)
+    }
+
+    pub fn read_float32(&mut self, endianness: Endianness) -> Result<f32> {
+        Ok(f32::from_bits(self.read_uint32(endianness)?))
+    }
+
+    pub fn read_float64(&mut self, endianness: Endianness) -> Result<f64> {
+        Ok(f64::from_bits(self.read_uint64(endianness)?))
+    }
+
+    /// Reads `n` whole bytes into a new vector, e.g. one frame to hand to a
+    /// generated `decode`. The source is read in bounded chunks, so a forged
+    /// length fails at end of input rather than allocating `n` bytes up front.
+    pub fn read_bytes_vec(&mut self, n: usize) -> Result<Vec<u8>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        if self.bit_offset != 0 {
+            return (0..n).map(|_| self.read_uint8()).collect();
+        }
+        let end = self.byte_offset.checked_add(n).ok_or(BinSchemaError::UnexpectedEof)?;
+        self.fill_to(end - 1)?;
+        let start = self.byte_offset - self.window_start;
+        let bytes = self.window[start..start + n].to_vec();
+        self.byte_offset = end;
+        Ok(bytes)
+    }
+
+    fn read_uint(&mut self, size: usize, endianness: Endianness) -> Result<u64> {
+        let mut value = 0u64;
+        for i in 0..size {
+            let byte = self.read_uint8()? as u64;
+            match endianness {
+                Endianness::BigEndian => value = (value << 8) | byte,
+                Endianness::LittleEndian => value |= byte << (8 * i),
+            }
+        }
+        Ok(value)
+    }
+
+    /// The byte at the current position, pulled from the source if needed
+    fn current_byte(&mut self) -> Result<u8> {
+        self.fill_to(self.byte_offset)?;
+        Ok(self.window[self.byte_offset - self.window_start])
+    }
+
+    /// Pulls from the source until the byte at stream offset `offset` is in
+    /// the window, first dropping bytes that have fallen out of it. Trimming
+    /// waits until twice the window is behind, so the copy is amortized.
+    fn fill_to(&mut self, offset: usize) -> Result<()> {
+        let behind = self.byte_offset - self.window_start;
+        if behind > self.window_size.saturating_mul(2) {
+            let drop = behind - self.window_size;
+            self.window.drain(..drop);
+            self.window_start += drop;
+        }
+
+        while self.window_start + self.window.len() <= offset {
+            let len = self.window.len();
+            let want = (offset + 1 - self.window_start - len).min(READ_CHUNK);
+            self.window.resize(len + want, 0);
+            let read = loop {
+                match self.inner.read(&mut self.window[len..]) {
+                    Ok(read) => break read,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        self.window.truncate(len);
+                        return Err(BinSchemaError::Io(e.to_string()));
+                    }
+                }
+            };
+            self.window.truncate(len + read);
+            if read == 0 {
+                return Err(BinSchemaError::UnexpectedEof);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes bit-level data to any `Write`, producing the same bytes as
+/// `BitStreamEncoder`.
+///
+/// Complete bytes are held until a few KiB have built up and then written out,
+/// so memory use stays flat however long the stream gets. A partly written
+/// byte stays behind until it fills up, `finish` pads it, or more writes
+/// complete it. Errors from the sink are returned as `Io`.
+///
+/// Written bytes can't be revisited, so there is no counterpart to
+/// `reserve`/`patch_reservation`; encode values that need them with a
+/// `BitStreamEncoder` and pass the result to `write_bytes`.
+pub struct BitStreamWriter<W: Write> {
+    inner: W,
+    encoder: BitStreamEncoder,
+    /// Bytes already written to `inner`
+    flushed: usize,
+}
+
+impl<W: Write> BitStreamWriter<W> {
+    pub fn new(inner: W, bit_order: BitOrder) -> Self {
+        Self { inner, encoder: BitStreamEncoder::new(bit_order), flushed: 0 }
+    }
+
+    /// Bytes written so far, counting a partly written byte as a whole one,
+    /// like `BitStreamEncoder::byte_offset`
+    pub fn position(&self) -> usize {
+        self.flushed + self.encoder.byte_offset()
+    }
+
+    pub fn write_bits(&mut self, value: u64, num_bits: u8) -> Result<()> {
+        self.encoder.try_write_bits(value, num_bits)?;
+        self.spill()
+    }
+
+    pub fn write_uint8(&mut self, value: u8) -> Result<()> {
+        self.encoder.write_uint8(value);
+        self.spill()
+    }
+
+    pub fn write_uint16(&mut self, value: u16, endianness: Endianness) -> Result<()> {
+        self.encoder.write_uint16(value, endianness);
+        self.spill()
+    }
+
+    pub fn write_uint32(&mut self, value: u32, endianness: Endianness) -> Result<()> {
+        self.encoder.write_uint32(value, endianness);
+        self.spill()
+    }
+
+    pub fn write_uint64(&mut self, value: u64, endianness: Endianness) -> Result<()> {
+        self.encoder.write_uint64(value, endianness);
+        self.spill()
+    }
+
+    pub fn write_float32(&mut self, value: f32, endianness: Endianness) -> Result<()> {
+        self.write_uint32(value.to_bits(), endianness)
+    }
+
+    pub fn write_float64(&mut self, value: f64, endianness: Endianness) -> Result<()> {
+        self.write_uint64(value.to_bits(), endianness)
+    }
+
+    /// Writes each byte as `write_uint8` would, e.g. a message encoded by a
+    /// generated `encode`
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.encoder.write_bytes(bytes);
+        self.spill()
+    }
+
+    /// Completes a partly written byte with zero bits. Does nothing when
+    /// already aligned.
+    pub fn align_to_byte(&mut self) -> Result<()> {
+        self.encoder.align_to_byte();
+        self.spill()
+    }
+
+    /// Writes out every complete byte and flushes the sink. A partly written
+    /// byte is kept back, since later bits still go into it.
+    pub fn flush(&mut self) -> Result<()> {
+        self.write_out()?;
+        self.inner.flush().map_err(|e| BinSchemaError::Io(e.to_string()))
+    }
+
+    /// Pads a partly written byte with zero bits, flushes everything and
+    /// returns the sink
+    pub fn finish(mut self) -> Result<W> {
+        self.encoder.align_to_byte();
+        self.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Writes out the complete bytes once enough have built up
+    fn spill(&mut self) -> Result<()> {
+        if self.encoder.buffer().len() >= WRITE_CHUNK {
+            self.write_out()?;
+        }
+        Ok(())
+    }
+
+    fn write_out(&mut self) -> Result<()> {
+        let complete = self.encoder.buffer();
+        self.inner.write_all(complete).map_err(|e| BinSchemaError::Io(e.to_string()))?;
+        self.flushed += complete.len();
+        self.encoder.discard_complete_bytes();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitStreamDecoder;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_reader_matches_decoder_on_cursor() {
+        // 3 bits, an unaligned byte, then aligned big- and little-endian values
+        let bytes = vec![0b1010_1100, 0b1110_0000, 0x12, 0x34, 0x78, 0x56, 0xAA];
+        let mut decoder = BitStreamDecoder::new(&bytes, BitOrder::MsbFirst);
+        let mut reader = BitStreamReader::new(Cursor::new(bytes.clone()), BitOrder::MsbFirst);
+
+        assert_eq!(reader.read_bits(3).unwrap(), decoder.read_bits(3).unwrap());
+        assert_eq!(reader.read_uint8().unwrap(), decoder.read_uint8().unwrap());
+        reader.align_to_byte();
+        decoder.align_to_byte();
+        assert_eq!(reader.read_uint16(Endianness::BigEndian).unwrap(), 0x1234);
+        assert_eq!(reader.read_uint16(Endianness::LittleEndian).unwrap(), 0x5678);
+        assert_eq!(reader.position(), 6);
+        assert_eq!(reader.read_bytes_vec(1).unwrap(), vec![0xAA]);
+        assert_eq!(reader.read_uint8(), Err(BinSchemaError::UnexpectedEof));
+    }
+
+    #[test]
+    fn test_reader_pulls_only_what_it_reads() {
+        let mut reader = BitStreamReader::new(Cursor::new(vec![1, 2, 3, 4, 5]), BitOrder::MsbFirst);
+        assert_eq!(reader.read_uint16(Endianness::BigEndian).unwrap(), 0x0102);
+        let cursor = reader.into_inner();
+        assert_eq!(cursor.position(), 2);
+    }
+
+    #[test]
+    fn test_reader_seeks_back_within_window_only() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut reader = BitStreamReader::new(Cursor::new(bytes), BitOrder::MsbFirst).with_window(16);
+
+        reader.seek(40).unwrap();
+        assert_eq!(reader.read_uint8().unwrap(), 40);
+        reader.seek(30).unwrap();
+        assert_eq!(reader.read_uint8().unwrap(), 30);
+
+        // Once more than twice the window is behind, older bytes are dropped
+        reader.seek(200).unwrap();
+        assert_eq!(reader.read_uint8().unwrap(), 200);
+        assert!(matches!(reader.seek(10), Err(BinSchemaError::InvalidValue(_))));
+        reader.seek(190).unwrap();
+        assert_eq!(reader.read_uint8().unwrap(), 190);
+        assert_eq!(reader.seek(300), Err(BinSchemaError::UnexpectedEof));
+    }
+
+    #[test]
+    fn test_writer_matches_encoder() {
+        for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut encoder = BitStreamEncoder::new(bit_order);
+            let mut writer = BitStreamWriter::new(Vec::new(), bit_order);
+            encoder.write_bits(0b101, 3);
+            writer.write_bits(0b101, 3).unwrap();
+            encoder.write_uint16(0xBEEF, Endianness::LittleEndian);
+            writer.write_uint16(0xBEEF, Endianness::LittleEndian).unwrap();
+            encoder.write_float64(1.5, Endianness::BigEndian);
+            writer.write_float64(1.5, Endianness::BigEndian).unwrap();
+            assert_eq!(writer.position(), encoder.byte_offset());
+            assert_eq!(writer.finish().unwrap(), encoder.finish());
+        }
+    }
+
+    #[test]
+    fn test_writer_streams_large_output() {
+        let mut writer = BitStreamWriter::new(Vec::new(), BitOrder::MsbFirst);
+        writer.write_bits(1, 1).unwrap();
+        for i in 0..3 * WRITE_CHUNK {
+            writer.write_uint8(i as u8).unwrap();
+        }
+        // Complete bytes have gone out while the partial one is held back
+        assert!(writer.inner.len() >= 2 * WRITE_CHUNK);
+        assert_eq!(writer.position(), 3 * WRITE_CHUNK + 1);
+
+        let written = writer.finish().unwrap();
+        let mut reader = BitStreamReader::new(Cursor::new(written), BitOrder::MsbFirst);
+        assert_eq!(reader.read_bits(1).unwrap(), 1);
+        for i in 0..3 * WRITE_CHUNK {
+            assert_eq!(reader.read_uint8().unwrap(), i as u8);
+        }
+    }
+}
@@ -152,6 +152,12 @@ pub struct Field {
     pub count_expr: Option<String>,  // For computed_count arrays
     #[serde(default)]
     pub bit_order: Option<String>,  // For bit fields
+    #[serde(default)]
+    pub layout: Option<String>,  // For uuid fields: "rfc4122" or "guid"
+    #[serde(default)]
+    pub sequence: Option<Vec<Field>>,  // For inline group fields
+    #[serde(default)]
+    pub repeat: Option<u32>,  // For inline group fields: number of copies
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -189,7 +195,8 @@ pub struct UnionVariant {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TestCase {
     pub description: String,
-    pub value: serde_json::Value,
+    #[serde(default)]
+    pub value: serde_json::Value,  // Absent for decode error cases, which only have bytes
     #[serde(default)]
     pub decoded_value: Option<serde_json::Value>,  // For decode comparison (includes computed fields)
     #[serde(default)]
@@ -202,4 +209,8 @@ pub struct TestCase {
     pub should_error_on_encode: Option<bool>,
     #[serde(default)]
     pub should_error_on_decode: Option<bool>,
+    #[serde(default)]
+    pub should_error: Option<bool>,  // Decode of `bytes` must fail
+    #[serde(default)]
+    pub error_message: Option<String>,  // Expected substring of the TypeScript error
 }
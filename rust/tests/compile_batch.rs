@@ -7,7 +7,6 @@
 
 use binschema_runtime::test_schema::{TestCase, TestSuite, Schema, TypeDef, Field};
 use serde::Serialize;
-use serde_json;
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
@@ -92,8 +91,8 @@ fn find_test_files(dir: &str) -> Vec<PathBuf> {
             let path = entry.path();
             if path.is_dir() {
                 files.extend(find_test_files(path.to_str().unwrap()));
-            } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if path
+            } else if path.extension().and_then(|s| s.to_str()) == Some("json")
+                && path
                     .file_name()
                     .and_then(|s| s.to_str())
                     .map(|s| s.contains(".test."))
@@ -101,7 +100,6 @@ fn find_test_files(dir: &str) -> Vec<PathBuf> {
                 {
                     files.push(path);
                 }
-            }
         }
     }
 
@@ -151,179 +149,43 @@ fn generate_rust_code(schema_json: &str, type_name: &str) -> Result<String, Box<
     Ok(code)
 }
 
-/// Prefix all type names in generated code to avoid conflicts
-fn prefix_type_names(code: &str, prefix: &str) -> String {
-    // First, protect Rust standard library paths from replacement
-    // by replacing them with placeholders
-    let mut result = code.to_string();
-    result = result.replace("std::string::String", "__PLACEHOLDER_STD_STRING__");
-    result = result.replace("std::vec::Vec", "__PLACEHOLDER_STD_VEC__");
-    result = result.replace("std::option::Option", "__PLACEHOLDER_STD_OPTION__");
-    result = result.replace("std::result::Result", "__PLACEHOLDER_STD_RESULT__");
-
-    // Find all struct, enum, and type alias definitions
-    let re_struct = regex::Regex::new(r"pub struct ([A-Z][a-zA-Z0-9_]*)").unwrap();
-    let re_enum = regex::Regex::new(r"pub enum ([A-Z][a-zA-Z0-9_]*)").unwrap();
-    let re_type = regex::Regex::new(r"pub type ([A-Z][a-zA-Z0-9_]*)").unwrap();
-
-    let mut type_names: Vec<String> = re_struct
-        .captures_iter(&code)
-        .map(|cap| cap[1].to_string())
-        .collect();
+/// Writes a crate that compiles each suite's generated code, unmodified, into
+/// its own `gen_N` module, plus the harness that runs every suite's cases.
+/// Types with the same name in different suites never meet.
+fn write_batch_crate(dir: &Path, suites: &[(TestSuite, String)]) {
+    let src_dir = dir.join("src");
+    fs::create_dir_all(&src_dir).expect("Create src dir");
 
-    type_names.extend(
-        re_enum
-            .captures_iter(&code)
-            .map(|cap| cap[1].to_string())
-    );
+    let mut mod_content = String::new();
+    for (i, (_suite, code)) in suites.iter().enumerate() {
+        fs::write(src_dir.join(format!("gen_{}.rs", i)), code).expect("Write generated code");
+        mod_content.push_str(&format!("pub mod gen_{};\n", i));
+    }
+    fs::write(src_dir.join("lib.rs"), &mod_content).expect("Write lib.rs");
 
-    type_names.extend(
-        re_type
-            .captures_iter(&code)
-            .map(|cap| cap[1].to_string())
-    );
+    let suite_refs: Vec<(String, TestSuite)> = suites
+        .iter()
+        .enumerate()
+        .map(|(i, (suite, _))| (format!("gen_{}", i), suite.clone()))
+        .collect();
+    let harness = generate_test_harness(&suite_refs);
+    fs::write(src_dir.join("main.rs"), &harness).expect("Write main.rs");
 
-    // Collect enum variant names (first capture group) - these should NOT be prefixed in ::Variant( patterns
-    // Pattern: EnumVariant(TypeName) or EnumVariant(TypeName,
-    let re_variant_types = regex::Regex::new(r"\s+([A-Z][a-zA-Z0-9_]*)\(([A-Z][a-zA-Z0-9_]*)[\),]").unwrap();
-    let mut variant_names: std::collections::HashSet<String> = std::collections::HashSet::new();
-    for cap in re_variant_types.captures_iter(&code) {
-        // Collect variant name (first group) - don't replace in ::VariantName( patterns
-        if let Some(variant_match) = cap.get(1) {
-            variant_names.insert(variant_match.as_str().to_string());
-        }
-        // Collect type name (second group) - add to types to be prefixed
-        if let Some(type_match) = cap.get(2) {
-            type_names.push(type_match.as_str().to_string());
-        }
-    }
-
-    // Remove duplicates
-    type_names.sort();
-    type_names.dedup();
-
-    // Sort by length (longest first) to avoid substring replacement issues
-    // E.g., replace "ChoiceTypeATypeB" before "TypeB" to avoid partial matches
-    type_names.sort_by_key(|name| std::cmp::Reverse(name.len()));
-
-    for type_name in &type_names {
-        let prefixed = format!("{}_{}", prefix, type_name);
-
-        // Replace struct/enum/type alias definition
-        result = result.replace(
-            &format!("pub struct {}", type_name),
-            &format!("pub struct {}", prefixed),
-        );
-        result = result.replace(
-            &format!("pub enum {}", type_name),
-            &format!("pub enum {}", prefixed),
-        );
-        result = result.replace(
-            &format!("pub type {}", type_name),
-            &format!("pub type {}", prefixed),
-        );
-
-        // Replace impl block
-        result = result.replace(
-            &format!("impl {}", type_name),
-            &format!("impl {}", prefixed),
-        );
-
-        // Replace type references in various contexts:
-        // 1. Enum variant with tuple: `Variant(TypeName)` or `Variant(TypeName,`
-        let re_tuple_variant = regex::Regex::new(&format!(r"\b([A-Z][a-zA-Z0-9_]*)\({}([,\)])", regex::escape(type_name))).unwrap();
-        result = re_tuple_variant.replace_all(&result, format!("$1({}$2", prefixed)).to_string();
-
-        // 2. Field types ending with comma: `: Foo,`
-        result = result.replace(
-            &format!(": {},", type_name),
-            &format!(": {},", prefixed),
-        );
-
-        // 3. Field types ending with space: `: Foo `
-        result = result.replace(
-            &format!(": {} ", type_name),
-            &format!(": {} ", prefixed),
-        );
-
-        // 4. Generic parameters: `Vec<Foo>`
-        result = result.replace(
-            &format!("<{}>", type_name),
-            &format!("<{}>", prefixed),
-        );
-
-        // 4b. Newtype/tuple struct inner types: `(pub TypeName)` or `(pub TypeName,`
-        result = result.replace(
-            &format!("(pub {})", type_name),
-            &format!("(pub {})", prefixed),
-        );
-        result = result.replace(
-            &format!("(pub {},", type_name),
-            &format!("(pub {},", prefixed),
-        );
-
-        // 5. Method calls: `Foo::decode` or `EnumName::Variant`
-        // Use word boundary to avoid matching TypeB:: inside ChoiceTypeATypeB::
-        let re_method_call = regex::Regex::new(&format!(r"\b{}::", regex::escape(type_name))).unwrap();
-        result = re_method_call.replace_all(&result, format!("{}::", prefixed)).to_string();
-
-        // 6. Type alias right-hand side: `= TypeName;`
-        result = result.replace(
-            &format!("= {};", type_name),
-            &format!("= {};", prefixed),
-        );
-
-        // 7. Qualified enum variants in match/construction: `SomeEnum::TypeName(`
-        // This handles patterns like `ChoiceAB::TypeA(` in match arms
-        // IMPORTANT: Only replace if TypeName is NOT an enum variant name (variants should not be prefixed)
-        if !variant_names.contains(type_name) {
-            let re_qualified = regex::Regex::new(&format!(r"::{}([\(\,\)])", regex::escape(type_name))).unwrap();
-            result = re_qualified.replace_all(&result, format!("::{}{}", prefixed, "$1")).to_string();
-        }
-
-        // 7. Return type: `-> Foo`
-        result = result.replace(
-            &format!("-> {}", type_name),
-            &format!("-> {}", prefixed),
-        );
-
-        // 8. Let binding types: `let x: Foo =`
-        result = result.replace(
-            &format!(": {} =", type_name),
-            &format!(": {} =", prefixed),
-        );
-
-        // 9. From trait target type: `for TypeName {`
-        result = result.replace(
-            &format!("for {} {{", type_name),
-            &format!("for {} {{", prefixed),
-        );
-
-        // 10. Function parameter type: `o: TypeName)`
-        result = result.replace(
-            &format!(": {})", type_name),
-            &format!(": {})", prefixed),
-        );
-
-        // 11. Result/Option wrapped types: `Result<Foo>` or `Option<Foo>`
-        result = result.replace(
-            &format!("Result<{}>", type_name),
-            &format!("Result<{}>", prefixed),
-        );
-        result = result.replace(
-            &format!("Option<{}>", type_name),
-            &format!("Option<{}>", prefixed),
-        );
-
-    }
-
-    // Restore the protected Rust standard library paths
-    result = result.replace("__PLACEHOLDER_STD_STRING__", "std::string::String");
-    result = result.replace("__PLACEHOLDER_STD_VEC__", "std::vec::Vec");
-    result = result.replace("__PLACEHOLDER_STD_OPTION__", "std::option::Option");
-    result = result.replace("__PLACEHOLDER_STD_RESULT__", "std::result::Result");
+    let runtime_path = fs::canonicalize("..").expect("Get runtime path");
+    let cargo_toml = format!(
+        r#"[package]
+name = "binschema-test"
+version = "0.1.0"
+edition = "2021"
 
-    result
+[dependencies]
+binschema-runtime = {{ path = "{}/rust" }}
+serde = {{ version = "1.0", features = ["derive"] }}
+serde_json = "1.0"
+"#,
+        runtime_path.display()
+    );
+    fs::write(dir.join("Cargo.toml"), &cargo_toml).expect("Write Cargo.toml");
 }
 
 /// Check if a sequence type needs separate Input/Output structs.
@@ -341,7 +203,8 @@ fn type_needs_input_output_split(type_name: &str, schema: &Schema) -> bool {
     }
 }
 
-/// Generate the test harness main function
+/// Generate the test harness main function. Each suite is paired with the
+/// module its generated code lives in, and types are named through it.
 fn generate_test_harness(suites: &[(String, TestSuite)]) -> String {
     let mut harness = String::from(
         r#"// Generated test harness
@@ -357,34 +220,76 @@ struct TestResult {
     error: Option<String>,
 }
 
+/// The first `bit_len` bits of `bytes` in stream order, one 0/1 per element
+fn bits_of(bytes: &[u8], bit_len: usize, lsb_first: bool) -> Vec<u8> {
+    (0..bit_len)
+        .map(|i| {
+            let shift = if lsb_first { i % 8 } else { 7 - i % 8 };
+            bytes.get(i / 8).map_or(0, |byte| (byte >> shift) & 1)
+        })
+        .collect()
+}
+
+/// Feeds truncated, bit-flipped and random variants of `seeds` to `decode`.
+/// Malformed input must come back as `Err`; returns a description of the
+/// first input that made decode panic instead.
+fn fuzz_no_panic<T>(seeds: &[Vec<u8>], decode: fn(&[u8]) -> Result<T>) -> Option<String> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut failure = None;
+    'seeds: for seed in seeds {
+        for round in 0..64u64 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let mut input = seed.clone();
+            match round % 3 {
+                0 => input.truncate(state as usize % (seed.len() + 1)),
+                1 if !input.is_empty() => {
+                    let i = state as usize % input.len();
+                    input[i] ^= (state >> 56) as u8 | 1;
+                }
+                _ => input = state.to_le_bytes().iter().cycle().take(state as usize % 64).copied().collect(),
+            }
+            if std::panic::catch_unwind(|| { let _ = decode(&input); }).is_err() {
+                failure = Some(format!("decode panicked on input {:?}", input));
+                break 'seeds;
+            }
+        }
+    }
+    std::panic::set_hook(hook);
+    failure
+}
+
 fn main() {
     let mut all_results: Vec<Vec<TestResult>> = Vec::new();
 
 "#,
     );
 
-    for (prefix, suite) in suites {
+    for (module, suite) in suites {
         // Convert test_type to PascalCase to match Rust generator naming
         let type_pascal = to_pascal_case(&suite.test_type);
-        let prefixed_type = format!("{}_{}", prefix, type_pascal);
+        let qualified_type = format!("{}::{}", module, type_pascal);
         // Check if the test type needs Input/Output separation (has computed or const fields)
         let uses_input_output = type_needs_input_output_split(&suite.test_type, &suite.schema);
         let input_type = if uses_input_output {
-            format!("{}Input", prefixed_type)
+            format!("{}Input", qualified_type)
         } else {
-            prefixed_type.clone()
+            qualified_type.clone()
         };
         let output_type = if uses_input_output {
-            format!("{}Output", prefixed_type)
+            format!("{}Output", qualified_type)
         } else {
-            prefixed_type.clone()
+            qualified_type.clone()
         };
 
         // Check if the test type has instance fields (position-based decode-only fields)
         // Types with instances skip encoding and only test decoding from expected bytes
         let has_instances = suite.schema.types.get(&suite.test_type)
             .map(|t| match t {
-                TypeDef::Sequence { instances, .. } => instances.as_ref().map_or(false, |i| !i.is_empty()),
+                TypeDef::Sequence { instances, .. } => instances.as_ref().is_some_and(|i| !i.is_empty()),
                 _ => false,
             })
             .unwrap_or(false);
@@ -394,12 +299,10 @@ fn main() {
         harness.push_str("        let mut results: Vec<TestResult> = Vec::new();\n\n");
 
         for tc in &suite.test_cases {
-            // Skip tests that expect errors for now
-            if tc.error.is_some() {
-                continue;
-            }
-
             let should_error_on_encode = tc.should_error_on_encode.unwrap_or(false);
+            let should_error_on_decode = tc.should_error.unwrap_or(false)
+                || tc.should_error_on_decode.unwrap_or(false)
+                || tc.error.is_some();
 
             // Generate test case
             harness.push_str(&format!(
@@ -412,7 +315,25 @@ fn main() {
                 tc.description.replace("\"", "\\\"")
             ));
 
-            if has_instances {
+            if should_error_on_decode {
+                // Malformed input must be rejected. Error messages are worded
+                // differently per language, so any Err counts.
+                let expected_error = tc.error_message.as_ref().or(tc.error.as_ref()).map(|m| m.as_str()).unwrap_or("any error");
+                harness.push_str(&format!(
+                    "            let input: Vec<u8> = vec![{}];\n",
+                    tc.bytes.as_deref().unwrap_or_default().iter().map(|b| b.to_string()).collect::<Vec<_>>().join(", ")
+                ));
+                harness.push_str(&format!("            match {}::decode(&input) {{\n", output_type));
+                harness.push_str("                Ok(_decoded) => {\n");
+                harness.push_str(&format!(
+                    "                    result.error = Some(\"decode succeeded, expected {}\".to_string());\n",
+                    expected_error.replace('\\', "\\\\").replace('"', "\\\"")
+                ));
+                harness.push_str("                }\n");
+                harness.push_str("                Err(_e) => result.pass = true,\n");
+                harness.push_str("            }\n");
+                harness.push_str("            results.push(result);\n");
+            } else if has_instances {
                 // Types with instance fields: skip encoding, test decode-only from expected bytes
                 // This matches TypeScript's behavior (encoding is skipped for types with instances)
                 if let Some(bytes) = &tc.bytes {
@@ -446,18 +367,22 @@ fn main() {
                 // Normal test: construct value, encode, compare bytes, decode
                 // Generate value construction using Input type for composite types
                 // Pass test description to help determine negative infinity
-                harness.push_str(&generate_value_construction(&input_type, &tc.value, "test_value", &suite.schema, prefix, &suite.test_type, &tc.description));
+                harness.push_str(&generate_value_construction(&input_type, &tc.value, "test_value", &suite.schema, module, &suite.test_type, &tc.description));
 
                 // Encode (handle Result)
                 harness.push_str("            match test_value.encode() {\n");
 
                 if should_error_on_encode {
-                    // In Rust, computed fields are excluded from Input types, so users
-                    // can't provide invalid computed values. If encoding succeeds, that's
-                    // architecturally correct — treat as pass. If it fails, also pass
-                    // (the error was expected).
+                    // With an Input/Output split, computed fields are excluded from
+                    // the Input type, so users can't provide invalid computed values
+                    // and a successful encode is architecturally correct. Otherwise
+                    // the value itself is invalid and encode must fail.
                     harness.push_str("                Ok(_encoded) => {\n");
-                    harness.push_str("                    result.pass = true;\n");
+                    if uses_input_output {
+                        harness.push_str("                    result.pass = true;\n");
+                    } else {
+                        harness.push_str("                    result.error = Some(\"encode succeeded, expected an error\".to_string());\n");
+                    }
                     harness.push_str("                    results.push(result);\n");
                     harness.push_str("                }\n");
                     harness.push_str("                Err(_e) => {\n");
@@ -498,6 +423,33 @@ fn main() {
                         harness.push_str("                            }\n");
                         harness.push_str("                        }\n");
                         harness.push_str("                    }\n");
+                    } else if let Some(bits) = &tc.bits {
+                        // Sub-byte suites: compare only the bits written, ignoring the final byte's padding
+                        let lsb_first = suite.schema.config.as_ref()
+                            .and_then(|c| c.bit_order.as_deref()) == Some("lsb_first");
+                        harness.push_str(&format!(
+                            "                    let expected_bits: Vec<u8> = vec![{}];\n",
+                            bits
+                                .iter()
+                                .map(|b| b.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                        harness.push_str(&format!(
+                            "                    let encoded_bits = bits_of(&encoded, expected_bits.len(), {});\n",
+                            lsb_first
+                        ));
+                        harness.push_str("                    if encoded.len() != expected_bits.len().div_ceil(8) || encoded_bits != expected_bits {\n");
+                        harness.push_str("                        result.error = Some(format!(\"encode mismatch: got bits {:?} from {:?}, want {:?}\", encoded_bits, encoded, expected_bits));\n");
+                        harness.push_str(&format!("                    }} else if let Err(e) = {}::decode(&encoded) {{\n", output_type));
+                        harness.push_str("                        result.error = Some(format!(\"decode error: {}\", e));\n");
+                        harness.push_str("                    } else {\n");
+                        harness.push_str("                        result.pass = true;\n");
+                        harness.push_str("                    }\n");
+                        harness.push_str("                    results.push(result);\n");
+                    } else {
+                        harness.push_str("                    result.error = Some(\"no bytes or bits to compare\".to_string());\n");
+                        harness.push_str("                    results.push(result);\n");
                     }
 
                     // Close Ok(encoded) arm
@@ -515,6 +467,19 @@ fn main() {
             harness.push_str("        }\n\n");
         }
 
+        // No-panic fuzz: mutate every known encoding (error cases included)
+        // and require decode to fail cleanly rather than panic. The empty seed
+        // keeps the random-buffer rounds running for suites without bytes.
+        let seeds = std::iter::once("Vec::new()".to_string()).chain(suite.test_cases.iter()
+            .filter_map(|tc| tc.bytes.as_ref())
+            .map(|bytes| format!("vec![{}]", bytes.iter().map(|b| b.to_string()).collect::<Vec<_>>().join(", "))))
+            .collect::<Vec<_>>();
+        harness.push_str("        {\n");
+        harness.push_str(&format!("            let seeds: Vec<Vec<u8>> = vec![{}];\n", seeds.join(", ")));
+        harness.push_str(&format!("            let error = fuzz_no_panic(&seeds, {}::decode);\n", output_type));
+        harness.push_str("            results.push(TestResult { description: \"decode never panics on malformed input\".to_string(), pass: error.is_none(), error });\n");
+        harness.push_str("        }\n\n");
+
         harness.push_str("        all_results.push(results);\n");
         harness.push_str("    }\n\n");
     }
@@ -538,7 +503,7 @@ fn generate_value_construction(
     value: &serde_json::Value,
     var_name: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     current_type_name: &str,
     test_description: &str,
 ) -> String {
@@ -587,20 +552,20 @@ fn generate_value_construction(
                     match item_type_def {
                         TypeDef::DiscriminatedUnion { .. } => {
                             let formatted: Vec<String> = arr.iter()
-                                .map(|v| format_discriminated_union_value(v, item_type, schema, prefix))
+                                .map(|v| format_discriminated_union_value(v, item_type, schema, module))
                                 .collect();
                             return format!("            let {} = {} {{ value: vec![{}] }};\n", var_name, type_name, formatted.join(", "));
                         }
                         TypeDef::Sequence { .. } => {
                             let formatted: Vec<String> = arr.iter()
-                                .map(|v| format_nested_struct(v, item_type, schema, prefix))
+                                .map(|v| format_nested_struct(v, item_type, schema, module))
                                 .collect();
                             return format!("            let {} = {} {{ value: vec![{}] }};\n", var_name, type_name, formatted.join(", "));
                         }
                         TypeDef::Direct { .. } => {
                             // Item is a Direct type (newtype) - format as newtype
                             let formatted: Vec<String> = arr.iter()
-                                .map(|v| format_value_as_newtype(v, item_type, prefix, schema))
+                                .map(|v| format_value_as_newtype(v, item_type, module, schema))
                                 .collect();
                             return format!("            let {} = {} {{ value: vec![{}] }};\n", var_name, type_name, formatted.join(", "));
                         }
@@ -625,7 +590,7 @@ fn generate_value_construction(
         Some(def) => def,
         None => {
             // Fallback: iterate JSON keys if type not found
-            return generate_value_construction_from_json(type_name, value_map, var_name, schema, prefix);
+            return generate_value_construction_from_json(type_name, value_map, var_name, schema, module);
         }
     };
 
@@ -634,7 +599,7 @@ fn generate_value_construction(
         TypeDef::Sequence { sequence, .. } => sequence,
         _ => {
             // For non-sequence types, fallback to JSON iteration
-            return generate_value_construction_from_json(type_name, value_map, var_name, schema, prefix);
+            return generate_value_construction_from_json(type_name, value_map, var_name, schema, module);
         }
     };
 
@@ -675,7 +640,7 @@ fn generate_value_construction(
         } else {
             "Output"
         };
-        let formatted_value = format_value_with_field_and_suffix(field_value, field, schema, prefix, current_type_name, child_suffix, test_description);
+        let formatted_value = format_value_with_field_and_suffix(field_value, field, schema, module, current_type_name, child_suffix, test_description);
         result.push_str(&format!("                {}: {},\n", rust_field_name, formatted_value));
     }
 
@@ -689,7 +654,7 @@ fn generate_value_construction_from_json(
     value_map: &serde_json::Map<String, serde_json::Value>,
     var_name: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
 ) -> String {
     let mut result = format!("            let {} = {} {{\n", var_name, type_name);
     for (key, val) in value_map {
@@ -707,11 +672,11 @@ fn format_value_with_field(
     value: &serde_json::Value,
     field: &Field,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     test_description: &str,
 ) -> String {
     // Call with default empty containing type name
-    format_value_with_field_and_context(value, field, schema, prefix, "", test_description)
+    format_value_with_field_and_context(value, field, schema, module, "", test_description)
 }
 
 /// Format a value with full context including containing type name
@@ -719,12 +684,12 @@ fn format_value_with_field_and_context(
     value: &serde_json::Value,
     field: &Field,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     containing_type_name: &str,
     test_description: &str,
 ) -> String {
     // Default to Input suffix for regular struct fields
-    format_value_with_field_and_suffix(value, field, schema, prefix, containing_type_name, "Input", test_description)
+    format_value_with_field_and_suffix(value, field, schema, module, containing_type_name, "Input", test_description)
 }
 
 /// Format a field value with explicit suffix propagation
@@ -732,7 +697,7 @@ fn format_value_with_field_and_suffix(
     value: &serde_json::Value,
     field: &Field,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     containing_type_name: &str,
     suffix: &str,
     test_description: &str,
@@ -746,12 +711,12 @@ fn format_value_with_field_and_suffix(
             if let Some(type_def) = schema.types.get(value_type) {
                 match type_def {
                     TypeDef::Sequence { .. } => {
-                        let inner = format_nested_struct_with_suffix(value, value_type, schema, prefix, suffix);
+                        let inner = format_nested_struct_with_suffix(value, value_type, schema, module, suffix);
                         return format!("Some({})", inner);
                     }
                     TypeDef::Direct { .. } => {
                         // Direct type reference (newtype wrapper)
-                        let inner = format_value_as_newtype(value, value_type, prefix, schema);
+                        let inner = format_value_as_newtype(value, value_type, module, schema);
                         return format!("Some({})", inner);
                     }
                     _ => {}
@@ -766,7 +731,7 @@ fn format_value_with_field_and_suffix(
     // Handle conditional fields - they are also Option<T> in Rust
     if field.conditional.is_some() {
         // Format the inner value based on field type, then wrap in Some(...)
-        let inner = format_conditional_inner_value(value, field_type, schema, prefix, suffix, containing_type_name);
+        let inner = format_conditional_inner_value(value, field_type, schema, module, suffix, containing_type_name);
         return format!("Some({})", inner);
     }
 
@@ -780,14 +745,14 @@ fn format_value_with_field_and_suffix(
             } else {
                 format!("{}{}", to_pascal_case(containing_type_name), to_pascal_case(field_name))
             };
-            return format_bitfield_struct_with_name(value, &struct_name, prefix);
+            return format_bitfield_struct_with_name(value, &struct_name, module);
         }
     }
 
     // Handle array fields - propagate suffix for choice types inside arrays
     if field_type == "array" {
         if let serde_json::Value::Array(arr) = value {
-            return format_array_with_field_and_suffix(arr, field, schema, prefix, suffix, containing_type_name);
+            return format_array_with_field_and_suffix(arr, field, schema, module, suffix, containing_type_name);
         }
         return "vec![]".to_string();
     }
@@ -800,7 +765,7 @@ fn format_value_with_field_and_suffix(
                 .map(|v| v.type_name.clone())
                 .collect();
             let field_name = field.name.as_deref().unwrap_or("");
-            return format_inline_discriminated_union_value(value, &variant_types, schema, prefix, containing_type_name, field_name);
+            return format_inline_discriminated_union_value(value, &variant_types, schema, module, containing_type_name, field_name);
         }
     }
 
@@ -811,7 +776,7 @@ fn format_value_with_field_and_suffix(
                 .map(|c| c.type_name.clone())
                 .collect();
             let field_name = field.name.as_deref().unwrap_or("");
-            return format_choice_value(value, &variant_types, schema, prefix, containing_type_name, field_name);
+            return format_choice_value(value, &variant_types, schema, module, containing_type_name, field_name);
         }
     }
 
@@ -819,18 +784,18 @@ fn format_value_with_field_and_suffix(
     if let Some(type_def) = schema.types.get(field_type) {
         match type_def {
             TypeDef::Sequence { .. } => {
-                return format_nested_struct_with_suffix(value, field_type, schema, prefix, suffix);
+                return format_nested_struct_with_suffix(value, field_type, schema, module, suffix);
             }
             TypeDef::DiscriminatedUnion { .. } => {
-                return format_discriminated_union_value(value, field_type, schema, prefix);
+                return format_discriminated_union_value(value, field_type, schema, module);
             }
             TypeDef::BackReference { target_type, .. } => {
                 // Back reference - format as the target type newtype wrapper
-                return format_value_as_newtype(value, field_type, prefix, schema);
+                return format_value_as_newtype(value, field_type, module, schema);
             }
             TypeDef::Array { items: item_def, .. } => {
                 // Array type alias - format as struct with `value` field
-                let rust_type_name = format!("{}_{}", prefix, to_pascal_case(field_type));
+                let rust_type_name = format!("{}::{}", module, to_pascal_case(field_type));
                 if let serde_json::Value::Array(arr) = value {
                     let item_type = &item_def.field_type;
                     // Check if items are a named type in the schema
@@ -838,20 +803,20 @@ fn format_value_with_field_and_suffix(
                         match item_type_def {
                             TypeDef::DiscriminatedUnion { .. } => {
                                 let formatted: Vec<String> = arr.iter()
-                                    .map(|v| format_discriminated_union_value(v, item_type, schema, prefix))
+                                    .map(|v| format_discriminated_union_value(v, item_type, schema, module))
                                     .collect();
                                 return format!("{} {{ value: vec![{}] }}", rust_type_name, formatted.join(", "));
                             }
                             TypeDef::Sequence { .. } => {
                                 let formatted: Vec<String> = arr.iter()
-                                    .map(|v| format_nested_struct_with_suffix(v, item_type, schema, prefix, suffix))
+                                    .map(|v| format_nested_struct_with_suffix(v, item_type, schema, module, suffix))
                                     .collect();
                                 return format!("{} {{ value: vec![{}] }}", rust_type_name, formatted.join(", "));
                             }
                             TypeDef::Direct { type_name: _inner_type, .. } => {
                                 // Item is a Direct type - format as newtype
                                 let formatted: Vec<String> = arr.iter()
-                                    .map(|v| format_value_as_newtype(v, item_type, prefix, schema))
+                                    .map(|v| format_value_as_newtype(v, item_type, module, schema))
                                     .collect();
                                 return format!("{} {{ value: vec![{}] }}", rust_type_name, formatted.join(", "));
                             }
@@ -860,7 +825,7 @@ fn format_value_with_field_and_suffix(
                     }
                     // Fallback: primitive array
                     let items: Vec<String> = arr.iter()
-                        .map(|v| format_value_simple(v))
+                        .map(format_value_simple)
                         .collect();
                     return format!("{} {{ value: vec![{}] }}", rust_type_name, items.join(", "));
                 }
@@ -868,7 +833,7 @@ fn format_value_with_field_and_suffix(
             }
             TypeDef::Direct { .. } => {
                 // Direct type reference (newtype wrapper like String, InlineString)
-                return format_value_as_newtype(value, field_type, prefix, schema);
+                return format_value_as_newtype(value, field_type, module, schema);
             }
             TypeDef::Enum { repr, .. } => {
                 // Enum type — construct via from_value
@@ -878,12 +843,12 @@ fn format_value_with_field_and_suffix(
                     "uint32" => "u32",
                     _ => "u8",
                 };
-                let prefixed_type = format!("{}_{}", prefix, to_pascal_case(field_type));
+                let qualified_type = format!("{}::{}", module, to_pascal_case(field_type));
                 if let serde_json::Value::Number(n) = value {
                     let val = n.as_u64().unwrap_or(0);
-                    return format!("{}::from_value({} as {}).unwrap()", prefixed_type, val, rust_repr);
+                    return format!("{}::from_value({} as {}).unwrap()", qualified_type, val, rust_repr);
                 }
-                return format!("{}::from_value(0).unwrap()", prefixed_type);
+                return format!("{}::from_value(0).unwrap()", qualified_type);
             }
         }
     }
@@ -946,7 +911,7 @@ fn format_conditional_inner_value(
     value: &serde_json::Value,
     field_type: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     suffix: &str,
     containing_type_name: &str,
 ) -> String {
@@ -954,23 +919,23 @@ fn format_conditional_inner_value(
     if let Some(type_def) = schema.types.get(field_type) {
         match type_def {
             TypeDef::Sequence { .. } => {
-                return format_nested_struct_with_suffix(value, field_type, schema, prefix, suffix);
+                return format_nested_struct_with_suffix(value, field_type, schema, module, suffix);
             }
             TypeDef::DiscriminatedUnion { .. } => {
-                return format_discriminated_union_value(value, field_type, schema, prefix);
+                return format_discriminated_union_value(value, field_type, schema, module);
             }
             TypeDef::BackReference { .. } => {
-                return format_value_as_newtype(value, field_type, prefix, schema);
+                return format_value_as_newtype(value, field_type, module, schema);
             }
             TypeDef::Array { .. } => {
                 if let serde_json::Value::Array(arr) = value {
-                    let items: Vec<String> = arr.iter().map(|v| format_value_simple(v)).collect();
+                    let items: Vec<String> = arr.iter().map(format_value_simple).collect();
                     return format!("vec![{}]", items.join(", "));
                 }
                 return "vec![]".to_string();
             }
             TypeDef::Direct { .. } => {
-                return format_value_as_newtype(value, field_type, prefix, schema);
+                return format_value_as_newtype(value, field_type, module, schema);
             }
             TypeDef::Enum { repr, .. } => {
                 let rust_repr = match repr.as_str() {
@@ -979,12 +944,12 @@ fn format_conditional_inner_value(
                     "uint32" => "u32",
                     _ => "u8",
                 };
-                let prefixed_type = format!("{}_{}", prefix, to_pascal_case(field_type));
+                let qualified_type = format!("{}::{}", module, to_pascal_case(field_type));
                 if let serde_json::Value::Number(n) = value {
                     let val = n.as_u64().unwrap_or(0);
-                    return format!("{}::from_value({} as {}).unwrap()", prefixed_type, val, rust_repr);
+                    return format!("{}::from_value({} as {}).unwrap()", qualified_type, val, rust_repr);
                 }
-                return format!("{}::from_value(0).unwrap()", prefixed_type);
+                return format!("{}::from_value(0).unwrap()", qualified_type);
             }
         }
     }
@@ -992,7 +957,7 @@ fn format_conditional_inner_value(
     // Handle array types
     if field_type == "array" {
         if let serde_json::Value::Array(arr) = value {
-            let items: Vec<String> = arr.iter().map(|v| format_value_simple(v)).collect();
+            let items: Vec<String> = arr.iter().map(format_value_simple).collect();
             return format!("vec![{}]", items.join(", "));
         }
         return "vec![]".to_string();
@@ -1030,10 +995,10 @@ fn format_conditional_inner_value(
 fn format_value_as_newtype(
     value: &serde_json::Value,
     type_name: &str,
-    prefix: &str,
+    module: &str,
     schema: &Schema,
 ) -> String {
-    let rust_type = format!("{}_{}", prefix, to_pascal_case(type_name));
+    let rust_type = format!("{}::{}", module, to_pascal_case(type_name));
 
     // Look up the type definition to see what it wraps
     if let Some(type_def) = schema.types.get(type_name) {
@@ -1046,22 +1011,20 @@ fn format_value_as_newtype(
                 }
 
                 // Check if the wrapped type is a composite (Sequence) type
-                if let Some(wrapped_def) = schema.types.get(wrapped_type) {
-                    if let TypeDef::Sequence { .. } = wrapped_def {
-                        // Composite wrapper - use struct literal with Output suffix
-                        let inner = format_nested_struct_with_suffix(value, wrapped_type, schema, prefix, "Output");
-                        return format!("{}Output {{ value: {} }}", rust_type, inner);
-                    }
+                if let Some(TypeDef::Sequence { .. }) = schema.types.get(wrapped_type) {
+                    // Composite wrapper - use struct literal with Output suffix
+                    let inner = format_nested_struct_with_suffix(value, wrapped_type, schema, module, "Output");
+                    return format!("{}Output {{ value: {} }}", rust_type, inner);
                 }
 
                 // Non-composite wrapper - recursively format the inner value
-                let inner = format_value_as_newtype(value, wrapped_type, prefix, schema);
+                let inner = format_value_as_newtype(value, wrapped_type, module, schema);
                 return format!("{} {{ value: {} }}", rust_type, inner);
             }
             TypeDef::BackReference { target_type, .. } => {
                 // Back reference - wrap the value in the target type first
                 // The target type is what the back_reference points to (e.g., Label for LabelPointer)
-                let inner = format_value_as_newtype(value, target_type, prefix, schema);
+                let inner = format_value_as_newtype(value, target_type, module, schema);
                 return format!("{}({})", rust_type, inner);
             }
             _ => {}
@@ -1078,9 +1041,9 @@ fn format_nested_struct(
     value: &serde_json::Value,
     type_name: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
 ) -> String {
-    format_nested_struct_with_suffix(value, type_name, schema, prefix, "Input")
+    format_nested_struct_with_suffix(value, type_name, schema, module, "Input")
 }
 
 /// Format a nested struct value for enum variant payloads (uses Output suffix)
@@ -1088,9 +1051,9 @@ fn format_nested_struct_for_enum(
     value: &serde_json::Value,
     type_name: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
 ) -> String {
-    format_nested_struct_with_suffix(value, type_name, schema, prefix, "Output")
+    format_nested_struct_with_suffix(value, type_name, schema, module, "Output")
 }
 
 /// Format a nested struct value with configurable suffix
@@ -1098,7 +1061,7 @@ fn format_nested_struct_with_suffix(
     value: &serde_json::Value,
     type_name: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     suffix: &str,
 ) -> String {
     // First, check the type definition to handle Direct and BackReference types properly
@@ -1106,7 +1069,7 @@ fn format_nested_struct_with_suffix(
 
     // Handle Direct types (type aliases) - they might have non-Object values
     if let Some(TypeDef::Direct { type_name: wrapped_type, .. }) = type_def {
-        let rust_type_name = format!("{}_{}", prefix, to_pascal_case(type_name));
+        let rust_type_name = format!("{}::{}", module, to_pascal_case(type_name));
         // Check what the wrapped type is
         if wrapped_type == "string" {
             // String wrapper - format as tuple struct
@@ -1119,12 +1082,12 @@ fn format_nested_struct_with_suffix(
             match wrapped_def {
                 TypeDef::Sequence { .. } => {
                     // Wrapped composite type - recurse
-                    let inner = format_nested_struct_with_suffix(value, wrapped_type, schema, prefix, suffix);
+                    let inner = format_nested_struct_with_suffix(value, wrapped_type, schema, module, suffix);
                     return format!("{}({})", rust_type_name, inner);
                 }
                 TypeDef::DiscriminatedUnion { .. } => {
                     // Wrapped discriminated union - format as union value
-                    return format_discriminated_union_value(value, wrapped_type, schema, prefix);
+                    return format_discriminated_union_value(value, wrapped_type, schema, module);
                 }
                 _ => {}
             }
@@ -1135,9 +1098,9 @@ fn format_nested_struct_with_suffix(
 
     // Handle BackReference types - they wrap a target type (e.g., LabelPointer wraps Label)
     if let Some(TypeDef::BackReference { target_type, .. }) = type_def {
-        let rust_type_name = format!("{}_{}", prefix, to_pascal_case(type_name));
+        let rust_type_name = format!("{}::{}", module, to_pascal_case(type_name));
         // Format the value as the target type first, then wrap in BackReference
-        let inner = format_nested_struct_with_suffix(value, target_type, schema, prefix, suffix);
+        let inner = format_nested_struct_with_suffix(value, target_type, schema, module, suffix);
         return format!("{}({})", rust_type_name, inner);
     }
 
@@ -1145,10 +1108,10 @@ fn format_nested_struct_with_suffix(
         serde_json::Value::Object(map) => map,
         serde_json::Value::String(s) => {
             // Non-object value for a type that's not Direct - might be a string wrapper
-            let rust_type_name = format!("{}_{}", prefix, to_pascal_case(type_name));
+            let rust_type_name = format!("{}::{}", module, to_pascal_case(type_name));
             return format!("{}({:?}.to_string())", rust_type_name, s);
         }
-        _ => return format!("{}_{} {{ }}", prefix, to_pascal_case(type_name)),
+        _ => return format!("{}::{} {{ }}", module, to_pascal_case(type_name)),
     };
 
     // Get the type definition (for Sequence types)
@@ -1156,19 +1119,19 @@ fn format_nested_struct_with_suffix(
         Some(def) => def,
         None => {
             // Fallback: format without schema info
-            return format_nested_object_simple(value_map, type_name, prefix);
+            return format_nested_object_simple(value_map, type_name, module);
         }
     };
 
     let sequence = match type_def {
         TypeDef::Sequence { sequence, .. } => sequence,
         _ => {
-            return format_nested_object_simple(value_map, type_name, prefix);
+            return format_nested_object_simple(value_map, type_name, module);
         }
     };
 
     // Composite types use the specified suffix only if they need Input/Output split
-    let rust_type_name = format!("{}_{}", prefix, to_pascal_case(type_name));
+    let rust_type_name = format!("{}::{}", module, to_pascal_case(type_name));
     let needs_split = type_needs_input_output_split(type_name, schema);
     let rust_type_name = if needs_split {
         format!("{}{}", rust_type_name, suffix)
@@ -1236,11 +1199,11 @@ fn format_nested_struct_with_suffix(
         // its fields reference split children as ChildOutput, so children need "Output".
         // If the current type is split, propagate the existing suffix.
         let child_suffix = if needs_split { suffix } else { "Output" };
-        let formatted_value = format_value_with_field_and_suffix(field_value, field, schema, prefix, type_name, child_suffix, "");
+        let formatted_value = format_value_with_field_and_suffix(field_value, field, schema, module, type_name, child_suffix, "");
         result.push_str(&format!("{}: {}, ", rust_field_name, formatted_value));
     }
 
-    result.push_str("}");
+    result.push('}');
     result
 }
 
@@ -1248,9 +1211,9 @@ fn format_nested_struct_with_suffix(
 fn format_nested_object_simple(
     value_map: &serde_json::Map<String, serde_json::Value>,
     type_name: &str,
-    prefix: &str,
+    module: &str,
 ) -> String {
-    let rust_type_name = format!("{}_{}", prefix, to_pascal_case(type_name));
+    let rust_type_name = format!("{}::{}", module, to_pascal_case(type_name));
     let mut result = format!("{} {{ ", rust_type_name);
 
     for (key, val) in value_map {
@@ -1259,7 +1222,7 @@ fn format_nested_object_simple(
         result.push_str(&format!("{}: {}, ", field_name, field_value));
     }
 
-    result.push_str("}");
+    result.push('}');
     result
 }
 
@@ -1268,7 +1231,7 @@ fn format_array_with_field(
     arr: &[serde_json::Value],
     field: &Field,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     containing_type_name: &str,
 ) -> String {
     if arr.is_empty() {
@@ -1295,7 +1258,7 @@ fn format_array_with_field(
                 .collect();
             let field_name = field.name.as_deref().unwrap_or("");
             let formatted: Vec<String> = arr.iter()
-                .map(|v| format_choice_value(v, &variant_types, schema, prefix, containing_type_name, field_name))
+                .map(|v| format_choice_value(v, &variant_types, schema, module, containing_type_name, field_name))
                 .collect();
             return format!("vec![{}]", formatted.join(", "));
         }
@@ -1306,13 +1269,13 @@ fn format_array_with_field(
         match type_def {
             TypeDef::Sequence { .. } => {
                 let formatted: Vec<String> = arr.iter()
-                    .map(|v| format_nested_struct(v, item_type, schema, prefix))
+                    .map(|v| format_nested_struct(v, item_type, schema, module))
                     .collect();
                 return format!("vec![{}]", formatted.join(", "));
             }
             TypeDef::DiscriminatedUnion { .. } => {
                 let formatted: Vec<String> = arr.iter()
-                    .map(|v| format_discriminated_union_value(v, item_type, schema, prefix))
+                    .map(|v| format_discriminated_union_value(v, item_type, schema, module))
                     .collect();
                 return format!("vec![{}]", formatted.join(", "));
             }
@@ -1334,7 +1297,7 @@ fn format_array_with_field_and_suffix(
     arr: &[serde_json::Value],
     field: &Field,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     suffix: &str,
     containing_type_name: &str,
 ) -> String {
@@ -1362,7 +1325,7 @@ fn format_array_with_field_and_suffix(
                 .collect();
             let field_name = field.name.as_deref().unwrap_or("");
             let formatted: Vec<String> = arr.iter()
-                .map(|v| format_choice_value(v, &variant_types, schema, prefix, containing_type_name, field_name))
+                .map(|v| format_choice_value(v, &variant_types, schema, module, containing_type_name, field_name))
                 .collect();
             return format!("vec![{}]", formatted.join(", "));
         }
@@ -1373,13 +1336,13 @@ fn format_array_with_field_and_suffix(
         match type_def {
             TypeDef::Sequence { .. } => {
                 let formatted: Vec<String> = arr.iter()
-                    .map(|v| format_nested_struct_with_suffix(v, item_type, schema, prefix, suffix))
+                    .map(|v| format_nested_struct_with_suffix(v, item_type, schema, module, suffix))
                     .collect();
                 return format!("vec![{}]", formatted.join(", "));
             }
             TypeDef::DiscriminatedUnion { .. } => {
                 let formatted: Vec<String> = arr.iter()
-                    .map(|v| format_discriminated_union_value(v, item_type, schema, prefix))
+                    .map(|v| format_discriminated_union_value(v, item_type, schema, module))
                     .collect();
                 return format!("vec![{}]", formatted.join(", "));
             }
@@ -1400,14 +1363,14 @@ fn format_array_with_field_and_suffix(
 fn format_bitfield_struct_with_name(
     value: &serde_json::Value,
     struct_name: &str,
-    prefix: &str,
+    module: &str,
 ) -> String {
     let value_map = match value {
         serde_json::Value::Object(map) => map,
-        _ => return format!("{}_{} {{ }}", prefix, struct_name),
+        _ => return format!("{}::{} {{ }}", module, struct_name),
     };
 
-    let rust_type_name = format!("{}_{}", prefix, struct_name);
+    let rust_type_name = format!("{}::{}", module, struct_name);
     let mut result = format!("{} {{ ", rust_type_name);
 
     for (key, val) in value_map {
@@ -1416,7 +1379,7 @@ fn format_bitfield_struct_with_name(
         result.push_str(&format!("{}: {}, ", field_name, field_value));
     }
 
-    result.push_str("}");
+    result.push('}');
     result
 }
 
@@ -1506,61 +1469,26 @@ fn format_value_typed(value: &serde_json::Value, type_name: &str) -> String {
 /// Get the type of a field from the schema
 /// For optional fields, returns the value_type instead of "optional"
 fn get_field_type(schema: &Schema, type_name: &str, field_name: &str) -> Option<String> {
-    if let Some(type_def) = schema.types.get(type_name) {
-        match type_def {
-            TypeDef::Sequence { sequence, .. } => {
-                for field in sequence {
-                    if field.name.as_deref() == Some(field_name) {
-                        // For optional fields, return the value_type
-                        if field.field_type == "optional" {
-                            if let Some(ref value_type) = field.value_type {
-                                return Some(value_type.clone());
-                            }
-                        }
-                        return Some(field.field_type.clone());
-                    }
-                }
-            }
-            _ => {}
+    let field = schema.field_type(type_name, field_name)?;
+    // For optional fields, return the value_type
+    if field.field_type == "optional" {
+        if let Some(ref value_type) = field.value_type {
+            return Some(value_type.clone());
         }
     }
-    None
+    Some(field.field_type.clone())
 }
 
 /// Check if a field exists in the schema sequence (not in instances)
 fn field_exists_in_schema(schema: &Schema, type_name: &str, field_name: &str) -> bool {
-    if let Some(type_def) = schema.types.get(type_name) {
-        match type_def {
-            TypeDef::Sequence { sequence, .. } => {
-                for field in sequence {
-                    if field.name.as_deref() == Some(field_name) {
-                        return true;
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
-    false
+    schema.field_type(type_name, field_name).is_some()
 }
 
 /// Check if a field is a bitfield with sub-fields
 fn is_bitfield_with_subfields(schema: &Schema, type_name: &str, field_name: &str) -> bool {
-    if let Some(type_def) = schema.types.get(type_name) {
-        match type_def {
-            TypeDef::Sequence { sequence, .. } => {
-                for field in sequence {
-                    if field.name.as_deref() == Some(field_name) {
-                        if field.field_type == "bitfield" && field.fields.is_some() {
-                            return true;
-                        }
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
-    false
+    schema
+        .field_type(type_name, field_name)
+        .is_some_and(|field| field.field_type == "bitfield" && field.fields.is_some())
 }
 
 /// Get the generated struct name for a bitfield field
@@ -1590,36 +1518,30 @@ fn to_pascal_case(s: &str) -> String {
 
 /// Get the item type for an array field (returns None if it's a primitive array)
 fn get_array_item_type(schema: &Schema, type_name: &str, field_name: &str) -> Option<ArrayItemType> {
-    if let Some(type_def) = schema.types.get(type_name) {
-        match type_def {
-            TypeDef::Sequence { sequence, .. } => {
-                for field in sequence {
-                    if field.name.as_deref() == Some(field_name) {
-                        if field.field_type == "array" {
-                            if let Some(ref items) = field.items {
-                                // Check if items type is "choice" with choices
-                                if items.field_type == "choice" {
-                                    if let Some(ref choices) = items.choices {
-                                        let choice_types: Vec<String> = choices.iter()
-                                            .map(|c| c.type_name.clone())
-                                            .collect();
-                                        return Some(ArrayItemType::Choice(choice_types));
-                                    }
-                                }
-                                // Check if items type is a named type in schema (struct or discriminated union)
-                                if schema.types.contains_key(&items.field_type) {
-                                    if is_discriminated_union(schema, &items.field_type) {
-                                        return Some(ArrayItemType::DiscriminatedUnion(items.field_type.clone()));
-                                    } else {
-                                        return Some(ArrayItemType::Struct(items.field_type.clone()));
-                                    }
-                                }
+    if let Some(TypeDef::Sequence { sequence, .. }) = schema.types.get(type_name) {
+        for field in sequence {
+            if field.name.as_deref() == Some(field_name)
+                && field.field_type == "array" {
+                    if let Some(ref items) = field.items {
+                        // Check if items type is "choice" with choices
+                        if items.field_type == "choice" {
+                            if let Some(ref choices) = items.choices {
+                                let choice_types: Vec<String> = choices.iter()
+                                    .map(|c| c.type_name.clone())
+                                    .collect();
+                                return Some(ArrayItemType::Choice(choice_types));
+                            }
+                        }
+                        // Check if items type is a named type in schema (struct or discriminated union)
+                        if schema.types.contains_key(&items.field_type) {
+                            if is_discriminated_union(schema, &items.field_type) {
+                                return Some(ArrayItemType::DiscriminatedUnion(items.field_type.clone()));
+                            } else {
+                                return Some(ArrayItemType::Struct(items.field_type.clone()));
                             }
                         }
                     }
                 }
-            }
-            _ => {}
         }
     }
     None
@@ -1634,14 +1556,7 @@ enum ArrayItemType {
 
 /// Check if a type is a discriminated union
 fn is_discriminated_union(schema: &Schema, type_name: &str) -> bool {
-    if let Some(type_def) = schema.types.get(type_name) {
-        match type_def {
-            TypeDef::DiscriminatedUnion { .. } => true,
-            _ => false,
-        }
-    } else {
-        false
-    }
+    matches!(schema.types.get(type_name), Some(TypeDef::DiscriminatedUnion { .. }))
 }
 
 /// Format a discriminated union value
@@ -1649,7 +1564,7 @@ fn format_discriminated_union_value(
     value: &serde_json::Value,
     enum_type_name: &str,
     schema: &Schema,
-    prefix: &str,
+    module: &str,
 ) -> String {
     if let serde_json::Value::Object(map) = value {
         // Get the variant type from the "type" field
@@ -1661,16 +1576,16 @@ fn format_discriminated_union_value(
         let payload = map.get("value");
 
         if !variant_type.is_empty() {
-            let prefixed_enum = format!("{}_{}", prefix, to_pascal_case(enum_type_name));
+            let qualified_enum = format!("{}::{}", module, to_pascal_case(enum_type_name));
             let variant_pascal = to_pascal_case(variant_type);
 
             if let Some(payload_val) = payload {
                 // Enum variants use Output types (with const fields)
-                let payload_str = format_nested_struct_for_enum(payload_val, variant_type, schema, prefix);
-                return format!("{}::{}({})", prefixed_enum, variant_pascal, payload_str);
+                let payload_str = format_nested_struct_for_enum(payload_val, variant_type, schema, module);
+                return format!("{}::{}({})", qualified_enum, variant_pascal, payload_str);
             } else {
                 // No payload - unit variant (shouldn't happen for discriminated unions but handle it)
-                return format!("{}::{}", prefixed_enum, variant_pascal);
+                return format!("{}::{}", qualified_enum, variant_pascal);
             }
         }
     }
@@ -1683,7 +1598,7 @@ fn format_inline_discriminated_union_value(
     value: &serde_json::Value,
     variant_types: &[String],
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     containing_type_name: &str,
     field_name: &str,
 ) -> String {
@@ -1698,12 +1613,12 @@ fn format_inline_discriminated_union_value(
 
         if !variant_type.is_empty() {
             // Build the enum name from parent type + field name
-            let enum_name = format!("{}_{}{}", prefix, to_pascal_case(containing_type_name), to_pascal_case(field_name));
+            let enum_name = format!("{}::{}{}", module, to_pascal_case(containing_type_name), to_pascal_case(field_name));
             let variant_pascal = to_pascal_case(variant_type);
 
             if let Some(payload_val) = payload {
                 // Format the variant payload - use Output suffix for enum variants
-                let payload_str = format_nested_struct_for_enum(payload_val, variant_type, schema, prefix);
+                let payload_str = format_nested_struct_for_enum(payload_val, variant_type, schema, module);
                 return format!("{}::{}({})", enum_name, variant_pascal, payload_str);
             } else {
                 // No payload - unit variant
@@ -1716,7 +1631,7 @@ fn format_inline_discriminated_union_value(
 }
 
 /// Get a default value for a given field type (for conditional fields)
-fn get_default_value_for_type(field_type: &str, schema: &Schema, prefix: &str) -> String {
+fn get_default_value_for_type(field_type: &str, schema: &Schema, module: &str) -> String {
     match field_type {
         // Primitive types
         "uint8" | "uint16" | "uint32" | "uint64" |
@@ -1733,7 +1648,7 @@ fn get_default_value_for_type(field_type: &str, schema: &Schema, prefix: &str) -
                 match type_def {
                     TypeDef::Sequence { sequence, .. } => {
                         // Create a struct with default values for all fields
-                        let rust_type_name = format!("{}_{}", prefix, to_pascal_case(field_type));
+                        let rust_type_name = format!("{}::{}", module, to_pascal_case(field_type));
                         let mut fields = Vec::new();
                         for field in sequence {
                             if let Some(ref name) = field.name {
@@ -1746,7 +1661,7 @@ fn get_default_value_for_type(field_type: &str, schema: &Schema, prefix: &str) -
                                     continue;
                                 }
                                 let field_name = escape_rust_keyword(&to_snake_case(name));
-                                let default_val = get_default_value_for_type(&field.field_type, schema, prefix);
+                                let default_val = get_default_value_for_type(&field.field_type, schema, module);
                                 fields.push(format!("{}: {}", field_name, default_val));
                             }
                         }
@@ -1755,7 +1670,7 @@ fn get_default_value_for_type(field_type: &str, schema: &Schema, prefix: &str) -
                     }
                     TypeDef::Direct { type_name: wrapped_type, .. } => {
                         // Type alias - generate proper default based on wrapped type
-                        let rust_type_name = format!("{}_{}", prefix, to_pascal_case(field_type));
+                        let rust_type_name = format!("{}::{}", module, to_pascal_case(field_type));
                         // Check what the wrapped type is
                         if wrapped_type == "string" {
                             // String wrapper - use tuple struct constructor
@@ -1764,7 +1679,7 @@ fn get_default_value_for_type(field_type: &str, schema: &Schema, prefix: &str) -
                             match wrapped_def {
                                 TypeDef::Sequence { .. } => {
                                     // Wrapped composite - create struct with value field
-                                    let inner_default = get_default_value_for_type(wrapped_type, schema, prefix);
+                                    let inner_default = get_default_value_for_type(wrapped_type, schema, module);
                                     format!("{} {{ value: {} }}", rust_type_name, inner_default)
                                 }
                                 _ => format!("{} {{ value: Default::default() }}", rust_type_name)
@@ -1789,7 +1704,7 @@ fn format_choice_value(
     value: &serde_json::Value,
     variant_types: &[String],
     schema: &Schema,
-    prefix: &str,
+    module: &str,
     containing_type_name: &str,
     field_name: &str,
 ) -> String {
@@ -1805,7 +1720,7 @@ fn format_choice_value(
     };
 
     // Build the choice enum name from parent type + field name
-    let prefixed_enum = format!("{}_{}{}", prefix, to_pascal_case(containing_type_name), to_pascal_case(field_name));
+    let qualified_enum = format!("{}::{}{}", module, to_pascal_case(containing_type_name), to_pascal_case(field_name));
 
     // The variant name in Rust is PascalCase
     let variant_pascal = to_pascal_case(variant_type);
@@ -1819,9 +1734,9 @@ fn format_choice_value(
     let payload_value = serde_json::Value::Object(payload_map);
 
     // Format the payload struct - enum variants use Output types (with const fields)
-    let payload_str = format_nested_struct_for_enum(&payload_value, variant_type, schema, prefix);
+    let payload_str = format_nested_struct_for_enum(&payload_value, variant_type, schema, module);
 
-    format!("{}::{}({})", prefixed_enum, variant_pascal, payload_str)
+    format!("{}::{}({})", qualified_enum, variant_pascal, payload_str)
 }
 
 /// Escape Rust reserved keywords
@@ -1862,6 +1777,50 @@ enum SuiteResult {
     Ran { passed: usize, failed: usize, errors: Vec<String> },
 }
 
+/// A partial patch overwrites only the fields it sets; the rest of the base
+/// record, including its non-optional fields, is left as it was.
+#[test]
+fn test_apply_patch_keeps_unset_fields() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "DeviceConfig": { "sequence": [
+                { "name": "version", "type": "uint8" },
+                { "name": "baud_rate", "type": "optional", "value_type": "uint32" },
+                { "name": "timeout_ms", "type": "optional", "value_type": "uint16" },
+                { "name": "retries", "type": "optional", "value_type": "uint8" }
+            ] }
+        }
+    }"#;
+
+    let patch_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn partial_patch_overrides_only_present_fields() {
+    let mut config = DeviceConfig { version: 1, baud_rate: Some(9600), timeout_ms: Some(100), retries: None };
+    let patch = DeviceConfig { version: 7, baud_rate: None, timeout_ms: Some(250), retries: Some(3) };
+    config.apply_patch(&patch);
+    assert_eq!(config, DeviceConfig { version: 1, baud_rate: Some(9600), timeout_ms: Some(250), retries: Some(3) });
+}
+
+#[test]
+fn decoded_patch_applies_to_decoded_base() {
+    // version 1, baud_rate 115200, timeout_ms 500, no retries
+    let mut config = DeviceConfig::decode(&[1, 1, 0x00, 0x01, 0xC2, 0x00, 1, 0x01, 0xF4, 0]).unwrap();
+    // version 2, only retries set
+    let patch = DeviceConfig::decode(&[2, 0, 0, 1, 5]).unwrap();
+    config.apply_patch(&patch);
+    assert_eq!(config.version, 1);
+    assert_eq!(config.baud_rate, Some(115200));
+    assert_eq!(config.timeout_ms, Some(500));
+    assert_eq!(config.retries, Some(5));
+}
+"#;
+
+    run_generated_crate_test("apply_patch", schema_json, "apply_patch.rs", patch_test);
+}
+
 #[test]
 fn test_compile_and_run_all() {
     // Guard: ensure tests are run via `just test-rust`, not raw cargo commands.
@@ -1901,27 +1860,26 @@ fn test_compile_and_run_all() {
 
     println!("Found {} test files total", test_files.len());
 
-    // Check for duplicate test names (would cause ambiguous type definitions)
+    // Check for duplicate test names (results are reported by suite name)
     let mut seen_names: std::collections::HashMap<String, PathBuf> = std::collections::HashMap::new();
     for path in &test_files {
         if let Ok((suite, _)) = load_test_suite(path) {
-            let prefix = suite.name.replace("-", "_").replace(".", "_");
-            if let Some(existing_path) = seen_names.get(&prefix) {
+            if let Some(existing_path) = seen_names.get(&suite.name) {
                 panic!(
                     "Duplicate test name '{}' found in:\n  - {}\n  - {}\n\
-                    Test names must be unique across all directories to avoid type name collisions.",
+                    Test names must be unique across all directories.",
                     suite.name,
                     existing_path.display(),
                     path.display()
                 );
             }
-            seen_names.insert(prefix, path.clone());
+            seen_names.insert(suite.name.clone(), path.clone());
         }
     }
 
     // Track results per suite
     let mut suite_results: Vec<(String, SuiteResult)> = Vec::new();
-    let mut codegen_success: Vec<(String, TestSuite, String)> = Vec::new();
+    let mut codegen_success: Vec<(TestSuite, String)> = Vec::new();
     let mut codegen_failures: Vec<(String, String)> = Vec::new();
 
     // Try to generate code for each suite
@@ -1936,11 +1894,7 @@ fn test_compile_and_run_all() {
         };
 
         match generate_rust_code(&raw_schema_json, &suite.test_type) {
-            Ok(code) => {
-                let prefix = suite.name.replace("-", "_").replace(".", "_");
-                let prefixed_code = prefix_type_names(&code, &prefix);
-                codegen_success.push((prefix, suite, prefixed_code));
-            }
+            Ok(code) => codegen_success.push((suite, code)),
             Err(e) => {
                 codegen_failures.push((suite.name.clone(), e.to_string()));
                 suite_results.push((suite.name.clone(), SuiteResult::CodeGenError(e.to_string())));
@@ -1973,45 +1927,7 @@ fn test_compile_and_run_all() {
 
     // Create temp directory for batched compilation
     let temp_dir = tempfile::tempdir().expect("Create temp dir");
-    let src_dir = temp_dir.path().join("src");
-    fs::create_dir_all(&src_dir).expect("Create src dir");
-
-    // Write all generated code files
-    let mut mod_content = String::new();
-    for (i, (prefix, _suite, code)) in all_suites.iter().enumerate() {
-        let filename = format!("gen_{}.rs", i);
-        fs::write(src_dir.join(&filename), code).expect("Write generated code");
-        mod_content.push_str(&format!("mod gen_{};\npub use gen_{}::*;\n", i, i));
-    }
-
-    // Write lib.rs
-    fs::write(src_dir.join("lib.rs"), &mod_content).expect("Write lib.rs");
-
-    // Generate and write test harness
-    let suite_refs: Vec<(String, TestSuite)> = all_suites
-        .iter()
-        .map(|(prefix, suite, _)| (prefix.clone(), suite.clone()))
-        .collect();
-    let harness = generate_test_harness(&suite_refs);
-    fs::write(src_dir.join("main.rs"), &harness).expect("Write main.rs");
-
-    // Write Cargo.toml
-    let runtime_path = fs::canonicalize("..").expect("Get runtime path");
-    let cargo_toml = format!(
-        r#"[package]
-name = "binschema-test"
-version = "0.1.0"
-edition = "2021"
-
-[dependencies]
-binschema-runtime = {{ path = "{}/rust" }}
-serde = {{ version = "1.0", features = ["derive"] }}
-serde_json = "1.0"
-regex = "1.10"
-"#,
-        runtime_path.display()
-    );
-    fs::write(temp_dir.path().join("Cargo.toml"), &cargo_toml).expect("Write Cargo.toml");
+    write_batch_crate(temp_dir.path(), &all_suites);
 
     // Keep temp dir if DEBUG_GENERATED is set
     let keep_temp = std::env::var("DEBUG_GENERATED").ok();
@@ -2028,7 +1944,7 @@ regex = "1.10"
             if entry.path().is_dir() {
                 copy_dir_all(&entry.path(), &dest).ok();
             } else {
-                fs::copy(&entry.path(), &dest).ok();
+                fs::copy(entry.path(), &dest).ok();
             }
         }
         println!("Debug output saved to: {:?}", debug_dir);
@@ -2106,7 +2022,7 @@ regex = "1.10"
 
     println!("\n=== Test Results ===");
     for (i, suite_results) in results.iter().enumerate() {
-        let suite_name = &all_suites[i].1.name;
+        let suite_name = &all_suites[i].0.name;
         let passed = suite_results.iter().filter(|r| r.pass).count();
         let failed = suite_results.iter().filter(|r| !r.pass).count();
         total_passed += passed;
@@ -2147,6 +2063,2098 @@ regex = "1.10"
         } else { 0.0 });
 }
 
+#[test]
+fn test_harness_expects_errors_for_error_cases() {
+    let suite: TestSuite = json5::from_str(r#"{
+        "name": "byte_value", "description": "", "test_type": "Byte",
+        "schema": { "types": { "Byte": { "sequence": [{ "name": "v", "type": "uint8" }] } } },
+        "test_cases": [
+            { "description": "empty input", "bytes": [], "should_error": true, "error_message": "Unexpected \"end\"" },
+            { "description": "out of range", "value": { "v": 1 }, "should_error_on_encode": true }
+        ]
+    }"#).expect("Parse suite");
+    let harness = generate_test_harness(&[("gen_0".to_string(), suite)]);
+
+    assert!(harness.contains("            let input: Vec<u8> = vec![];\n            match gen_0::Byte::decode(&input) {\n"));
+    assert!(harness.contains("result.error = Some(\"decode succeeded, expected Unexpected \\\"end\\\"\".to_string());"));
+    assert!(harness.contains("                Err(_e) => result.pass = true,\n"));
+    assert!(harness.contains("result.error = Some(\"encode succeeded, expected an error\".to_string());"));
+}
+
+#[test]
+fn test_harness_compares_bits_only_cases_bit_by_bit() {
+    let suite: TestSuite = json5::from_str(r#"{
+        "name": "three_bits", "description": "", "test_type": "Flags",
+        "schema": { "config": { "bit_order": "lsb_first" }, "types": {
+            "Flags": { "sequence": [{ "name": "a", "type": "bit", "size": 1 }, { "name": "b", "type": "bit", "size": 2 }] }
+        } },
+        "test_cases": [{ "description": "a=1 b=2", "value": { "a": 1, "b": 2 }, "bits": [1, 0, 1] }]
+    }"#).expect("Parse suite");
+    let harness = generate_test_harness(&[("gen_0".to_string(), suite)]);
+
+    assert!(harness.contains("fn bits_of(bytes: &[u8], bit_len: usize, lsb_first: bool) -> Vec<u8> {"));
+    assert!(harness.contains("                    let expected_bits: Vec<u8> = vec![1, 0, 1];\n"));
+    assert!(harness.contains("                    let encoded_bits = bits_of(&encoded, expected_bits.len(), true);\n"));
+    assert!(harness.contains("} else if let Err(e) = gen_0::Flags::decode(&encoded) {"));
+    assert!(!harness.contains("no bytes or bits to compare"));
+}
+
+#[test]
+fn test_batch_suites_with_same_type_name_are_isolated() {
+    if std::env::var("RUST_TESTS").is_err() {
+        println!("Skipping batch isolation test (set RUST_TESTS=1 to run)");
+        return;
+    }
+
+    // Both suites define `Point`, with different layouts
+    let suite_jsons = [
+        r#"{
+            "name": "point_narrow", "description": "", "test_type": "Point",
+            "schema": { "config": { "endianness": "big_endian" }, "types": {
+                "Point": { "sequence": [{ "name": "x", "type": "uint8" }, { "name": "y", "type": "uint8" }] }
+            } },
+            "test_cases": [{ "description": "narrow point", "value": { "x": 1, "y": 2 }, "bytes": [1, 2] }]
+        }"#,
+        r#"{
+            "name": "point_wide", "description": "", "test_type": "Point",
+            "schema": { "config": { "endianness": "big_endian" }, "types": {
+                "Point": { "sequence": [{ "name": "x", "type": "uint16" }, { "name": "y", "type": "uint16" }, { "name": "z", "type": "uint16" }] }
+            } },
+            "test_cases": [{ "description": "wide point", "value": { "x": 1, "y": 2, "z": 3 }, "bytes": [0, 1, 0, 2, 0, 3] }]
+        }"#,
+    ];
+
+    let mut suites = Vec::new();
+    for json in suite_jsons {
+        let suite: TestSuite = json5::from_str(json).expect("Parse suite");
+        let schema_json = serde_json::to_string(&serde_json::from_str::<serde_json::Value>(json).unwrap()["schema"]).unwrap();
+        let code = generate_rust_code(&schema_json, &suite.test_type).expect("Generate code");
+        suites.push((suite, code));
+    }
+
+    let temp_dir = tempfile::tempdir().expect("Create temp dir");
+    write_batch_crate(temp_dir.path(), &suites);
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Run cargo run");
+    assert!(output.status.success(), "batch crate failed:\n{}", String::from_utf8_lossy(&output.stderr));
+
+    let results: Vec<Vec<TestResult>> = serde_json::from_slice(&output.stdout).expect("Parse results");
+    assert_eq!(results.len(), 2);
+    for result in results.iter().flatten() {
+        assert!(result.pass, "{}: {:?}", result.description, result.error);
+    }
+}
+
+/// Writes `schema_json` to a temp dir and runs `binschema generate --language
+/// rust` on it with `flags`. Returns the temp dir (the crate lives as long as
+/// it does) and the generated crate's directory.
+fn generate_crate(schema_json: &str, flags: &[&str]) -> (tempfile::TempDir, PathBuf) {
+    let temp_dir = tempfile::tempdir().expect("Create temp dir");
+    let schema_path = temp_dir.path().join("schema.json");
+    fs::write(&schema_path, schema_json).expect("Write schema");
+    let out_dir = temp_dir.path().join("out");
+
+    let output = Command::new("bun")
+        .args(["run", "src/cli/index.ts", "generate", "--language", "rust"])
+        .args(flags)
+        .arg("--schema")
+        .arg(&schema_path)
+        .arg("--out")
+        .arg(&out_dir)
+        .current_dir("../packages/binschema")
+        .output()
+        .expect("Run binschema CLI");
+    assert!(output.status.success(), "CLI failed: {}", String::from_utf8_lossy(&output.stderr));
+    (temp_dir, out_dir)
+}
+
+/// Generates a crate from `schema_json`, adds `test_src` to it as
+/// `tests/<test_file>` and runs its tests. Skipped unless RUST_TESTS is set,
+/// since each call compiles a crate.
+fn run_generated_crate_test(label: &str, schema_json: &str, test_file: &str, test_src: &str) {
+    run_generated_crate_test_with_features(label, schema_json, test_file, test_src, &[]);
+}
+
+/// `run_generated_crate_test` with extra cargo features enabled on the
+/// generated crate
+fn run_generated_crate_test_with_features(
+    label: &str,
+    schema_json: &str,
+    test_file: &str,
+    test_src: &str,
+    features: &[&str],
+) {
+    if std::env::var("RUST_TESTS").is_err() {
+        println!("Skipping {} test (set RUST_TESTS=1 to run)", label);
+        return;
+    }
+
+    let (_temp_dir, out_dir) = generate_crate(schema_json, &[]);
+    fs::create_dir_all(out_dir.join("tests")).expect("Create tests dir");
+    fs::write(out_dir.join("tests").join(test_file), test_src).expect("Write test file");
+
+    let mut cargo = Command::new("cargo");
+    cargo.args(["test", "--quiet"]);
+    if !features.is_empty() {
+        cargo.args(["--features", &features.join(",")]);
+    }
+    let output = cargo.current_dir(&out_dir).output().expect("Run cargo test");
+    assert!(
+        output.status.success(),
+        "{} test failed:\n{}{}",
+        label,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// `generate --modules` must produce a crate whose per-type modules import
+/// each other correctly (struct fields, array items, union variants and a
+/// back-reference target all cross module boundaries here).
+#[test]
+fn test_generate_to_modules_compiles() {
+    if std::env::var("RUST_TESTS").is_err() {
+        println!("Skipping multi-module compilation test (set RUST_TESTS=1 to run)");
+        return;
+    }
+
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "sequence": [
+                { "name": "tag", "type": "uint8", "const": 1 },
+                { "name": "text", "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "utf8" }
+            ] },
+            "LabelPointer": {
+                "type": "back_reference", "storage": "uint16", "offset_mask": "0x3FFF",
+                "offset_from": "message_start", "target_type": "Label"
+            },
+            "LabelRef": { "sequence": [
+                { "name": "tag", "type": "uint8", "const": 2 },
+                { "name": "target", "type": "LabelPointer" }
+            ] },
+            "Name": { "sequence": [
+                { "name": "labels", "type": "array", "kind": "length_prefixed", "length_type": "uint8",
+                  "items": { "type": "choice", "choices": [{ "type": "Label" }, { "type": "LabelRef" }] } }
+            ] },
+            "Question": { "sequence": [
+                { "name": "qname", "type": "Name" },
+                { "name": "qtype", "type": "uint16" }
+            ] },
+            "Message": { "sequence": [
+                { "name": "id", "type": "uint16" },
+                { "name": "count", "type": "uint8", "computed": { "type": "length_of", "target": "questions" } },
+                { "name": "questions", "type": "array", "kind": "field_referenced", "length_field": "count",
+                  "items": { "type": "Question" } }
+            ] }
+        }
+    }"#;
+
+    let (_temp_dir, out_dir) = generate_crate(schema_json, &["--modules"]);
+
+    let module_dir = out_dir.join("src").join("generated");
+    let message = fs::read_to_string(module_dir.join("message.rs")).expect("Read message.rs");
+    assert!(message.contains("use super::question::"), "message.rs does not import Question:\n{}", message);
+    assert!(!out_dir.join("src").join("generated.rs").exists());
+
+    let output = Command::new("cargo")
+        .args(["build", "--quiet"])
+        .current_dir(&out_dir)
+        .output()
+        .expect("Run cargo build");
+    assert!(output.status.success(), "Multi-module crate failed to compile:\n{}", String::from_utf8_lossy(&output.stderr));
+}
+
+/// Generates a union keyed on (version, msg_type) and checks which variant
+/// each combination selects, including one that has no variant.
+#[test]
+fn test_multi_field_discriminator_selection() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Ping": { "sequence": [{ "name": "nonce", "type": "uint16" }] },
+            "Data": { "sequence": [{ "name": "value", "type": "uint8" }] },
+            "DataV2": { "sequence": [{ "name": "value", "type": "uint32" }] },
+            "Message": { "sequence": [
+                { "name": "version", "type": "uint8" },
+                { "name": "msg_type", "type": "uint8" },
+                { "name": "body", "type": "discriminated_union",
+                  "discriminator": { "fields": ["version", "msg_type"] },
+                  "variants": [
+                      { "values": [1, 1], "type": "Ping" },
+                      { "values": [2, 1], "type": "Ping" },
+                      { "values": [1, 2], "type": "Data" },
+                      { "values": [2, 2], "type": "DataV2" }
+                  ] }
+            ] }
+        }
+    }"#;
+
+    let selection_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn selects_variant_by_field_combination() {
+    let ping = Message::decode(&[1, 1, 0x12, 0x34]).unwrap();
+    assert!(matches!(ping.body, MessageBody::Ping(Ping { nonce: 0x1234 })));
+    let ping_v2 = Message::decode(&[2, 1, 0x00, 0x07]).unwrap();
+    assert!(matches!(ping_v2.body, MessageBody::Ping(Ping { nonce: 7 })));
+    let data = Message::decode(&[1, 2, 0x2A]).unwrap();
+    assert!(matches!(data.body, MessageBody::Data(Data { value: 42 })));
+    let data_v2 = Message::decode(&[2, 2, 0, 0, 1, 0]).unwrap();
+    assert!(matches!(data_v2.body, MessageBody::DataV2(DataV2 { value: 256 })));
+}
+
+#[test]
+fn rejects_unmapped_combination() {
+    let err = Message::decode(&[3, 1, 0, 0]).unwrap_err();
+    assert!(matches!(err, binschema_runtime::BinSchemaError::InvalidValue(_)), "{:?}", err);
+}
+"#;
+
+    run_generated_crate_test("multi-field discriminator", schema_json, "selection.rs", selection_test);
+}
+
+/// Generates a type with `endianness_marker` and checks that big- and
+/// little-endian serializations of one message decode to the same value.
+#[test]
+fn test_decode_autodetect_endianness() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Point": { "sequence": [
+                { "name": "x", "type": "int16" },
+                { "name": "y", "type": "int16" }
+            ] },
+            "Record": {
+                "endianness_marker": "magic",
+                "sequence": [
+                    { "name": "magic", "type": "uint32", "const": 305419896 },
+                    { "name": "timestamp", "type": "uint64" },
+                    { "name": "count", "type": "uint16", "computed": { "type": "length_of", "target": "points" } },
+                    { "name": "points", "type": "array", "kind": "field_referenced", "length_field": "count",
+                      "items": { "type": "Point" } }
+                ]
+            }
+        }
+    }"#;
+
+    let autodetect_test = r#"
+use binschema_generated::generated::*;
+
+const BIG_ENDIAN: &[u8] = &[
+    0x12, 0x34, 0x56, 0x78,
+    0x00, 0x00, 0x01, 0x8B, 0xCF, 0xE5, 0x68, 0x00,
+    0x00, 0x02,
+    0x00, 0x01, 0xFF, 0xFE,
+    0x01, 0x00, 0x00, 0x03,
+];
+
+const LITTLE_ENDIAN: &[u8] = &[
+    0x78, 0x56, 0x34, 0x12,
+    0x00, 0x68, 0xE5, 0xCF, 0x8B, 0x01, 0x00, 0x00,
+    0x02, 0x00,
+    0x01, 0x00, 0xFE, 0xFF,
+    0x00, 0x01, 0x03, 0x00,
+];
+
+#[test]
+fn both_byte_orders_decode_to_the_same_value() {
+    let big = Record::decode_autodetect_endianness(BIG_ENDIAN).unwrap();
+    let little = Record::decode_autodetect_endianness(LITTLE_ENDIAN).unwrap();
+    assert_eq!(big, little);
+    assert_eq!(big.timestamp, 1_700_000_000_000);
+    assert_eq!(big.points, vec![Point { x: 1, y: -2 }, Point { x: 256, y: 3 }]);
+    // The schema's own byte order is unaffected
+    assert_eq!(Record::decode(BIG_ENDIAN).unwrap(), big);
+    assert!(Record::decode(LITTLE_ENDIAN).is_err());
+}
+
+#[test]
+fn unknown_marker_is_rejected() {
+    let mut bytes = BIG_ENDIAN.to_vec();
+    bytes[0] = 0xFF;
+    assert!(Record::decode_autodetect_endianness(&bytes).is_err());
+}
+"#;
+
+    run_generated_crate_test("endianness autodetection", schema_json, "autodetect.rs", autodetect_test);
+}
+
+#[test]
+fn test_repeated_inline_group() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Mesh": {
+                "sequence": [
+                    { "name": "id", "type": "uint8" },
+                    { "name": "coords", "type": "group", "repeat": 3, "sequence": [
+                        { "name": "tag", "type": "uint8", "const": 7 },
+                        { "name": "x", "type": "int16" },
+                        { "name": "y", "type": "int16" },
+                        { "name": "z", "type": "int16" }
+                    ] },
+                    { "name": "flags", "type": "uint8" }
+                ]
+            }
+        }
+    }"#;
+
+    let group_test = r#"
+use binschema_generated::generated::*;
+
+fn coord(x: i16, y: i16, z: i16) -> MeshCoordsInput {
+    MeshCoordsInput { x, y, z }
+}
+
+#[test]
+fn group_round_trips_as_fixed_array() {
+    let input = MeshInput {
+        id: 9,
+        coords: [coord(1, -1, 2), coord(3, 4, 5), coord(-6, 7, 8)],
+        flags: 0xA5,
+    };
+    let bytes = input.encode().unwrap();
+    assert_eq!(bytes.len(), 1 + 3 * 7 + 1);
+    assert_eq!(&bytes[..8], &[9, 7, 0x00, 0x01, 0xFF, 0xFF, 0x00, 0x02]);
+    assert_eq!(bytes[bytes.len() - 1], 0xA5);
+
+    let decoded = MeshOutput::decode(&bytes).unwrap();
+    let coords: &[MeshCoordsOutput; 3] = &decoded.coords;
+    assert_eq!((coords[0].x, coords[0].y, coords[0].z), (1, -1, 2));
+    assert_eq!((coords[2].x, coords[2].y, coords[2].z), (-6, 7, 8));
+    assert_eq!(coords[1].tag, 7);
+    assert_eq!(decoded.flags, 0xA5);
+
+    assert_eq!(MeshInput::from(decoded).encode().unwrap(), bytes);
+}
+
+#[test]
+fn short_input_is_rejected() {
+    let bytes = [9, 7, 0, 1, 0, 2, 0, 3, 7, 0, 4];
+    assert!(MeshOutput::decode(&bytes).is_err());
+}
+"#;
+
+    run_generated_crate_test("repeated inline group", schema_json, "group.rs", group_test);
+}
+
+#[test]
+fn test_follow_pointers_splices_labels() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" },
+            "LabelPointer": { "type": "back_reference", "storage": "uint16", "endianness": "big_endian",
+                              "offset_mask": "0x3FFF", "offset_from": "message_start", "target_type": "Label" },
+            "CompressedLabel": {
+                "type": "discriminated_union",
+                "discriminator": { "peek": "uint8" },
+                "variants": [
+                    { "type": "Label", "when": "value < 0xC0" },
+                    { "type": "LabelPointer", "when": "value >= 0xC0" }
+                ]
+            },
+            "DomainName": {
+                "type": "array", "kind": "null_terminated", "items": { "type": "CompressedLabel" },
+                "terminal_variants": ["LabelPointer"], "follow_pointers": true
+            },
+            "Names": {
+                "sequence": [
+                    { "name": "origin", "type": "DomainName" },
+                    { "name": "host", "type": "DomainName" },
+                    { "name": "ttl", "type": "uint8" }
+                ]
+            }
+        }
+    }"#;
+
+    let pointer_test = r#"
+use binschema_generated::generated::*;
+
+fn dotted(name: &DomainName) -> String {
+    name.value
+        .iter()
+        .map(|label| match label {
+            CompressedLabel::Label(l) => l.0.clone(),
+            CompressedLabel::LabelPointer(_) => panic!("pointers are followed, not stored"),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[test]
+fn literal_labels_then_pointer_reconstruct_full_name() {
+    let mut bytes = vec![7];
+    bytes.extend_from_slice(b"example");
+    bytes.push(3);
+    bytes.extend_from_slice(b"com");
+    bytes.push(0);
+    // "www" followed by a pointer to "example.com" at offset 0
+    bytes.push(3);
+    bytes.extend_from_slice(b"www");
+    bytes.extend_from_slice(&[0xC0, 0x00]);
+    bytes.push(60);
+
+    let names = Names::decode(&bytes).unwrap();
+    assert_eq!(dotted(&names.origin), "example.com");
+    assert_eq!(dotted(&names.host), "www.example.com");
+    // Decoding resumed right after the pointer
+    assert_eq!(names.ttl, 60);
+}
+
+#[test]
+fn pointer_loop_is_rejected() {
+    // "a" then a pointer back to itself
+    let bytes = [1, b'a', 0xC0, 0x00];
+    assert!(DomainName::decode(&bytes).is_err());
+}
+"#;
+
+    run_generated_crate_test("pointer-following label", schema_json, "pointers.rs", pointer_test);
+}
+
+#[test]
+fn test_decode_into_matches_decode() {
+    let schema_json = r#"{
+        "config": { "endianness": "little_endian" },
+        "types": {
+            "Vec3": { "sequence": [
+                { "name": "x", "type": "float32" },
+                { "name": "y", "type": "float32" },
+                { "name": "z", "type": "float32" }
+            ] },
+            "Sample": {
+                "decode_into": true,
+                "sequence": [
+                    { "name": "magic", "type": "uint16", "const": 43981 },
+                    { "name": "flags", "type": "bitfield", "size": 8, "fields": [
+                        { "name": "valid", "offset": 0, "size": 1 },
+                        { "name": "channel", "offset": 1, "size": 7 }
+                    ] },
+                    { "name": "timestamp", "type": "uint64" },
+                    { "name": "position", "type": "Vec3" }
+                ]
+            }
+        }
+    }"#;
+
+    let decode_into_test = r#"
+use binschema_generated::generated::*;
+
+fn sample_bytes(timestamp: u64, x: f32) -> Vec<u8> {
+    let mut bytes = vec![0xCD, 0xAB, 0x85];
+    bytes.extend_from_slice(&timestamp.to_le_bytes());
+    for v in [x, 2.5f32, -1.0f32] {
+        bytes.extend_from_slice(&v.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn decode_into_matches_decode() {
+    let first = sample_bytes(1, 0.5);
+    let second = sample_bytes(1_700_000_000, 8.25);
+
+    let mut out = SampleOutput::decode(&first).unwrap();
+    SampleOutput::decode_into(&second, &mut out).unwrap();
+    assert_eq!(out, SampleOutput::decode(&second).unwrap());
+    assert_eq!(out.timestamp, 1_700_000_000);
+    assert_eq!(out.position.x, 8.25);
+}
+
+#[test]
+fn decode_into_rejects_truncated_input() {
+    let bytes = sample_bytes(7, 1.0);
+    let mut out = SampleOutput::decode(&bytes).unwrap();
+    assert!(SampleOutput::decode_into(&bytes[..bytes.len() - 1], &mut out).is_err());
+}
+"#;
+
+    run_generated_crate_test("decode_into", schema_json, "decode_into.rs", decode_into_test);
+}
+
+#[test]
+fn test_encode_length_limits() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "sequence": [
+                { "name": "text", "type": "string", "kind": "length_prefixed", "length_type": "uint8",
+                  "encoding": "ascii", "max_length": 63 }
+            ] },
+            "Name": { "sequence": [
+                { "name": "labels", "type": "array", "kind": "null_terminated",
+                  "items": { "type": "Label" }, "max_encoded_length": 255 }
+            ] },
+            "Note": { "sequence": [
+                { "name": "text", "type": "string", "kind": "length_prefixed", "length_type": "uint8" }
+            ] }
+        }
+    }"#;
+
+    let limits_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::BinSchemaError;
+
+fn label(len: usize) -> Label {
+    Label { text: "a".repeat(len) }
+}
+
+#[test]
+fn label_of_63_bytes_encodes() {
+    let bytes = label(63).encode().unwrap();
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(bytes[0], 63);
+}
+
+#[test]
+fn label_of_64_bytes_is_rejected() {
+    assert!(matches!(label(64).encode(), Err(BinSchemaError::InvalidValue(_))));
+}
+
+#[test]
+fn name_over_255_bytes_is_rejected() {
+    // Three full labels fit (3 * 64 + 1 = 193 bytes), four do not (257)
+    let fits = Name { labels: (0..3).map(|_| label(63)).collect() };
+    assert_eq!(fits.encode().unwrap().len(), 193);
+    let too_long = Name { labels: (0..4).map(|_| label(63)).collect() };
+    assert!(matches!(too_long.encode(), Err(BinSchemaError::InvalidValue(_))));
+}
+
+#[test]
+fn length_prefix_never_truncates() {
+    let note = Note { text: "a".repeat(256) };
+    assert!(matches!(note.encode(), Err(BinSchemaError::InvalidValue(_))));
+}
+"#;
+
+    run_generated_crate_test("encode length limit", schema_json, "length_limits.rs", limits_test);
+}
+
+#[test]
+fn test_generated_roundtrip_tests_pass() {
+    if std::env::var("RUST_TESTS").is_err() {
+        println!("Skipping roundtrip test generation test (set RUST_TESTS=1 to run)");
+        return;
+    }
+
+    let schema_json = r#"{
+        "config": { "endianness": "little_endian" },
+        "types": {
+            "Color": { "type": "enum", "repr": "uint8", "variants": { "Red": 1, "Green": 2, "Blue": 4 } },
+            "Point": { "sequence": [
+                { "name": "x", "type": "int32" },
+                { "name": "y", "type": "uint64" },
+                { "name": "weight", "type": "float64" },
+                { "name": "visible", "type": "bool" },
+                { "name": "color", "type": "Color" }
+            ] },
+            "Shape": { "sequence": [
+                { "name": "name", "type": "string", "kind": "length_prefixed", "length_type": "uint8" },
+                { "name": "tag", "type": "string", "kind": "null_terminated", "encoding": "ascii" },
+                { "name": "corners", "type": "array", "kind": "fixed", "length": 3, "items": { "type": "uint16" } },
+                { "name": "points", "type": "array", "kind": "length_prefixed", "length_type": "uint16",
+                  "items": { "type": "Point" } }
+            ] }
+        }
+    }"#;
+
+    let (_temp_dir, out_dir) = generate_crate(schema_json, &["--roundtrip-tests"]);
+
+    let output = Command::new("cargo")
+        .args(["test", "roundtrips"])
+        .current_dir(&out_dir)
+        .output()
+        .expect("Run cargo test");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "generated roundtrip tests failed:\n{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for test in ["point_roundtrips", "shape_roundtrips"] {
+        assert!(stdout.contains(&format!("roundtrip_tests::{} ... ok", test)), "{} did not run:\n{}", test, stdout);
+    }
+}
+
+#[test]
+fn test_record_alignment_pads_between_elements() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Entry": { "sequence": [
+                { "name": "id", "type": "uint16" },
+                { "name": "flags", "type": "uint8" }
+            ] },
+            "Table": { "sequence": [
+                { "name": "entries", "type": "array", "kind": "length_prefixed", "length_type": "uint32",
+                  "items": { "type": "Entry" }, "record_alignment": 4 }
+            ] }
+        }
+    }"#;
+
+    let alignment_test = r#"
+use binschema_generated::generated::*;
+
+// Two 3-byte entries, each followed by 1 byte of padding to the next 4-byte boundary
+const TABLE_BYTES: &[u8] = &[
+    0x00, 0x00, 0x00, 0x02,
+    0x00, 0x01, 0x05, 0x00,
+    0x00, 0x02, 0x07, 0x00,
+];
+
+fn table() -> Table {
+    Table {
+        entries: vec![Entry { id: 1, flags: 5 }, Entry { id: 2, flags: 7 }],
+    }
+}
+
+#[test]
+fn encode_writes_inter_element_padding() {
+    assert_eq!(table().encode().unwrap(), TABLE_BYTES);
+}
+
+#[test]
+fn decode_skips_inter_element_padding() {
+    assert_eq!(Table::decode(TABLE_BYTES).unwrap(), table());
+
+    // Padding content is ignored
+    let mut dirty = TABLE_BYTES.to_vec();
+    dirty[7] = 0xFF;
+    dirty[11] = 0xFF;
+    assert_eq!(Table::decode(&dirty).unwrap(), table());
+}
+"#;
+
+    run_generated_crate_test("record_alignment", schema_json, "record_alignment.rs", alignment_test);
+}
+
+#[test]
+fn test_xor_masked_field_round_trips() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Masked": { "sequence": [
+                { "name": "key", "type": "uint16" },
+                { "name": "value", "type": "uint16" },
+                { "name": "masked", "type": "uint16", "computed": { "type": "xor_of", "targets": ["key", "value"] } },
+                { "name": "check", "type": "uint8", "computed": { "type": "complement_of", "target": "key" } }
+            ] }
+        }
+    }"#;
+
+    let xor_test = r#"
+use binschema_generated::generated::*;
+
+// key 0x5AA5, value 0x1234, masked = key ^ value, check = !key truncated to a byte
+const MASKED_BYTES: &[u8] = &[0x5A, 0xA5, 0x12, 0x34, 0x48, 0x91, 0x5A];
+
+#[test]
+fn encode_writes_masked_bytes() {
+    let input = MaskedInput { key: 0x5AA5, value: 0x1234 };
+    assert_eq!(input.encode().unwrap(), MASKED_BYTES);
+}
+
+#[test]
+fn decode_recovers_value_and_checks_mask() {
+    let decoded = MaskedOutput::decode(MASKED_BYTES).unwrap();
+    assert_eq!((decoded.key, decoded.value, decoded.masked, decoded.check), (0x5AA5, 0x1234, 0x4891, 0x5A));
+    assert_eq!(decoded.masked ^ decoded.key, decoded.value);
+
+    let mut tampered = MASKED_BYTES.to_vec();
+    tampered[5] ^= 0x01;
+    assert!(MaskedOutput::decode(&tampered).is_err());
+
+    let mut tampered = MASKED_BYTES.to_vec();
+    tampered[6] = 0x00;
+    assert!(MaskedOutput::decode(&tampered).is_err());
+}
+"#;
+
+    run_generated_crate_test("xor_of", schema_json, "xor_of.rs", xor_test);
+}
+
+#[test]
+fn test_union_decode_reports_each_variant_failure() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Short": { "sequence": [
+                { "name": "tag", "type": "uint8", "const": 1 },
+                { "name": "v", "type": "uint8" }
+            ] },
+            "Long": { "sequence": [
+                { "name": "tag", "type": "uint8", "const": 2 },
+                { "name": "v", "type": "uint32" }
+            ] },
+            "Entry": { "sequence": [
+                { "name": "data", "type": "choice", "choices": [{ "type": "Short" }, { "type": "Long" }] }
+            ] }
+        }
+    }"#;
+
+    let union_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::BinSchemaError;
+
+#[test]
+fn every_attempted_variant_is_named() {
+    // Tag 3 matches neither variant's const tag
+    let err = EntryData::decode(&[0x03, 0x00, 0x00, 0x00, 0x00]).unwrap_err();
+    let BinSchemaError::InvalidValue(msg) = &err else { panic!("unexpected error {:?}", err) };
+    assert!(msg.contains("EntryData"), "{}", msg);
+    assert!(msg.contains("Short: Invalid variant discriminator: 3"), "{}", msg);
+    assert!(msg.contains("Long: Invalid variant discriminator: 3"), "{}", msg);
+}
+
+#[test]
+fn truncated_input_is_still_end_of_input() {
+    assert_eq!(EntryData::decode(&[0x02, 0x00]).unwrap_err(), BinSchemaError::UnexpectedEof);
+}
+"#;
+
+    run_generated_crate_test("union error", schema_json, "union_errors.rs", union_test);
+}
+
+#[test]
+fn test_bit_length_prefixed_array_packs_items() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian", "bit_order": "msb_first" },
+        "types": {
+            "Packed": { "sequence": [
+                { "name": "values", "type": "array", "kind": "bit_length_prefixed", "length_bits": 7,
+                  "items": { "type": "bit", "size": 5 } }
+            ] }
+        }
+    }"#;
+
+    let packing_test = r#"
+use binschema_generated::generated::*;
+
+// 7-bit prefix 25 (five 5-bit items), then 1, 2, 3, 4, 31:
+// 0011001 00001 00010 00011 00100 11111
+const PACKED_BYTES: &[u8] = &[0x32, 0x11, 0x0C, 0x9F];
+
+#[test]
+fn encode_packs_prefix_and_items() {
+    let packed = Packed { values: vec![1, 2, 3, 4, 31] };
+    assert_eq!(packed.encode().unwrap(), PACKED_BYTES);
+}
+
+#[test]
+fn decode_reads_count_from_bit_length() {
+    assert_eq!(Packed::decode(PACKED_BYTES).unwrap().values, vec![1, 2, 3, 4, 31]);
+}
+
+#[test]
+fn bit_length_must_be_whole_items() {
+    // Prefix 24 isn't a multiple of 5
+    assert!(Packed::decode(&[0x30, 0x00, 0x00, 0x00]).is_err());
+}
+
+#[test]
+fn bit_length_must_fit_the_prefix() {
+    // 26 items are 130 bits, past the 127 a 7-bit prefix holds
+    assert!(Packed { values: vec![0; 25] }.encode().is_ok());
+    assert!(Packed { values: vec![0; 26] }.encode().is_err());
+}
+"#;
+
+    run_generated_crate_test("bit_length_prefixed", schema_json, "bit_length_prefixed.rs", packing_test);
+}
+
+#[test]
+fn test_countdown_array_shared_across_variants() {
+    // Run-length coded pixels: pixel_count is shared by literals (one pixel
+    // each) and runs (count pixels each)
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Literal": { "sequence": [{ "name": "value", "type": "uint8" }] },
+            "Run": { "sequence": [
+                { "name": "flag", "type": "uint8" },
+                { "name": "count", "type": "uint8" },
+                { "name": "value", "type": "uint8" }
+            ] },
+            "Chunk": {
+                "type": "discriminated_union",
+                "discriminator": { "peek": "uint8" },
+                "variants": [
+                    { "type": "Literal", "when": "value < 0x80" },
+                    { "type": "Run", "when": "value >= 0x80" }
+                ]
+            },
+            "Image": { "sequence": [
+                { "name": "pixel_count", "type": "uint16" },
+                { "name": "chunks", "type": "array", "kind": "countdown", "length_field": "pixel_count",
+                  "countdown_by": "count", "items": { "type": "Chunk" } }
+            ] }
+        }
+    }"#;
+
+    let countdown_test = r#"
+use binschema_generated::generated::*;
+
+// 6 pixels: literal 5, a run of four 9s, literal 7
+const IMAGE_BYTES: &[u8] = &[0x00, 0x06, 0x05, 0x80, 0x04, 0x09, 0x07];
+
+fn image() -> Image {
+    Image {
+        pixel_count: 6,
+        chunks: vec![
+            Chunk::Literal(Literal { value: 5 }),
+            Chunk::Run(Run { flag: 0x80, count: 4, value: 9 }),
+            Chunk::Literal(Literal { value: 7 }),
+        ],
+    }
+}
+
+#[test]
+fn encode_writes_chunks() {
+    assert_eq!(image().encode().unwrap(), IMAGE_BYTES);
+}
+
+#[test]
+fn decode_stops_when_countdown_reaches_zero() {
+    assert_eq!(Image::decode(IMAGE_BYTES).unwrap(), image());
+
+    // With 5 pixels the run finishes the image; the last byte isn't read
+    let mut short = IMAGE_BYTES.to_vec();
+    short[1] = 5;
+    assert_eq!(Image::decode(&short).unwrap().chunks.len(), 2);
+}
+
+#[test]
+fn decode_rejects_overshoot_and_empty_runs() {
+    // The 4-pixel run overshoots the 2 pixels left of 3
+    let mut overshoot = IMAGE_BYTES.to_vec();
+    overshoot[1] = 3;
+    assert!(Image::decode(&overshoot).is_err());
+
+    // A zero-length run would never finish the countdown
+    let mut empty_run = IMAGE_BYTES.to_vec();
+    empty_run[4] = 0;
+    assert!(Image::decode(&empty_run).is_err());
+}
+
+#[test]
+fn encode_rejects_chunks_that_miss_the_count() {
+    let mut wrong = image();
+    wrong.pixel_count = 7;
+    assert!(wrong.encode().is_err());
+}
+"#;
+
+    run_generated_crate_test("countdown", schema_json, "countdown.rs", countdown_test);
+}
+
+#[test]
+fn test_newtype_fields_are_distinct_types() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Soa": { "sequence": [
+                { "name": "serial", "type": "uint32", "newtype": "Serial" },
+                { "name": "refresh", "type": "uint32", "newtype": "Ttl" },
+                { "name": "retry", "type": "uint32", "newtype": "Ttl" }
+            ] }
+        }
+    }"#;
+
+    let newtype_test = r#"
+use binschema_generated::generated::*;
+use std::any::TypeId;
+
+const SOA_BYTES: &[u8] = &[
+    0x78, 0x56, 0x34, 0x12,
+    0x00, 0x00, 0x0E, 0x10,
+    0x00, 0x00, 0x03, 0x84,
+];
+
+fn soa() -> Soa {
+    Soa { serial: Serial(0x7856_3412), refresh: Ttl(3600), retry: Ttl(900) }
+}
+
+#[test]
+fn newtypes_are_distinct_wrappers() {
+    assert_ne!(TypeId::of::<Serial>(), TypeId::of::<Ttl>());
+    assert_eq!(std::mem::size_of::<Ttl>(), std::mem::size_of::<u32>());
+    let ttl: Ttl = 60u32.into();
+    assert_eq!(u32::from(ttl), 60);
+}
+
+#[test]
+fn newtype_fields_round_trip_as_integers() {
+    assert_eq!(soa().encode().unwrap(), SOA_BYTES);
+    let decoded = Soa::decode(SOA_BYTES).unwrap();
+    assert_eq!(decoded, soa());
+    assert!(decoded.retry < decoded.refresh);
+}
+"#;
+
+    run_generated_crate_test("newtype", schema_json, "newtype.rs", newtype_test);
+}
+
+#[test]
+fn test_unknown_enum_values_error_or_keep_raw() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Opcode": { "type": "enum", "repr": "uint8", "variants": { "Query": 0, "Notify": 4 } },
+            "Rcode": { "type": "enum", "repr": "uint16", "on_unknown_enum": "keep_raw",
+                "variants": { "NoError": 0, "ServFail": 2 } },
+            "Reply": { "sequence": [
+                { "name": "opcode", "type": "Opcode" },
+                { "name": "rcode", "type": "Rcode" }
+            ] }
+        }
+    }"#;
+
+    let unknown_enum_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn declared_values_decode_to_variants() {
+    let reply = Reply::decode(&[0x04, 0x00, 0x02]).unwrap();
+    assert_eq!(reply, Reply { opcode: Opcode::Notify, rcode: Rcode::ServFail });
+}
+
+#[test]
+fn unknown_value_is_an_error_by_default() {
+    assert_eq!(Opcode::decode(&[0x07]), Err(binschema_runtime::BinSchemaError::InvalidVariant(7)));
+    assert!(Reply::decode(&[0x07, 0x00, 0x00]).is_err());
+}
+
+#[test]
+fn unknown_value_round_trips_as_raw() {
+    let bytes = [0x00, 0x00, 0x17];
+    let reply = Reply::decode(&bytes).unwrap();
+    assert_eq!(reply.rcode, Rcode::Raw(23));
+    assert_eq!(reply.rcode.value(), 23);
+    assert_eq!(reply.encode().unwrap(), bytes);
+}
+
+#[test]
+fn raw_value_too_wide_for_repr_fails_to_encode() {
+    assert!(Rcode::Raw(0x1_0000).encode().is_err());
+}
+"#;
+
+    run_generated_crate_test("unknown enum", schema_json, "unknown_enum.rs", unknown_enum_test);
+}
+
+#[test]
+fn test_delta_zigzag_array_round_trips() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Series": { "sequence": [
+                { "name": "timestamps", "type": "array", "kind": "length_prefixed", "length_type": "uint8",
+                  "encoding": "delta_zigzag", "items": { "type": "uint32" } }
+            ] }
+        }
+    }"#;
+
+    let delta_test = r#"
+use binschema_generated::generated::*;
+
+// Count, first value in full, then zigzag deltas +1 +2 +7 0 -5
+const SERIES_BYTES: &[u8] = &[0x06, 0x00, 0x00, 0x03, 0xE8, 0x02, 0x04, 0x0E, 0x00, 0x09];
+
+fn series() -> Series {
+    Series { timestamps: vec![1000, 1001, 1003, 1010, 1010, 1005] }
+}
+
+#[test]
+fn increasing_sequence_encodes_compactly() {
+    let bytes = series().encode().unwrap();
+    assert_eq!(bytes, SERIES_BYTES);
+    assert!(bytes.len() < 1 + 4 * series().timestamps.len());
+}
+
+#[test]
+fn deltas_accumulate_back_to_the_values() {
+    assert_eq!(Series::decode(SERIES_BYTES).unwrap(), series());
+    let empty = Series { timestamps: vec![] };
+    assert_eq!(Series::decode(&empty.encode().unwrap()).unwrap(), empty);
+}
+
+#[test]
+fn delta_below_zero_is_rejected() {
+    // 1 followed by a delta of -3
+    assert!(Series::decode(&[0x02, 0x00, 0x00, 0x00, 0x01, 0x05]).is_err());
+}
+"#;
+
+    run_generated_crate_test("delta_zigzag", schema_json, "delta_zigzag.rs", delta_test);
+}
+
+#[test]
+fn test_flagged_field_when_truncated() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Hello": { "sequence": [
+                { "name": "flags", "type": "uint8" },
+                { "name": "extensions", "type": "uint16", "conditional": "flags & 0x01", "when_truncated": "absent" }
+            ] },
+            "StrictHello": { "sequence": [
+                { "name": "flags", "type": "uint8" },
+                { "name": "extensions", "type": "uint16", "conditional": "flags & 0x01", "when_truncated": "error" }
+            ] }
+        }
+    }"#;
+
+    let truncated_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn flag_set_and_field_present() {
+    let hello = Hello::decode(&[0x01, 0x00, 0x05]).unwrap();
+    assert_eq!(hello.extensions, Some(5));
+    assert_eq!(StrictHello::decode(&[0x01, 0x00, 0x05]).unwrap().extensions, Some(5));
+}
+
+#[test]
+fn flag_set_but_truncated_is_absent_when_lenient() {
+    let hello = Hello::decode(&[0x01]).unwrap();
+    assert_eq!(hello, Hello { flags: 0x01, extensions: None });
+    // Re-encoding keeps the message as short as it arrived
+    assert_eq!(hello.encode().unwrap(), vec![0x01]);
+    assert_eq!(Hello::decode(&[0x01, 0x00]).unwrap().extensions, None);
+}
+
+#[test]
+fn flag_set_but_truncated_is_an_error_when_strict() {
+    assert!(matches!(StrictHello::decode(&[0x01]), Err(binschema_runtime::BinSchemaError::UnexpectedEof)));
+    assert!(matches!(StrictHello::decode(&[0x01, 0x00]), Err(binschema_runtime::BinSchemaError::UnexpectedEof)));
+    assert_eq!(StrictHello::decode(&[0x00]).unwrap().extensions, None);
+}
+"#;
+
+    run_generated_crate_test("when_truncated", schema_json, "when_truncated.rs", truncated_test);
+}
+
+#[test]
+fn test_builder_setters_reject_bad_values_immediately() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Header": { "builder": true, "sequence": [
+                { "name": "version", "type": "bit", "size": 4 },
+                { "name": "priority", "type": "bit", "size": 4 },
+                { "name": "name", "type": "string", "kind": "length_prefixed", "length_type": "uint8", "max_length": 8 },
+                { "name": "options", "type": "uint16", "conditional": "version > 4" }
+            ] }
+        }
+    }"#;
+
+    let builder_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::BinSchemaError;
+
+#[test]
+fn out_of_range_value_fails_at_its_setter() {
+    let err = Header::builder().with_version(16).unwrap_err();
+    assert!(matches!(&err, BinSchemaError::InvalidValue(m) if m.contains("Header.version")), "{:?}", err);
+    assert!(Header::builder().with_version(15).is_ok());
+    assert!(Header::builder().with_name("much too long".to_string()).is_err());
+}
+
+#[test]
+fn build_requires_every_unconditional_field() {
+    let partial = Header::builder().with_version(4).unwrap().with_priority(1).unwrap();
+    let err = partial.build().unwrap_err();
+    assert!(matches!(&err, BinSchemaError::InvalidValue(m) if m.contains("Header.name")), "{:?}", err);
+}
+
+#[test]
+fn built_value_round_trips() {
+    let header = Header::builder()
+        .with_version(5).unwrap()
+        .with_priority(2).unwrap()
+        .with_name("eth0".to_string()).unwrap()
+        .with_options(0x0102).unwrap()
+        .build()
+        .unwrap();
+    let bytes = header.encode().unwrap();
+    assert_eq!(bytes, vec![0x52, 0x04, b'e', b't', b'h', b'0', 0x01, 0x02]);
+    assert_eq!(Header::decode(&bytes).unwrap(), header);
+}
+"#;
+
+    run_generated_crate_test("builder", schema_json, "builder.rs", builder_test);
+}
+
+#[test]
+fn test_uuid_guid_and_rfc4122_layouts_round_trip() {
+    let schema_json = r#"{
+        "config": { "endianness": "little_endian" },
+        "types": {
+            "Ids": { "sequence": [
+                { "name": "guid", "type": "uuid", "layout": "guid" },
+                { "name": "uuid", "type": "uuid", "layout": "rfc4122" }
+            ] }
+        }
+    }"#;
+
+    let uuid_test = r#"
+use binschema_generated::generated::*;
+
+// EFI system partition type: C12A7328-F81F-11D2-BA4B-00A0C93EC93B
+const ESP: [u8; 16] = [
+    0xC1, 0x2A, 0x73, 0x28, 0xF8, 0x1F, 0x11, 0xD2,
+    0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B,
+];
+
+#[test]
+fn layouts_arrange_the_same_uuid_differently() {
+    let ids = Ids { guid: ESP, uuid: ESP };
+    let bytes = ids.encode().unwrap();
+    assert_eq!(&bytes[..16], &[
+        0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11,
+        0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B,
+    ]);
+    assert_eq!(&bytes[16..], &ESP);
+    assert_eq!(Ids::decode(&bytes).unwrap(), ids);
+}
+"#;
+
+    run_generated_crate_test("uuid", schema_json, "uuid.rs", uuid_test);
+}
+
+#[test]
+fn test_offset_binary_field_round_trips() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Sample": { "sequence": [
+                { "name": "level", "type": "uint8", "signed_repr": "offset_binary", "bias": 128 }
+            ] }
+        }
+    }"#;
+
+    let offset_binary_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn extremes_and_zero_store_as_biased_bytes() {
+    for (level, byte) in [(-128i8, 0x00u8), (0, 0x80), (127, 0xFF)] {
+        let sample = Sample { level };
+        let bytes = sample.encode().unwrap();
+        assert_eq!(bytes, vec![byte]);
+        assert_eq!(Sample::decode(&bytes).unwrap(), sample);
+    }
+}
+"#;
+
+    run_generated_crate_test("offset binary", schema_json, "offset_binary.rs", offset_binary_test);
+}
+
+#[test]
+fn test_message_bytes_extract_and_reembed() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Inner": { "sequence": [
+                { "name": "tag", "type": "uint16" },
+                { "name": "value", "type": "uint8" }
+            ] },
+            "Envelope": { "sequence": [
+                { "name": "version", "type": "uint8" },
+                { "name": "inner_len", "type": "uint16", "computed": { "type": "length_of", "target": "payload" } },
+                { "name": "payload", "type": "message_bytes", "length_field": "inner_len" },
+                { "name": "trailer", "type": "uint8" }
+            ] }
+        }
+    }"#;
+
+    let message_bytes_test = r#"
+use binschema_generated::generated::*;
+
+const WRAPPED: [u8; 7] = [0x01, 0x00, 0x03, 0xCA, 0xFE, 0x2A, 0xFF];
+
+#[test]
+fn extracts_payload_without_decoding_it() {
+    let envelope = EnvelopeOutput::decode(&WRAPPED).unwrap();
+    assert_eq!(envelope.inner_len, 3);
+    assert_eq!(envelope.payload, vec![0xCA, 0xFE, 0x2A]);
+    assert_eq!(envelope.trailer, 0xFF);
+
+    // The extracted bytes parse with a schema the envelope knows nothing about
+    let inner = Inner::decode(&envelope.payload).unwrap();
+    assert_eq!((inner.tag, inner.value), (0xCAFE, 42));
+}
+
+#[test]
+fn reembeds_payload_with_its_length() {
+    let payload = Inner { tag: 0xCAFE, value: 42 }.encode().unwrap();
+    let envelope = EnvelopeInput { version: 1, payload, trailer: 0xFF };
+    assert_eq!(envelope.encode().unwrap(), WRAPPED.to_vec());
+
+    let empty = EnvelopeInput { version: 1, payload: Vec::new(), trailer: 0xFF };
+    assert_eq!(empty.encode().unwrap(), vec![0x01, 0x00, 0x00, 0xFF]);
+}
+
+#[test]
+fn rejects_payload_longer_than_input() {
+    assert!(EnvelopeOutput::decode(&[0x01, 0x00, 0x05, 0xCA, 0xFE]).is_err());
+}
+"#;
+
+    run_generated_crate_test("message bytes", schema_json, "message_bytes.rs", message_bytes_test);
+}
+
+#[test]
+fn test_decode_limits_each_trigger() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" },
+            "LabelPointer": {
+                "type": "back_reference", "storage": "uint16", "offset_mask": "0x3FFF",
+                "offset_from": "message_start", "target_type": "Label"
+            },
+            "CompressedLabel": {
+                "type": "discriminated_union", "discriminator": { "peek": "uint8" },
+                "variants": [
+                    { "type": "Label", "when": "value < 0xC0" },
+                    { "type": "LabelPointer", "when": "value >= 0xC0" }
+                ]
+            },
+            "DomainName": {
+                "type": "array", "kind": "null_terminated", "items": { "type": "CompressedLabel" },
+                "terminal_variants": ["LabelPointer"], "follow_pointers": true
+            },
+            "Query": { "sequence": [{ "name": "name", "type": "DomainName" }] },
+            "Node": { "sequence": [
+                { "name": "value", "type": "uint8" },
+                { "name": "children", "type": "array", "kind": "length_prefixed", "length_type": "uint8",
+                  "items": { "type": "Node" } }
+            ] },
+            "Blob": { "sequence": [
+                { "name": "data", "type": "bytes", "kind": "length_prefixed", "length_type": "uint32" }
+            ] }
+        }
+    }"#;
+
+    let limits_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::{BinSchemaError, DecodeLimits};
+
+fn exceeded<T: std::fmt::Debug>(result: Result<T, BinSchemaError>, limit: &str) {
+    match result {
+        Err(BinSchemaError::LimitExceeded(msg)) => assert!(msg.starts_with(limit), "{}", msg),
+        other => panic!("expected {} to be exceeded, got {:?}", limit, other),
+    }
+}
+
+/// A chain of `depth` nodes, each the only child of the one before
+fn chain(depth: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for i in 0..depth {
+        bytes.push(i as u8);
+        bytes.push(u8::from(i + 1 < depth));
+    }
+    bytes
+}
+
+fn blob(len: usize) -> Vec<u8> {
+    let mut bytes = (len as u32).to_be_bytes().to_vec();
+    bytes.resize(4 + len, 0xAB);
+    bytes
+}
+
+#[test]
+fn pointer_jumps() {
+    // Two pointers, then the label "a"
+    let bytes = [0xC0, 0x02, 0xC0, 0x04, 0x01, b'a', 0x00];
+    assert!(Query::decode_with_limits(&bytes, &DecodeLimits::default()).is_ok());
+    let limits = DecodeLimits { max_pointer_jumps: 1, ..DecodeLimits::default() };
+    exceeded(Query::decode_with_limits(&bytes, &limits), "max_pointer_jumps");
+}
+
+#[test]
+fn label_and_name_length() {
+    let bytes = [0x03, b'a', b'b', b'c', 0x00];
+    let fits = DecodeLimits { max_label_len: 4, max_name_len: 5, ..DecodeLimits::default() };
+    assert!(Query::decode_with_limits(&bytes, &fits).is_ok());
+    exceeded(Query::decode_with_limits(&bytes, &DecodeLimits { max_label_len: 3, ..fits }), "max_label_len");
+    exceeded(Query::decode_with_limits(&bytes, &DecodeLimits { max_name_len: 4, ..fits }), "max_name_len");
+}
+
+#[test]
+fn nesting_depth() {
+    let limits = DecodeLimits { max_depth: 4, ..DecodeLimits::default() };
+    assert!(Node::decode_with_limits(&chain(4), &limits).is_ok());
+    exceeded(Node::decode_with_limits(&chain(5), &limits), "max_depth");
+}
+
+#[test]
+fn allocation_and_message_size() {
+    let limits = DecodeLimits { max_alloc: 9, ..DecodeLimits::default() };
+    assert!(Blob::decode_with_limits(&blob(9), &limits).is_ok());
+    exceeded(Blob::decode_with_limits(&blob(10), &limits), "max_alloc");
+
+    let limits = DecodeLimits { max_message_len: 12, ..DecodeLimits::default() };
+    exceeded(Blob::decode_with_limits(&blob(10), &limits), "max_message_len");
+}
+
+#[test]
+fn unlimited_accepts_large_valid_input() {
+    let deep = chain(200);
+    exceeded(Node::decode_with_limits(&deep, &DecodeLimits::default()), "max_depth");
+    assert!(Node::decode_with_limits(&deep, &DecodeLimits::unlimited()).is_ok());
+
+    let big = blob(100_000);
+    let small = DecodeLimits { max_alloc: 65_536, ..DecodeLimits::default() };
+    exceeded(Blob::decode_with_limits(&big, &small), "max_alloc");
+    assert_eq!(Blob::decode_with_limits(&big, &DecodeLimits::unlimited()).unwrap().data.len(), 100_000);
+}
+"#;
+
+    run_generated_crate_test("decode limits", schema_json, "limits.rs", limits_test);
+}
+
+#[test]
+fn test_primitive_arrays_round_trip() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Record": { "sequence": [
+                { "name": "count", "type": "uint8" },
+                { "name": "raw", "type": "array", "kind": "length_prefixed", "length_type": "uint16", "items": { "type": "uint8" } },
+                { "name": "words", "type": "array", "kind": "length_prefixed", "length_type": "uint16", "items": { "type": "uint16" } },
+                { "name": "ids", "type": "array", "kind": "field_referenced", "length_field": "count", "items": { "type": "uint8" } }
+            ] }
+        }
+    }"#;
+
+    let primitive_arrays_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn prefixed_and_field_referenced_arrays_round_trip() {
+    let record = Record { count: 2, raw: vec![1, 2], words: vec![0x0102, 0x0304], ids: vec![9, 8] };
+    let bytes = record.encode().unwrap();
+    assert_eq!(bytes, vec![0x02, 0x00, 0x02, 0x01, 0x02, 0x00, 0x02, 0x01, 0x02, 0x03, 0x04, 0x09, 0x08]);
+    assert_eq!(Record::decode(&bytes).unwrap(), record);
+}
+
+#[test]
+fn empty_arrays_write_zero_prefixes() {
+    let record = Record { count: 0, raw: vec![], words: vec![], ids: vec![] };
+    let bytes = record.encode().unwrap();
+    assert_eq!(bytes, vec![0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(Record::decode(&bytes).unwrap(), record);
+}
+"#;
+
+    run_generated_crate_test("primitive array", schema_json, "primitive_arrays.rs", primitive_arrays_test);
+}
+
+#[test]
+fn test_nested_struct_references() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Inner": { "sequence": [
+                { "name": "a", "type": "uint8" },
+                { "name": "b", "type": "uint16" }
+            ] },
+            "Outer": { "sequence": [
+                { "name": "tag", "type": "uint8" },
+                { "name": "inner", "type": "Inner" }
+            ] },
+            "Chain": { "sequence": [
+                { "name": "value", "type": "uint8" },
+                { "name": "has_next", "type": "uint8" },
+                { "name": "next", "type": "Chain", "conditional": "has_next == 1" }
+            ] }
+        }
+    }"#;
+
+    let nested_structs_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn outer_embeds_inner_inline() {
+    let outer = Outer { tag: 7, inner: Inner { a: 1, b: 0x0203 } };
+    let bytes = outer.encode().unwrap();
+    assert_eq!(bytes, vec![0x07, 0x01, 0x02, 0x03]);
+    assert_eq!(Outer::decode(&bytes).unwrap(), outer);
+}
+
+#[test]
+fn self_reference_is_boxed() {
+    let tail = Chain { value: 2, has_next: 0, next: Box::new(None) };
+    let chain = Chain { value: 1, has_next: 1, next: Box::new(Some(tail)) };
+    let bytes = chain.encode().unwrap();
+    assert_eq!(bytes, vec![0x01, 0x01, 0x02, 0x00]);
+    assert_eq!(Chain::decode(&bytes).unwrap(), chain);
+}
+"#;
+
+    run_generated_crate_test("nested struct", schema_json, "nested_structs.rs", nested_structs_test);
+}
+
+#[test]
+fn test_string_fields_round_trip() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Labels": { "sequence": [
+                { "name": "name", "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "utf8" },
+                { "name": "title", "type": "string", "kind": "fixed", "length": 6, "encoding": "utf8" },
+                { "name": "tag", "type": "string", "kind": "null_terminated", "encoding": "utf8" }
+            ] }
+        }
+    }"#;
+
+    let string_fields_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::BinSchemaError;
+
+#[test]
+fn ascii_strings_in_each_kind() {
+    let labels = Labels { name: "host".to_string(), title: "abc".to_string(), tag: "x".to_string() };
+    let bytes = labels.encode().unwrap();
+    assert_eq!(bytes, b"\x04hostabc\0\0\0x\0".to_vec());
+    assert_eq!(Labels::decode(&bytes).unwrap(), labels);
+}
+
+#[test]
+fn multi_byte_utf8_counts_bytes() {
+    let labels = Labels { name: "h\u{e9}llo".to_string(), title: "\u{20ac}".to_string(), tag: "\u{e9}".to_string() };
+    let bytes = labels.encode().unwrap();
+    assert_eq!(bytes[0], 6);
+    assert_eq!(&bytes[7..13], &[0xE2, 0x82, 0xAC, 0, 0, 0]);
+    assert_eq!(Labels::decode(&bytes).unwrap(), labels);
+}
+
+#[test]
+fn invalid_utf8_is_rejected() {
+    let bytes = b"\x02\xC3\x28abc\0\0\0x\0";
+    assert_eq!(Labels::decode(bytes), Err(BinSchemaError::InvalidUtf8));
+}
+"#;
+
+    run_generated_crate_test("string field", schema_json, "string_fields.rs", string_fields_test);
+}
+
+#[test]
+fn test_lsb_first_bit_order_layout() {
+    let schema_json = r#"{
+        "config": { "endianness": "little_endian", "bit_order": "lsb_first" },
+        "types": {
+            "UsbFlags": { "sequence": [
+                { "name": "low", "type": "bit", "size": 3 },
+                { "name": "high", "type": "bit", "size": 5 }
+            ] }
+        }
+    }"#;
+
+    let lsb_first_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn first_field_fills_the_low_bits() {
+    let flags = UsbFlags { low: 0b101, high: 0b10011 };
+    let bytes = flags.encode().unwrap();
+    // MSB-first would give 0xB3
+    assert_eq!(bytes, vec![0x9D]);
+    assert_eq!(UsbFlags::decode(&bytes).unwrap(), flags);
+}
+"#;
+
+    run_generated_crate_test("lsb_first bit order", schema_json, "lsb_first.rs", lsb_first_test);
+}
+
+#[test]
+fn test_bitfield_struct_dns_flags_layout() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian", "bit_order": "msb_first" },
+        "types": {
+            "Header": { "sequence": [
+                { "name": "id", "type": "uint16" },
+                { "name": "flags", "type": "bitfield", "size": 16, "fields": [
+                    { "name": "qr", "offset": 0, "size": 1 },
+                    { "name": "opcode", "offset": 1, "size": 4 },
+                    { "name": "aa", "offset": 5, "size": 1 },
+                    { "name": "tc", "offset": 6, "size": 1 },
+                    { "name": "rd", "offset": 7, "size": 1 },
+                    { "name": "ra", "offset": 8, "size": 1 },
+                    { "name": "z", "offset": 9, "size": 3 },
+                    { "name": "rcode", "offset": 12, "size": 4 }
+                ] }
+            ] }
+        }
+    }"#;
+
+    let bitfield_flags_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn dns_flags_pack_into_two_bytes() {
+    let flags = HeaderFlags { qr: 1, opcode: 0, aa: 0, tc: 0, rd: 1, ra: 1, z: 0, rcode: 3 };
+    let header = Header { id: 0xBEEF, flags };
+    let bytes = header.encode().unwrap();
+    assert_eq!(bytes, vec![0xBE, 0xEF, 0x81, 0x83]);
+    assert_eq!(Header::decode(&bytes).unwrap(), header);
+}
+
+#[test]
+fn every_flags_bit_lands_in_place() {
+    let flags = HeaderFlags { qr: 0, opcode: 0xF, aa: 1, tc: 0, rd: 1, ra: 0, z: 0x5, rcode: 0xA };
+    let bytes = Header { id: 0, flags: flags.clone() }.encode().unwrap();
+    assert_eq!(&bytes[2..], &[0x7D, 0x5A]);
+    assert_eq!(Header::decode(&bytes).unwrap().flags, flags);
+}
+"#;
+
+    run_generated_crate_test("bitfield struct", schema_json, "bitfield_flags.rs", bitfield_flags_test);
+}
+
+#[test]
+fn test_const_magic_fields_are_checked() {
+    let schema_json = r#"{
+        "config": { "endianness": "little_endian" },
+        "types": {
+            "Gif": { "sequence": [
+                { "name": "signature", "type": "string", "kind": "fixed", "length": 4, "encoding": "ascii", "const": "GIF8" },
+                { "name": "version", "type": "uint8", "const": 57 },
+                { "name": "width", "type": "uint16" }
+            ] }
+        }
+    }"#;
+
+    let const_magic_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::BinSchemaError;
+
+#[test]
+fn magic_is_written_and_checked() {
+    let bytes = GifInput { width: 320 }.encode().unwrap();
+    assert_eq!(bytes, b"GIF89\x40\x01".to_vec());
+    let decoded = GifOutput::decode(&bytes).unwrap();
+    assert_eq!((decoded.signature.as_str(), decoded.version, decoded.width), ("GIF8", 57, 320));
+}
+
+#[test]
+fn wrong_magic_is_invalid_value() {
+    assert_eq!(
+        GifOutput::decode(b"PNG89\x40\x01"),
+        Err(BinSchemaError::InvalidValue("Gif.signature is \"PNG8\", expected \"GIF8\"".to_string()))
+    );
+    assert_eq!(
+        GifOutput::decode(b"GIF87\x40\x01"),
+        Err(BinSchemaError::InvalidValue("Gif.version is 55, expected 57".to_string()))
+    );
+}
+"#;
+
+    run_generated_crate_test("const magic", schema_json, "const_magic.rs", const_magic_test);
+}
+
+#[test]
+fn test_conditional_and_optional_presence() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Packet": { "sequence": [
+                { "name": "has_extra", "type": "uint8" },
+                { "name": "extra", "type": "uint16", "conditional": "has_extra == 1" },
+                { "name": "flags", "type": "uint8" },
+                { "name": "checksum", "type": "uint8", "conditional": "flags & 0x04" },
+                { "name": "note", "type": "optional", "value_type": "uint8" }
+            ] }
+        }
+    }"#;
+
+    let conditional_presence_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn present_when_conditions_hold() {
+    let packet = Packet { has_extra: 1, extra: Some(0x0102), flags: 0x04, checksum: Some(0x99), note: Some(7) };
+    let bytes = packet.encode().unwrap();
+    assert_eq!(bytes, vec![0x01, 0x01, 0x02, 0x04, 0x99, 0x01, 0x07]);
+    assert_eq!(Packet::decode(&bytes).unwrap(), packet);
+}
+
+#[test]
+fn absent_when_conditions_fail() {
+    let packet = Packet { has_extra: 0, extra: None, flags: 0x03, checksum: None, note: None };
+    let bytes = packet.encode().unwrap();
+    assert_eq!(bytes, vec![0x00, 0x03, 0x00]);
+    assert_eq!(Packet::decode(&bytes).unwrap(), packet);
+}
+"#;
+
+    run_generated_crate_test("conditional presence", schema_json, "conditional_presence.rs", conditional_presence_test);
+}
+
+#[test]
+fn test_generated_types_convert_to_json() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "SensorReading": { "sequence": [
+                { "name": "sensor_type", "type": "bit", "size": 4 },
+                { "name": "battery_low", "type": "bit", "size": 1 },
+                { "name": "error", "type": "bit", "size": 1 },
+                { "name": "reserved", "type": "bit", "size": 2 },
+                { "name": "timestamp", "type": "uint32" },
+                { "name": "value", "type": "float32" }
+            ] }
+        }
+    }"#;
+
+    let json_conversion_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::BinSchemaValue;
+
+#[test]
+fn sensor_reading_round_trips_through_json() {
+    let bytes = [0x04, 0x61, 0x56, 0x5C, 0x00, 0x41, 0xBC, 0x00, 0x00];
+    let reading = SensorReading::decode(&bytes).unwrap();
+    let json = reading.to_json();
+    assert_eq!(json["timestamp"], 1633049600);
+    assert_eq!(json["value"], 23.5);
+
+    let restored = SensorReading::from_json(&json).unwrap();
+    assert_eq!(restored, reading);
+    assert_eq!(restored.encode().unwrap(), bytes.to_vec());
+}
+"#;
+
+    run_generated_crate_test_with_features("JSON conversion", schema_json, "json_conversion.rs", json_conversion_test, &["serde"]);
+}
+
+#[test]
+fn test_sum_of_type_sizes_over_sibling_array() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "ARecord": { "sequence": [
+                { "name": "type_tag", "type": "uint8", "const": 1 },
+                { "name": "address", "type": "array", "kind": "fixed", "length": 4, "items": { "type": "uint8" } }
+            ] },
+            "TxtRecord": { "sequence": [
+                { "name": "type_tag", "type": "uint8", "const": 16 },
+                { "name": "text", "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" }
+            ] },
+            "Answers": { "sequence": [
+                { "name": "a_bytes", "type": "uint16", "computed": { "type": "sum_of_type_sizes", "target": "records", "element_type": "ARecord" } },
+                { "name": "records", "type": "array", "kind": "length_prefixed", "length_type": "uint8", "items": {
+                    "type": "choice", "choices": [{ "type": "ARecord" }, { "type": "TxtRecord" }]
+                } }
+            ] }
+        }
+    }"#;
+
+    let sum_of_type_sizes_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn computed_field_sums_a_record_sizes() {
+    // Two A records (5 bytes each) around a 4-byte TXT record
+    let bytes: Vec<u8> = vec![0, 10, 3, 1, 10, 0, 0, 1, 16, 2, b'h', b'i', 1, 10, 0, 0, 2];
+    let decoded = AnswersOutput::decode(&bytes).unwrap();
+    assert_eq!(decoded.a_bytes, 10);
+    assert_eq!(AnswersInput::from(decoded.clone()).encode().unwrap(), bytes);
+
+    // Dropping an A record shrinks the sum on re-encode
+    let mut input = AnswersInput::from(decoded);
+    input.records.remove(0);
+    assert_eq!(input.encode().unwrap()[..3], [0, 5, 2]);
+}
+"#;
+
+    run_generated_crate_test("sum_of_type_sizes", schema_json, "sum_of_type_sizes.rs", sum_of_type_sizes_test);
+}
+
+#[test]
+fn test_builder_fills_unset_fields_with_defaults() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Question": { "sequence": [
+                { "name": "qname", "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" },
+                { "name": "qtype", "type": "uint16" }
+            ] },
+            "DnsMessage": { "sequence": [
+                { "name": "id", "type": "uint16" },
+                { "name": "qdcount", "type": "uint16", "computed": { "type": "length_of", "target": "questions" } },
+                { "name": "questions", "type": "array", "kind": "field_referenced", "length_field": "qdcount", "items": { "type": "Question" } }
+            ] }
+        }
+    }"#;
+
+    let defaults_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn unset_fields_take_their_defaults() {
+    let message = DnsMessageInput::builder().id(0x1234).build();
+    assert_eq!(message, DnsMessageInput { id: 0x1234, ..Default::default() });
+    assert_eq!(message.encode().unwrap(), vec![0x12, 0x34, 0x00, 0x00]);
+}
+
+#[test]
+fn partial_message_round_trips() {
+    let question = Question::builder().qname("a".to_string()).qtype(1).build();
+    let message = DnsMessageInput::builder().questions(vec![question.clone()]).build();
+    let bytes = message.encode().unwrap();
+    assert_eq!(bytes, vec![0x00, 0x00, 0x00, 0x01, 0x01, b'a', 0x00, 0x01]);
+    let decoded = DnsMessage::decode(&bytes).unwrap();
+    assert_eq!(decoded.id, 0);
+    assert_eq!(decoded.questions, vec![question]);
+}
+"#;
+
+    run_generated_crate_test("defaulting builder", schema_json, "defaults.rs", defaults_test);
+}
+
+#[test]
+fn test_null_terminated_domain_name_decode_terminates() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" },
+            "DomainName": { "type": "array", "kind": "null_terminated", "items": { "type": "Label" } }
+        }
+    }"#;
+
+    let domain_name_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn decode_stops_at_the_zero_length_byte() {
+    let mut bytes = vec![7];
+    bytes.extend_from_slice(b"example");
+    bytes.push(3);
+    bytes.extend_from_slice(b"com");
+    bytes.push(0);
+
+    let name = DomainName::decode(&bytes).unwrap();
+    let labels: Vec<&str> = name.value.iter().map(|label| label.0.as_str()).collect();
+    assert_eq!(labels, ["example", "com"]);
+    assert_eq!(name.encode().unwrap(), bytes);
+}
+
+#[test]
+fn missing_terminator_is_an_error() {
+    assert!(DomainName::decode(&[3, b'c', b'o', b'm']).is_err());
+}
+"#;
+
+    run_generated_crate_test("null-terminated name", schema_json, "domain_name.rs", domain_name_test);
+}
+
+#[test]
+fn test_computed_fields_split_input_and_output() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Packet": { "sequence": [
+                { "name": "len", "type": "uint8", "computed": { "type": "length_of", "target": "payload" } },
+                { "name": "payload", "type": "array", "kind": "field_referenced", "length_field": "len", "items": { "type": "uint8" } }
+            ] },
+            "Point": { "sequence": [
+                { "name": "x", "type": "uint8" },
+                { "name": "y", "type": "uint8" }
+            ] }
+        }
+    }"#;
+
+    let io_split_test = r#"
+use binschema_generated::generated::*;
+
+#[test]
+fn decoded_output_converts_back_to_input() {
+    let input = PacketInput { payload: vec![1, 2, 3] };
+    let bytes = input.encode().unwrap();
+    assert_eq!(bytes, vec![3, 1, 2, 3]);
+
+    let output: PacketOutput = Packet::decode(&bytes).unwrap();
+    assert_eq!(output.len, 3);
+
+    // Editing the decoded value and re-encoding recomputes the length
+    let mut edited = PacketInput::from(output);
+    assert_eq!(edited, input);
+    edited.payload.push(4);
+    assert_eq!(edited.encode().unwrap(), vec![4, 1, 2, 3, 4]);
+}
+
+#[test]
+fn types_without_computed_fields_stay_unified() {
+    let point = Point { x: 1, y: 2 };
+    assert_eq!(Point::decode(&point.encode().unwrap()).unwrap(), point);
+}
+"#;
+
+    run_generated_crate_test("input/output split", schema_json, "io_split.rs", io_split_test);
+}
+
+#[test]
+fn test_forged_lengths_fail_without_allocating() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Record": { "sequence": [{ "name": "id", "type": "uint16" }] },
+            "Marker": { "sequence": [] },
+            "Table": { "sequence": [
+                { "name": "count", "type": "uint32" },
+                { "name": "records", "type": "array", "kind": "field_referenced", "length_field": "count",
+                  "items": { "type": "Record" } }
+            ] },
+            "Blob": { "sequence": [
+                { "name": "data", "type": "bytes", "kind": "length_prefixed", "length_type": "uint32" }
+            ] },
+            "Note": { "sequence": [
+                { "name": "text", "type": "string", "kind": "length_prefixed", "length_type": "uint32", "encoding": "utf8" }
+            ] },
+            "Markers": { "sequence": [
+                { "name": "markers", "type": "array", "kind": "length_prefixed", "length_type": "uint32",
+                  "items": { "type": "Marker" } }
+            ] }
+        }
+    }"#;
+
+    let forged_length_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::{BinSchemaError, DecodeLimits};
+
+const HUGE: [u8; 6] = [0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x01];
+
+#[test]
+fn huge_count_on_tiny_buffer_is_invalid() {
+    assert!(matches!(Table::decode(&HUGE), Err(BinSchemaError::InvalidValue(_))));
+    assert!(matches!(Blob::decode(&HUGE), Err(BinSchemaError::InvalidValue(_))));
+}
+
+#[test]
+fn huge_string_length_on_tiny_buffer_is_out_of_input() {
+    assert_eq!(Note::decode(&HUGE), Err(BinSchemaError::UnexpectedEof));
+}
+
+#[test]
+fn huge_count_of_empty_items_hits_default_max_alloc() {
+    // Markers take no input, so only max_alloc bounds the loop
+    assert!(matches!(Markers::decode(&HUGE[..4]), Err(BinSchemaError::InvalidValue(_))));
+    let small = DecodeLimits { max_alloc: 2, ..DecodeLimits::unlimited() };
+    assert_eq!(
+        Markers::decode_with_limits(&[0, 0, 0, 3], &small),
+        Err(BinSchemaError::InvalidValue("count 3 exceeds max_alloc 2".into()))
+    );
+    assert_eq!(Markers::decode(&[0, 0, 0, 3]).unwrap().markers.len(), 3);
+}
+
+#[test]
+fn plausible_lengths_still_decode() {
+    let table = Table::decode(&[0, 0, 0, 1, 0x12, 0x34]).unwrap();
+    assert_eq!(table.records.len(), 1);
+    assert_eq!(table.records[0].id, 0x1234);
+    assert_eq!(Blob::decode(&[0, 0, 0, 2, 7, 8]).unwrap().data, vec![7, 8]);
+}
+"#;
+
+    run_generated_crate_test("forged length", schema_json, "forged_length.rs", forged_length_test);
+}
+
+#[test]
+fn test_try_decode_never_panics_on_random_input() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" },
+            "LabelPointer": {
+                "type": "back_reference", "storage": "uint16", "offset_mask": "0x3FFF",
+                "offset_from": "message_start", "target_type": "Label"
+            },
+            "CompressedLabel": {
+                "type": "discriminated_union", "discriminator": { "peek": "uint8" },
+                "variants": [
+                    { "type": "Label", "when": "value < 0xC0" },
+                    { "type": "LabelPointer", "when": "value >= 0xC0" }
+                ]
+            },
+            "DomainName": {
+                "type": "array", "kind": "null_terminated", "items": { "type": "CompressedLabel" },
+                "terminal_variants": ["LabelPointer"], "follow_pointers": true
+            },
+            "RecordType": { "type": "enum", "repr": "uint16", "variants": { "A": 1, "Cname": 5, "Txt": 16 } },
+            "Question": { "sequence": [
+                { "name": "name", "type": "DomainName" },
+                { "name": "qtype", "type": "RecordType" },
+                { "name": "qclass", "type": "uint16" }
+            ] },
+            "Record": { "sequence": [
+                { "name": "name", "type": "DomainName" },
+                { "name": "rtype", "type": "RecordType" },
+                { "name": "ttl", "type": "uint32" },
+                { "name": "rdata", "type": "array", "kind": "length_prefixed", "length_type": "uint16", "items": { "type": "uint8" } }
+            ] },
+            "Tree": { "sequence": [
+                { "name": "value", "type": "uint8" },
+                { "name": "children", "type": "array", "kind": "length_prefixed", "length_type": "uint8", "items": { "type": "Tree" } }
+            ] },
+            "Message": { "sequence": [
+                { "name": "id", "type": "uint16" },
+                { "name": "qdcount", "type": "uint16", "computed": { "type": "length_of", "target": "questions" } },
+                { "name": "ancount", "type": "uint16", "computed": { "type": "length_of", "target": "answers" } },
+                { "name": "questions", "type": "array", "kind": "field_referenced", "length_field": "qdcount", "items": { "type": "Question" } },
+                { "name": "answers", "type": "array", "kind": "field_referenced", "length_field": "ancount", "items": { "type": "Record" } }
+            ] }
+        }
+    }"#;
+
+    let try_decode_fuzz_test = r#"
+use binschema_generated::generated::*;
+use binschema_runtime::{try_decode, Decodable};
+
+/// xorshift64, so the inputs are the same on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self, max_len: u64) -> Vec<u8> {
+        let len = self.next() % max_len;
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// Throws random inputs, plus mutations of a valid message, at `T`
+fn fuzz<T: Decodable>(seed: &[u8]) {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    for _ in 0..5_000 {
+        let _ = try_decode::<T>(&rng.bytes(96));
+        let mut mutated = seed.to_vec();
+        for _ in 0..1 + rng.next() % 4 {
+            let i = (rng.next() % mutated.len() as u64) as usize;
+            mutated[i] = rng.next() as u8;
+        }
+        let _ = try_decode::<T>(&mutated);
+        let _ = try_decode::<T>(&mutated[..(rng.next() % mutated.len() as u64) as usize]);
+    }
+}
+
+fn message() -> Vec<u8> {
+    MessageInput {
+        id: 0x1234,
+        questions: vec![Question {
+            name: vec![CompressedLabel::Label(Label("example".into())), CompressedLabel::Label(Label("com".into()))],
+            qtype: RecordType::A,
+            qclass: 1,
+        }],
+        answers: vec![Record {
+            name: vec![CompressedLabel::Label(Label("example".into())), CompressedLabel::Label(Label("com".into()))],
+            rtype: RecordType::A,
+            ttl: 300,
+            rdata: vec![93, 184, 216, 34],
+        }],
+    }
+    .encode()
+    .unwrap()
+}
+
+#[test]
+fn valid_input_decodes_through_the_trait() {
+    let bytes = message();
+    assert_eq!(try_decode::<Message>(&bytes).unwrap(), Message::decode(&bytes).unwrap());
+    assert_eq!(try_decode::<RecordType>(&[0x00, 0x05]).unwrap(), RecordType::Cname);
+}
+
+#[test]
+fn random_input_never_panics() {
+    let bytes = message();
+    fuzz::<Message>(&bytes);
+    fuzz::<Question>(&bytes[6..]);
+    fuzz::<Tree>(&[0x01, 0x02, 0x02, 0x00, 0x03, 0x00]);
+    fuzz::<RecordType>(&[0x00, 0x10]);
+}
+
+#[test]
+fn deep_nesting_is_an_error_not_a_stack_overflow() {
+    let deep = [0x00, 0x01].repeat(100_000);
+    assert!(try_decode::<Tree>(&deep).is_err());
+}
+"#;
+
+    run_generated_crate_test("try_decode fuzz", schema_json, "try_decode_fuzz.rs", try_decode_fuzz_test);
+}
+
+#[test]
+fn test_rdlength_is_back_patched_after_rdata() {
+    let schema_json = r#"{
+        "config": { "endianness": "big_endian" },
+        "types": {
+            "Label": { "type": "string", "kind": "length_prefixed", "length_type": "uint8", "encoding": "ascii" },
+            "DomainName": { "type": "array", "kind": "null_terminated", "items": { "type": "Label" } },
+            "Mx": { "sequence": [
+                { "name": "preference", "type": "uint16" },
+                { "name": "exchange", "type": "DomainName" }
+            ] },
+            "ResourceRecord": { "sequence": [
+                { "name": "name", "type": "DomainName" },
+                { "name": "ttl", "type": "uint32" },
+                { "name": "rdlength", "type": "uint16", "computed": { "type": "length_of", "target": "rdata" } },
+                { "name": "rdata", "type": "Mx" },
+                { "name": "trailer", "type": "uint8" }
+            ] }
+        }
+    }"#;
+
+    let rdlength_back_patch_test = r#"
+use binschema_generated::generated::*;
+
+fn record() -> ResourceRecordInput {
+    ResourceRecordInput {
+        name: vec![Label("mx".into())],
+        ttl: 60,
+        rdata: Mx { preference: 10, exchange: vec![Label("mail".into()), Label("example".into())] },
+        trailer: 0xEE,
+    }
+}
+
+#[test]
+fn rdlength_is_the_encoded_rdata_size() {
+    let bytes = record().encode().unwrap();
+    // preference, "mail", "example", terminator
+    let rdata_len = 2 + 5 + 8 + 1;
+    // name (02 'm' 'x' 00) and ttl come first
+    assert_eq!(&bytes[8..10], &(rdata_len as u16).to_be_bytes());
+    assert_eq!(bytes.len(), 10 + rdata_len + 1);
+    assert_eq!(bytes[10..10 + rdata_len], record().rdata.encode().unwrap()[..]);
+    assert_eq!(bytes[10 + rdata_len], 0xEE);
+}
+
+#[test]
+fn back_patched_record_round_trips() {
+    let bytes = record().encode().unwrap();
+    let decoded = ResourceRecord::decode(&bytes).unwrap();
+    assert_eq!(decoded.rdlength, 16);
+    assert_eq!(ResourceRecordInput::from(decoded), record());
+}
+"#;
+
+    run_generated_crate_test("rdlength back-patch", schema_json, "rdlength_back_patch.rs", rdlength_back_patch_test);
+}
+
 /// Helper to recursively copy a directory
 fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
     fs::create_dir_all(dst)?;
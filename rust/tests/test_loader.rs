@@ -13,15 +13,14 @@ fn find_test_files(dir: &str) -> Vec<PathBuf> {
             let path = entry.path();
             if path.is_dir() {
                 files.extend(find_test_files(path.to_str().unwrap()));
-            } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if path.file_name()
+            } else if path.extension().and_then(|s| s.to_str()) == Some("json")
+                && path.file_name()
                     .and_then(|s| s.to_str())
                     .map(|s| s.contains(".test."))
                     .unwrap_or(false)
                 {
                     files.push(path);
                 }
-            }
         }
     }
 
@@ -85,7 +84,7 @@ fn test_primitive_suite_structure() {
     println!("Number of test cases: {}", suite.test_cases.len());
 
     assert_eq!(suite.name, "uint8");
-    assert!(suite.test_cases.len() > 0);
+    assert!(!suite.test_cases.is_empty());
 
     // Check first test case
     let first = &suite.test_cases[0];
@@ -93,10 +92,10 @@ fn test_primitive_suite_structure() {
     println!("  Description: {}", first.description);
     if let Some(bytes) = &first.bytes {
         println!("  Expected bytes: {:?}", bytes);
-        assert!(bytes.len() > 0);
+        assert!(!bytes.is_empty());
     } else if let Some(bits) = &first.bits {
         println!("  Expected bits: {:?}", bits);
-        assert!(bits.len() > 0);
+        assert!(!bits.is_empty());
     } else {
         panic!("Test case should have either bytes or bits");
     }
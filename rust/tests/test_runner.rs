@@ -13,15 +13,14 @@ fn find_test_files(dir: &str) -> Vec<PathBuf> {
             let path = entry.path();
             if path.is_dir() {
                 files.extend(find_test_files(path.to_str().unwrap()));
-            } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if path.file_name()
+            } else if path.extension().and_then(|s| s.to_str()) == Some("json")
+                && path.file_name()
                     .and_then(|s| s.to_str())
                     .map(|s| s.contains(".test."))
                     .unwrap_or(false)
                 {
                     files.push(path);
                 }
-            }
         }
     }
 